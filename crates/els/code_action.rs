@@ -9,11 +9,44 @@ use erg_compiler::artifact::BuildRunnable;
 use erg_compiler::erg_parser::token::{Token, TokenKind};
 use erg_compiler::hir::Expr;
 
-use lsp_types::{CodeAction, CodeActionKind, CodeActionParams, TextEdit, Url, WorkspaceEdit};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionParams, Diagnostic, TextEdit, Url, WorkspaceEdit,
+};
 
 use crate::server::{ELSResult, Server};
 use crate::util;
 
+/// Converts the fix-its attached to the diagnostics (see `diagnostics.rs`) into quick fixes.
+pub(crate) fn gen_fix_it_actions(uri: &Url, diag: &Diagnostic) -> Vec<CodeAction> {
+    let Some(fix_its) = diag
+        .data
+        .as_ref()
+        .and_then(|data| data["fix_its"].as_array())
+    else {
+        return vec![];
+    };
+    let mut actions = vec![];
+    for fix_it in fix_its {
+        let (Some(title), Ok(edit)) = (
+            fix_it["title"].as_str(),
+            TextEdit::deserialize(&fix_it["edit"]),
+        ) else {
+            continue;
+        };
+        let mut map = HashMap::new();
+        map.insert(uri.clone(), vec![edit]);
+        actions.push(CodeAction {
+            title: title.to_string(),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diag.clone()]),
+            edit: Some(WorkspaceEdit::new(map)),
+            is_preferred: Some(fix_its.len() == 1),
+            ..Default::default()
+        });
+    }
+    actions
+}
+
 impl<Checker: BuildRunnable> Server<Checker> {
     fn gen_eliminate_unused_vars_action(
         &self,
@@ -153,6 +186,10 @@ impl<Checker: BuildRunnable> Server<Checker> {
         if diags.is_empty() {
             return Ok(result);
         }
+        let uri = util::normalize_url(params.text_document.uri.clone());
+        for diag in diags.iter() {
+            result.extend(gen_fix_it_actions(&uri, diag));
+        }
         if diags.first().unwrap().message.ends_with("is not used") {
             let actions = self.gen_eliminate_unused_vars_action(msg, params)?;
             result.extend(actions);
@@ -181,3 +218,49 @@ impl<Checker: BuildRunnable> Server<Checker> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use erg_common::config::Input;
+    use erg_common::error::Location;
+    use erg_compiler::error::TyCheckError;
+
+    use lsp_types::{Position, Range};
+
+    use super::*;
+    use crate::diagnostics::fix_its_data;
+
+    #[test]
+    fn fix_it_quick_fixes() {
+        // `a: [Int; Str] = (1, "a")`
+        let loc = Location::range(1, 3, 1, 13);
+        let err = TyCheckError::array_len_type_error(
+            Input::pipe("a: [Int; Str] = (1, \"a\")".to_string()),
+            0,
+            loc,
+            "<module>".to_string(),
+            "Int",
+            "Str",
+        );
+        let mut diag = Diagnostic::new_simple(util::loc_to_range(loc).unwrap(), String::new());
+        diag.data = fix_its_data(&err);
+        let uri = Url::parse("file:///main.er").unwrap();
+        let actions = gen_fix_it_actions(&uri, &diag);
+        let range = Range::new(Position::new(0, 3), Position::new(0, 13));
+        let expected = [
+            ("fix: replace with `(Int, Str)`", "(Int, Str)"),
+            ("fix: replace with `{Int: Str}`", "{Int: Str}"),
+        ];
+        assert_eq!(actions.len(), expected.len());
+        for (action, (title, new_text)) in actions.iter().zip(expected) {
+            assert_eq!(action.title, title);
+            assert_eq!(action.kind, Some(CodeActionKind::QUICKFIX));
+            assert_eq!(action.is_preferred, Some(false));
+            let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+            assert_eq!(
+                changes[&uri],
+                vec![TextEdit::new(range, new_text.to_string())]
+            );
+        }
+    }
+}
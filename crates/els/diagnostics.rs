@@ -1,16 +1,33 @@
-use serde_json::json;
+use serde_json::{json, Value};
 
 use erg_common::style::*;
 use erg_common::traits::Stream;
 
 use erg_compiler::artifact::BuildRunnable;
-use erg_compiler::error::CompileErrors;
+use erg_compiler::error::{CompileError, CompileErrors};
 
-use lsp_types::{Diagnostic, DiagnosticSeverity, Position, PublishDiagnosticsParams, Range, Url};
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, NumberOrString, Position, PublishDiagnosticsParams, Range,
+    TextEdit, Url,
+};
 
 use crate::server::{ELSResult, Server};
 use crate::util;
 
+/// The fix-its are sent back in `textDocument/codeAction` and offered as quick fixes (see `code_action.rs`).
+pub(crate) fn fix_its_data(err: &CompileError) -> Option<Value> {
+    let fix_its = err
+        .core
+        .fix_its()
+        .filter_map(|fix_it| {
+            let range = util::loc_to_range(fix_it.loc)?;
+            let edit = TextEdit::new(range, fix_it.replacement.clone());
+            Some(json!({ "title": remove_style(&fix_it.description()), "edit": edit }))
+        })
+        .collect::<Vec<_>>();
+    (!fix_its.is_empty()).then(|| json!({ "fix_its": fix_its }))
+}
+
 impl<Checker: BuildRunnable> Server<Checker> {
     pub(crate) fn check_file<S: Into<String>>(&mut self, uri: Url, code: S) -> ELSResult<()> {
         Self::send_log(format!("checking {uri}"))?;
@@ -84,7 +101,7 @@ impl<Checker: BuildRunnable> Server<Checker> {
                 uri.clone()
             };
             let mut message = remove_style(&err.core.main_message);
-            for sub in err.core.sub_messages {
+            for sub in err.core.sub_messages.iter() {
                 for msg in sub.get_msg() {
                     message.push('\n');
                     message.push_str(&remove_style(msg));
@@ -100,20 +117,23 @@ impl<Checker: BuildRunnable> Server<Checker> {
                 loc.col_begin().unwrap_or(0),
             );
             let end = Position::new(loc.ln_end().unwrap_or(1) - 1, loc.col_end().unwrap_or(0));
-            let severity = if err.core.kind.is_warning() {
+            let severity = if err.core.kind.is_warning() && !err.core.denied {
                 DiagnosticSeverity::WARNING
             } else {
                 DiagnosticSeverity::ERROR
             };
-            let diag = Diagnostic::new(
+            let mut diag = Diagnostic::new(
                 Range::new(start, end),
                 Some(severity),
-                None,
+                err.core
+                    .code
+                    .map(|code| NumberOrString::String(code.to_string())),
                 None,
                 message,
                 None,
                 None,
             );
+            diag.data = fix_its_data(&err);
             if let Some((_, diags)) = uri_and_diags.iter_mut().find(|x| x.0 == err_uri) {
                 diags.push(diag);
             } else {
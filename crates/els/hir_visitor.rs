@@ -256,6 +256,11 @@ impl<'a> HIRVisitor<'a> {
                 return Some(expr);
             }
         }
+        if let Some(kw_var) = &args.kw_var_args {
+            if let Some(expr) = self.get_expr(&kw_var.expr, token) {
+                return Some(expr);
+            }
+        }
         None
     }
 
@@ -523,6 +528,11 @@ impl<'a> HIRVisitor<'a> {
                 return Some(expr);
             }
         }
+        if let Some(kw_var) = &args.kw_var_args {
+            if let Some(expr) = self.get_expr_info(&kw_var.expr, token) {
+                return Some(expr);
+            }
+        }
         None
     }
 
@@ -555,6 +565,11 @@ impl<'a> HIRVisitor<'a> {
                 return Some(vi);
             }
         }
+        if let Some(kw_var) = &params.kw_var_params {
+            if kw_var.raw.pat.loc() == token.loc() {
+                return Some(kw_var.vi.clone());
+            }
+        }
         None
     }
 
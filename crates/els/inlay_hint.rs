@@ -141,6 +141,17 @@ impl<Checker: BuildRunnable> Server<Checker> {
             );
             result.push(hint);
         }
+        if let Some(kw_var_params) = &params.kw_var_params {
+            if kw_var_params.raw.t_spec.is_none() {
+                let hint = type_anot(
+                    kw_var_params.ln_end().unwrap(),
+                    kw_var_params.col_end().unwrap(),
+                    &kw_var_params.vi.t,
+                    false,
+                );
+                result.push(hint);
+            }
+        }
         result
     }
 
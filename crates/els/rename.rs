@@ -142,7 +142,8 @@ impl<Checker: BuildRunnable> Server<Checker> {
     pub fn dependencies_of(&self, uri: &Url) -> Vec<Url> {
         let graph = &self.get_shared().unwrap().graph;
         let path = util::uri_to_path(uri);
-        graph.sort().unwrap();
+        // cyclic imports are allowed, in which case the graph is left unsorted
+        let _ = graph.sort();
         let self_node = graph.get_node(&path).unwrap();
         graph
             .iter()
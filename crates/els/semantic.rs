@@ -228,7 +228,7 @@ impl ASTSemanticState {
 
     fn gen_from_args(&mut self, args: Args) -> Vec<SemanticToken> {
         let mut tokens = vec![];
-        let (pos_args, var_args, kw_args, ..) = args.deconstruct();
+        let (pos_args, var_args, kw_args, kw_var_args, ..) = args.deconstruct();
         for arg in pos_args {
             tokens.extend(self.gen_from_expr(arg.expr));
         }
@@ -238,6 +238,9 @@ impl ASTSemanticState {
         for arg in kw_args {
             tokens.extend(self.gen_from_expr(arg.expr));
         }
+        if let Some(kw_var_args) = kw_var_args {
+            tokens.extend(self.gen_from_expr(kw_var_args.expr));
+        }
         tokens
     }
 
@@ -260,7 +263,7 @@ impl ASTSemanticState {
         self.namespaces.push(Dict::new());
         let mut tokens = vec![];
         if let Some(params) = params {
-            let (nd_params, var_params, d_params, ..) = params.deconstruct();
+            let (nd_params, var_params, d_params, kw_var_params, ..) = params.deconstruct();
             for param in nd_params.into_iter() {
                 let typ = SemanticTokenType::PARAMETER;
                 tokens.push(self.gen_token(param.loc(), typ));
@@ -273,6 +276,10 @@ impl ASTSemanticState {
                 let typ = SemanticTokenType::PARAMETER;
                 tokens.push(self.gen_token(param.loc(), typ));
             }
+            if let Some(kw_var_param) = kw_var_params {
+                let typ = SemanticTokenType::PARAMETER;
+                tokens.push(self.gen_token(kw_var_param.loc(), typ));
+            }
         }
         for expr in block.into_iter() {
             tokens.extend(self.gen_from_expr(expr));
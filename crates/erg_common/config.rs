@@ -10,13 +10,16 @@ use std::path::{Path, PathBuf};
 use std::process;
 use std::str::FromStr;
 
+use crate::error::Location;
 use crate::help_messages::{command_message, mode_message, OPTIONS};
 use crate::levenshtein::get_similar_name;
+use crate::lint::{validate_lint_name, LintLevel, LintLevels};
 use crate::normalize_path;
 use crate::python_util::{detect_magic_number, get_python_version, PythonVersion};
 use crate::random::random;
 use crate::serialize::{get_magic_num_from_bytes, get_ver_from_magic_num};
 use crate::stdin::GLOBAL_STDIN;
+use crate::venv::VirtualEnv;
 use crate::{power_assert, read_file};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -28,9 +31,23 @@ pub enum ErgMode {
     FullCheck,
     Compile,
     Transpile,
+    /// generates `.d.er` and `.pyi` of a module
+    DeclareGen,
     Execute,
+    Interpret,
+    Server,
     LanguageServer,
     Read,
+    /// the package manager (`erg pack ...`)
+    Pack,
+    /// the virtual environment manager (`erg env ...`)
+    Env,
+    /// the test runner (`erg test ...`)
+    Test,
+    /// the package builder (`erg build ...`)
+    Build,
+    /// shows the explanation of an error code (`erg --explain <code>`)
+    Explain,
 }
 
 impl TryFrom<&str> for ErgMode {
@@ -44,9 +61,17 @@ impl TryFrom<&str> for ErgMode {
             "fullcheck" | "check" | "checker" => Ok(Self::FullCheck),
             "compile" | "compiler" => Ok(Self::Compile),
             "transpile" | "transpiler" => Ok(Self::Transpile),
+            "declare-gen" | "declgen" => Ok(Self::DeclareGen),
             "execute" => Ok(Self::Execute),
+            "interpret" | "interpreter" => Ok(Self::Interpret),
+            "server" => Ok(Self::Server),
             "language-server" => Ok(Self::LanguageServer),
             "byteread" | "read" | "reader" => Ok(Self::Read),
+            "pack" => Ok(Self::Pack),
+            "env" => Ok(Self::Env),
+            "test" => Ok(Self::Test),
+            "build" => Ok(Self::Build),
+            "explain" => Ok(Self::Explain),
             _ => Err(()),
         }
     }
@@ -62,9 +87,17 @@ impl From<ErgMode> for &str {
             ErgMode::FullCheck => "fullcheck",
             ErgMode::Compile => "compile",
             ErgMode::Transpile => "transpile",
+            ErgMode::DeclareGen => "declare-gen",
             ErgMode::Execute => "execute",
+            ErgMode::Interpret => "interpret",
+            ErgMode::Server => "server",
             ErgMode::LanguageServer => "language-server",
             ErgMode::Read => "read",
+            ErgMode::Pack => "pack",
+            ErgMode::Env => "env",
+            ErgMode::Test => "test",
+            ErgMode::Build => "build",
+            ErgMode::Explain => "explain",
         }
     }
 }
@@ -78,6 +111,8 @@ impl fmt::Display for ErgMode {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DummyStdin {
     pub name: String,
+    /// the directory from which the imports are resolved (the current directory if `None`)
+    pub dir: Option<PathBuf>,
     current_line: usize,
     lines: Vec<String>,
 }
@@ -86,11 +121,17 @@ impl DummyStdin {
     pub fn new(name: String, lines: Vec<String>) -> Self {
         Self {
             name,
+            dir: None,
             current_line: 0,
             lines,
         }
     }
 
+    pub fn with_dir(mut self, dir: PathBuf) -> Self {
+        self.dir = Some(dir);
+        self
+    }
+
     pub fn read_line(&mut self) -> String {
         let mut stdout = std::io::stdout();
         if self.current_line >= self.lines.len() {
@@ -185,6 +226,16 @@ impl Input {
         }
     }
 
+    /// The directory from which the local imports are resolved.
+    /// An empty path means the current directory.
+    pub fn dir(&self) -> PathBuf {
+        match self {
+            Self::File(path) => path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            Self::DummyREPL(stdin) => stdin.dir.clone().unwrap_or_default(),
+            _ => PathBuf::new(),
+        }
+    }
+
     pub const fn id(&self) -> u64 {
         match self {
             Input::File(_) | Input::DummyREPL(_) | Input::Dummy => 0,
@@ -331,6 +382,38 @@ impl Input {
         }
     }
 
+    /// The source code in the range of `loc` (e.g. to suggest a fix-it that reuses a part of the code).
+    pub fn reread_loc(&self, loc: Location) -> Option<String> {
+        let Location::Range {
+            ln_begin,
+            col_begin,
+            ln_end,
+            col_end,
+        } = loc
+        else {
+            return None;
+        };
+        if matches!(self, Self::Dummy) {
+            return None;
+        }
+        let lines = self.reread_lines(ln_begin as usize, ln_end as usize);
+        let last = lines.len().checked_sub(1)?;
+        let mut code = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            let begin = if i == 0 { col_begin as usize } else { 0 };
+            let end = if i == last {
+                col_end as usize
+            } else {
+                line.chars().count()
+            };
+            if i != 0 {
+                code.push('\n');
+            }
+            code.extend(line.chars().skip(begin).take(end.saturating_sub(begin)));
+        }
+        Some(code)
+    }
+
     pub fn reread(&self) -> String {
         match self {
             Self::File(_filename) => todo!(),
@@ -342,12 +425,7 @@ impl Input {
     }
 
     pub fn local_resolve(&self, path: &Path) -> Result<PathBuf, std::io::Error> {
-        let mut dir = if let Self::File(mut path) = self.clone() {
-            path.pop();
-            path
-        } else {
-            PathBuf::new()
-        };
+        let mut dir = self.dir();
         dir.push(path);
         dir.set_extension("er");
         let path = dir
@@ -381,12 +459,7 @@ impl Input {
     }
 
     pub fn local_py_resolve(&self, path: &Path) -> Result<PathBuf, std::io::Error> {
-        let mut dir = if let Self::File(mut path) = self.clone() {
-            path.pop();
-            path
-        } else {
-            PathBuf::new()
-        };
+        let mut dir = self.dir();
         dir.push(path);
         dir.set_extension("py");
         let path = dir.canonicalize().or_else(|_| {
@@ -412,11 +485,21 @@ pub struct ErgConfig {
     pub py_magic_num: Option<u32>, // the magic number cannot be uniquely determined from `target_version`
     pub py_command: Option<&'static str>,
     pub target_version: Option<PythonVersion>,
+    /// the virtual environment activated for the project (`erg env activate`)
+    pub venv: Option<VirtualEnv>,
     pub py_server_timeout: u64,
     pub quiet_repl: bool,
     pub show_type: bool,
     pub input: Input,
     pub output_dir: Option<&'static str>,
+    /// compile each Erg module into its own file instead of linking them into one
+    pub separate: bool,
+    /// transpile into human-readable Python code (no name mangling unless the names collide)
+    pub readable: bool,
+    /// emit a source map (`.pyc.map`, `.py.map`) that maps the generated code to the Erg source
+    pub source_map: bool,
+    /// strip the docstrings and the line number tables from the generated code (only `erg build --release` sets this)
+    pub strip: bool,
     /// module name to be executed
     pub module: &'static str,
     /// verbosity level for system messages.
@@ -427,6 +510,12 @@ pub struct ErgConfig {
     pub ps1: &'static str,
     pub ps2: &'static str,
     pub runtime_args: Vec<&'static str>,
+    /// send the request to the compile server (`--mode server`) instead of compiling locally
+    pub client: bool,
+    /// the path of the Unix socket of the compile server
+    pub server_socket: Option<&'static str>,
+    /// the levels of the compile warnings (`-A`/`-W`/`-D` and `.erg/lint.er`)
+    pub lint_levels: LintLevels,
 }
 
 impl Default for ErgConfig {
@@ -439,16 +528,24 @@ impl Default for ErgConfig {
             py_magic_num: None,
             py_command: None,
             target_version: None,
+            venv: None,
             py_server_timeout: 10,
             quiet_repl: false,
             show_type: false,
             input: Input::repl(),
             output_dir: None,
+            separate: false,
+            readable: false,
+            source_map: false,
+            strip: false,
             module: "<module>",
             verbose: 1,
             ps1: ">>> ",
             ps2: "... ",
             runtime_args: vec![],
+            client: false,
+            server_socket: None,
+            lint_levels: LintLevels::default(),
         }
     }
 }
@@ -477,6 +574,13 @@ impl ErgConfig {
         }
     }
 
+    /// The directory where the output files are placed (the directory of the input by default).
+    pub fn dump_dir(&self) -> PathBuf {
+        let mut dump_path = self.dump_path();
+        dump_path.pop();
+        dump_path
+    }
+
     pub fn dump_filename(&self) -> String {
         if let Some(output) = &self.output_dir {
             format!("{output}/{}", self.input.filename())
@@ -513,6 +617,7 @@ impl ErgConfig {
         let mut args = env::args();
         args.next(); // "ergc"
         let mut cfg = Self::default();
+        let mut lint_levels = LintLevels::default();
         // not `for` because we need to consume the next argument
         while let Some(arg) = args.next() {
             match &arg[..] {
@@ -528,9 +633,33 @@ impl ErgConfig {
                 "--check" => {
                     cfg.mode = ErgMode::FullCheck;
                 }
+                "--client" => {
+                    cfg.client = true;
+                }
                 "--compile" | "--dump-as-pyc" => {
                     cfg.mode = ErgMode::Compile;
                 }
+                "--explain" => {
+                    cfg.mode = ErgMode::Explain;
+                    if let Some(code) = args.next() {
+                        cfg.runtime_args.push(Box::leak(code.into_boxed_str()));
+                    }
+                }
+                "-A" | "--allow" | "-W" | "--warn" | "-D" | "--deny" => {
+                    let level = match &arg[..] {
+                        "-A" | "--allow" => LintLevel::Allow,
+                        "-W" | "--warn" => LintLevel::Warn,
+                        _ => LintLevel::Deny,
+                    };
+                    let lint = args
+                        .next()
+                        .unwrap_or_else(|| panic!("the value of `{arg}` is not passed"));
+                    if let Err(err) = validate_lint_name(&lint) {
+                        eprintln!("{err}");
+                        process::exit(2);
+                    }
+                    lint_levels.push(lint, level);
+                }
                 "--language-server" => {
                     cfg.mode = ErgMode::LanguageServer;
                 }
@@ -633,6 +762,22 @@ impl ErgConfig {
                 "--quiet-startup" | "--quiet-repl" => {
                     cfg.quiet_repl = true;
                 }
+                "--readable" => {
+                    cfg.readable = true;
+                }
+                "--separate" | "--separate-compilation" => {
+                    cfg.separate = true;
+                }
+                "--source-map" => {
+                    cfg.source_map = true;
+                }
+                "--server-socket" => {
+                    let socket = args
+                        .next()
+                        .expect("the value of `--server-socket` is not passed")
+                        .into_boxed_str();
+                    cfg.server_socket = Some(Box::leak(socket));
+                }
                 "-t" | "--show-type" => {
                     cfg.show_type = true;
                 }
@@ -677,6 +822,35 @@ impl ErgConfig {
                     println!();
                     process::exit(0);
                 }
+                // subcommands (the rest of the arguments are passed to them)
+                "pack" if cfg.input.is_repl() => {
+                    cfg.mode = ErgMode::Pack;
+                    for arg in args {
+                        cfg.runtime_args.push(Box::leak(arg.into_boxed_str()));
+                    }
+                    break;
+                }
+                "env" if cfg.input.is_repl() => {
+                    cfg.mode = ErgMode::Env;
+                    for arg in args {
+                        cfg.runtime_args.push(Box::leak(arg.into_boxed_str()));
+                    }
+                    break;
+                }
+                "test" if cfg.input.is_repl() => {
+                    cfg.mode = ErgMode::Test;
+                    for arg in args {
+                        cfg.runtime_args.push(Box::leak(arg.into_boxed_str()));
+                    }
+                    break;
+                }
+                "build" if cfg.input.is_repl() => {
+                    cfg.mode = ErgMode::Build;
+                    for arg in args {
+                        cfg.runtime_args.push(Box::leak(arg.into_boxed_str()));
+                    }
+                    break;
+                }
                 other if other.starts_with('-') => {
                     if let Some(option) = get_similar_name(OPTIONS.iter().copied(), other) {
                         eprintln!("invalid option: {other} (did you mean `{option}`?)");
@@ -706,7 +880,18 @@ USAGE:
                 }
             }
         }
-        if cfg.input.is_repl() && cfg.mode != ErgMode::LanguageServer {
+        if cfg.input.is_repl()
+            && !matches!(
+                cfg.mode,
+                ErgMode::LanguageServer
+                    | ErgMode::Server
+                    | ErgMode::Pack
+                    | ErgMode::Env
+                    | ErgMode::Test
+                    | ErgMode::Build
+                    | ErgMode::Explain
+            )
+        {
             use crate::tty::IsTty;
             let is_stdin_piped = !stdin().is_tty();
             let input = if is_stdin_piped {
@@ -718,6 +903,48 @@ USAGE:
             };
             cfg.input = input;
         }
+        cfg.apply_venv();
+        cfg.apply_lint_levels(lint_levels);
         cfg
     }
+
+    /// The levels in `.erg/lint.er` are overridden by the command line options.
+    fn apply_lint_levels(&mut self, cli_levels: LintLevels) {
+        if let Ok(cwd) = env::current_dir() {
+            let dir = match self.input.path().and_then(Path::parent) {
+                Some(dir) => cwd.join(dir),
+                None => cwd,
+            };
+            match LintLevels::find(&dir) {
+                Some(Ok(levels)) => self.lint_levels = levels,
+                Some(Err(err)) => eprintln!("warning: {err}"),
+                None => {}
+            }
+        }
+        self.lint_levels.extend(cli_levels);
+    }
+
+    /// Uses the interpreter of the project's virtual environment unless it is specified explicitly.
+    fn apply_venv(&mut self) {
+        let Ok(cwd) = env::current_dir() else {
+            return;
+        };
+        let dir = match self.input.path().and_then(Path::parent) {
+            Some(dir) => cwd.join(dir),
+            None => cwd,
+        };
+        self.venv = VirtualEnv::find(&dir);
+        let Some(venv) = &self.venv else {
+            return;
+        };
+        if self.py_command.is_none() {
+            self.py_command = Some(Box::leak(venv.py_command.clone().into_boxed_str()));
+            if self.py_magic_num.is_none() {
+                self.py_magic_num = Some(venv.py_magic_num);
+            }
+        }
+        if self.target_version.is_none() {
+            self.target_version = Some(venv.target_version);
+        }
+    }
 }
@@ -17,7 +17,8 @@ use crate::traits::{Locational, Stream};
 use crate::{impl_display_from_debug, switch_lang};
 
 /// This includes not only Error but also Warning, Exception
-/// Numbering of this is not specifically related to ErrFmt.errno().
+/// The numbers are the stable codes of the diagnostics (see `ErrorKind::code`), so they must not be changed.
+/// Numbering of this is not specifically related to ErrorCore.errno.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum ErrorKind {
@@ -45,6 +46,7 @@ pub enum ErrorKind {
     VisibilityError = 20,
     MethodError = 21,
     DummyError = 22,
+    CyclicReferenceError = 23,
     /* compile warnings */
     AttributeWarning = 60,
     CastWarning = 61,
@@ -57,6 +59,7 @@ pub enum ErrorKind {
     NameWarning = 68,
     UnusedWarning = 69,
     Warning = 70,
+    TypeNameWarning = 71,
     /* runtime errors */
     ArithmeticError = 100,
     AssertionError = 101,
@@ -153,6 +156,7 @@ impl From<&str> for ErrorKind {
             "HasEffect" => Self::HasEffect,
             "PurityError" => Self::PurityError,
             "MoveError" => Self::MoveError,
+            "CyclicReferenceError" => Self::CyclicReferenceError,
             "AttributeWarning" => Self::AttributeWarning,
             "CastWarning" => Self::CastWarning,
             "DeprecationWarning" => Self::DeprecationWarning,
@@ -164,6 +168,7 @@ impl From<&str> for ErrorKind {
             "NameWarning" => Self::NameWarning,
             "UnusedWarning" => Self::UnusedWarning,
             "Warning" => Self::Warning,
+            "TypeNameWarning" => Self::TypeNameWarning,
             "ArithmeticError" => Self::ArithmeticError,
             "AssertionError" => Self::AssertionError,
             "BlockingIOError" => Self::BlockingIOError,
@@ -498,11 +503,44 @@ fn format_context<E: ErrorDisplay + ?Sized>(
     context.to_string() + "\n"
 }
 
+/// A suggested edit that resolves the error: the code at `loc` is replaced with `replacement`.
+/// Shown inline by the terminal renderer and offered as a quick fix by the language server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixIt {
+    pub loc: Location,
+    pub replacement: String,
+}
+
+impl FixIt {
+    pub fn new<S: Into<String>>(loc: Location, replacement: S) -> Self {
+        Self {
+            loc,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// e.g. "fix: replace with `{x = Int}`"
+    pub fn description(&self) -> String {
+        let replacement = StyledStr::new(
+            &self.replacement,
+            Some(THEME.colors.hint),
+            Some(Attribute::Bold),
+        );
+        switch_lang!(
+            "japanese" => format!("修正案: `{replacement}`に置き換える"),
+            "simplified_chinese" => format!("修复: 替换为`{replacement}`"),
+            "traditional_chinese" => format!("修復: 替換為`{replacement}`"),
+            "english" => format!("fix: replace with `{replacement}`"),
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SubMessage {
     pub loc: Location,
     pub msg: Vec<String>,
     pub hint: Option<String>,
+    pub fix_its: Vec<FixIt>,
 }
 
 impl SubMessage {
@@ -548,7 +586,12 @@ impl SubMessage {
     /// ```
     ///
     pub fn ambiguous_new(loc: Location, msg: Vec<String>, hint: Option<String>) -> Self {
-        Self { loc, msg, hint }
+        Self {
+            loc,
+            msg,
+            hint,
+            fix_its: vec![],
+        }
     }
 
     ///
@@ -565,6 +608,7 @@ impl SubMessage {
             loc,
             msg: Vec::new(),
             hint: None,
+            fix_its: Vec::new(),
         }
     }
 
@@ -580,6 +624,20 @@ impl SubMessage {
         self.msg.as_ref()
     }
 
+    pub fn push_fix_it(&mut self, fix_it: FixIt) {
+        self.fix_its.push(fix_it);
+    }
+
+    pub fn get_fix_its(&self) -> &[FixIt] {
+        &self.fix_its
+    }
+
+    /// The messages followed by the descriptions of the fix-its.
+    fn messages(&self) -> Vec<String> {
+        let fix_its = self.fix_its.iter().map(FixIt::description);
+        self.msg.iter().cloned().chain(fix_its).collect()
+    }
+
     // Line breaks are not included except for line breaks that signify the end of a sentence.
     // In other words, do not include blank lines for formatting purposes.
     fn format_code_and_pointer<E: ErrorDisplay + ?Sized>(
@@ -590,6 +648,7 @@ impl SubMessage {
         mark: char,
         chars: &Characters,
     ) -> String {
+        let msgs = self.messages();
         match self.loc.unknown_or(e.core().loc) {
             Location::Range {
                 ln_begin,
@@ -606,7 +665,7 @@ impl SubMessage {
                 gutter_color,
                 chars,
                 mark,
-                &self.msg,
+                &msgs,
                 self.hint.as_ref(),
             ),
             Location::LineRange(ln_begin, ln_end) => {
@@ -627,7 +686,7 @@ impl SubMessage {
                     cxt.push_str("\n");
                 }
                 cxt.push_str("\n");
-                for msg in self.msg.iter() {
+                for msg in msgs.iter() {
                     cxt.push_str(msg);
                     cxt.push_str("\n");
                 }
@@ -647,7 +706,7 @@ impl SubMessage {
                 cxt.push_str_with_color(&format!(" {lineno} {vbar} "), gutter_color);
                 cxt.push_str(&code);
                 cxt.push_str("\n");
-                for msg in self.msg.iter() {
+                for msg in msgs.iter() {
                     cxt.push_str(msg);
                     cxt.push_str("\n");
                 }
@@ -666,7 +725,7 @@ impl SubMessage {
                     cxt.push_str_with_color(&format!(" ? {vbar} "), gutter_color);
                     cxt.push_str(&other.reread());
                     cxt.push_str("\n");
-                    for msg in self.msg.iter() {
+                    for msg in msgs.iter() {
                         cxt.push_str(msg);
                         cxt.push_str("\n");
                     }
@@ -688,9 +747,17 @@ impl SubMessage {
 pub struct ErrorCore {
     pub sub_messages: Vec<SubMessage>,
     pub main_message: String,
+    /// The line of the compiler source where the error was raised.
+    /// This is only for debugging; use `code` to identify the error.
     pub errno: usize,
+    /// The stable code of the diagnostic, e.g. `E0025`, `W0004` (`erg --explain <code>`).
+    /// The codes are registered in `erg_compiler::error::codes`.
+    /// The exceptions raised by Python at runtime have no code.
+    pub code: Option<&'static str>,
     pub kind: ErrorKind,
     pub loc: Location,
+    /// The warning is reported as an error (`-D <lint>`).
+    pub denied: bool,
     theme: Theme,
 }
 
@@ -714,12 +781,19 @@ impl ErrorCore {
             sub_messages,
             main_message: main_message.into(),
             errno,
+            code: None,
             kind,
             loc,
+            denied: false,
             theme: THEME,
         }
     }
 
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
     pub fn dummy(errno: usize) -> Self {
         Self::new(
             vec![SubMessage::only_loc(Location::Unknown)],
@@ -754,6 +828,7 @@ impl ErrorCore {
             CompilerSystemError,
             loc,
         )
+        .with_code("E0001")
     }
 
     pub fn get_loc_with_fallback(&self) -> Location {
@@ -769,6 +844,10 @@ impl ErrorCore {
         }
     }
 
+    pub fn fix_its(&self) -> impl Iterator<Item = &FixIt> {
+        self.sub_messages.iter().flat_map(|sub| sub.get_fix_its())
+    }
+
     pub fn fmt_header(&self, color: Color, caused_by: &str, input: &str) -> String {
         let loc = match self.loc {
             Location::Range {
@@ -781,14 +860,24 @@ impl ErrorCore {
             Location::Line(lineno) => format!(", line {lineno}"),
             Location::Unknown => "".to_string(),
         };
-        let kind = if self.kind.is_error() {
+        let kind = if self.kind.is_error() || self.denied {
             "Error"
         } else if self.kind.is_warning() {
             "Warning"
         } else {
             "Exception"
         };
-        let kind = self.theme.characters.error_kind_format(kind, self.errno);
+        #[cfg(feature = "debug")]
+        let code = Some(self.code.map_or_else(
+            || format!("#{}", self.errno),
+            |code| format!("{code} #{}", self.errno),
+        ));
+        #[cfg(not(feature = "debug"))]
+        let code = self.code.map(String::from);
+        let kind = match code {
+            Some(code) => self.theme.characters.error_kind_format(kind, &code),
+            None => kind.to_string(),
+        };
         format!(
             "{kind}: File {input}{loc}, {caused_by}",
             kind = StyledStr::new(&kind, Some(color), Some(Attribute::Bold))
@@ -796,7 +885,7 @@ impl ErrorCore {
     }
 
     fn specified_theme(&self) -> (Color, char) {
-        let (color, mark) = if self.kind.is_error() {
+        let (color, mark) = if self.kind.is_error() || self.denied {
             self.theme.error()
         } else if self.kind.is_warning() {
             self.theme.warning()
@@ -809,7 +898,7 @@ impl ErrorCore {
 
 /// format:
 /// ```txt
-/// Error[#{.errno}]: File {file}, line {.loc (as line)}, in {.caused_by}
+/// Error[{.code}]: File {file}, line {.loc (as line)}, in {.caused_by}
 ///
 /// {.loc (as line)}| {src}
 /// {offset}        : {pointer}
@@ -822,7 +911,7 @@ impl ErrorCore {
 ///
 /// example:
 /// ```txt
-/// Error[#2223]: File <stdin>, line 1, in <module>
+/// Error[E0011]: File <stdin>, line 1, in <module>
 ///
 /// 1 │ 100 = i
 ///   · ---
@@ -20,11 +20,24 @@ OPTIONS
     --python-version/-p (uint 32 number) Pythonバージョンを指定
     --py-server-timeout (uint 64 number) PythonのREPLサーバーのタイムアウト時間を指定
     --dump-as-pyc                        .pycファイルにダンプ
+    --separate                           モジュールごとに.pycファイルを出力
+    --readable                           読みやすいPythonコードにトランスパイル
+    --source-map                         生成コードからErgソースへのソースマップを出力
     --mode lex|parse|compile|exec        指定モードで実行(詳細は--mode --helpを参照)
+    --client                             コンパイルサーバーにリクエストを送る
+    --server-socket <path>               コンパイルサーバーのソケットのパスを指定
+    --explain <code>                     エラーコード(E0025など)の説明を表示
+    --allow/-A <lint>                    警告(W0004, UnusedWarning, warningsなど)を無視
+    --warn/-W <lint>                     警告を表示(デフォルト)
+    --deny/-D <lint>                     警告をエラーとして扱う
 
 SUBCOMMAND
     -c cmd : 文字列をプログラムに譲渡
-    -m mod : モジュールを実行",
+    -m mod : モジュールを実行
+    pack   : パッケージマネージャー(詳細はpack --helpを参照)
+    env    : 仮想環境マネージャー(詳細はenv --helpを参照)
+    test   : テストを実行(詳細はtest --helpを参照)
+    build  : パッケージをビルド(詳細はbuild --helpを参照)",
 
     "simplified_chinese" =>
     "\
@@ -43,11 +56,24 @@ OPTIONS
     --python-version/-p (uint 32 number) Python 版本
     --py-server-timeout (uint 64 number) 指定等待 REPL 输出的秒数
     --dump-as-pyc                        转储为 .pyc 文件
+    --separate                           为每个模块输出 .pyc 文件
+    --readable                           转译为易读的 Python 代码
+    --source-map                         输出从生成代码到 Erg 源码的源映射
     --mode lex|parse|compile|exec        执行模式 (更多信息见`--mode --help`)
+    --client                             将请求发送到编译服务器
+    --server-socket <path>               编译服务器的套接字路径
+    --explain <code>                     显示错误代码 (如 E0025) 的说明
+    --allow/-A <lint>                    忽略警告 (如 W0004, UnusedWarning, warnings)
+    --warn/-W <lint>                     显示警告 (默认)
+    --deny/-D <lint>                     将警告视为错误
 
 SUBCOMMAND
     -c cmd : 作为字符串传入程序
-    -m mod : 要执行的模块",
+    -m mod : 要执行的模块
+    pack   : 包管理器 (更多信息见`pack --help`)
+    env    : 虚拟环境管理器 (更多信息见`env --help`)
+    test   : 运行测试 (更多信息见`test --help`)
+    build  : 构建包 (更多信息见`build --help`)",
 
     "traditional_chinese" =>
         "\
@@ -66,11 +92,24 @@ OPTIONS
     --python-version/-p (uint 32 number) Python 版本
     --py-server-timeout (uint 64 number) 指定等待 REPL 輸出的秒數
     --dump-as-pyc                        轉儲為 .pyc 文件
+    --separate                           為每個模塊輸出 .pyc 文件
+    --readable                           轉譯為易讀的 Python 代碼
+    --source-map                         輸出從生成代碼到 Erg 源碼的源映射
     --mode lex|parse|compile|exec        執行模式 (更多信息見`--mode --help`)
+    --client                             將請求發送到編譯伺服器
+    --server-socket <path>               編譯伺服器的套接字路徑
+    --explain <code>                     顯示錯誤代碼 (如 E0025) 的說明
+    --allow/-A <lint>                    忽略警告 (如 W0004, UnusedWarning, warnings)
+    --warn/-W <lint>                     顯示警告 (默認)
+    --deny/-D <lint>                     將警告視為錯誤
 
 SUBCOMMAND
     -c cmd : 作為字串傳入程式
-    -m mod : 要執行的模塊",
+    -m mod : 要執行的模塊
+    pack   : 包管理器 (更多信息見`pack --help`)
+    env    : 虛擬環境管理器 (更多信息見`env --help`)
+    test   : 運行測試 (更多信息見`test --help`)
+    build  : 構建包 (更多信息見`build --help`)",
 
     "english" =>
         "\
@@ -89,11 +128,236 @@ OPTIONS
     --python-version/-p (uint 32 number) Python version
     --py-server-timeout (uint 64 number) timeout for the Python REPL server
     --dump-as-pyc                        dump as .pyc file
+    --separate                           output a .pyc file for each module
+    --readable                           transpile into human-readable Python code
+    --source-map                         emit a source map from the generated code to the Erg source
     --mode lex|parse|compile|exec        execution mode (See `--mode --help` for details)
+    --client                             send the request to the compile server
+    --server-socket <path>               path of the compile server socket
+    --explain <code>                     explain an error code (e.g. E0025)
+    --allow/-A <lint>                    ignore a warning (e.g. W0004, UnusedWarning, warnings)
+    --warn/-W <lint>                     report a warning (default)
+    --deny/-D <lint>                     report a warning as an error
 
 SUBCOMMAND
     -c cmd : program passed in as string
-    -m mod : module to be executed",
+    -m mod : module to be executed
+    pack   : package manager (See `pack --help` for details)
+    env    : virtual environment manager (See `env --help` for details)
+    test   : run the tests (See `test --help` for details)
+    build  : build the package (See `build --help` for details)",
+    )
+}
+
+/// erg pack -h/--help
+pub fn pack_message<'a>() -> &'a str {
+    switch_lang!(
+        "japanese" =>
+        "\
+USAGE:
+    erg pack [SUBCOMMAND] [ARGS]...
+
+SUBCOMMAND
+    init [app|lib|hybrid] [--name <name>]    カレントディレクトリをパッケージとして初期化
+    build [<options>]                        パッケージをビルド(erg buildと同じ)
+    add <name> [<version>] (--path <path> | --git <url> [--rev <rev>]) [--as <alias>]
+                                             依存関係を追加してインストール
+    install                                  依存関係を.erg/depsにインストールし、package.lock.erを更新
+    run [-- <args>...]                       パッケージをビルドしてアプリケーションを実行",
+
+    "simplified_chinese" =>
+        "\
+USAGE:
+    erg pack [SUBCOMMAND] [ARGS]...
+
+SUBCOMMAND
+    init [app|lib|hybrid] [--name <name>]    将当前目录初始化为包
+    build [<options>]                        构建包 (与 erg build 相同)
+    add <name> [<version>] (--path <path> | --git <url> [--rev <rev>]) [--as <alias>]
+                                             添加并安装依赖
+    install                                  将依赖安装到 .erg/deps 并更新 package.lock.er
+    run [-- <args>...]                       构建包并运行应用程序",
+
+    "traditional_chinese" =>
+        "\
+USAGE:
+    erg pack [SUBCOMMAND] [ARGS]...
+
+SUBCOMMAND
+    init [app|lib|hybrid] [--name <name>]    將當前目錄初始化為包
+    build [<options>]                        構建包 (與 erg build 相同)
+    add <name> [<version>] (--path <path> | --git <url> [--rev <rev>]) [--as <alias>]
+                                             添加並安裝依賴
+    install                                  將依賴安裝到 .erg/deps 並更新 package.lock.er
+    run [-- <args>...]                       構建包並運行應用程式",
+
+    "english" =>
+        "\
+USAGE:
+    erg pack [SUBCOMMAND] [ARGS]...
+
+SUBCOMMAND
+    init [app|lib|hybrid] [--name <name>]    initialize the current directory as a package
+    build [<options>]                        build the package (same as `erg build`)
+    add <name> [<version>] (--path <path> | --git <url> [--rev <rev>]) [--as <alias>]
+                                             add a dependency and install it
+    install                                  install the dependencies into .erg/deps and update package.lock.er
+    run [-- <args>...]                       build the package and run the application",
+    )
+}
+
+/// erg env -h/--help
+pub fn env_message<'a>() -> &'a str {
+    switch_lang!(
+        "japanese" =>
+        "\
+USAGE:
+    erg env [SUBCOMMAND] [ARGS]...
+
+SUBCOMMAND
+    create [<name>] [--python <command>]    .erg/envs/<name>にPythonの仮想環境を作成
+    activate <name>                         仮想環境を有効化(インタプリタを.erg/env.erに記録)
+    deactivate                              仮想環境を無効化
+    list                                    仮想環境を一覧表示",
+
+    "simplified_chinese" =>
+        "\
+USAGE:
+    erg env [SUBCOMMAND] [ARGS]...
+
+SUBCOMMAND
+    create [<name>] [--python <command>]    在 .erg/envs/<name> 中创建 Python 虚拟环境
+    activate <name>                         激活虚拟环境 (将解释器记录到 .erg/env.er)
+    deactivate                              停用虚拟环境
+    list                                    列出虚拟环境",
+
+    "traditional_chinese" =>
+        "\
+USAGE:
+    erg env [SUBCOMMAND] [ARGS]...
+
+SUBCOMMAND
+    create [<name>] [--python <command>]    在 .erg/envs/<name> 中創建 Python 虛擬環境
+    activate <name>                         激活虛擬環境 (將解釋器記錄到 .erg/env.er)
+    deactivate                              停用虛擬環境
+    list                                    列出虛擬環境",
+
+    "english" =>
+        "\
+USAGE:
+    erg env [SUBCOMMAND] [ARGS]...
+
+SUBCOMMAND
+    create [<name>] [--python <command>]    create a Python virtual environment in .erg/envs/<name>
+    activate <name>                         activate the environment (record its interpreter in .erg/env.er)
+    deactivate                              deactivate the environment
+    list                                    list the environments",
+    )
+}
+
+/// erg test -h/--help
+pub fn test_message<'a>() -> &'a str {
+    switch_lang!(
+        "japanese" =>
+        "\
+USAGE:
+    erg test [OPTIONS] [PATH]...
+
+    tests/**/*.erと*.test.erの@Testサブルーチン、およびドキュメントコメント中のergコードブロックを実行します
+
+OPTIONS
+    --filter <pattern>                 名前に<pattern>を含むテストのみ実行
+    --format human|junit               結果の出力形式を指定(デフォルト: human)",
+
+    "simplified_chinese" =>
+        "\
+USAGE:
+    erg test [OPTIONS] [PATH]...
+
+    运行 tests/**/*.er 和 *.test.er 中的 @Test 子程序以及文档注释中的 erg 代码块
+
+OPTIONS
+    --filter <pattern>                 只运行名称包含 <pattern> 的测试
+    --format human|junit               指定结果的输出格式 (默认: human)",
+
+    "traditional_chinese" =>
+        "\
+USAGE:
+    erg test [OPTIONS] [PATH]...
+
+    運行 tests/**/*.er 和 *.test.er 中的 @Test 子程序以及文檔註釋中的 erg 代碼塊
+
+OPTIONS
+    --filter <pattern>                 只運行名稱包含 <pattern> 的測試
+    --format human|junit               指定結果的輸出格式 (默認: human)",
+
+    "english" =>
+        "\
+USAGE:
+    erg test [OPTIONS] [PATH]...
+
+    run the @Test subroutines in tests/**/*.er and *.test.er, and the erg code blocks in the doc comments
+
+OPTIONS
+    --filter <pattern>                 run only the tests whose names contain <pattern>
+    --format human|junit               specify the output format (default: human)",
+    )
+}
+
+/// erg build -h/--help
+pub fn build_message<'a>() -> &'a str {
+    switch_lang!(
+        "japanese" =>
+        "\
+USAGE:
+    erg build [OPTIONS]
+
+    ドキュメントのコードブロックを検査し、パッケージをbuild/debug(build/release)にコンパイルし、テストを実行します
+    アプリケーションの場合、build/debug/binに起動スクリプトを生成します
+
+OPTIONS
+    --release                          最適化してbuild/releaseにビルド(行番号テーブルとdocstringを除去)
+    --no-doc                           doc/**/*.mdのコードブロックを検査しない
+    --no-test                          テストを実行しない",
+
+    "simplified_chinese" =>
+        "\
+USAGE:
+    erg build [OPTIONS]
+
+    检查文档中的代码块, 将包编译到 build/debug (build/release), 并运行测试
+    对于应用程序, 在 build/debug/bin 中生成启动脚本
+
+OPTIONS
+    --release                          优化并构建到 build/release (删除行号表和 docstring)
+    --no-doc                           不检查 doc/**/*.md 中的代码块
+    --no-test                          不运行测试",
+
+    "traditional_chinese" =>
+        "\
+USAGE:
+    erg build [OPTIONS]
+
+    檢查文檔中的代碼塊, 將包編譯到 build/debug (build/release), 並運行測試
+    對於應用程式, 在 build/debug/bin 中生成啟動腳本
+
+OPTIONS
+    --release                          優化並構建到 build/release (刪除行號表和 docstring)
+    --no-doc                           不檢查 doc/**/*.md 中的代碼塊
+    --no-test                          不運行測試",
+
+    "english" =>
+        "\
+USAGE:
+    erg build [OPTIONS]
+
+    check the code blocks in the documents, compile the package into build/debug (build/release), and run the tests
+    for applications, the launcher scripts are generated in build/debug/bin
+
+OPTIONS
+    --release                          build optimized artifacts into build/release (without line number tables and docstrings)
+    --no-doc                           do not check the code blocks in doc/**/*.md
+    --no-test                          do not run the tests",
     )
 }
 
@@ -102,7 +366,7 @@ pub fn mode_message<'a>() -> &'a str {
         "japanese" =>
         "\
 USAGE:
-    erg --mode [lex | parse | lower | check | compile | exec | read] [SUBCOMMAND] [ARGS]...
+    erg --mode [lex | parse | lower | check | compile | declare-gen | exec | interpret | read | server] [SUBCOMMAND] [ARGS]...
 
 lex
     <filename>.erやREPLなどから入力を受け取り、字句を解析
@@ -124,16 +388,28 @@ compile
     checkを実行
     HIRをからバイトコードを生成し、<filename>.pycを出力する
 
+declare-gen
+    checkを実行
+    公開されたメンバーの宣言ファイル<filename>.d.erとPythonの型スタブ<filename>.pyiを出力する
+
 exec
     compileを実行し、更に<filename>.pycを実行
 
+interpret
+    checkを実行し、HIRをPythonを介さずに直接評価
+
 read
-    <filename>.pycをデシリアライズしコードオブジェクトの情報をダンプ",
+    <filename>.pycをデシリアライズしコードオブジェクトの情報をダンプ
+
+server
+    コンパイルサーバーを起動し、`--client`からのリクエストを処理
+    ビルトインやインポートしたモジュールの情報を保持する
+    `erg --client --mode server`で停止",
 
     "simplified_chinese" =>
     "\
 USAGE:
-    erg --mode [lex | parse | lower | check | compile | exec | read] [SUBCOMMAND] [ARGS]...
+    erg --mode [lex | parse | lower | check | compile | declare-gen | exec | interpret | read | server] [SUBCOMMAND] [ARGS]...
 
 lex
     从 <filename>.er, REPL 等接受输入, 并标记文本
@@ -155,17 +431,29 @@ compile
     运行 check 以获取检查完成的 AST
     编译 AST 并返回 <文件名>.pyc
 
+declare-gen
+    执行 check
+    输出公开成员的声明文件 <文件名>.d.er 和 Python 类型存根 <文件名>.pyi
+
 exec
     运行 check 以获取检查完成的 AST
     在执行 <文件名>.pyc 后删除 <文件名>.pyc
 
+interpret
+    执行 check, 不经过 Python 直接解释执行 HIR
+
 read
-    反序列化 <文件名>.pyc 和 dump",
+    反序列化 <文件名>.pyc 和 dump
+
+server
+    启动编译服务器, 处理来自 `--client` 的请求
+    保留内置和已导入模块的信息
+    使用 `erg --client --mode server` 停止",
 
     "traditional_chinese" =>
     "\
 USAGE:
-        erg --mode [lex | parse | lower | check | compile | exec | read] [SUBCOMMAND] [ARGS]...
+        erg --mode [lex | parse | lower | check | compile | declare-gen | exec | interpret | read | server] [SUBCOMMAND] [ARGS]...
 
 lex
     從 <檔名>.er, REPL 等接受輸入, 並標記文字
@@ -187,17 +475,29 @@ compile
     運行 check 以獲取檢查完成的 AST
     編譯 AST 並返回 <檔名>.pyc
 
+declare-gen
+    執行 check
+    輸出公開成員的宣告檔案 <檔名>.d.er 和 Python 型別存根 <檔名>.pyi
+
 exec
     運行check以獲取檢查完成的 AST
     在執行 <檔名>.pyc 後删除 <檔名>.pyc
 
+interpret
+    執行 check, 不經過 Python 直接解釋執行 HIR
+
 read
-    反序列化 <檔名>.pyc 和 dump",
+    反序列化 <檔名>.pyc 和 dump
+
+server
+    啟動編譯伺服器, 處理來自 `--client` 的請求
+    保留內建和已匯入模組的資訊
+    使用 `erg --client --mode server` 停止",
 
     "english" =>
     "\
 USAGE:
-    erg --mode [lex | parse | lower | check | compile | exec | read] [SUBCOMMAND] [ARGS]...
+    erg --mode [lex | parse | lower | check | compile | declare-gen | exec | interpret | read | server] [SUBCOMMAND] [ARGS]...
 
 lex
     Receive input from <filename>.er, REPL, etc. and lex the text
@@ -219,22 +519,40 @@ compile
     Execute check
     Generates bytecode from HIR and outputs <filename>.pyc
 
+declare-gen
+    Execute check
+    Outputs the declaration file <filename>.d.er and the Python type stub <filename>.pyi of the public members
+
 exec
     Execute compile and then <filename>.pyc
 
+interpret
+    Execute check and evaluate HIR directly, without Python
+
 read
-    Deserialize <filename>.pyc and dump code object information",
+    Deserialize <filename>.pyc and dump code object information
+
+server
+    Start the compile server and handle requests from `--client`
+    The builtins and imported modules are kept between requests
+    Stop it with `erg --client --mode server`",
     )
 }
 
 pub const OPTIONS: &[&str] = &[
+    "-A",
+    "--allow",
     "--build-features",
     "-c",
     "--code",
     "--check",
+    "--client",
     "--compile",
+    "-D",
+    "--deny",
     "--dest",
     "--dump-as-pyc",
+    "--explain",
     "--language-server",
     "--no-std",
     "--help",
@@ -261,10 +579,17 @@ pub const OPTIONS: &[&str] = &[
     "--python-magic-number",
     "--quiet-startup",
     "--quiet-repl",
+    "--readable",
+    "--separate",
+    "--separate-compilation",
+    "--server-socket",
     "--show-type",
     "-t",
+    "--source-map",
     "--target-version",
     "--version",
     "-V",
     "--verbose",
+    "-W",
+    "--warn",
 ];
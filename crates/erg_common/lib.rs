@@ -15,6 +15,7 @@ pub mod fxhash;
 pub mod help_messages;
 pub mod lang;
 pub mod levenshtein;
+pub mod lint;
 pub mod macros;
 pub mod opcode;
 pub mod opcode308;
@@ -22,6 +23,7 @@ pub mod opcode310;
 pub mod opcode311;
 pub mod python_util;
 pub mod random;
+pub mod semver;
 pub mod serialize;
 pub mod set;
 pub mod shared;
@@ -32,6 +34,7 @@ pub mod style;
 pub mod traits;
 pub mod tsort;
 pub mod tty;
+pub mod venv;
 pub mod vis;
 
 use crate::set::Set;
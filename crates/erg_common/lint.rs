@@ -0,0 +1,205 @@
+//! Levels of the compile warnings (allow / warn / deny).
+//!
+//! The levels are specified by `-A`/`-W`/`-D` options or `.erg/lint.er` of the project root.
+//! A warning is specified by its code (e.g. `W0004`), its kind name (e.g. `UnusedWarning`) or `warnings` (all warnings).
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::ErrorKind;
+
+pub const LINT_CONFIG_FILE: &str = ".erg/lint.er";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintLevel {
+    /// the warning is not reported
+    Allow,
+    /// the warning is reported (default)
+    Warn,
+    /// the warning is reported as an error
+    Deny,
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Warn => write!(f, "warn"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "deny" => Ok(Self::Deny),
+            _ => Err(format!(
+                "invalid lint level: {s} (expected allow, warn or deny)"
+            )),
+        }
+    }
+}
+
+/// Checks whether `lint` names a compile warning.
+pub fn validate_lint_name(lint: &str) -> Result<(), String> {
+    if lint == "warnings" {
+        return Ok(());
+    }
+    let is_code = lint.len() == 5
+        && lint.starts_with(['W', 'w'])
+        && lint[1..].chars().all(|c| c.is_ascii_digit());
+    let is_kind = ErrorKind::from(lint).is_warning();
+    if is_code || is_kind {
+        Ok(())
+    } else {
+        Err(format!("unknown lint: {lint}"))
+    }
+}
+
+/// e.g. `-A UnusedWarning -D warnings` => `[(UnusedWarning, Allow), (warnings, Deny)]`
+///
+/// The later entries take precedence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintLevels {
+    entries: Vec<(String, LintLevel)>,
+}
+
+impl LintLevels {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push(&mut self, lint: impl Into<String>, level: LintLevel) {
+        self.entries.push((lint.into(), level));
+    }
+
+    pub fn extend(&mut self, other: LintLevels) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Returns the entry which decides the level of the warning (`kind` and `code`, e.g. `UnusedWarning` and `W0004`).
+    /// `None` means the default level (`Warn`).
+    pub fn get(&self, kind: ErrorKind, code: Option<&str>) -> Option<(&str, LintLevel)> {
+        if !kind.is_warning() {
+            return None;
+        }
+        let name = kind.to_string();
+        self.entries
+            .iter()
+            .rev()
+            .find(|(lint, _)| {
+                lint == "warnings"
+                    || code.is_some_and(|code| lint.eq_ignore_ascii_case(code))
+                    || lint.eq_ignore_ascii_case(&name)
+            })
+            .map(|(lint, level)| (&lint[..], *level))
+    }
+
+    pub fn level(&self, kind: ErrorKind, code: Option<&str>) -> LintLevel {
+        self.get(kind, code)
+            .map_or(LintLevel::Warn, |(_, level)| level)
+    }
+
+    /// Searches `.erg/lint.er` in `dir` and its ancestors.
+    pub fn find(dir: &Path) -> Option<Result<Self, String>> {
+        let root = dir
+            .ancestors()
+            .find(|dir| dir.join(LINT_CONFIG_FILE).is_file())?;
+        Some(Self::read(root))
+    }
+
+    /// format (`.erg/lint.er`):
+    /// ```erg
+    /// UnusedWarning = "allow"
+    /// W0006 = "deny"
+    /// ```
+    pub fn read(root: &Path) -> Result<Self, String> {
+        let path = root.join(LINT_CONFIG_FILE);
+        let src = fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let mut levels = Self::default();
+        for line in src.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((lint, level)) = line.split_once('=') else {
+                return Err(format!("invalid line in {}: {line}", path.display()));
+            };
+            let lint = lint.trim();
+            let level = level.trim();
+            let level = level
+                .strip_prefix('"')
+                .and_then(|level| level.strip_suffix('"'))
+                .unwrap_or(level);
+            validate_lint_name(lint).map_err(|err| format!("{err} ({})", path.display()))?;
+            levels.push(lint, level.parse()?);
+        }
+        Ok(levels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_levels() {
+        let mut levels = LintLevels::default();
+        assert_eq!(
+            levels.level(ErrorKind::UnusedWarning, Some("W0004")),
+            LintLevel::Warn
+        );
+        levels.push("warnings", LintLevel::Deny);
+        levels.push("unusedwarning", LintLevel::Allow);
+        levels.push("w0006", LintLevel::Warn);
+        assert_eq!(
+            levels.level(ErrorKind::UnusedWarning, Some("W0004")),
+            LintLevel::Allow
+        );
+        assert_eq!(
+            levels.level(ErrorKind::NameWarning, Some("W0006")),
+            LintLevel::Warn
+        );
+        assert_eq!(
+            levels.level(ErrorKind::TypeWarning, Some("W0005")),
+            LintLevel::Deny
+        );
+        // errors are not affected
+        assert_eq!(
+            levels.level(ErrorKind::TypeError, Some("E0025")),
+            LintLevel::Warn
+        );
+        assert!(validate_lint_name("W0004").is_ok());
+        assert!(validate_lint_name("UnusedWarning").is_ok());
+        assert!(validate_lint_name("TypeError").is_err());
+        assert!(validate_lint_name("W69").is_err());
+    }
+
+    #[test]
+    fn lint_config() {
+        let root = std::env::temp_dir().join(format!("erg-test-lint-{}", std::process::id()));
+        fs::create_dir_all(root.join(".erg")).unwrap();
+        fs::write(
+            root.join(LINT_CONFIG_FILE),
+            "# lint levels\nUnusedWarning = \"allow\"\nW0006 = \"deny\"\n",
+        )
+        .unwrap();
+        let levels = LintLevels::find(&root.join("src")).unwrap().unwrap();
+        assert_eq!(
+            levels.level(ErrorKind::UnusedWarning, Some("W0004")),
+            LintLevel::Allow
+        );
+        assert_eq!(
+            levels.level(ErrorKind::NameWarning, Some("W0006")),
+            LintLevel::Deny
+        );
+        fs::write(root.join(LINT_CONFIG_FILE), "UnusedWarning = \"ignore\"\n").unwrap();
+        assert!(LintLevels::read(&root).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -57,6 +57,8 @@ impl_u8_enum! {Opcode310;
     GET_YIELD_FROM_ITER = 69,
     PRINT_EXPR = 70,
     LOAD_BUILD_CLASS = 71,
+    YIELD_FROM = 72,
+    GET_AWAITABLE = 73,
     LOAD_ASSERTION_ERROR = 74,
     LIST_TO_TUPLE = 82,
     RETURN_VALUE = 83,
@@ -93,9 +95,12 @@ impl_u8_enum! {Opcode310;
     IS_OP = 117,
     CONTAINS_OP = 118,
     RERAISE = 119,
+    JUMP_IF_NOT_EXC_MATCH = 121,
+    SETUP_FINALLY = 122,
     LOAD_FAST = 124,
     STORE_FAST = 125,
     DELETE_FAST = 126,
+    GEN_START = 129,
     RAISE_VARARGS = 130,
     CALL_FUNCTION = 131,
     MAKE_FUNCTION = 132,
@@ -109,6 +114,7 @@ impl_u8_enum! {Opcode310;
     LOAD_METHOD = 160,
     CALL_METHOD = 161,
     LIST_EXTEND = 162,
+    DICT_MERGE = 164,
     // Erg-specific opcodes (must have a unary `ERG_`)
     // Define in descending order from 219, 255
     ERG_POP_NTH = 196,
@@ -36,6 +36,7 @@ impl_u8_enum! {Opcode311;
     PRINT_EXPR = 70,
     LOAD_BUILD_CLASS = 71,
     LOAD_ASSERTION_ERROR = 74,
+    RETURN_GENERATOR = 75,
     LIST_TO_TUPLE = 82,
     RETURN_VALUE = 83,
     IMPORT_STAR = 84,
@@ -80,8 +81,9 @@ impl_u8_enum! {Opcode311;
     STORE_FAST = 125,
     DELETE_FAST = 126,
     RAISE_VARARGS = 130,
-    CALL_FUNCTION = 131,
+    GET_AWAITABLE = 131,
     MAKE_FUNCTION = 132,
+    JUMP_BACKWARD_NO_INTERRUPT = 134,
     MAKE_CELL = 135,
     LOAD_CLOSURE = 136,
     LOAD_DEREF = 137,
@@ -99,6 +101,7 @@ impl_u8_enum! {Opcode311;
     FORMAT_VALUE = 155,
     LOAD_METHOD = 160,
     LIST_EXTEND = 162,
+    DICT_MERGE = 164,
     PRECALL = 166,
     CALL = 171,
     KW_NAMES = 172,
@@ -1,6 +1,7 @@
 //! utilities for calling CPython.
 //!
 //! CPythonを呼び出すためのユーティリティー
+use std::path::Path;
 use std::process::Command;
 
 use crate::serialize::get_magic_num_from_bytes;
@@ -619,6 +620,19 @@ impl PythonVersion {
     }
 }
 
+impl std::fmt::Display for PythonVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+        }
+        if let Some(micro) = self.micro {
+            write!(f, ".{micro}")?;
+        }
+        Ok(())
+    }
+}
+
 impl std::str::FromStr for PythonVersion {
     type Err = String;
 
@@ -665,24 +679,32 @@ pub fn env_python_version() -> PythonVersion {
 }
 
 /// executes over a shell, cause `python` may not exist as an executable file (like pyenv)
+///
+/// If `hook` (a runner script, e.g. `_erg_source_map.py`) is passed, executes `file` through it, as `python hook file argv...`
 pub fn exec_pyc<S: Into<String>>(
     file: S,
+    hook: Option<&str>,
     py_command: Option<&str>,
     argv: &[&'static str],
 ) -> Option<i32> {
     let command = py_command
         .map(ToString::to_string)
         .unwrap_or_else(which_python);
+    let args = hook
+        .map(ToString::to_string)
+        .into_iter()
+        .chain([file.into()])
+        .collect::<Vec<_>>();
     let mut out = if cfg!(windows) {
         Command::new("cmd")
             .arg("/C")
             .arg(command)
-            .arg(&file.into())
+            .args(&args)
             .args(argv)
             .spawn()
             .expect("cannot execute python")
     } else {
-        let exec_command = format!("{command} {} {}", file.into(), argv.join(" "));
+        let exec_command = format!("{command} {} {}", args.join(" "), argv.join(" "));
         Command::new("sh")
             .arg("-c")
             .arg(exec_command)
@@ -751,19 +773,20 @@ pub fn env_spawn_py(code: &str) {
     }
 }
 
-pub fn spawn_py(py_command: Option<&str>, code: &str) {
-    if cfg!(windows) {
-        Command::new(py_command.unwrap_or(&which_python()))
-            .arg("-c")
-            .arg(code)
-            .spawn()
-            .expect("cannot execute python");
+/// Spawns a Python process running `code` in `dir` (the current directory if `None`).
+pub fn spawn_py(py_command: Option<&str>, code: &str, dir: Option<&Path>) {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new(py_command.unwrap_or(&which_python()));
+        command.arg("-c").arg(code);
+        command
     } else {
         let exec_command = format!("{} -c \"{}\"", py_command.unwrap_or(&which_python()), code);
-        Command::new("sh")
-            .arg("-c")
-            .arg(exec_command)
-            .spawn()
-            .expect("cannot execute python");
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(exec_command);
+        command
+    };
+    if let Some(dir) = dir {
+        command.current_dir(dir);
     }
+    command.spawn().expect("cannot execute python");
 }
@@ -0,0 +1,206 @@
+//! Semantic versioning for Erg packages.
+//!
+//! The structures correspond to those of `lib/std/semver.er`.
+use std::fmt;
+use std::str::FromStr;
+
+/// e.g. `nightly.0`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Identifier {
+    pub name: String,
+    pub num: u64,
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.name, self.num)
+    }
+}
+
+impl FromStr for Identifier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, num) = s.rsplit_once('.').unwrap_or((s, "0"));
+        let num = num
+            .parse()
+            .map_err(|_| format!("invalid pre-release identifier: {s}"))?;
+        Ok(Self {
+            name: name.to_string(),
+            num,
+        })
+    }
+}
+
+/// e.g. `1.2.3`, `0.1.0-nightly.0`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Option<Identifier>,
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (version, pre) = match s.split_once('-') {
+            Some((version, pre)) => (version, Some(pre.parse()?)),
+            None => (s, None),
+        };
+        let mut nums = version.split('.').map(|n| n.parse::<u64>());
+        match (nums.next(), nums.next(), nums.next(), nums.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Ok(Self::new(major, minor, patch, pre))
+            }
+            _ => Err(format!("invalid version: {s}")),
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    /// A pre-release version is lower than the normal version.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => std::cmp::Ordering::Equal,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (Some(l), Some(r)) => (&l.name, l.num).cmp(&(&r.name, r.num)),
+            })
+    }
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64, pre: Option<Identifier>) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre,
+        }
+    }
+}
+
+/// A version range.
+/// `*` matches any number, e.g. `1.*.*`, `1.2.*`, `*`.
+/// A fully specified version (e.g. `1.2.3`) matches only that version.
+/// Pre-release versions are matched only when they are specified exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VersionReq {
+    pub major: Option<u64>,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+    pub pre: Option<Identifier>,
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_num = |n: Option<u64>| n.map_or("*".to_string(), |n| n.to_string());
+        if self.major.is_none() {
+            return write!(f, "*");
+        }
+        write!(
+            f,
+            "{}.{}.{}",
+            fmt_num(self.major),
+            fmt_num(self.minor),
+            fmt_num(self.patch)
+        )?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (version, pre) = match s.split_once('-') {
+            Some((version, pre)) => (version, Some(pre.parse()?)),
+            None => (s, None),
+        };
+        let mut nums = vec![];
+        for num in version.split('.') {
+            if num == "*" {
+                nums.push(None);
+            } else {
+                let num = num.parse().map_err(|_| format!("invalid version: {s}"))?;
+                // `1.*.2` is not allowed
+                if nums.last() == Some(&None) {
+                    return Err(format!("invalid version: {s}"));
+                }
+                nums.push(Some(num));
+            }
+        }
+        if nums.len() > 3 || (pre.is_some() && nums.iter().any(Option::is_none)) {
+            return Err(format!("invalid version: {s}"));
+        }
+        nums.resize(3, None);
+        Ok(Self {
+            major: nums[0],
+            minor: nums[1],
+            patch: nums[2],
+            pre,
+        })
+    }
+}
+
+impl VersionReq {
+    pub const ANY: Self = Self {
+        major: None,
+        minor: None,
+        patch: None,
+        pre: None,
+    };
+
+    pub fn matches(&self, version: &Version) -> bool {
+        let matches_num = |req: Option<u64>, num: u64| req.is_none_or(|req| req == num);
+        matches_num(self.major, version.major)
+            && matches_num(self.minor, version.minor)
+            && matches_num(self.patch, version.patch)
+            && self.pre == version.pre
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_req() {
+        let v = |s: &str| s.parse::<Version>().unwrap();
+        let req = |s: &str| s.parse::<VersionReq>().unwrap();
+        assert!(req("1.*.*").matches(&v("1.2.0")));
+        assert!(req("1.2").matches(&v("1.2.5")));
+        assert!(!req("1.*.*").matches(&v("2.0.0")));
+        assert!(req("*").matches(&v("0.1.0")));
+        assert!(!req("*").matches(&v("0.1.0-nightly.1")));
+        assert!(req("0.1.0-nightly.1").matches(&v("0.1.0-nightly.1")));
+        assert!(req("1.1.0").matches(&v("1.1.0")));
+        assert!(!req("1.1.0").matches(&v("1.1.1")));
+        assert!("1.*.2".parse::<VersionReq>().is_err());
+        assert!(v("0.1.0-nightly.1") < v("0.1.0"));
+        assert_eq!(req("1.*").to_string(), "1.*.*");
+    }
+}
@@ -162,16 +162,14 @@ impl Characters {
         format!("{}{} ", self.vbar, self.line)
     }
 
-    // kind[padded error number]
+    // kind[error code]
     #[cfg(not(feature = "pretty"))]
-    pub fn error_kind_format(&self, kind: &str, err_num: usize) -> String {
-        const PADDING: usize = 4;
-        format!("{kind}{}#{err_num:>0PADDING$}{}", self.lbrac, self.rbrac,)
+    pub fn error_kind_format(&self, kind: &str, code: &str) -> String {
+        format!("{kind}{}{code}{}", self.lbrac, self.rbrac)
     }
 
     #[cfg(feature = "pretty")]
-    pub fn error_kind_format(&self, kind: &str, err_num: usize) -> String {
-        const PADDING: usize = 4;
+    pub fn error_kind_format(&self, kind: &str, code: &str) -> String {
         let emoji = if kind == "Error" {
             "🚫"
         } else if kind == "Warning" {
@@ -179,10 +177,7 @@ impl Characters {
         } else {
             "😱"
         };
-        format!(
-            "{emoji} {kind}{}#{err_num:>0PADDING$}{}",
-            self.lbrac, self.rbrac,
-        )
+        format!("{emoji} {kind}{}{code}{}", self.lbrac, self.rbrac)
     }
 }
 
@@ -102,6 +102,47 @@ pub fn tsort<T: Eq + Hash + Clone, U>(g: Graph<T, U>) -> Result<Graph<T, U>, Top
     Ok(reorder_by_key(g, idx))
 }
 
+fn _dep_path<T: Eq + Hash + Clone, U>(
+    g: &Graph<T, U>,
+    from: &T,
+    to: &T,
+    used: &mut Set<T>,
+) -> Option<Vec<T>> {
+    if from == to {
+        return Some(vec![to.clone()]);
+    }
+    used.insert(from.clone());
+    let vertex = g.iter().find(|n| &n.id == from)?;
+    for node_id in vertex.depends_on.iter() {
+        if used.contains(node_id) {
+            continue;
+        }
+        if let Some(mut path) = _dep_path(g, node_id, to, used) {
+            path.insert(0, from.clone());
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// returns a path `from -> ... -> to` following the dependencies, if exists
+pub fn dep_path<T: Eq + Hash + Clone, U>(g: &Graph<T, U>, from: &T, to: &T) -> Option<Vec<T>> {
+    _dep_path(g, from, to, &mut Set::new())
+}
+
+/// returns a cyclic path (`a -> ... -> a`) if the graph has cyclic references
+pub fn find_cycle<T: Eq + Hash + Clone, U>(g: &Graph<T, U>) -> Option<Vec<T>> {
+    for v in g.iter() {
+        for node_id in v.depends_on.iter() {
+            if let Some(mut path) = dep_path(g, node_id, &v.id) {
+                path.insert(0, v.id.clone());
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,4 +191,15 @@ mod tests {
         assert_eq!(sorted[4], odd);
         Ok(())
     }
+
+    #[test]
+    fn test_find_cycle() {
+        let a = Node::new("A", (), set!["B"]);
+        let b = Node::new("B", (), set!["C"]);
+        let c = Node::new("C", (), set!["A"]);
+        let d = Node::new("D", (), set![]);
+        let g = vec![d.clone(), a, b, c];
+        assert_eq!(find_cycle(&g), Some(vec!["A", "B", "C", "A"]));
+        assert_eq!(find_cycle(&vec![d]), None);
+    }
 }
@@ -0,0 +1,198 @@
+//! Python virtual environments tied to Erg projects (`erg env`).
+//!
+//! The environments are placed in `.erg/envs/<name>` of the project root.
+//! The active one is recorded in `.erg/env.er`, and `ErgConfig::parse` picks up its interpreter from there.
+//! Declaration files (`.d.er`) generated for the installed site-packages are cached in `.erg/envs/<name>/decl`.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::normalize_path;
+use crate::python_util::PythonVersion;
+
+pub const ENVS_DIR: &str = ".erg/envs";
+pub const ENV_CONFIG_FILE: &str = ".erg/env.er";
+
+pub fn env_dir(root: &Path, name: &str) -> PathBuf {
+    root.join(ENVS_DIR).join(name)
+}
+
+/// The interpreter of a venv created in `dir`.
+pub fn venv_python(dir: &Path) -> PathBuf {
+    if cfg!(windows) {
+        dir.join("Scripts").join("python.exe")
+    } else {
+        dir.join("bin").join("python")
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+/// The active virtual environment of a project.
+///
+/// format (`.erg/env.er`):
+/// ```erg
+/// name = "default"
+/// py_command = "/path/to/project/.erg/envs/default/bin/python"
+/// py_magic_num = 3495
+/// target_version = "3.11.7"
+/// site_packages = "/path/to/project/.erg/envs/default/lib/python3.11/site-packages"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualEnv {
+    /// the project root (the directory which has `.erg`)
+    pub root: PathBuf,
+    pub name: String,
+    pub py_command: String,
+    pub py_magic_num: u32,
+    pub target_version: PythonVersion,
+    pub site_packages: PathBuf,
+}
+
+impl VirtualEnv {
+    pub fn dir(&self) -> PathBuf {
+        env_dir(&self.root, &self.name)
+    }
+
+    pub fn decl_dir(&self) -> PathBuf {
+        self.dir().join("decl")
+    }
+
+    /// Searches `.erg/env.er` in `dir` and its ancestors.
+    pub fn find(dir: &Path) -> Option<Self> {
+        let root = dir
+            .ancestors()
+            .find(|dir| dir.join(ENV_CONFIG_FILE).is_file())?;
+        Self::read(root).ok()
+    }
+
+    pub fn read(root: &Path) -> Result<Self, String> {
+        let path = root.join(ENV_CONFIG_FILE);
+        let src = fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let (mut name, mut py_command, mut py_magic_num, mut target_version, mut site_packages) =
+            (None, None, None, None, None);
+        for line in src.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("invalid line in {}: {line}", path.display()));
+            };
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .map_or_else(|| value.to_string(), unescape);
+            match key.trim() {
+                "name" => name = Some(value),
+                "py_command" => py_command = Some(value),
+                "py_magic_num" => {
+                    py_magic_num = Some(
+                        value
+                            .parse::<u32>()
+                            .map_err(|_| format!("invalid magic number: {value}"))?,
+                    )
+                }
+                "target_version" => target_version = Some(value.parse::<PythonVersion>()?),
+                "site_packages" => site_packages = Some(PathBuf::from(value)),
+                _ => {}
+            }
+        }
+        let missing = |key: &str| format!("`{key}` is not specified in {}", path.display());
+        Ok(Self {
+            root: normalize_path(root.to_path_buf()),
+            name: name.ok_or_else(|| missing("name"))?,
+            py_command: py_command.ok_or_else(|| missing("py_command"))?,
+            py_magic_num: py_magic_num.ok_or_else(|| missing("py_magic_num"))?,
+            target_version: target_version.ok_or_else(|| missing("target_version"))?,
+            site_packages: site_packages.ok_or_else(|| missing("site_packages"))?,
+        })
+    }
+
+    pub fn write(&self) -> std::io::Result<()> {
+        let src = format!(
+            "# This file is automatically generated by `erg env`.\n\
+            name = \"{}\"\n\
+            py_command = \"{}\"\n\
+            py_magic_num = {}\n\
+            target_version = \"{}\"\n\
+            site_packages = \"{}\"\n",
+            escape(&self.name),
+            escape(&self.py_command),
+            self.py_magic_num,
+            self.target_version,
+            escape(&self.site_packages.to_string_lossy()),
+        );
+        fs::write(self.root.join(ENV_CONFIG_FILE), src)
+    }
+
+    /// Resolves a cached declaration file, e.g. `numpy` -> `decl/numpy.d.er`.
+    pub fn resolve_decl(&self, path: &Path) -> Option<PathBuf> {
+        let decl_dir = self.decl_dir();
+        decl_dir
+            .join(format!("{}.d.er", path.display()))
+            .canonicalize()
+            .or_else(|_| {
+                decl_dir
+                    .join(format!("{}.d", path.display()))
+                    .join("__init__.d.er")
+                    .canonicalize()
+            })
+            .ok()
+            .map(normalize_path)
+    }
+
+    /// Resolves an installed Python module, e.g. `numpy` -> `site-packages/numpy/__init__.py`.
+    pub fn resolve_site_package(&self, path: &Path) -> Option<PathBuf> {
+        self.site_packages
+            .join(format!("{}.py", path.display()))
+            .canonicalize()
+            .or_else(|_| {
+                self.site_packages
+                    .join(path)
+                    .join("__init__.py")
+                    .canonicalize()
+            })
+            .ok()
+            .map(normalize_path)
+    }
+
+    /// Moves the declaration file generated by `pylyzer --dump-decl <py_path>` into the cache.
+    pub fn cache_decl(&self, py_path: &Path, path: &Path) -> Option<PathBuf> {
+        let mut generated = py_path.parent()?.join("__pycache__");
+        generated.push(py_path.file_stem()?);
+        generated.set_extension("d.er");
+        let cached = self.decl_dir().join(format!("{}.d.er", path.display()));
+        fs::create_dir_all(cached.parent()?).ok()?;
+        fs::copy(generated, &cached).ok()?;
+        cached.canonicalize().ok().map(normalize_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_config() {
+        let root = std::env::temp_dir().join(format!("erg-test-venv-{}", std::process::id()));
+        fs::create_dir_all(root.join(ENVS_DIR)).unwrap();
+        let env = VirtualEnv {
+            root: normalize_path(root.canonicalize().unwrap()),
+            name: "dev".to_string(),
+            py_command: "C:\\envs\\dev\\Scripts\\python.exe".to_string(),
+            py_magic_num: 3495,
+            target_version: PythonVersion::new(3, Some(11), Some(7)),
+            site_packages: PathBuf::from("/envs/dev/lib/python3.11/site-packages"),
+        };
+        env.write().unwrap();
+        assert_eq!(VirtualEnv::find(&root.join(ENVS_DIR)), Some(env));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -4,7 +4,7 @@
 use std::fmt;
 use std::process;
 
-use crate::ty::codeobj::{CodeObj, CodeObjFlags, MakeFunctionFlags};
+use crate::ty::codeobj::{CodeObj, CodeObjFlags, ExceptionTableEntry, MakeFunctionFlags};
 use crate::ty::value::GenTypeObj;
 use erg_common::cache::CacheSet;
 use erg_common::config::{ErgConfig, Input};
@@ -37,6 +37,7 @@ use crate::hir::{
     Literal, NonDefaultParamSignature, Params, PatchDef, PosArg, ReDef, Record, Signature,
     SubrSignature, Tuple, UnaryOp, VarSignature, HIR,
 };
+use crate::source_map::SourceMap;
 use crate::ty::value::ValueObj;
 use crate::ty::{HasType, Type, TypeCode, TypePair};
 use crate::varinfo::VarInfo;
@@ -99,6 +100,7 @@ pub struct PyCodeGenUnit {
     pub(crate) lasti: usize,
     pub(crate) prev_lasti: usize,
     pub(crate) _refs: Vec<ValueObj>, // ref-counted objects
+    pub(crate) exception_handlers: Vec<ExceptionTableEntry>,
 }
 
 impl PartialEq for PyCodeGenUnit {
@@ -138,8 +140,16 @@ impl PyCodeGenUnit {
             lasti: 0,
             prev_lasti: 0,
             _refs: vec![],
+            exception_handlers: vec![],
         }
     }
+
+    pub fn into_codeobj(mut self) -> CodeObj {
+        if !self.exception_handlers.is_empty() {
+            self.codeobj.set_exception_table(&self.exception_handlers);
+        }
+        self.codeobj
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -162,6 +172,9 @@ pub struct PyCodeGenerator {
     abc_loaded: bool,
     unit_size: usize,
     units: PyCodeGenStack,
+    source_map: SourceMap,
+    /// the positions in the modules inlined by the linker are not mapped (they are in other files)
+    inlined_depth: usize,
 }
 
 impl PyCodeGenerator {
@@ -180,6 +193,8 @@ impl PyCodeGenerator {
             abc_loaded: false,
             unit_size: 0,
             units: PyCodeGenStack::empty(),
+            source_map: SourceMap::default(),
+            inlined_depth: 0,
         }
     }
 
@@ -187,6 +202,11 @@ impl PyCodeGenerator {
         self.units.clear();
     }
 
+    /// The source map of the last emitted code object.
+    pub fn take_source_map(&mut self) -> SourceMap {
+        std::mem::take(&mut self.source_map)
+    }
+
     #[inline]
     fn input(&self) -> &Input {
         &self.cfg.input
@@ -381,6 +401,23 @@ impl PyCodeGenerator {
             self.mut_cur_block().lasti += 2;
             shift_bytes += 2;
         }
+        for handler in self.mut_cur_block().exception_handlers.iter_mut() {
+            if handler.start >= before_instr {
+                handler.start += shift_bytes;
+            }
+            if handler.end > before_instr {
+                handler.end += shift_bytes;
+            }
+            if handler.target >= before_instr {
+                handler.target += shift_bytes;
+            }
+        }
+        let (name, firstlineno) = (
+            self.cur_block_codeobj().name.clone(),
+            self.cur_block_codeobj().firstlineno,
+        );
+        self.source_map
+            .shift_offsets(&name, firstlineno, before_instr, shift_bytes);
         shift_bytes
     }
 
@@ -525,6 +562,14 @@ impl PyCodeGenerator {
         {
             if current_is_toplevel {
                 Some(Name::local(idx))
+            } else if self
+                .cur_block_codeobj()
+                .freevars
+                .iter()
+                .any(|f| &**f == name)
+            {
+                // in 3.11 freevars are also registered in varnames
+                Some(Name::deref(idx))
             } else {
                 Some(Name::fast(idx))
             }
@@ -659,7 +704,7 @@ impl PyCodeGenerator {
         log!(info "entered {}({ident})", fn_name!());
         let escaped = escape_ident(ident);
         match &escaped[..] {
-            "if__" | "for__" | "while__" | "with__" | "discard__" => {
+            "if__" | "for__" | "while__" | "with__" | "discard__" | "panic__" | "raise__" => {
                 self.load_control();
             }
             "int__" | "nat__" | "str__" | "float__" => {
@@ -878,22 +923,29 @@ impl PyCodeGenerator {
         }
     }
 
+    /// The order of the parameter names is the same as in CPython:
+    /// positional parameters, `*args`, `**kwargs`
     fn gen_param_names(&self, params: &Params) -> Vec<Str> {
         params
             .non_defaults
             .iter()
             .map(|p| p.inspect().map(|s| &s[..]).unwrap_or("_"))
-            .chain(if let Some(var_args) = &params.var_params {
-                vec![var_args.inspect().map(|s| &s[..]).unwrap_or("_")]
-            } else {
-                vec![]
-            })
             .chain(
                 params
                     .defaults
                     .iter()
                     .map(|p| p.inspect().map(|s| &s[..]).unwrap_or("_")),
             )
+            .chain(if let Some(var_args) = &params.var_params {
+                vec![var_args.inspect().map(|s| &s[..]).unwrap_or("_")]
+            } else {
+                vec![]
+            })
+            .chain(if let Some(kw_var_args) = &params.kw_var_params {
+                vec![kw_var_args.inspect().map(|s| &s[..]).unwrap_or("_")]
+            } else {
+                vec![]
+            })
             .enumerate()
             .map(|(i, s)| {
                 if s == "_" {
@@ -1089,7 +1141,7 @@ impl PyCodeGenerator {
         }
         // end of flagging
         let unit = self.units.pop().unwrap();
-        if !self.units.is_empty() {
+        if !self.units.is_empty() && unit.prev_lineno > self.cur_block().prev_lineno {
             let ld = unit.prev_lineno - self.cur_block().prev_lineno;
             if ld != 0 {
                 if let Some(l) = self.mut_cur_block_codeobj().lnotab.last_mut() {
@@ -1098,7 +1150,7 @@ impl PyCodeGenerator {
                 self.mut_cur_block().prev_lineno += ld;
             }
         }
-        unit.codeobj
+        unit.into_codeobj()
     }
 
     fn emit_empty_func(
@@ -1139,7 +1191,7 @@ impl PyCodeGenerator {
                     self.mut_cur_block().prev_lineno += ld;
                 }
             }
-            unit.codeobj
+            unit.into_codeobj()
         };
         self.emit_load_const(code);
         if self.py_version.minor < Some(11) {
@@ -1247,6 +1299,12 @@ impl PyCodeGenerator {
         let name = sig.ident.inspect().clone();
         let mut make_function_flag = 0;
         let params = self.gen_param_names(&sig.params);
+        let mut flags = Self::params_flags(&sig.params);
+        if sig.is_async() {
+            flags += CodeObjFlags::Coroutine as u32;
+        } else if sig.is_generator {
+            flags += CodeObjFlags::Generator as u32;
+        }
         if !sig.params.defaults.is_empty() {
             let defaults_len = sig.params.defaults.len();
             sig.params
@@ -1258,11 +1316,6 @@ impl PyCodeGenerator {
             self.stack_dec_n(defaults_len - 1);
             make_function_flag += MakeFunctionFlags::Defaults as usize;
         }
-        let flags = if sig.params.var_params.is_some() {
-            CodeObjFlags::VarArgs as u32
-        } else {
-            0
-        };
         let code = self.emit_block(body.block, Some(name.clone()), params, flags);
         // code.flags += CodeObjFlags::Optimized as u32;
         self.register_cellvars(&mut make_function_flag);
@@ -1290,6 +1343,7 @@ impl PyCodeGenerator {
         log!(info "entered {} ({lambda})", fn_name!());
         let mut make_function_flag = 0;
         let params = self.gen_param_names(&lambda.params);
+        let flags = Self::params_flags(&lambda.params);
         if !lambda.params.defaults.is_empty() {
             let defaults_len = lambda.params.defaults.len();
             lambda
@@ -1302,11 +1356,6 @@ impl PyCodeGenerator {
             self.stack_dec_n(defaults_len - 1);
             make_function_flag += MakeFunctionFlags::Defaults as usize;
         }
-        let flags = if lambda.params.var_params.is_some() {
-            CodeObjFlags::VarArgs as u32
-        } else {
-            0
-        };
         let code = self.emit_block(lambda.body, Some("<lambda>".into()), params, flags);
         self.register_cellvars(&mut make_function_flag);
         self.emit_load_const(code);
@@ -1324,6 +1373,17 @@ impl PyCodeGenerator {
         }
     }
 
+    fn params_flags(params: &Params) -> u32 {
+        let mut flags = 0;
+        if params.var_params.is_some() {
+            flags += CodeObjFlags::VarArgs as u32;
+        }
+        if params.kw_var_params.is_some() {
+            flags += CodeObjFlags::VarKeywords as u32;
+        }
+        flags
+    }
+
     fn register_cellvars(&mut self, flag: &mut usize) {
         if !self.cur_block_codeobj().cellvars.is_empty() {
             let cellvars_len = self.cur_block_codeobj().cellvars.len();
@@ -1670,8 +1730,9 @@ impl PyCodeGenerator {
                 // let params = self.gen_param_names(&lambda.params);
                 self.emit_frameless_block(lambda.body, vec![]);
             }
+            // if! cond, f!, g! => if cond: f!() else: g!()
             other => {
-                self.emit_expr(other);
+                self.emit_expr(other.call_expr(Args::empty()));
             }
         }
         if args.get(0).is_some() {
@@ -1691,7 +1752,7 @@ impl PyCodeGenerator {
                     self.emit_frameless_block(lambda.body, vec![]);
                 }
                 other => {
-                    self.emit_expr(other);
+                    self.emit_expr(other.call_expr(Args::empty()));
                 }
             }
             let idx_end = self.lasti();
@@ -2060,6 +2121,382 @@ impl PyCodeGenerator {
         self.emit_load_name_instr(stash);
     }
 
+    fn emit_try_instr(&mut self, args: Args) {
+        match self.py_version.minor {
+            Some(11) => self.emit_try_instr_311(args),
+            Some(10 | 9) => self.emit_try_instr_310(args),
+            Some(8 | 7) => self.emit_try_instr_308(args),
+            _ => {
+                CompileError::feature_error(
+                    self.cfg.input.clone(),
+                    args.loc(),
+                    &format!("`try!` on Python {}", self.py_version),
+                    String::from("try!"),
+                )
+                .write_to_stderr();
+                self.emit_load_const(ValueObj::None);
+            }
+        }
+    }
+
+    /// `try! body, (e: T) => ...` in 3.11 uses the exception table
+    fn emit_try_instr_311(&mut self, mut args: Args) {
+        log!(info "entered {}", fn_name!());
+        let init_stack_len = self.stack_len();
+        let body = args.remove(0);
+        let line = body.ln_begin().unwrap_or(0);
+        let stash = Identifier::private_with_line(Str::from(fresh_varname()), line);
+        let body_start = self.lasti();
+        self.emit_try_body(body);
+        self.emit_store_instr(stash.clone(), Name);
+        let mut jump_forward_points = vec![self.lasti()];
+        self.write_instr(JUMP_FORWARD);
+        self.write_arg(0);
+        let handler_start = self.lasti();
+        // exception -> previous exception, exception
+        self.stack_inc();
+        self.write_instr(Opcode311::PUSH_EXC_INFO);
+        self.write_arg(0);
+        self.stack_inc();
+        while let Some(expr) = args.try_remove(0) {
+            let mut lambda = enum_unwrap!(expr, Expr::Lambda);
+            let param = lambda.params.non_defaults.remove(0);
+            let pop_jump_point = if let Some(t_spec) = param.t_spec_as_expr {
+                self.emit_exc_type_spec(t_spec);
+                self.write_instr(Opcode311::CHECK_EXC_MATCH);
+                self.write_arg(0);
+                let pop_jump_point = self.lasti();
+                self.write_instr(Opcode311::POP_JUMP_FORWARD_IF_FALSE);
+                self.write_arg(0);
+                self.stack_dec();
+                Some(pop_jump_point)
+            } else {
+                None
+            };
+            self.emit_exc_binding(param.raw.pat);
+            self.emit_try_arm_body(lambda.body);
+            self.emit_store_instr(stash.clone(), Name);
+            self.write_instr(Opcode311::POP_EXCEPT);
+            self.write_arg(0);
+            self.stack_dec();
+            jump_forward_points.push(self.lasti());
+            self.write_instr(JUMP_FORWARD);
+            self.write_arg(0);
+            if let Some(pop_jump_point) = pop_jump_point {
+                let shift =
+                    self.calc_edit_jump(pop_jump_point + 1, self.lasti() - pop_jump_point - 2);
+                Self::shift_jump_points(&mut jump_forward_points, pop_jump_point, shift);
+            }
+            // the next arm starts with (previous exception, exception)
+            self.stack_inc_n(2);
+        }
+        // no arm matched
+        self.write_instr(Opcode311::RERAISE);
+        self.write_arg(0);
+        self.stack_dec();
+        let cleanup_start = self.lasti();
+        // previous exception, lasti, exception
+        self.stack_inc_n(2);
+        self.write_instr(Opcode311::COPY);
+        self.write_arg(3);
+        self.stack_inc();
+        self.write_instr(Opcode311::POP_EXCEPT);
+        self.write_arg(0);
+        self.stack_dec();
+        self.write_instr(Opcode311::RERAISE);
+        self.write_arg(1);
+        self.stack_dec_n(3);
+        let depth = init_stack_len;
+        let handlers = &mut self.mut_cur_block().exception_handlers;
+        handlers.push(ExceptionTableEntry::new(
+            body_start,
+            handler_start,
+            handler_start,
+            depth,
+            false,
+        ));
+        handlers.push(ExceptionTableEntry::new(
+            handler_start,
+            cleanup_start,
+            cleanup_start,
+            depth + 1,
+            true,
+        ));
+        self.edit_jump_forward_points(jump_forward_points);
+        self.emit_load_name_instr(stash);
+        debug_assert_eq!(self.stack_len(), init_stack_len + 1);
+    }
+
+    /// Also used for 3.9 (jump arguments are in bytes)
+    fn emit_try_instr_310(&mut self, mut args: Args) {
+        log!(info "entered {}", fn_name!());
+        let init_stack_len = self.stack_len();
+        let unit = if self.py_version.minor >= Some(10) {
+            2
+        } else {
+            1
+        };
+        let body = args.remove(0);
+        let line = body.ln_begin().unwrap_or(0);
+        let stash = Identifier::private_with_line(Str::from(fresh_varname()), line);
+        let idx_setup_finally = self.lasti();
+        self.write_instr(Opcode310::SETUP_FINALLY);
+        self.write_arg(0);
+        self.emit_try_body(body);
+        self.emit_store_instr(stash.clone(), Name);
+        self.write_instr(POP_BLOCK);
+        self.write_arg(0);
+        let mut jump_forward_points = vec![self.lasti()];
+        self.write_instr(JUMP_FORWARD);
+        self.write_arg(0);
+        self.edit_code(
+            idx_setup_finally + 1,
+            (self.lasti() - idx_setup_finally - 2) / unit,
+        );
+        // previous (traceback, value, type), traceback, value, type
+        self.stack_inc_n(6);
+        while let Some(expr) = args.try_remove(0) {
+            let mut lambda = enum_unwrap!(expr, Expr::Lambda);
+            let param = lambda.params.non_defaults.remove(0);
+            let jump_point = if let Some(t_spec) = param.t_spec_as_expr {
+                self.dup_top();
+                self.emit_exc_type_spec(t_spec);
+                let jump_point = self.lasti();
+                self.write_instr(Opcode310::JUMP_IF_NOT_EXC_MATCH);
+                self.write_arg(0);
+                self.stack_dec_n(2);
+                Some(jump_point)
+            } else {
+                None
+            };
+            self.emit_pop_top();
+            self.emit_exc_binding(param.raw.pat);
+            self.emit_pop_top();
+            self.emit_try_arm_body(lambda.body);
+            self.emit_store_instr(stash.clone(), Name);
+            self.write_instr(Opcode310::POP_EXCEPT);
+            self.write_arg(0);
+            self.stack_dec_n(3);
+            jump_forward_points.push(self.lasti());
+            self.write_instr(JUMP_FORWARD);
+            self.write_arg(0);
+            if let Some(jump_point) = jump_point {
+                let shift = self.edit_code(jump_point + 1, self.lasti() / unit);
+                Self::shift_jump_points(&mut jump_forward_points, jump_point, shift);
+            }
+            self.stack_inc_n(6);
+        }
+        // no arm matched
+        if self.py_version.minor >= Some(10) {
+            self.write_instr(Opcode310::RERAISE);
+        } else {
+            // re-raise the exception being handled
+            self.write_instr(RAISE_VARARGS);
+        }
+        self.write_arg(0);
+        self.stack_dec_n(6);
+        self.edit_jump_forward_points(jump_forward_points);
+        self.emit_load_name_instr(stash);
+        debug_assert_eq!(self.stack_len(), init_stack_len + 1);
+    }
+
+    fn emit_try_instr_308(&mut self, mut args: Args) {
+        log!(info "entered {}", fn_name!());
+        let init_stack_len = self.stack_len();
+        let body = args.remove(0);
+        let line = body.ln_begin().unwrap_or(0);
+        let stash = Identifier::private_with_line(Str::from(fresh_varname()), line);
+        let idx_setup_finally = self.lasti();
+        self.write_instr(Opcode308::SETUP_FINALLY);
+        self.write_arg(0);
+        self.emit_try_body(body);
+        self.emit_store_instr(stash.clone(), Name);
+        self.write_instr(POP_BLOCK);
+        self.write_arg(0);
+        let mut jump_forward_points = vec![self.lasti()];
+        self.write_instr(JUMP_FORWARD);
+        self.write_arg(0);
+        self.edit_code(idx_setup_finally + 1, self.lasti() - idx_setup_finally - 2);
+        // previous (traceback, value, type), traceback, value, type
+        self.stack_inc_n(6);
+        while let Some(expr) = args.try_remove(0) {
+            let mut lambda = enum_unwrap!(expr, Expr::Lambda);
+            let param = lambda.params.non_defaults.remove(0);
+            let jump_point = if let Some(t_spec) = param.t_spec_as_expr {
+                self.dup_top();
+                self.emit_exc_type_spec(t_spec);
+                // PyCmp_EXC_MATCH
+                self.write_instr(Opcode308::COMPARE_OP);
+                self.write_arg(10);
+                self.stack_dec();
+                let jump_point = self.lasti();
+                self.write_instr(Opcode308::POP_JUMP_IF_FALSE);
+                self.write_arg(0);
+                self.stack_dec();
+                Some(jump_point)
+            } else {
+                None
+            };
+            self.emit_pop_top();
+            self.emit_exc_binding(param.raw.pat);
+            self.emit_pop_top();
+            self.emit_try_arm_body(lambda.body);
+            self.emit_store_instr(stash.clone(), Name);
+            self.write_instr(Opcode308::POP_EXCEPT);
+            self.write_arg(0);
+            self.stack_dec_n(3);
+            jump_forward_points.push(self.lasti());
+            self.write_instr(JUMP_FORWARD);
+            self.write_arg(0);
+            if let Some(jump_point) = jump_point {
+                let shift = self.edit_code(jump_point + 1, self.lasti());
+                Self::shift_jump_points(&mut jump_forward_points, jump_point, shift);
+            }
+            self.stack_inc_n(6);
+        }
+        // no arm matched (re-raise)
+        self.write_instr(Opcode308::END_FINALLY);
+        self.write_arg(0);
+        self.stack_dec_n(6);
+        self.edit_jump_forward_points(jump_forward_points);
+        self.emit_load_name_instr(stash);
+        debug_assert_eq!(self.stack_len(), init_stack_len + 1);
+    }
+
+    /// The body of `try!` is a nullary procedure.
+    /// If it is a lambda, it is expanded inline, otherwise it is called.
+    fn emit_try_body(&mut self, body: Expr) {
+        match body {
+            Expr::Lambda(lambda) => self.emit_try_arm_body(lambda.body),
+            other => {
+                self.emit_push_null();
+                self.emit_expr(other);
+                self.emit_call_instr(0, Name);
+            }
+        }
+    }
+
+    fn emit_try_arm_body(&mut self, block: Block) {
+        let init_stack_len = self.stack_len();
+        self.emit_frameless_block(block, vec![]);
+        if self.stack_len() == init_stack_len {
+            self.emit_load_const(ValueObj::None);
+        }
+    }
+
+    /// `A or B` -> `(A, B)`
+    fn emit_exc_type_spec(&mut self, t_spec: Expr) {
+        fn flatten(expr: Expr, types: &mut Vec<Expr>) {
+            match expr {
+                Expr::BinOp(bin) if bin.op.is(TokenKind::OrOp) => {
+                    flatten(*bin.lhs, types);
+                    flatten(*bin.rhs, types);
+                }
+                other => types.push(other),
+            }
+        }
+        let mut types = vec![];
+        flatten(t_spec, &mut types);
+        if types.len() == 1 {
+            self.emit_expr(types.remove(0));
+        } else {
+            let len = types.len();
+            for t in types {
+                self.emit_expr(t);
+            }
+            self.write_instr(BUILD_TUPLE);
+            self.write_arg(len);
+            self.stack_dec_n(len - 1);
+        }
+    }
+
+    fn emit_exc_binding(&mut self, pat: ParamPattern) {
+        match pat {
+            ParamPattern::VarName(name) => {
+                let ident = Identifier::bare(None, name);
+                self.emit_store_instr(ident, AccessKind::Name);
+            }
+            ParamPattern::Discard(_) => {
+                self.emit_pop_top();
+            }
+            _other => unreachable!(),
+        }
+    }
+
+    fn shift_jump_points(points: &mut [usize], edited: usize, shift: usize) {
+        for point in points.iter_mut().filter(|p| **p > edited) {
+            *point += shift;
+        }
+    }
+
+    fn edit_jump_forward_points(&mut self, jump_forward_points: Vec<usize>) {
+        let mut points = jump_forward_points;
+        for i in 0..points.len() {
+            let point = points[i];
+            let shift = self.calc_edit_jump(point + 1, self.lasti() - point - 2);
+            Self::shift_jump_points(&mut points, point, shift);
+        }
+    }
+
+    fn emit_await_instr(&mut self, mut args: Args) {
+        log!(info "entered {}", fn_name!());
+        let init_stack_len = self.stack_len();
+        self.emit_expr(args.remove(0));
+        if self.py_version.minor >= Some(11) {
+            self.write_instr(Opcode311::GET_AWAITABLE);
+        } else {
+            self.write_instr(Opcode310::GET_AWAITABLE);
+        }
+        self.write_arg(0);
+        // 3: after `await`
+        self.emit_send_loop(3);
+        debug_assert_eq!(self.stack_len(), init_stack_len + 1);
+    }
+
+    fn emit_yield_from_instr(&mut self, mut args: Args) {
+        log!(info "entered {}", fn_name!());
+        let init_stack_len = self.stack_len();
+        self.emit_expr(args.remove(0));
+        self.write_instr(GET_YIELD_FROM_ITER);
+        self.write_arg(0);
+        // 2: after `yield from`
+        self.emit_send_loop(2);
+        debug_assert_eq!(self.stack_len(), init_stack_len + 1);
+    }
+
+    /// delegate to the receiver on the stack top until it is exhausted (`await`/`yield from`)
+    fn emit_send_loop(&mut self, resume_arg: usize) {
+        self.emit_load_const(ValueObj::None);
+        if self.py_version.minor >= Some(11) {
+            let idx_send = self.lasti();
+            self.write_instr(Opcode311::SEND);
+            self.write_arg(0);
+            self.write_instr(Opcode311::YIELD_VALUE);
+            self.write_arg(0);
+            self.write_instr(Opcode311::RESUME);
+            self.write_arg(resume_arg);
+            self.write_instr(Opcode311::JUMP_BACKWARD_NO_INTERRUPT);
+            self.write_arg((self.lasti() + 1 - idx_send) / 2);
+            self.edit_code(idx_send + 1, (self.lasti() - idx_send - 2) / 2);
+        } else {
+            self.write_instr(Opcode310::YIELD_FROM);
+            self.write_arg(0);
+        }
+        // receiver, value -> result
+        self.stack_dec();
+    }
+
+    fn emit_raise_instr(&mut self, mut args: Args) {
+        log!(info "entered {}", fn_name!());
+        let init_stack_len = self.stack_len();
+        self.emit_expr(args.remove(0));
+        self.write_instr(RAISE_VARARGS);
+        self.write_arg(1);
+        self.stack_dec();
+        self.emit_load_const(ValueObj::None);
+        debug_assert_eq!(self.stack_len(), init_stack_len + 1);
+    }
+
     fn emit_call(&mut self, call: Call) {
         log!(info "entered {} ({call})", fn_name!());
         let init_stack_len = self.stack_len();
@@ -2073,9 +2510,10 @@ impl PyCodeGenerator {
                 }
                 other => {
                     let is_py_api = other.is_py_api();
+                    let sig_t = other.ref_t().clone();
                     self.emit_push_null();
                     self.emit_expr(other);
-                    self.emit_args_311(call.args, Name, is_py_api);
+                    self.emit_args_311(call.args, Some(&sig_t), Name, is_py_api);
                 }
             }
         }
@@ -2093,6 +2531,9 @@ impl PyCodeGenerator {
             "while!" => self.emit_while_instr(args),
             "if" | "if!" => self.emit_if_instr(args),
             "match" | "match!" => self.emit_match_instr(args, true),
+            "try!" => self.emit_try_instr(args),
+            "await!" => self.emit_await_instr(args),
+            "raise" => self.emit_raise_instr(args),
             "with!" => match self.py_version.minor {
                 Some(11) => self.emit_with_instr_311(args),
                 Some(10) => self.emit_with_instr_310(args),
@@ -2102,9 +2543,10 @@ impl PyCodeGenerator {
             // "pyimport" | "py" are here
             _ => {
                 let is_py_api = local.is_py_api();
+                let sig_t = local.vi.t.clone();
                 self.emit_push_null();
                 self.emit_load_name_instr(local);
-                self.emit_args_311(args, Name, is_py_api);
+                self.emit_args_311(args, Some(&sig_t), Name, is_py_api);
             }
         }
     }
@@ -2122,19 +2564,29 @@ impl PyCodeGenerator {
             "return" if obj.ref_t().is_callable() => {
                 return self.emit_return_instr(args);
             }
-            // TODO: create `Generator` type
             "yield" /* if obj.ref_t().is_callable() */ => {
                 return self.emit_yield_instr(args);
             }
+            "yield_from" => {
+                return self.emit_yield_from_instr(args);
+            }
             _ => {}
         }
         if let Some(func_name) = debind(&method_name) {
             return self.emit_call_fake_method(obj, func_name, method_name, args);
         }
         let is_py_api = method_name.is_py_api();
+        let sig_t = method_name.vi.t.clone();
+        // `CALL_FUNCTION_EX` cannot call an unbound method loaded by `LOAD_METHOD`
+        if args.var_args.is_some() || args.kw_var_args.is_some() {
+            self.emit_push_null();
+            self.emit_expr(obj);
+            self.emit_load_attr_instr(method_name);
+            return self.emit_args_ex(args, Some(&sig_t), is_py_api);
+        }
         self.emit_expr(obj);
         self.emit_load_method_instr(method_name);
-        self.emit_args_311(args, Method, is_py_api);
+        self.emit_args_311(args, Some(&sig_t), Method, is_py_api);
     }
 
     fn emit_var_args_311(&mut self, pos_len: usize, var_args: &PosArg) {
@@ -2163,26 +2615,101 @@ impl PyCodeGenerator {
         }
     }
 
-    fn emit_args_311(&mut self, mut args: Args, kind: AccessKind, is_py_api: bool) {
-        let argc = args.len();
+    /// Keyword arguments passed to the variable-length keyword parameter are not escaped,
+    /// because they are used as the keys of the `kwargs` dict.
+    fn escape_kw_arg(keyword: &Token, sig_t: Option<&Type>, is_py_api: bool) -> Str {
+        let is_named_param = |t: &Type| {
+            t.non_default_params()
+                .into_iter()
+                .flatten()
+                .chain(t.default_params().into_iter().flatten())
+                .any(|pt| pt.name() == Some(keyword.inspect()))
+        };
+        let to_kw_var_params = sig_t
+            .map(|t| t.kw_var_params().is_some() && !is_named_param(t))
+            .unwrap_or(false);
+        if is_py_api || to_kw_var_params {
+            keyword.content.clone()
+        } else {
+            escape_name(&keyword.content, Visibility::Private)
+        }
+    }
+
+    /// `f(*args, **kwargs)` => `CALL_FUNCTION_EX`
+    fn emit_args_ex(&mut self, mut args: Args, sig_t: Option<&Type>, is_py_api: bool) {
         let pos_len = args.pos_args.len();
-        let mut kws = Vec::with_capacity(args.kw_len());
         while let Some(arg) = args.try_remove_pos(0) {
             self.emit_expr(arg.expr);
         }
-        if let Some(var_args) = &args.var_args {
+        if let Some(var_args) = args.var_args.take() {
             if self.py_version.minor >= Some(10) {
-                self.emit_var_args_311(pos_len, var_args);
+                self.emit_var_args_311(pos_len, &var_args);
+            } else {
+                self.emit_var_args_38(pos_len, &var_args);
+            }
+            // positional args + var args -> args tuple
+            self.stack_dec_n(pos_len);
+        } else {
+            self.write_instr(BUILD_TUPLE);
+            self.write_arg(pos_len);
+            if pos_len == 0 {
+                self.stack_inc();
             } else {
-                self.emit_var_args_38(pos_len, var_args);
+                self.stack_dec_n(pos_len - 1);
             }
         }
+        let kw_len = args.kw_len();
         while let Some(arg) = args.try_remove_kw(0) {
-            let kw = if is_py_api {
-                arg.keyword.content
-            } else {
-                escape_name(&arg.keyword.content, Visibility::Private)
-            };
+            let kw = Self::escape_kw_arg(&arg.keyword, sig_t, is_py_api);
+            self.emit_load_const(kw);
+            self.emit_expr(arg.expr);
+        }
+        if kw_len > 0 {
+            self.write_instr(BUILD_MAP);
+            self.write_arg(kw_len);
+            self.stack_dec_n(kw_len * 2 - 1);
+        }
+        let has_kwargs = kw_len > 0 || args.kw_var_args.is_some();
+        if let Some(kw_var_args) = args.kw_var_args.take() {
+            self.emit_expr(kw_var_args.expr);
+            if kw_len > 0 {
+                if self.py_version.minor >= Some(9) {
+                    self.write_instr(Opcode310::DICT_MERGE);
+                    self.write_arg(1);
+                } else {
+                    self.write_instr(Opcode308::BUILD_MAP_UNPACK_WITH_CALL);
+                    self.write_arg(2);
+                }
+                self.stack_dec();
+            }
+        }
+        self.write_instr(CALL_FUNCTION_EX);
+        self.write_arg(has_kwargs as usize);
+        // <subroutine> + <args tuple> (+ <kwargs dict>) -> <return object>
+        self.stack_dec_n(1 + has_kwargs as usize);
+        // pop NULL
+        if self.py_version.minor >= Some(11) {
+            self.stack_dec();
+        }
+    }
+
+    fn emit_args_311(
+        &mut self,
+        mut args: Args,
+        sig_t: Option<&Type>,
+        kind: AccessKind,
+        is_py_api: bool,
+    ) {
+        if args.var_args.is_some() || args.kw_var_args.is_some() {
+            return self.emit_args_ex(args, sig_t, is_py_api);
+        }
+        let argc = args.len();
+        let mut kws = Vec::with_capacity(args.kw_len());
+        while let Some(arg) = args.try_remove_pos(0) {
+            self.emit_expr(arg.expr);
+        }
+        while let Some(arg) = args.try_remove_kw(0) {
+            let kw = Self::escape_kw_arg(&arg.keyword, sig_t, is_py_api);
             kws.push(ValueObj::Str(kw));
             self.emit_expr(arg.expr);
         }
@@ -2195,16 +2722,7 @@ impl PyCodeGenerator {
                 1
             }
         } else {
-            if args.var_args.is_some() {
-                self.write_instr(CALL_FUNCTION_EX);
-                if kws.is_empty() {
-                    self.write_arg(0);
-                } else {
-                    self.write_arg(1);
-                }
-            } else {
-                self.emit_call_instr(argc, kind);
-            }
+            self.emit_call_instr(argc, kind);
             0
         };
         // (1 (subroutine) + argc + kwsc) input objects -> 1 return object
@@ -2281,6 +2799,11 @@ impl PyCodeGenerator {
         }
         self.write_instr(YIELD_VALUE);
         self.write_arg(0);
+        if self.py_version.minor >= Some(11) {
+            // 1: after `yield`
+            self.write_instr(Opcode311::RESUME);
+            self.write_arg(1);
+        }
     }
 
     /// 1.abs() => abs(1)
@@ -2297,7 +2820,7 @@ impl PyCodeGenerator {
         self.emit_push_null();
         self.emit_load_name_instr(method_name);
         args.insert_pos(0, PosArg::new(obj));
-        self.emit_args_311(args, Name, true);
+        self.emit_args_311(args, None, Name, true);
     }
 
     // assert takes 1 or 2 arguments (0: cond, 1: message)
@@ -2368,6 +2891,8 @@ impl PyCodeGenerator {
                 if self.py_version.minor >= Some(11) {
                     self.write_instr(Opcode311::BINARY_OP);
                     self.write_arg(BinOpCode::Multiply as usize);
+                    // cache
+                    self.write_bytes(&[0; 2]);
                 } else {
                     self.write_instr(Opcode310::BINARY_MULTIPLY);
                     self.write_arg(0);
@@ -2537,6 +3062,16 @@ impl PyCodeGenerator {
 
     fn push_lnotab(&mut self, expr: &Expr) {
         let ln_begin = expr.ln_begin().unwrap_or_else(|| panic!("{expr}"));
+        if self.inlined_depth == 0 {
+            let col = expr.col_begin().unwrap_or(0);
+            let (name, firstlineno) = (
+                self.cur_block_codeobj().name.clone(),
+                self.cur_block_codeobj().firstlineno,
+            );
+            let lasti = self.lasti();
+            self.source_map
+                .add_offset(&name, firstlineno, lasti, ln_begin, col);
+        }
         if ln_begin > self.cur_block().prev_lineno {
             let sd = self.lasti() - self.cur_block().prev_lasti;
             let ld = ln_begin - self.cur_block().prev_lineno;
@@ -2556,6 +3091,11 @@ impl PyCodeGenerator {
                 }
                 self.mut_cur_block().prev_lineno += ld;
                 self.mut_cur_block().prev_lasti = self.lasti();
+                if self.inlined_depth == 0 {
+                    // the line numbers of the code objects are the same as the Erg source
+                    let col = expr.col_begin().unwrap_or(0);
+                    self.source_map.add(ln_begin, 0, ln_begin, col);
+                }
             } else {
                 CompileError::compiler_bug(
                     0,
@@ -2570,6 +3110,13 @@ impl PyCodeGenerator {
         }
     }
 
+    /// Docstrings are stripped in the release build (`cfg.strip`), unless they are the value of the block.
+    fn is_stripped_docstring(&self, chunk: &Expr, is_last: bool) -> bool {
+        self.cfg.strip
+            && !is_last
+            && matches!(chunk, Expr::Lit(lit) if lit.is_doc_comment())
+    }
+
     fn emit_chunk(&mut self, chunk: Expr) {
         log!(info "entered {} ({chunk})", fn_name!());
         self.push_lnotab(&chunk);
@@ -2590,7 +3137,9 @@ impl PyCodeGenerator {
             Expr::Dict(dict) => self.emit_dict(dict),
             Expr::Record(rec) => self.emit_record(rec),
             Expr::Code(code) => {
+                self.inlined_depth += 1;
                 let code = self.emit_block(code, None, vec![], 0);
+                self.inlined_depth -= 1;
                 self.emit_load_const(code);
             }
             Expr::Compound(chunks) => self.emit_compound(chunks),
@@ -2651,7 +3200,9 @@ impl PyCodeGenerator {
             Expr::Dict(dict) => self.emit_dict(dict),
             Expr::Record(rec) => self.emit_record(rec),
             Expr::Code(code) => {
+                self.inlined_depth += 1;
                 let code = self.emit_block(code, None, vec![], 0);
+                self.inlined_depth -= 1;
                 self.emit_load_const(code);
             }
             Expr::Compound(chunks) => self.emit_compound(chunks),
@@ -2673,7 +3224,11 @@ impl PyCodeGenerator {
             self.emit_store_instr(Identifier::public_with_line(DOT, param, line), Name);
         }
         let init_stack_len = self.stack_len();
-        for chunk in block.into_iter() {
+        let len = block.len();
+        for (i, chunk) in block.into_iter().enumerate() {
+            if self.is_stripped_docstring(&chunk, i + 1 == len) {
+                continue;
+            }
             self.emit_chunk(chunk);
             if self.stack_len() > init_stack_len {
                 self.emit_pop_top();
@@ -2753,7 +3308,8 @@ impl PyCodeGenerator {
         }
         // end of flagging
         let unit = self.units.pop().unwrap();
-        if !self.units.is_empty() {
+        // the lines of inlined modules may go backwards
+        if !self.units.is_empty() && unit.prev_lineno > self.cur_block().prev_lineno {
             let ld = unit.prev_lineno - self.cur_block().prev_lineno;
             if ld != 0 {
                 if let Some(l) = self.mut_cur_block_codeobj().lnotab.last_mut() {
@@ -2762,7 +3318,7 @@ impl PyCodeGenerator {
                 self.mut_cur_block().prev_lineno += ld;
             }
         }
-        unit.codeobj
+        unit.into_codeobj()
     }
 
     fn emit_init_method(&mut self, sig: &Signature, __new__: Type) {
@@ -2790,15 +3346,15 @@ impl PyCodeGenerator {
                 erg_parser::ast::NonDefaultParamSignature::new(ParamPattern::VarName(param), None);
             let vi = VarInfo::parameter(new_first_param.typ().clone(), ident.vi.def_loc.clone());
             let param = NonDefaultParamSignature::new(raw, vi, None);
-            let params = Params::new(vec![self_param, param], None, vec![], None);
+            let params = Params::new(vec![self_param, param], None, vec![], None, None);
             (param_name, params)
         } else {
             (
                 "_".into(),
-                Params::new(vec![self_param], None, vec![], None),
+                Params::new(vec![self_param], None, vec![], None, None),
             )
         };
-        let subr_sig = SubrSignature::new(ident, params, sig.t_spec().cloned());
+        let subr_sig = SubrSignature::new(ident, params, sig.t_spec().cloned(), false);
         let mut attrs = vec![];
         match new_first_param.map(|pt| pt.typ()) {
             // namedtupleは仕様上::xなどの名前を使えない
@@ -2873,20 +3429,20 @@ impl PyCodeGenerator {
             let raw =
                 erg_parser::ast::NonDefaultParamSignature::new(ParamPattern::VarName(param), None);
             let param = NonDefaultParamSignature::new(raw, vi, None);
-            let params = Params::new(vec![param], None, vec![], None);
-            let sig = SubrSignature::new(ident, params, sig.t_spec().cloned());
+            let params = Params::new(vec![param], None, vec![], None, None);
+            let sig = SubrSignature::new(ident, params, sig.t_spec().cloned(), false);
             let arg = PosArg::new(Expr::Accessor(Accessor::private_with_line(
                 Str::from(param_name),
                 line,
             )));
-            let call = class_new.call_expr(Args::new(vec![arg], None, vec![], None));
+            let call = class_new.call_expr(Args::new(vec![arg], None, vec![], None, None));
             let block = Block::new(vec![call]);
             let body = DefBody::new(EQUAL, block, DefId(0));
             self.emit_subr_def(Some(class_ident.inspect()), sig, body);
         } else {
-            let params = Params::new(vec![], None, vec![], None);
-            let sig = SubrSignature::new(ident, params, sig.t_spec().cloned());
-            let call = class_new.call_expr(Args::new(vec![], None, vec![], None));
+            let params = Params::new(vec![], None, vec![], None, None);
+            let sig = SubrSignature::new(ident, params, sig.t_spec().cloned(), false);
+            let call = class_new.call_expr(Args::new(vec![], None, vec![], None, None));
             let block = Block::new(vec![call]);
             let body = DefBody::new(EQUAL, block, DefId(0));
             self.emit_subr_def(Some(class_ident.inspect()), sig, body);
@@ -2920,18 +3476,36 @@ impl PyCodeGenerator {
             firstlineno,
             flags,
         ));
+        let is_coroutine = flags & CodeObjFlags::Coroutine as u32 != 0;
+        let is_generator = flags & CodeObjFlags::Generator as u32 != 0;
         let idx_copy_free_vars = if self.py_version.minor >= Some(11) {
             let idx_copy_free_vars = self.lasti();
             self.write_instr(Opcode311::COPY_FREE_VARS);
             self.write_arg(0);
+            if is_coroutine || is_generator {
+                self.write_instr(Opcode311::RETURN_GENERATOR);
+                self.write_arg(0);
+                // discard the first sent value (None)
+                self.write_instr(POP_TOP);
+                self.write_arg(0);
+            }
             self.write_instr(Opcode311::RESUME);
             self.write_arg(0);
             idx_copy_free_vars
         } else {
+            if (is_coroutine || is_generator) && self.py_version.minor == Some(10) {
+                self.write_instr(Opcode310::GEN_START);
+                // 0: generator, 1: coroutine
+                self.write_arg(is_coroutine as usize);
+            }
             0
         };
         let init_stack_len = self.stack_len();
-        for chunk in block.into_iter() {
+        let len = block.len();
+        for (i, chunk) in block.into_iter().enumerate() {
+            if self.is_stripped_docstring(&chunk, i + 1 == len) {
+                continue;
+            }
             self.emit_chunk(chunk);
             // NOTE: 各行のトップレベルでは0個または1個のオブジェクトが残っている
             // Pythonの場合使わなかったオブジェクトはそのまま捨てられるが、Ergではdiscardを使う必要がある
@@ -2988,7 +3562,7 @@ impl PyCodeGenerator {
                 self.mut_cur_block().prev_lineno += ld;
             }
         }
-        unit.codeobj
+        unit.into_codeobj()
     }
 
     fn load_prelude(&mut self) {
@@ -3103,6 +3677,8 @@ impl PyCodeGenerator {
 
     pub fn emit(&mut self, hir: HIR) -> CodeObj {
         log!(info "the code-generating process has started.{RESET}");
+        let filename = self.cfg.input.enclosed_name();
+        self.source_map = SourceMap::new(filename, filename);
         self.unit_size += 1;
         self.units.push(PyCodeGenUnit::new(
             self.unit_size,
@@ -3120,7 +3696,11 @@ impl PyCodeGenerator {
         if !self.cfg.no_std && !self.prelude_loaded {
             self.load_prelude();
         }
-        for chunk in hir.module.into_iter() {
+        let len = hir.module.len();
+        for (i, chunk) in hir.module.into_iter().enumerate() {
+            if self.is_stripped_docstring(&chunk, i + 1 == len) {
+                continue;
+            }
             self.emit_chunk(chunk);
             // TODO: discard
             if self.stack_len() == 1 {
@@ -3157,7 +3737,7 @@ impl PyCodeGenerator {
         }
         // end of flagging
         let unit = self.units.pop().unwrap();
-        if !self.units.is_empty() {
+        if !self.units.is_empty() && unit.prev_lineno > self.cur_block().prev_lineno {
             let ld = unit.prev_lineno - self.cur_block().prev_lineno;
             if ld != 0 {
                 if let Some(l) = self.mut_cur_block_codeobj().lnotab.last_mut() {
@@ -3167,6 +3747,11 @@ impl PyCodeGenerator {
             }
         }
         log!(info "the code-generating process has completed.{RESET}");
-        unit.codeobj
+        let mut code = unit.into_codeobj();
+        // the release build does not need the line numbers (debug information)
+        if self.cfg.strip {
+            code.strip_lnotab();
+        }
+        code
     }
 }
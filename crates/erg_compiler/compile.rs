@@ -7,6 +7,7 @@ use erg_common::config::ErgConfig;
 use erg_common::error::MultiErrorDisplay;
 use erg_common::log;
 use erg_common::traits::{Runnable, Stream};
+use erg_common::Str;
 
 use crate::artifact::{CompleteArtifact, ErrorArtifact};
 use crate::context::ContextProvider;
@@ -18,7 +19,8 @@ use crate::desugar_hir::HIRDesugarer;
 use crate::error::{CompileError, CompileErrors, CompileWarnings};
 use crate::hir::Expr;
 use crate::link::Linker;
-use crate::module::{SharedCompilerResource, SharedModuleCache};
+use crate::module::SharedCompilerResource;
+use crate::source_map::{source_map_path, SourceMap};
 
 /// * registered as global -> Global
 /// * defined in the toplevel scope (and called in the inner scope) -> Global
@@ -98,8 +100,12 @@ impl AccessKind {
 pub struct Compiler {
     pub cfg: ErgConfig,
     builder: HIRBuilder,
-    mod_cache: SharedModuleCache,
+    shared: SharedCompilerResource,
     code_generator: PyCodeGenerator,
+    /// whether the entries of `mod_cache` should be kept after linking
+    keep_mod_cache: bool,
+    /// whether the up-to-date outputs of the separately compiled modules are reused
+    incremental: bool,
 }
 
 impl Default for Compiler {
@@ -116,10 +122,12 @@ impl Runnable for Compiler {
     fn new(cfg: ErgConfig) -> Self {
         let shared = SharedCompilerResource::new(cfg.copy());
         Self {
-            mod_cache: shared.mod_cache.clone(),
+            shared: shared.clone(),
             builder: HIRBuilder::new_with_cache(cfg.copy(), "<module>", shared),
             code_generator: PyCodeGenerator::new(cfg.copy()),
             cfg,
+            keep_mod_cache: false,
+            incremental: false,
         }
     }
 
@@ -188,6 +196,30 @@ impl ContextProvider for Compiler {
 }
 
 impl Compiler {
+    /// Creates a compiler that shares the module caches with others.
+    /// The cached modules are not used up by linking.
+    pub fn new_with_cache<S: Into<Str>>(
+        cfg: ErgConfig,
+        mod_name: S,
+        shared: SharedCompilerResource,
+    ) -> Self {
+        Self {
+            shared: shared.clone(),
+            builder: HIRBuilder::new_with_cache(cfg.copy(), mod_name, shared),
+            code_generator: PyCodeGenerator::new(cfg.copy()),
+            cfg,
+            keep_mod_cache: true,
+            incremental: false,
+        }
+    }
+
+    /// If `true`, the separately compiled modules are not regenerated
+    /// as long as their outputs are newer than them and their dependencies.
+    pub fn incremental(mut self, incremental: bool) -> Self {
+        self.incremental = incremental;
+        self
+    }
+
     pub fn compile_and_dump_as_pyc<P: AsRef<Path>>(
         &mut self,
         pyc_path: P,
@@ -196,11 +228,106 @@ impl Compiler {
     ) -> Result<CompileWarnings, ErrorArtifact> {
         let arti = self.compile(src, mode)?;
         arti.object
-            .dump_as_pyc(pyc_path, self.cfg.py_magic_num)
+            .dump_as_pyc(pyc_path.as_ref(), self.cfg.py_magic_num)
             .expect("failed to dump a .pyc file (maybe permission denied)");
+        if self.cfg.source_map {
+            self.code_generator
+                .take_source_map()
+                .dump(source_map_path(pyc_path.as_ref()))
+                .expect("failed to dump a source map (maybe permission denied)");
+        }
+        if self.cfg.separate {
+            self.dump_modules_as_pyc();
+        }
         Ok(arti.warns)
     }
 
+    /// Takes the source map of the last compiled module.
+    /// `compile_and_dump_as_pyc` dumps it only with `--source-map`.
+    pub fn take_source_map(&mut self) -> SourceMap {
+        self.code_generator.take_source_map()
+    }
+
+    /// Compiles the Erg modules imported by the main module into their own .pyc files (`--separate`).
+    /// They are placed under the output directory, preserving the package structure.
+    fn dump_modules_as_pyc(&mut self) {
+        let Some(main) = self.cfg.input.path() else {
+            return;
+        };
+        let deps = self.shared.graph.dependencies(main);
+        let linker =
+            Linker::new(&self.cfg, &self.shared.mod_cache).keep_entries(self.keep_mod_cache);
+        // the modules whose interfaces are loaded have no HIR,
+        // and the modules imported only by them are not even in the cache
+        for path in deps.iter() {
+            let file_name = path.to_string_lossy();
+            if !file_name.ends_with(".er") || file_name.ends_with(".d.er") {
+                continue;
+            }
+            let name = match self.shared.mod_cache.get(path) {
+                Some(entry) if entry.hir.is_some() => continue,
+                Some(entry) => entry.module.context.name.clone(),
+                None => Str::rc(&path.file_stem().unwrap_or_default().to_string_lossy()),
+            };
+            let mut pyc_path = self.cfg.dump_dir().join(linker.relative_path(path));
+            pyc_path.set_extension("pyc");
+            if !(self.incremental && self.is_up_to_date(path, &pyc_path)) {
+                self.rebuild_module(path, name);
+            }
+        }
+        for (cfg, hir, output) in linker.link_modules(deps) {
+            let mut pyc_path = self.cfg.dump_dir().join(output);
+            pyc_path.set_extension("pyc");
+            if self.incremental
+                && cfg
+                    .input
+                    .path()
+                    .is_some_and(|path| self.is_up_to_date(path, &pyc_path))
+            {
+                continue;
+            }
+            if let Some(dir) = pyc_path.parent() {
+                std::fs::create_dir_all(dir).expect("failed to create the output directory");
+            }
+            let desugared = HIRDesugarer::desugar(hir);
+            let mut generator = PyCodeGenerator::new(cfg);
+            let codeobj = generator.emit(desugared);
+            codeobj
+                .dump_as_pyc(&pyc_path, self.cfg.py_magic_num)
+                .expect("failed to dump a .pyc file (maybe permission denied)");
+            if self.cfg.source_map {
+                generator
+                    .take_source_map()
+                    .dump(source_map_path(&pyc_path))
+                    .expect("failed to dump a source map (maybe permission denied)");
+            }
+        }
+    }
+
+    /// Checks the module to get its HIR.
+    fn rebuild_module(&self, path: &Path, name: Str) {
+        let mut cfg = self.cfg.inherit(path.to_path_buf());
+        let src = cfg.input.read();
+        let mut builder = HIRBuilder::new_with_cache(cfg, name, self.shared.clone());
+        if let Ok(artifact) = builder.build(src, "exec") {
+            let ctx = builder.pop_mod_ctx().unwrap();
+            self.shared
+                .mod_cache
+                .register(path.to_path_buf(), Some(artifact.object), ctx);
+        }
+    }
+
+    /// Whether `output` is newer than the module and all the modules it depends on.
+    fn is_up_to_date(&self, path: &Path, output: &Path) -> bool {
+        let mtime = |path: &Path| path.metadata().and_then(|meta| meta.modified()).ok();
+        let Some(output_mtime) = mtime(output) else {
+            return false;
+        };
+        std::iter::once(path.to_path_buf())
+            .chain(self.shared.graph.dependencies(path))
+            .all(|dep| mtime(&dep).is_some_and(|dep_mtime| dep_mtime <= output_mtime))
+    }
+
     pub fn eval_compile_and_dump_as_pyc<P: AsRef<Path>>(
         &mut self,
         pyc_path: P,
@@ -247,7 +374,8 @@ impl Compiler {
         mode: &str,
     ) -> Result<CompleteArtifact, ErrorArtifact> {
         let artifact = self.builder.build(src, mode)?;
-        let linker = Linker::new(&self.cfg, &self.mod_cache);
+        let linker =
+            Linker::new(&self.cfg, &self.shared.mod_cache).keep_entries(self.keep_mod_cache);
         let hir = linker.link(artifact.object);
         let desugared = HIRDesugarer::desugar(hir);
         Ok(CompleteArtifact::new(desugared, artifact.warns))
@@ -365,6 +365,9 @@ impl Context {
                 && ls.var_params.as_ref().zip(rs.var_params.as_ref()).map(|(l, r)| {
                     self.subtype_of(l.typ(), r.typ())
                 }).unwrap_or(true)
+                && ls.kw_var_params.as_ref().zip(rs.kw_var_params.as_ref()).map(|(l, r)| {
+                    self.subtype_of(l.typ(), r.typ())
+                }).unwrap_or(true)
                 && kw_check() // contravariant
             }
             // ?T(<: Nat) !:> ?U(:> Int)
@@ -94,7 +94,7 @@ fn op_to_name(op: OpKind) -> &'static str {
 }
 
 impl Context {
-    fn try_get_op_kind_from_token(&self, token: &Token) -> EvalResult<OpKind> {
+    pub(crate) fn try_get_op_kind_from_token(&self, token: &Token) -> EvalResult<OpKind> {
         match token.kind {
             TokenKind::Plus => Ok(OpKind::Add),
             TokenKind::Minus => Ok(OpKind::Sub),
@@ -225,6 +225,15 @@ impl Context {
             match acc {
                 Accessor::Ident(ident) => {
                     let obj = self.rec_get_const_obj(ident.inspect()).ok_or_else(|| {
+                        // e.g. `print! X` in a const subroutine
+                        if !ident.is_const() {
+                            return EvalError::not_const_expr(
+                                self.cfg.input.clone(),
+                                line!() as usize,
+                                call.loc(),
+                                self.caused_by(),
+                            );
+                        }
                         EvalError::no_var_error(
                             self.cfg.input.clone(),
                             line!() as usize,
@@ -279,11 +288,14 @@ impl Context {
         }
     }
 
-    fn call(&self, subr: ConstSubr, args: ValueArgs, loc: Location) -> EvalResult<ValueObj> {
+    pub(crate) fn call(
+        &self,
+        subr: ConstSubr,
+        args: ValueArgs,
+        loc: Location,
+    ) -> EvalResult<ValueObj> {
         match subr {
-            ConstSubr::User(_user) => {
-                feature_error!(self, loc, "calling user-defined subroutines").map_err(Into::into)
-            }
+            ConstSubr::User(user) => self.call_user_const_subr(user, args, loc),
             ConstSubr::Builtin(builtin) => builtin.call(args, self).map_err(|mut e| {
                 if e.0.loc.is_unknown() {
                     e.0.loc = loc;
@@ -297,6 +309,89 @@ impl Context {
         }
     }
 
+    /// Evaluates the body of a user-defined const subroutine.
+    /// The arguments are bound as constants of an instant context, so the body can refer to them.
+    fn call_user_const_subr(
+        &self,
+        subr: UserConstSubr,
+        mut args: ValueArgs,
+        loc: Location,
+    ) -> EvalResult<ValueObj> {
+        // HACK: should avoid cloning
+        let mut subr_ctx = Context::instant(
+            subr.name.clone(),
+            self.cfg.clone(),
+            subr.params.len(),
+            self.shared.clone(),
+            self.clone(),
+        );
+        let mut missing_params = vec![];
+        for sig in subr.params.non_defaults.iter() {
+            let name = sig.inspect().map(|n| &n[..]).unwrap_or("_");
+            if let Some(val) = args.remove_left_or_key(name) {
+                subr_ctx.bind_const_param(sig, val)?;
+            } else {
+                missing_params.push(Str::rc(name));
+            }
+        }
+        if !missing_params.is_empty() {
+            return Err(EvalErrors::from(EvalError::args_missing_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                loc,
+                &subr.name,
+                self.caused_by(),
+                missing_params,
+            )));
+        }
+        if let Some(var_params) = subr.params.var_params.as_ref() {
+            let rest = mem::take(&mut args.pos_args);
+            subr_ctx.bind_const_param(var_params, ValueObj::Array(RcArray::from(rest)))?;
+        }
+        for sig in subr.params.defaults.iter() {
+            let name = sig.inspect().map(|n| &n[..]).unwrap_or("_");
+            let val = if let Some(val) = args.remove_left_or_key(name) {
+                val
+            } else {
+                subr_ctx.eval_const_expr(&sig.default_val)?
+            };
+            subr_ctx.bind_const_param(&sig.sig, val)?;
+        }
+        if !args.pos_args.is_empty() || !args.kw_args.is_empty() {
+            let pos_args_len = subr.params.non_defaults.len() + args.pos_args.len();
+            return Err(EvalErrors::from(EvalError::too_many_args_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                loc,
+                &subr.name,
+                self.caused_by(),
+                subr.params.len(),
+                pos_args_len,
+                args.kw_args.len(),
+            )));
+        }
+        subr_ctx.eval_const_block(&subr.block)
+    }
+
+    fn bind_const_param(
+        &mut self,
+        sig: &NonDefaultParamSignature,
+        val: ValueObj,
+    ) -> EvalResult<()> {
+        match &sig.pat {
+            ParamPattern::VarName(name) | ParamPattern::Ref(name) | ParamPattern::RefMut(name) => {
+                self.consts.insert(name.clone(), val);
+                Ok(())
+            }
+            ParamPattern::Discard(_) => Ok(()),
+            other => feature_error!(
+                self,
+                other.loc(),
+                &format!("const parameter pattern {other}")
+            ),
+        }
+    }
+
     fn eval_const_def(&mut self, def: &Def) -> EvalResult<ValueObj> {
         if def.is_const() {
             let __name__ = def.sig.ident().unwrap().inspect();
@@ -482,6 +577,18 @@ impl Context {
             )?;
             default_params.push(pt);
         }
+        let kw_var_params = if let Some(p) = lambda.sig.params.kw_var_params.as_ref() {
+            let pt = self.instantiate_param_ty(
+                p,
+                None,
+                &mut tmp_tv_cache,
+                RegistrationMode::Normal,
+                ParamKind::KwParams,
+            )?;
+            Some(pt)
+        } else {
+            None
+        };
         // HACK: should avoid cloning
         let mut lambda_ctx = Context::instant(
             Str::ever("<lambda>"),
@@ -496,6 +603,7 @@ impl Context {
             non_default_params.clone(),
             var_params,
             default_params.clone(),
+            kw_var_params,
             return_t,
         );
         let sig_t = self.generalize_t(sig_t);
@@ -791,7 +899,7 @@ impl Context {
         }
     }
 
-    fn eval_unary_tp(&self, op: OpKind, val: TyParam) -> EvalResult<TyParam> {
+    pub(crate) fn eval_unary_tp(&self, op: OpKind, val: TyParam) -> EvalResult<TyParam> {
         match val {
             TyParam::Value(c) => self.eval_unary_val(op, c).map(TyParam::Value),
             TyParam::FreeVar(fv) if fv.is_linked() => self.eval_unary_tp(op, fv.crack().clone()),
@@ -885,12 +993,17 @@ impl Context {
                 for pt in subr.default_params.iter_mut() {
                     *pt.typ_mut() = self.eval_t_params(mem::take(pt.typ_mut()), level, t_loc)?;
                 }
+                if let Some(kw_var_args) = subr.kw_var_params.as_mut() {
+                    *kw_var_args.typ_mut() =
+                        self.eval_t_params(mem::take(kw_var_args.typ_mut()), level, t_loc)?;
+                }
                 let return_t = self.eval_t_params(*subr.return_t, level, t_loc)?;
                 Ok(subr_t(
                     subr.kind,
                     subr.non_default_params,
                     subr.var_params.map(|v| *v),
                     subr.default_params,
+                    subr.kw_var_params.map(|v| *v),
                     return_t,
                 ))
             }
@@ -161,12 +161,20 @@ impl Context {
                         uninit,
                     );
                 });
+                if let Some(kw_var_args) = &mut subr.kw_var_params {
+                    *kw_var_args.typ_mut() = self.generalize_t_inner(
+                        mem::take(kw_var_args.typ_mut()),
+                        Contravariant,
+                        uninit,
+                    );
+                }
                 let return_t = self.generalize_t_inner(*subr.return_t, Covariant, uninit);
                 subr_t(
                     subr.kind,
                     subr.non_default_params,
                     subr.var_params.map(|x| *x),
                     subr.default_params,
+                    subr.kw_var_params.map(|x| *x),
                     return_t,
                 )
             }
@@ -575,6 +583,10 @@ impl Context {
                     *d_param.typ_mut() =
                         self.deref_tyvar(mem::take(d_param.typ_mut()), Contravariant, loc)?;
                 }
+                if let Some(kw_var_args) = &mut subr.kw_var_params {
+                    *kw_var_args.typ_mut() =
+                        self.deref_tyvar(mem::take(kw_var_args.typ_mut()), Contravariant, loc)?;
+                }
                 subr.return_t =
                     Box::new(self.deref_tyvar(mem::take(&mut subr.return_t), Covariant, loc)?);
                 Ok(Type::Subr(subr))
@@ -777,6 +789,13 @@ impl Context {
                 self.deref_tyvar(mem::take(&mut param.sig.vi.t), Contravariant, param)?;
             self.resolve_expr_t(&mut param.default_val)?;
         }
+        if let Some(kw_var_params) = &mut params.kw_var_params {
+            kw_var_params.vi.t = self.deref_tyvar(
+                mem::take(&mut kw_var_params.vi.t),
+                Contravariant,
+                kw_var_params.as_ref(),
+            )?;
+        }
         Ok(())
     }
 
@@ -906,6 +925,9 @@ impl Context {
                 for arg in call.args.kw_args.iter_mut() {
                     self.resolve_expr_t(&mut arg.expr)?;
                 }
+                if let Some(kw_var_args) = &mut call.args.kw_var_args {
+                    self.resolve_expr_t(&mut kw_var_args.expr)?;
+                }
                 Ok(())
             }
             hir::Expr::Def(def) => {
@@ -953,7 +953,7 @@ impl Context {
         let mut record = Self::builtin_mono_class(RECORD, 2);
         record.register_superclass(Obj, &obj);
         /* Or (true or type) */
-        let or_t = poly(OR, vec![ty_tp(L), ty_tp(R)]);
+        let or_t = poly(OR, vec![ty_tp(L), ty_tp(R.clone())]);
         let mut or = Self::builtin_poly_class(OR, vec![PS::t_nd(TY_L), PS::t_nd(TY_R)], 2);
         or.register_superclass(Obj, &obj);
         /* Iterators */
@@ -998,6 +998,39 @@ impl Context {
         ));
         zip.register_marker_trait(poly(OUTPUT, vec![ty_tp(T.clone())]));
         zip.register_marker_trait(poly(OUTPUT, vec![ty_tp(U.clone())]));
+        /* Coroutine */
+        let mut coroutine = Self::builtin_poly_class(COROUTINE, vec![PS::t_nd(TY_T)], 2);
+        coroutine.register_superclass(Obj, &obj);
+        coroutine.register_marker_trait(poly(AWAITABLE, vec![ty_tp(T.clone())]));
+        coroutine.register_marker_trait(poly(OUTPUT, vec![ty_tp(T.clone())]));
+        let t = pr0_met(poly(COROUTINE, vec![ty_tp(T.clone())]), NoneType).quantify();
+        coroutine.register_builtin_py_impl(PROC_CLOSE, t, Immutable, Public, Some(FUNC_CLOSE));
+        /* Generator */
+        let generator_t = poly(
+            GENERATOR,
+            vec![ty_tp(T.clone()), ty_tp(U.clone()), ty_tp(R.clone())],
+        );
+        let mut generator = Self::builtin_poly_class(
+            GENERATOR,
+            vec![PS::t_nd(TY_T), PS::t_nd(TY_U), PS::t_nd(TY_R)],
+            2,
+        );
+        generator.register_superclass(Obj, &obj);
+        generator.register_marker_trait(poly(ITERABLE, vec![ty_tp(T.clone())]));
+        generator.register_marker_trait(poly(OUTPUT, vec![ty_tp(T.clone())]));
+        generator.register_marker_trait(poly(OUTPUT, vec![ty_tp(U.clone())]));
+        generator.register_marker_trait(poly(OUTPUT, vec![ty_tp(R.clone())]));
+        let t = pr_met(
+            generator_t.clone(),
+            vec![kw(KW_VALUE, U.clone())],
+            None,
+            vec![],
+            T.clone(),
+        )
+        .quantify();
+        generator.register_builtin_py_impl(PROC_SEND, t, Immutable, Public, Some(FUNC_SEND));
+        let t = pr0_met(generator_t.clone(), NoneType).quantify();
+        generator.register_builtin_py_impl(PROC_CLOSE, t, Immutable, Public, Some(FUNC_CLOSE));
         let mut obj_mut = Self::builtin_mono_class(MUTABLE_OBJ, 2);
         obj_mut.register_superclass(Obj, &obj);
         let mut obj_mut_mutable = Self::builtin_methods(Some(mono(MUTABLE)), 2);
@@ -1414,10 +1447,13 @@ impl Context {
         g_callable.register_superclass(Obj, &obj);
         let t_return = fn1_met(mono(GENERIC_CALLABLE), Obj, Never).quantify();
         g_callable.register_builtin_erg_impl(FUNC_RETURN, t_return, Immutable, Public);
+        // the types of these are refined by the lowerer (only available in generator functions)
+        let t_yield = fn1_met(mono(GENERIC_CALLABLE), Obj, Obj).quantify();
+        g_callable.register_builtin_erg_impl(FUNC_YIELD, t_yield, Immutable, Public);
+        let t_yield_from = fn1_met(mono(GENERIC_CALLABLE), Obj, Obj).quantify();
+        g_callable.register_builtin_erg_impl(FUNC_YIELD_FROM, t_yield_from, Immutable, Public);
         let mut g_generator = Self::builtin_mono_class(GENERIC_GENERATOR, 2);
         g_generator.register_superclass(mono(GENERIC_CALLABLE), &g_callable);
-        let t_yield = fn1_met(mono(GENERIC_GENERATOR), Obj, Never).quantify();
-        g_generator.register_builtin_erg_impl(FUNC_YIELD, t_yield, Immutable, Public);
         /* Proc */
         let mut proc = Self::builtin_mono_class(PROC, 2);
         proc.register_superclass(mono(GENERIC_CALLABLE), &g_callable);
@@ -1434,6 +1470,47 @@ impl Context {
         quant.register_superclass(mono(PROC), &proc);
         let mut qfunc = Self::builtin_mono_class(QUANTIFIED_FUNC, 2);
         qfunc.register_superclass(mono(FUNC), &func);
+        /* Exceptions */
+        let base_exception = Self::builtin_exception_class(BASE_EXCEPTION, Obj, &obj);
+        let exception =
+            Self::builtin_exception_class(EXCEPTION, mono(BASE_EXCEPTION), &base_exception);
+        let arithmetic_error =
+            Self::builtin_exception_class(ARITHMETIC_ERROR, mono(EXCEPTION), &exception);
+        let zero_division_error = Self::builtin_exception_class(
+            ZERO_DIVISION_ERROR,
+            mono(ARITHMETIC_ERROR),
+            &arithmetic_error,
+        );
+        let assertion_error =
+            Self::builtin_exception_class(ASSERTION_ERROR, mono(EXCEPTION), &exception);
+        let attribute_error =
+            Self::builtin_exception_class(ATTRIBUTE_ERROR, mono(EXCEPTION), &exception);
+        let lookup_error = Self::builtin_exception_class(LOOKUP_ERROR, mono(EXCEPTION), &exception);
+        let index_error =
+            Self::builtin_exception_class(INDEX_ERROR, mono(LOOKUP_ERROR), &lookup_error);
+        let key_error = Self::builtin_exception_class(KEY_ERROR, mono(LOOKUP_ERROR), &lookup_error);
+        let name_error = Self::builtin_exception_class(NAME_ERROR, mono(EXCEPTION), &exception);
+        let os_error = Self::builtin_exception_class(OS_ERROR, mono(EXCEPTION), &exception);
+        let file_not_found_error =
+            Self::builtin_exception_class(FILE_NOT_FOUND_ERROR, mono(OS_ERROR), &os_error);
+        let runtime_error =
+            Self::builtin_exception_class(RUNTIME_ERROR, mono(EXCEPTION), &exception);
+        let not_implemented_error = Self::builtin_exception_class(
+            NOT_IMPLEMENTED_ERROR,
+            mono(RUNTIME_ERROR),
+            &runtime_error,
+        );
+        let stop_iteration =
+            Self::builtin_exception_class(STOP_ITERATION, mono(EXCEPTION), &exception);
+        let type_error = Self::builtin_exception_class(TYPE_ERROR, mono(EXCEPTION), &exception);
+        let value_error = Self::builtin_exception_class(VALUE_ERROR, mono(EXCEPTION), &exception);
+        let keyboard_interrupt = Self::builtin_exception_class(
+            KEYBOARD_INTERRUPT,
+            mono(BASE_EXCEPTION),
+            &base_exception,
+        );
+        let system_exit =
+            Self::builtin_exception_class(SYSTEM_EXIT, mono(BASE_EXCEPTION), &base_exception);
         self.register_builtin_type(Never, never, vis, Const, Some(NEVER));
         self.register_builtin_type(Obj, obj, vis, Const, Some(FUNC_OBJECT));
         // self.register_type(mono(RECORD), vec![], record, Private, Const);
@@ -1531,12 +1608,20 @@ impl Context {
             Some(FUNC_REVERSED),
         );
         self.register_builtin_type(
-            poly(ZIP, vec![ty_tp(T), ty_tp(U)]),
+            poly(ZIP, vec![ty_tp(T.clone()), ty_tp(U)]),
             zip,
             Private,
             Const,
             Some(FUNC_ZIP),
         );
+        self.register_builtin_type(
+            poly(COROUTINE, vec![ty_tp(T)]),
+            coroutine,
+            vis,
+            Const,
+            Some(COROUTINE),
+        );
+        self.register_builtin_type(generator_t, generator, vis, Const, Some(GENERATOR));
         self.register_builtin_type(mono(MUT_FILE), file_mut, vis, Const, Some(FILE));
         self.register_builtin_type(array_mut_t, array_mut_, vis, Const, Some(FUNC_LIST));
         self.register_builtin_type(set_mut_t, set_mut_, vis, Const, Some(FUNC_SET));
@@ -1557,6 +1642,109 @@ impl Context {
         self.register_builtin_type(mono(PROC), proc, vis, Const, Some(PROC));
         self.register_builtin_type(mono(FUNC), func, vis, Const, Some(FUNC));
         self.register_builtin_type(range_t, range, vis, Const, Some(FUNC_RANGE));
+        self.register_builtin_type(
+            mono(BASE_EXCEPTION),
+            base_exception,
+            vis,
+            Const,
+            Some(BASE_EXCEPTION),
+        );
+        self.register_builtin_type(mono(EXCEPTION), exception, vis, Const, Some(EXCEPTION));
+        self.register_builtin_type(
+            mono(ARITHMETIC_ERROR),
+            arithmetic_error,
+            vis,
+            Const,
+            Some(ARITHMETIC_ERROR),
+        );
+        self.register_builtin_type(
+            mono(ZERO_DIVISION_ERROR),
+            zero_division_error,
+            vis,
+            Const,
+            Some(ZERO_DIVISION_ERROR),
+        );
+        self.register_builtin_type(
+            mono(ASSERTION_ERROR),
+            assertion_error,
+            vis,
+            Const,
+            Some(ASSERTION_ERROR),
+        );
+        self.register_builtin_type(
+            mono(ATTRIBUTE_ERROR),
+            attribute_error,
+            vis,
+            Const,
+            Some(ATTRIBUTE_ERROR),
+        );
+        self.register_builtin_type(
+            mono(LOOKUP_ERROR),
+            lookup_error,
+            vis,
+            Const,
+            Some(LOOKUP_ERROR),
+        );
+        self.register_builtin_type(
+            mono(INDEX_ERROR),
+            index_error,
+            vis,
+            Const,
+            Some(INDEX_ERROR),
+        );
+        self.register_builtin_type(mono(KEY_ERROR), key_error, vis, Const, Some(KEY_ERROR));
+        self.register_builtin_type(mono(NAME_ERROR), name_error, vis, Const, Some(NAME_ERROR));
+        self.register_builtin_type(mono(OS_ERROR), os_error, vis, Const, Some(OS_ERROR));
+        self.register_builtin_type(
+            mono(FILE_NOT_FOUND_ERROR),
+            file_not_found_error,
+            vis,
+            Const,
+            Some(FILE_NOT_FOUND_ERROR),
+        );
+        self.register_builtin_type(
+            mono(RUNTIME_ERROR),
+            runtime_error,
+            vis,
+            Const,
+            Some(RUNTIME_ERROR),
+        );
+        self.register_builtin_type(
+            mono(NOT_IMPLEMENTED_ERROR),
+            not_implemented_error,
+            vis,
+            Const,
+            Some(NOT_IMPLEMENTED_ERROR),
+        );
+        self.register_builtin_type(
+            mono(STOP_ITERATION),
+            stop_iteration,
+            vis,
+            Const,
+            Some(STOP_ITERATION),
+        );
+        self.register_builtin_type(mono(TYPE_ERROR), type_error, vis, Const, Some(TYPE_ERROR));
+        self.register_builtin_type(
+            mono(VALUE_ERROR),
+            value_error,
+            vis,
+            Const,
+            Some(VALUE_ERROR),
+        );
+        self.register_builtin_type(
+            mono(KEYBOARD_INTERRUPT),
+            keyboard_interrupt,
+            vis,
+            Const,
+            Some(KEYBOARD_INTERRUPT),
+        );
+        self.register_builtin_type(
+            mono(SYSTEM_EXIT),
+            system_exit,
+            vis,
+            Const,
+            Some(SYSTEM_EXIT),
+        );
         if !cfg!(feature = "py_compatible") {
             self.register_builtin_type(module_t, module, vis, Const, Some(MODULE_TYPE));
             self.register_builtin_type(mono(MUTABLE_OBJ), obj_mut, vis, Const, Some(FUNC_OBJECT));
@@ -1590,4 +1778,13 @@ impl Context {
             );
         }
     }
+
+    /// Python's built-in exception classes can be called like `ValueError "message"`
+    fn builtin_exception_class(name: &'static str, sup: Type, sup_ctx: &Self) -> Self {
+        let mut exc = Self::builtin_mono_class(name, 2);
+        exc.register_superclass(sup, sup_ctx);
+        let t_call = func(vec![], Some(anon(Obj)), vec![], mono(name));
+        exc.register_builtin_erg_impl(FUNDAMENTAL_CALL, t_call, Immutable, Private);
+        exc
+    }
 }
@@ -40,6 +40,7 @@ pub fn class_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueOb
                     ErrorKind::TypeError,
                     Location::Unknown,
                 )
+                .with_code("E0082")
                 .into())
             }
         }
@@ -58,6 +59,7 @@ pub fn inherit_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<Value
             ErrorKind::KeyError,
             Location::Unknown,
         )
+        .with_code("E0081")
     })?;
     let Some(sup) = sup.as_type() else {
         let sup_ty = StyledString::new(format!("{sup}"), Some(ERR), None);
@@ -69,7 +71,9 @@ pub fn inherit_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<Value
             line!() as usize,
             ErrorKind::TypeError,
             Location::Unknown,
-        ).into());
+        )
+        .with_code("E0082")
+        .into());
     };
     let impls = args.remove_left_or_key("Impl");
     let impls = impls.map(|v| v.as_type().unwrap());
@@ -92,6 +96,7 @@ pub fn inheritable_func(mut args: ValueArgs, _ctx: &Context) -> EvalValueResult<
             ErrorKind::KeyError,
             Location::Unknown,
         )
+        .with_code("E0081")
     })?;
     match class {
         ValueObj::Type(TypeObj::Generated(mut gen)) => {
@@ -129,6 +134,7 @@ pub fn trait_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueOb
             ErrorKind::KeyError,
             Location::Unknown,
         )
+        .with_code("E0081")
     })?;
     let Some(req) = req.as_type() else {
         let req = StyledString::new(format!("{req}"), Some(ERR), None);
@@ -140,7 +146,9 @@ pub fn trait_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueOb
             line!() as usize,
             ErrorKind::TypeError,
             Location::Unknown,
-        ).into());
+        )
+        .with_code("E0082")
+        .into());
     };
     let impls = args.remove_left_or_key("Impl");
     let impls = impls.map(|v| v.as_type().unwrap());
@@ -158,6 +166,7 @@ pub fn patch_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueOb
             ErrorKind::KeyError,
             Location::Unknown,
         )
+        .with_code("E0081")
     })?;
     let Some(base) = base.as_type() else {
         let base = StyledString::new(format!("{base}"), Some(ERR), None);
@@ -169,7 +178,9 @@ pub fn patch_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueOb
             line!() as usize,
             ErrorKind::TypeError,
             Location::Unknown,
-        ).into());
+        )
+        .with_code("E0082")
+        .into());
     };
     let impls = args.remove_left_or_key("Impl");
     let impls = impls.map(|v| v.as_type().unwrap());
@@ -187,6 +198,7 @@ pub fn subsume_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<Value
             ErrorKind::KeyError,
             Location::Unknown,
         )
+        .with_code("E0081")
     })?;
     let Some(sup) = sup.as_type() else {
         let sup = StyledString::new(format!("{sup}"), Some(ERR), None);
@@ -198,7 +210,9 @@ pub fn subsume_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<Value
             line!() as usize,
             ErrorKind::TypeError,
             Location::Unknown,
-        ).into());
+        )
+        .with_code("E0082")
+        .into());
     };
     let impls = args.remove_left_or_key("Impl");
     let impls = impls.map(|v| v.as_type().unwrap());
@@ -230,6 +244,7 @@ pub fn __array_getitem__(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<
             ErrorKind::IndexError,
             Location::Unknown,
         )
+        .with_code("E0083")
         .into())
     }
 }
@@ -270,6 +285,7 @@ pub fn __dict_getitem__(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<V
             ErrorKind::IndexError,
             Location::Unknown,
         )
+        .with_code("E0084")
         .into())
     }
 }
@@ -295,6 +311,7 @@ pub fn __range_getitem__(mut args: ValueArgs, _ctx: &Context) -> EvalValueResult
             ErrorKind::IndexError,
             Location::Unknown,
         )
+        .with_code("E0083")
         .into())
     }
 }
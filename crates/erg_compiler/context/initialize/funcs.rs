@@ -161,6 +161,7 @@ impl Context {
         let t_oct = nd_func(vec![kw(KW_X, Int)], None, Str);
         let t_ord = nd_func(vec![kw(KW_C, Str)], None, Nat);
         let t_panic = nd_func(vec![kw(KW_MSG, Str)], None, Never);
+        let t_raise = nd_func(vec![kw(KW_EXC, mono(BASE_EXCEPTION))], None, Never);
         let M = mono_q(TY_M, Constraint::Uninited);
         let M = mono_q(TY_M, subtypeof(poly(MUL, vec![ty_tp(M)])));
         // TODO: mod
@@ -305,7 +306,8 @@ impl Context {
             );
             self.register_builtin_py_impl(FUNC_LOG, t_log, Immutable, vis, Some(FUNC_PRINT));
             self.register_builtin_py_impl(FUNC_NAT, t_nat, Immutable, vis, Some(FUNC_NAT__));
-            self.register_builtin_py_impl(FUNC_PANIC, t_panic, Immutable, vis, Some(FUNC_QUIT));
+            self.register_builtin_py_impl(FUNC_PANIC, t_panic, Immutable, vis, Some(FUNC_PANIC__));
+            self.register_builtin_py_impl(FUNC_RAISE, t_raise, Immutable, vis, Some(FUNC_RAISE__));
             if cfg!(feature = "debug") {
                 self.register_builtin_py_impl(
                     PY,
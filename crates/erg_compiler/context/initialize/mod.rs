@@ -83,6 +83,8 @@ const ITERATOR: &str = "Iterator";
 const STR_ITERATOR: &str = "StrIterator";
 const FUNC_ITER: &str = "iter";
 const ITER: &str = "Iter";
+const AWAITABLE: &str = "Awaitable";
+const COROUTINE: &str = "Coroutine";
 const ADD: &str = "Add";
 const SUB: &str = "Sub";
 const MUL: &str = "Mul";
@@ -228,6 +230,7 @@ const GENERIC_CALLABLE: &str = "GenericCallable";
 const GENERIC_GENERATOR: &str = "GenericGenerator";
 const FUNC_RETURN: &str = "return";
 const FUNC_YIELD: &str = "yield";
+const FUNC_YIELD_FROM: &str = "yield_from";
 const PROC: &str = "Proc";
 const NAMED_PROC: &str = "NamedProc";
 const NAMED_FUNC: &str = "NamedFunc";
@@ -256,9 +259,32 @@ const FUNC_FILTER: &str = "filter";
 const FUNC_MAP: &str = "map";
 const FUNC_REVERSED: &str = "reversed";
 const FUNC_ZIP: &str = "zip";
+const PROC_CLOSE: &str = "close!";
+const FUNC_CLOSE: &str = "close";
+const PROC_SEND: &str = "send!";
+const FUNC_SEND: &str = "send";
 const FILE: &str = "File";
 const CALLABLE: &str = "Callable";
 const GENERATOR: &str = "Generator";
+const BASE_EXCEPTION: &str = "BaseException";
+const EXCEPTION: &str = "Exception";
+const ARITHMETIC_ERROR: &str = "ArithmeticError";
+const ZERO_DIVISION_ERROR: &str = "ZeroDivisionError";
+const ASSERTION_ERROR: &str = "AssertionError";
+const ATTRIBUTE_ERROR: &str = "AttributeError";
+const LOOKUP_ERROR: &str = "LookupError";
+const INDEX_ERROR: &str = "IndexError";
+const KEY_ERROR: &str = "KeyError";
+const NAME_ERROR: &str = "NameError";
+const OS_ERROR: &str = "OSError";
+const FILE_NOT_FOUND_ERROR: &str = "FileNotFoundError";
+const RUNTIME_ERROR: &str = "RuntimeError";
+const NOT_IMPLEMENTED_ERROR: &str = "NotImplementedError";
+const STOP_ITERATION: &str = "StopIteration";
+const TYPE_ERROR: &str = "TypeError";
+const VALUE_ERROR: &str = "ValueError";
+const KEYBOARD_INTERRUPT: &str = "KeyboardInterrupt";
+const SYSTEM_EXIT: &str = "SystemExit";
 const FUNC_RANGE: &str = "range";
 const FUNC_ALL: &str = "all";
 const FUNC_ANY: &str = "any";
@@ -293,6 +319,9 @@ const FUNC_PRINT: &str = "print";
 const FUNC_NAT: &str = "nat";
 const FUNC_NAT__: &str = "nat__";
 const FUNC_PANIC: &str = "panic";
+const FUNC_PANIC__: &str = "panic__";
+const FUNC_RAISE: &str = "raise";
+const FUNC_RAISE__: &str = "raise__";
 const FUNC_UNREACHABLE: &str = "unreachable";
 const SUBSUME: &str = "Subsume";
 const INHERIT: &str = "Inherit";
@@ -331,6 +360,7 @@ const OP_POS: &str = "__pos__";
 const OP_NEG: &str = "__neg__";
 
 const FUNDAMENTAL_NAME: &str = "__name__";
+const FUNDAMENTAL_CALL: &str = "__call__";
 const FUNDAMENTAL_STR: &str = "__str__";
 const FUNDAMENTAL_ITER: &str = "__iter__";
 const FUNDAMENTAL_MODULE: &str = "__module__";
@@ -398,12 +428,14 @@ const KW_OBJECT: &str = "object";
 const KW_OBJECTS: &str = "objects";
 const KW_TEST: &str = "test";
 const KW_MSG: &str = "msg";
+const KW_EXC: &str = "exc";
 const KW_STR: &str = "str";
 const KW_I: &str = "i";
 const KW_SRC: &str = "src";
 const KW_THEN: &str = "then";
 const KW_ELSE: &str = "else";
 const KW_OBJ: &str = "obj";
+const KW_VALUE: &str = "value";
 const KW_START: &str = "start";
 const KW_COND: &str = "cond";
 const KW_CLASSINFO: &str = "classinfo";
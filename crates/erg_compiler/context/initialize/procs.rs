@@ -109,12 +109,18 @@ impl Context {
         let t_with = nd_proc(
             vec![
                 kw("obj", T.clone()),
-                kw("proc!", nd_proc(vec![anon(T)], None, U.clone())),
+                kw("proc!", nd_proc(vec![anon(T.clone())], None, U.clone())),
             ],
             None,
             U,
         )
         .quantify();
+        let t_await = nd_proc(
+            vec![kw("aw", poly("Awaitable", vec![ty_tp(T.clone())]))],
+            None,
+            T,
+        )
+        .quantify();
         self.register_builtin_py_impl("dir!", t_dir, Immutable, vis, Some("dir"));
         self.register_py_builtin("print!", t_print, Some("print"), 81);
         self.register_builtin_py_impl("id!", t_id, Immutable, vis, Some("id"));
@@ -147,5 +153,11 @@ impl Context {
             "with__"
         };
         self.register_builtin_py_impl("with!", t_with, Immutable, vis, Some(name));
+        let name = if cfg!(feature = "py_compatible") {
+            "await"
+        } else {
+            "await__"
+        };
+        self.register_builtin_py_impl("await!", t_await, Immutable, vis, Some(name));
     }
 }
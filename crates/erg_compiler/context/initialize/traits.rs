@@ -115,6 +115,9 @@ impl Context {
         let t = fn0_met(Slf.clone(), proj(Slf, ITER)).quantify();
         iterable.register_builtin_py_decl(FUNC_ITER, t, Public, Some(FUNDAMENTAL_ITER));
         iterable.register_builtin_erg_decl(ITER, Type, Public);
+        /* Awaitable */
+        let mut awaitable = Self::builtin_poly_trait(AWAITABLE, vec![PS::t(TY_T, NonDefault)], 2);
+        awaitable.register_superclass(poly(OUTPUT, vec![ty_tp(T.clone())]), &output);
         let R = mono_q(TY_R, instanceof(Type));
         let params = vec![PS::t(TY_R, WithDefault)];
         let ty_params = vec![ty_tp(R.clone())];
@@ -204,12 +207,13 @@ impl Context {
         self.register_builtin_type(mono(NUM), num, vis, Const, None);
         self.register_builtin_type(poly(SEQ, vec![ty_tp(T.clone())]), seq, Private, Const, None);
         self.register_builtin_type(
-            poly(ITERABLE, vec![ty_tp(T)]),
+            poly(ITERABLE, vec![ty_tp(T.clone())]),
             iterable,
             Private,
             Const,
             None,
         );
+        self.register_builtin_type(poly(AWAITABLE, vec![ty_tp(T)]), awaitable, vis, Const, None);
         self.register_builtin_type(poly(ADD, ty_params.clone()), add, vis, Const, None);
         self.register_builtin_type(poly(SUB, ty_params.clone()), sub, vis, Const, None);
         self.register_builtin_type(poly(MUL, ty_params.clone()), mul, vis, Const, None);
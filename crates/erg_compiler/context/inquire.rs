@@ -19,7 +19,9 @@ use ast::VarName;
 use erg_parser::ast::{self, Identifier};
 use erg_parser::token::Token;
 
-use crate::ty::constructors::{anon, free_var, func, mono, poly, proc, proj, ref_, subr_t};
+use crate::ty::constructors::{
+    anon, free_var, func, mono, nd_proc, poly, proc, proj, ref_, subr_t,
+};
 use crate::ty::free::Constraint;
 use crate::ty::typaram::TyParam;
 use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
@@ -31,6 +33,7 @@ use crate::error::{
     binop_to_dname, readable_name, unaryop_to_dname, SingleTyCheckResult, TyCheckError,
     TyCheckErrors, TyCheckResult,
 };
+use crate::module::package::resolve_dependency;
 use crate::varinfo::{AbsLocation, Mutability, VarInfo, VarKind};
 use crate::AccessKind;
 use crate::{feature_error, hir};
@@ -329,6 +332,169 @@ impl Context {
         })
     }
 
+    /// `try!` is also a special form.
+    /// Each `except` arm is a lambda that takes an exception (a subtype of `BaseException`),
+    /// and the return type is the union of the body's and the arms' return types.
+    fn get_try_call_t(
+        &self,
+        pos_args: &[hir::PosArg],
+        kw_args: &[hir::KwArg],
+    ) -> TyCheckResult<VarInfo> {
+        if !kw_args.is_empty() {
+            return Err(TyCheckErrors::from(TyCheckError::default_param_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                kw_args[0].loc(),
+                self.caused_by(),
+                "try!",
+            )));
+        }
+        if pos_args.len() < 2 {
+            return Err(TyCheckErrors::from(TyCheckError::args_missing_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                pos_args.first().map_or(Location::Unknown, |arg| arg.loc()),
+                "try!",
+                self.caused_by(),
+                vec![Str::ever("except")],
+            )));
+        }
+        let body_t = pos_args[0].expr.ref_t();
+        let nullary = body_t
+            .non_default_params()
+            .is_some_and(|params| params.is_empty());
+        let Some(mut return_t) = body_t.return_t().filter(|_| nullary).cloned() else {
+            let expect = nd_proc(vec![], None, Obj);
+            return Err(TyCheckErrors::from(TyCheckError::type_mismatch_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                pos_args[0].loc(),
+                self.caused_by(),
+                "try!",
+                Some(1),
+                &expect,
+                body_t,
+                self.get_candidates(body_t),
+                None,
+            )));
+        };
+        let base_exception = mono("BaseException");
+        for pos_arg in pos_args.iter().skip(1) {
+            let t = pos_arg.expr.ref_t();
+            let hir::Expr::Lambda(lambda) = &pos_arg.expr else {
+                return Err(TyCheckErrors::from(TyCheckError::type_mismatch_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    pos_arg.loc(),
+                    self.caused_by(),
+                    "try!",
+                    None,
+                    &mono("LambdaFunc"),
+                    t,
+                    self.get_candidates(t),
+                    self.get_simple_type_mismatch_hint(&mono("LambdaFunc"), t),
+                )));
+            };
+            if !lambda.params.defaults.is_empty() {
+                return Err(TyCheckErrors::from(TyCheckError::default_param_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    pos_arg.loc(),
+                    self.caused_by(),
+                    "try!",
+                )));
+            }
+            if lambda.params.len() != 1 {
+                return Err(TyCheckErrors::from(TyCheckError::param_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    pos_arg.loc(),
+                    self.caused_by(),
+                    1,
+                    lambda.params.len(),
+                )));
+            }
+            let param = &lambda.params.non_defaults[0];
+            // an arm without a type specification catches any exception
+            if param.raw.t_spec.is_some()
+                && self
+                    .sub_unify(&param.vi.t, &base_exception, pos_arg, None)
+                    .is_err()
+            {
+                return Err(TyCheckErrors::from(TyCheckError::type_mismatch_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    param.loc(),
+                    self.caused_by(),
+                    "try!",
+                    None,
+                    &base_exception,
+                    &param.vi.t,
+                    self.get_candidates(&param.vi.t),
+                    None,
+                )));
+            }
+            return_t = self.union(&return_t, t.return_t().unwrap_or(&Type::Never));
+        }
+        let param_ts = pos_args
+            .iter()
+            .map(|a| ParamTy::anonymous(a.expr.ref_t().clone()))
+            .collect::<Vec<_>>();
+        Ok(VarInfo {
+            t: proc(param_ts, None, vec![], return_t),
+            ..VarInfo::default()
+        })
+    }
+
+    /// `await!` can only be used in procedures decorated with `@Async` (including the blocks and variable definitions in them)
+    fn check_in_async_proc(&self, local: &hir::Identifier) -> TyCheckResult<()> {
+        let mut ctx = self;
+        while let Some(outer) = ctx.get_outer() {
+            let name = ctx.name.rsplit([':', '.']).next().unwrap_or(&ctx.name);
+            if name.starts_with("<lambda") {
+                // blocks passed to `if!`, `for!`, etc. are inlined into the enclosing procedure,
+                // but other lambdas are compiled as separate (non-coroutine) functions
+                let is_inlined_block = outer.higher_order_caller.last().is_some_and(|caller| {
+                    matches!(
+                        &caller[..],
+                        "if" | "if!"
+                            | "for"
+                            | "for!"
+                            | "while!"
+                            | "match"
+                            | "match!"
+                            | "try!"
+                            | "with!"
+                    )
+                });
+                if !is_inlined_block {
+                    break;
+                }
+                ctx = outer;
+                continue;
+            }
+            let vi = outer.decls.get(name).or_else(|| outer.locals.get(name));
+            if vi
+                .and_then(|vi| vi.comptime_decos.as_ref())
+                .is_some_and(|decos| decos.contains("Async"))
+            {
+                return Ok(());
+            } else if vi.is_some_and(|vi| vi.t.is_subr()) {
+                break;
+            }
+            // variable definitions
+            ctx = outer;
+        }
+        Err(TyCheckErrors::from(
+            TyCheckError::await_outside_async_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                local.loc(),
+                self.caused_by(),
+            ),
+        ))
+    }
+
     pub(crate) fn rec_get_var_info(
         &self,
         ident: &Identifier,
@@ -714,6 +880,7 @@ impl Context {
                     vec![],
                     Some(ParamTy::pos(None, ref_(Obj))),
                     vec![],
+                    Some(ParamTy::pos(None, ref_(Obj))),
                     Failure,
                 )),
                 ..VarInfo::default()
@@ -902,13 +1069,14 @@ impl Context {
             for sub_msg in e.core.sub_messages {
                 sub_msges.push(SubMessage::ambiguous_new(loc, sub_msg.msg, sub_msg.hint));
             }
-            let core = ErrorCore::new(
+            let mut core = ErrorCore::new(
                 sub_msges,
                 e.core.main_message,
                 e.core.errno,
                 e.core.kind,
                 e.core.loc,
             );
+            core.code = e.core.code;
             TyCheckError::new(core, self.cfg.input.clone(), e.caused_by)
         } else {
             e
@@ -1072,7 +1240,7 @@ impl Context {
                     };
                     let ret_t = free_var(self.level, Constraint::new_type_of(Type));
                     let non_default_params = pos_args.iter().map(|a| anon(a.expr.t())).collect();
-                    let subr_t = subr_t(kind, non_default_params, None, vec![], ret_t);
+                    let subr_t = subr_t(kind, non_default_params, None, vec![], None, ret_t);
                     fv.link(&subr_t);
                     Ok(None)
                 }
@@ -1097,9 +1265,11 @@ impl Context {
                     obj.clone()
                 };
                 let params_len = subr.non_default_params.len() + subr.default_params.len();
-                if (params_len < pos_args.len() || params_len < pos_args.len() + kw_args.len())
-                    && subr.var_params.is_none()
-                {
+                // extra keyword arguments are passed to the variable-length keyword parameter
+                let too_many_pos_args = params_len < pos_args.len();
+                let too_many_kw_args =
+                    params_len < pos_args.len() + kw_args.len() && subr.kw_var_params.is_none();
+                if (too_many_pos_args || too_many_kw_args) && subr.var_params.is_none() {
                     return Err(self.gen_too_many_args_error(&callee, subr, pos_args, kw_args));
                 }
                 let mut passed_params = set! {};
@@ -1291,6 +1461,7 @@ impl Context {
                     .default_params
                     .iter()
                     .all(|pt| pt.name() != Some(kw_arg.keyword.inspect()))
+                && subr_ty.kw_var_params.is_none()
             {
                 unknown_args.push(kw_arg);
             }
@@ -1473,6 +1644,7 @@ impl Context {
             .iter()
             .chain(subr_ty.default_params.iter())
             .find(|pt| pt.name().as_ref() == Some(&kw_name))
+            .or(subr_ty.kw_var_params.as_deref())
         {
             let param_t = pt.typ();
             passed_params.insert(kw_name.clone());
@@ -1538,6 +1710,12 @@ impl Context {
                     "match!" => {
                         return self.get_match_call_t(SubrKind::Proc, pos_args, kw_args);
                     }
+                    "try!" => {
+                        return self.get_try_call_t(pos_args, kw_args);
+                    }
+                    "await!" => {
+                        self.check_in_async_proc(local)?;
+                    }
                     _ => {}
                 }
             }
@@ -2072,6 +2250,8 @@ impl Context {
     pub(crate) fn resolve_real_path(cfg: &ErgConfig, path: &Path) -> Option<PathBuf> {
         if let Ok(path) = cfg.input.local_resolve(path) {
             Some(path)
+        } else if let Some(path) = resolve_dependency(&cfg.input, path) {
+            Some(path)
         } else if let Ok(path) = erg_std_path()
             .join(format!("{}.er", path.display()))
             .canonicalize()
@@ -2103,7 +2283,7 @@ impl Context {
         {
             Some(normalize_path(path))
         } else {
-            None
+            cfg.venv.as_ref().and_then(|venv| venv.resolve_decl(path))
         }
     }
 
@@ -2350,6 +2530,7 @@ impl Context {
                                 subr.non_default_params.clone(),
                                 subr.var_params.as_ref().map(|p| *p.clone()),
                                 subr.default_params.clone(),
+                                subr.kw_var_params.as_ref().map(|p| *p.clone()),
                                 ret_t,
                             );
                             Type::Subr(subr)
@@ -3,6 +3,7 @@ use std::mem;
 use std::option::Option; // conflicting to Type::Option
 
 use erg_common::dict::Dict;
+use erg_common::error::Location;
 #[allow(unused)]
 use erg_common::log;
 use erg_common::set::Set;
@@ -24,7 +25,7 @@ use crate::ty::free::CanbeFree;
 use crate::ty::free::{Constraint, HasLevel};
 use crate::ty::typaram::{IntervalOp, TyParam, TyParamOrdering};
 use crate::ty::value::ValueObj;
-use crate::ty::{HasType, ParamTy, Predicate, SubrKind, Type};
+use crate::ty::{ConstSubr, HasType, ParamTy, Predicate, SubrKind, Type, ValueArgs};
 use crate::type_feature_error;
 use crate::unreachable_error;
 use TyParamOrdering::*;
@@ -330,6 +331,27 @@ impl Context {
                 }
             }
         }
+        let kw_var_args = if let Some(kw_var_args) = sig.params.kw_var_params.as_ref() {
+            let opt_decl_t = opt_decl_sig_t
+                .as_ref()
+                .and_then(|subr| subr.kw_var_params.as_ref().map(|v| v.as_ref()));
+            let pt = match self.instantiate_param_ty(
+                kw_var_args,
+                opt_decl_t,
+                &mut tmp_tv_cache,
+                mode,
+                ParamKind::KwParams,
+            ) {
+                Ok(pt) => pt,
+                Err(es) => {
+                    errs.extend(es);
+                    ParamTy::pos(kw_var_args.inspect().cloned(), Type::Failure)
+                }
+            };
+            Some(pt)
+        } else {
+            None
+        };
         let spec_return_t = if let Some(t_spec) = sig.return_t_spec.as_ref() {
             let opt_decl_t = opt_decl_sig_t
                 .as_ref()
@@ -356,11 +378,19 @@ impl Context {
             };
             free_var(level, Constraint::new_type_of(Type))
         };
-        let typ = if sig.ident.is_procedural() {
-            proc(non_defaults, var_args, defaults, spec_return_t)
+        let kind = if sig.ident.is_procedural() {
+            SubrKind::Proc
         } else {
-            func(non_defaults, var_args, defaults, spec_return_t)
+            SubrKind::Func
         };
+        let typ = subr_t(
+            kind,
+            non_defaults,
+            var_args,
+            defaults,
+            kw_var_args,
+            spec_return_t,
+        );
         if errs.is_empty() {
             Ok(typ)
         } else {
@@ -407,6 +437,14 @@ impl Context {
                     &sig.t_spec.as_ref().ok_or(sig),
                     None,
                 )?;
+            } else if kind.is_kw_params() {
+                let spec_t = str_dict_t(spec_t.clone());
+                self.sub_unify(
+                    decl_pt.typ(),
+                    &spec_t,
+                    &sig.t_spec.as_ref().ok_or(sig),
+                    None,
+                )?;
             } else {
                 self.sub_unify(
                     decl_pt.typ(),
@@ -584,6 +622,9 @@ impl Context {
                 }
                 if let Some((typ, _)) = self.rec_get_type(other) {
                     Ok(typ.clone())
+                } else if let Some(alias) = self.rec_get_const_obj(other).and_then(|v| v.as_type())
+                {
+                    Ok(alias.into_typ())
                 } else if not_found_is_qvar {
                     let tyvar = named_free_var(Str::rc(other), self.level, Constraint::Uninited);
                     tmp_tv_cache.push_or_init_tyvar(&Str::rc(other), &tyvar);
@@ -602,6 +643,14 @@ impl Context {
             other => {
                 let ctx = if let Some((_, ctx)) = self.rec_get_type(other) {
                     ctx
+                } else if let Some(ValueObj::Subr(subr)) = self.rec_get_const_obj(other) {
+                    let val = self.call_const_subr_in_typespec(
+                        subr.clone(),
+                        &simple.args,
+                        simple,
+                        tmp_tv_cache,
+                    )?;
+                    return self.instantiate_tp_as_type(TyParam::Value(val), simple);
                 } else {
                     return Err(TyCheckErrors::from(TyCheckError::no_type_error(
                         self.cfg.input.clone(),
@@ -721,6 +770,37 @@ impl Context {
                 }
                 Ok(TyParam::Tuple(tp_tuple))
             }
+            ast::ConstExpr::App(app) => {
+                let ast::ConstAccessor::Local(local) = &app.acc else {
+                    return type_feature_error!(
+                        self,
+                        app.loc(),
+                        &format!("instantiating const expression {}", app.acc)
+                    );
+                };
+                self.inc_ref_const_local(local);
+                let Some(ValueObj::Subr(subr)) = self.rec_get_const_obj(local.inspect()) else {
+                    return type_feature_error!(
+                        self,
+                        app.loc(),
+                        &format!("instantiating const expression {}", app.acc)
+                    );
+                };
+                let val =
+                    self.call_const_subr_in_typespec(subr.clone(), &app.args, app, tmp_tv_cache)?;
+                Ok(TyParam::Value(val))
+            }
+            ast::ConstExpr::BinOp(bin) => {
+                let lhs = self.instantiate_const_expr(&bin.lhs, erased_idx, tmp_tv_cache)?;
+                let rhs = self.instantiate_const_expr(&bin.rhs, erased_idx, tmp_tv_cache)?;
+                let op = self.try_get_op_kind_from_token(&bin.op)?;
+                self.eval_bin_tp(op, lhs, rhs)
+            }
+            ast::ConstExpr::UnaryOp(unary) => {
+                let val = self.instantiate_const_expr(&unary.expr, erased_idx, tmp_tv_cache)?;
+                let op = self.try_get_op_kind_from_token(&unary.op)?;
+                self.eval_unary_tp(op, val)
+            }
             other => type_feature_error!(
                 self,
                 other.loc(),
@@ -729,6 +809,45 @@ impl Context {
         }
     }
 
+    /// e.g. `Array(Int, Add1(2))`, `p: Point2D(Int)`
+    /// All the arguments must be evaluable at compile time.
+    fn call_const_subr_in_typespec(
+        &self,
+        subr: ConstSubr,
+        args: &ast::ConstArgs,
+        loc: &impl Locational,
+        tmp_tv_cache: &mut TyVarCache,
+    ) -> TyCheckResult<ValueObj> {
+        let mut pos_args = vec![];
+        for arg in args.pos_args() {
+            let tp = self.instantiate_const_expr(&arg.expr, None, tmp_tv_cache)?;
+            pos_args.push(self.convert_tp_into_value(tp, &arg.expr)?);
+        }
+        let mut kw_args = dict! {};
+        for arg in args.kw_args() {
+            let tp = self.instantiate_const_expr(&arg.expr, None, tmp_tv_cache)?;
+            let val = self.convert_tp_into_value(tp, &arg.expr)?;
+            kw_args.insert(arg.keyword.content.clone(), val);
+        }
+        self.call(subr, ValueArgs::new(pos_args, kw_args), loc.loc())
+    }
+
+    fn convert_tp_into_value(&self, tp: TyParam, loc: &impl Locational) -> TyCheckResult<ValueObj> {
+        match tp {
+            TyParam::FreeVar(fv) if fv.is_linked() => {
+                self.convert_tp_into_value(fv.crack().clone(), loc)
+            }
+            TyParam::Value(val) => Ok(val),
+            TyParam::Type(t) => Ok(ValueObj::builtin_t(*t)),
+            _ => Err(TyCheckErrors::from(TyCheckError::not_const_expr(
+                self.cfg.input.clone(),
+                line!() as usize,
+                loc.loc(),
+                self.caused_by(),
+            ))),
+        }
+    }
+
     pub(crate) fn instantiate_const_expr_as_type(
         &self,
         expr: &ast::ConstExpr,
@@ -746,6 +865,10 @@ impl Context {
             }
             TyParam::Type(t) => Ok(*t),
             TyParam::Value(ValueObj::Type(t)) => Ok(t.into_typ()),
+            // e.g. `{x = Int; y = Int}`
+            TyParam::Value(val @ ValueObj::Record(_)) if val.as_type().is_some() => {
+                Ok(val.as_type().unwrap().into_typ())
+            }
             TyParam::Set(set) => {
                 let t = set
                     .iter()
@@ -783,6 +906,65 @@ impl Context {
         }
     }
 
+    /// The locations of array/dict type specs do not include the brackets (`Int; 3` of `[Int; 3]`).
+    fn loc_with_brackets(&self, loc: Location, (l, r): (char, char)) -> Location {
+        if let Location::Range {
+            ln_begin,
+            col_begin,
+            ln_end,
+            col_end,
+        } = loc
+        {
+            let Some(col_begin) = col_begin.checked_sub(1) else {
+                return loc;
+            };
+            let outer = Location::range(ln_begin, col_begin, ln_end, col_end + 1);
+            let code = self.cfg.input.reread_loc(outer).unwrap_or_default();
+            if code.starts_with(l) && code.ends_with(r) {
+                return outer;
+            }
+        }
+        loc
+    }
+
+    fn is_nominal_class(&self, t: &Type) -> bool {
+        t != &Type::Never
+            && !t.is_refinement()
+            && t.has_no_qvar()
+            && t.has_no_unbound_var()
+            && self.is_class(t)
+    }
+
+    /// `{x: Int}` => `{x = Int}` (if the key `x` is not a type)
+    fn dict_as_record_error(
+        &self,
+        t_spec: &TypeSpec,
+        dict: &[(TypeSpec, TypeSpec)],
+        key: &TypeSpec,
+    ) -> Option<TyCheckErrors> {
+        let input = &self.cfg.input;
+        let mut attrs = vec![];
+        for (k, v) in dict {
+            let TypeSpec::PreDeclTy(PreDeclTypeSpec::Simple(simple)) = k else {
+                return None;
+            };
+            if !simple.args.is_empty() {
+                return None;
+            }
+            let v = input.reread_loc(v.loc())?;
+            attrs.push(format!("{} = {v}", simple.ident.inspect()));
+        }
+        let key = input.reread_loc(key.loc())?;
+        Some(TyCheckErrors::from(TyCheckError::dict_key_not_type_error(
+            input.clone(),
+            line!() as usize,
+            self.loc_with_brackets(t_spec.loc(), ('{', '}')),
+            self.caused_by(),
+            &key,
+            &format!("{{{}}}", attrs.join("; ")),
+        )))
+    }
+
     pub(crate) fn instantiate_typespec(
         &self,
         t_spec: &TypeSpec,
@@ -799,22 +981,46 @@ impl Context {
                 tmp_tv_cache,
                 not_found_is_qvar,
             )?),
-            TypeSpec::And(lhs, rhs) => Ok(self.intersection(
-                &self.instantiate_typespec(
+            TypeSpec::And(lhs, rhs) => {
+                let lhs_t = self.instantiate_typespec(
                     lhs,
                     opt_decl_t,
                     tmp_tv_cache,
                     mode,
                     not_found_is_qvar,
-                )?,
-                &self.instantiate_typespec(
+                )?;
+                let rhs_t = self.instantiate_typespec(
                     rhs,
                     opt_decl_t,
                     tmp_tv_cache,
                     mode,
                     not_found_is_qvar,
-                )?,
-            )),
+                )?;
+                let intersec = self.intersection(&lhs_t, &rhs_t);
+                // e.g. `Int and Str` (maybe `Int or Str`)
+                // only intersections of two classes (i.e. multiple inheritance) are rejected
+                if intersec == Type::Never
+                    && self.is_nominal_class(&lhs_t)
+                    && self.is_nominal_class(&rhs_t)
+                {
+                    let input = &self.cfg.input;
+                    let lhs = input
+                        .reread_loc(lhs.loc())
+                        .unwrap_or_else(|| lhs.to_string());
+                    let rhs = input
+                        .reread_loc(rhs.loc())
+                        .unwrap_or_else(|| rhs.to_string());
+                    return Err(TyCheckErrors::from(TyCheckError::empty_intersection_error(
+                        input.clone(),
+                        line!() as usize,
+                        t_spec.loc(),
+                        self.caused_by(),
+                        &lhs,
+                        &rhs,
+                    )));
+                }
+                Ok(intersec)
+            }
             TypeSpec::Or(lhs, rhs) => Ok(self.union(
                 &self.instantiate_typespec(
                     lhs,
@@ -847,6 +1053,24 @@ impl Context {
                     not_found_is_qvar,
                 )?;
                 let mut len = self.instantiate_const_expr(&arr.len, None, tmp_tv_cache)?;
+                // `[Int; Str]` (maybe a tuple or a dict type)
+                if let TyParam::Value(ValueObj::Type(_)) = &len {
+                    let input = &self.cfg.input;
+                    let elem = input
+                        .reread_loc(arr.ty.loc())
+                        .unwrap_or_else(|| arr.ty.to_string());
+                    let len = input
+                        .reread_loc(arr.len.loc())
+                        .unwrap_or_else(|| arr.len.to_string());
+                    return Err(TyCheckErrors::from(TyCheckError::array_len_type_error(
+                        input.clone(),
+                        line!() as usize,
+                        self.loc_with_brackets(t_spec.loc(), ('[', ']')),
+                        self.caused_by(),
+                        &elem,
+                        &len,
+                    )));
+                }
                 if let TyParam::Erased(t) = &mut len {
                     *t.as_mut() = Type::Nat;
                 }
@@ -882,14 +1106,13 @@ impl Context {
             TypeSpec::Dict(dict) => {
                 let mut inst_tys = dict! {};
                 for (k, v) in dict {
+                    let key_t = self
+                        .instantiate_typespec(k, opt_decl_t, tmp_tv_cache, mode, not_found_is_qvar)
+                        .map_err(|errs| {
+                            self.dict_as_record_error(t_spec, dict, k).unwrap_or(errs)
+                        })?;
                     inst_tys.insert(
-                        self.instantiate_typespec(
-                            k,
-                            opt_decl_t,
-                            tmp_tv_cache,
-                            mode,
-                            not_found_is_qvar,
-                        )?,
+                        key_t,
                         self.instantiate_typespec(
                             v,
                             opt_decl_t,
@@ -993,6 +1216,7 @@ impl Context {
                     non_defaults,
                     var_params,
                     defaults,
+                    None,
                     return_t,
                 ))
             }
@@ -1311,12 +1535,20 @@ impl Context {
                     *pt.typ_mut() =
                         self.instantiate_t_inner(mem::take(pt.typ_mut()), tmp_tv_cache, loc)?;
                 }
+                if let Some(kw_var_args) = subr.kw_var_params.as_mut() {
+                    *kw_var_args.typ_mut() = self.instantiate_t_inner(
+                        mem::take(kw_var_args.typ_mut()),
+                        tmp_tv_cache,
+                        loc,
+                    )?;
+                }
                 let return_t = self.instantiate_t_inner(*subr.return_t, tmp_tv_cache, loc)?;
                 let res = subr_t(
                     subr.kind,
                     subr.non_default_params,
                     subr.var_params.map(|p| *p),
                     subr.default_params,
+                    subr.kw_var_params.map(|p| *p),
                     return_t,
                 );
                 Ok(res)
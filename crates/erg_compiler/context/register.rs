@@ -5,35 +5,45 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::SystemTime;
 
+use erg_common::config::ErgMode;
+use erg_common::dict::Dict;
 use erg_common::env::erg_pystd_path;
 use erg_common::erg_util::BUILTIN_ERG_MODS;
 use erg_common::levenshtein::get_similar_name;
 use erg_common::python_util::BUILTIN_PYTHON_MODS;
 use erg_common::set::Set;
-use erg_common::traits::{Locational, Stream};
+use erg_common::traits::{Locational, Runnable, Stream};
+use erg_common::tsort::{find_cycle, Graph, Node};
 use erg_common::vis::Visibility;
 use erg_common::Str;
 use erg_common::{enum_unwrap, get_hash, log, set};
 
 use ast::{Decorator, DefId, Identifier, OperationKind, SimpleTypeSpec, VarName};
 use erg_parser::ast::{self, ConstIdentifier};
+use erg_parser::build_ast::ASTBuilder;
 
 use crate::ty::constructors::{
-    free_var, func, func0, func1, proc, ref_, ref_mut, unknown_len_array_t, v_enum,
+    free_var, func0, func1, poly, ref_, ref_mut, str_dict_t, subr_t, ty_tp, unknown_len_array_t,
+    v_enum,
 };
 use crate::ty::free::{Constraint, FreeKind, HasLevel};
 use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
-use crate::ty::{HasType, ParamTy, SubrType, Type};
+use crate::ty::{ConstSubr, HasType, ParamTy, SubrKind, SubrType, Type, UserConstSubr};
 
 use crate::build_hir::HIRBuilder;
 use crate::context::{
-    ClassDefType, Context, ContextKind, DefaultInfo, MethodInfo, RegistrationMode, TraitImpl,
+    ClassDefType, Context, ContextKind, DefaultInfo, MethodInfo, ModuleContext, RegistrationMode,
+    TraitImpl,
 };
 use crate::error::readable_name;
 use crate::error::{
     CompileError, CompileErrors, CompileResult, TyCheckError, TyCheckErrors, TyCheckResult,
 };
 use crate::hir::Literal;
+use crate::lower::ASTLowerer;
+use crate::module::interface::{read_interface, source_hash, write_interface, InterfaceHeader};
+use crate::module::{SharedCompilerResource, SharedModuleIndex};
+use crate::reorder::Reorderer;
 use crate::varinfo::{AbsLocation, Mutability, VarInfo, VarKind};
 use crate::{feature_error, hir};
 use Mutability::*;
@@ -140,7 +150,14 @@ impl Context {
         };
         let vis = ident.vis();
         let kind = id.map_or(VarKind::Declared, VarKind::Defined);
-        let sig_t = self.instantiate_var_sig_t(sig.t_spec.as_ref(), opt_t, PreRegister)?;
+        let sig_t = match self.instantiate_var_sig_t(sig.t_spec.as_ref(), opt_t, PreRegister) {
+            Ok(t) => t,
+            // the mismatch between the body and the type specification is reported when lowering the definition
+            Err(_) if sig.t_spec.is_some() => {
+                self.instantiate_var_sig_t(sig.t_spec.as_ref(), None, PreRegister)?
+            }
+            Err(errs) => return Err(errs),
+        };
         let py_name = if let ContextKind::PatchMethodDefs(_base) = &self.kind {
             Some(Str::from(format!("::{}{}", self.name, ident)))
         } else {
@@ -177,6 +194,7 @@ impl Context {
         &mut self,
         sig: &ast::SubrSignature,
         id: Option<DefId>,
+        is_generator: bool,
     ) -> TyCheckResult<()> {
         let name = sig.ident.inspect();
         let vis = sig.ident.vis();
@@ -194,10 +212,32 @@ impl Context {
             .collect::<Set<_>>();
         let default_ts =
             vec![free_var(self.level, Constraint::new_type_of(Type::Type)); sig.params.len()];
-        let (errs, t) = match self.instantiate_sub_sig_t(sig, default_ts, PreRegister) {
+        let (errs, mut t) = match self.instantiate_sub_sig_t(sig, default_ts, PreRegister) {
             Ok(t) => (TyCheckErrors::empty(), t),
             Err((errs, t)) => (errs, t),
         };
+        // generator functions return `Generator(Y, S, R)` (`Generator(?Y, NoneType, R)` if not specified)
+        if is_generator {
+            if let Some(return_t) = t.mut_return_t() {
+                if &return_t.qual_name()[..] != "Generator" {
+                    let yield_t = free_var(self.level, Constraint::new_type_of(Type::Type));
+                    *return_t = poly(
+                        "Generator",
+                        vec![
+                            ty_tp(yield_t),
+                            ty_tp(Type::NoneType),
+                            ty_tp(return_t.clone()),
+                        ],
+                    );
+                }
+            }
+        }
+        // `@Async` procedures return `Coroutine T`
+        if comptime_decos.contains("Async") {
+            if let Some(return_t) = t.mut_return_t() {
+                *return_t = poly("Coroutine", vec![ty_tp(return_t.clone())]);
+            }
+        }
         let py_name = if let ContextKind::PatchMethodDefs(_base) = &self.kind {
             Some(Str::from(format!("::{}{}", self.name, sig.ident)))
         } else {
@@ -315,6 +355,7 @@ impl Context {
         };
         let default = kind.default_info();
         let is_var_params = kind.is_var_params();
+        let is_kw_params = kind.is_kw_params();
         match &sig.raw.pat {
             // Literal patterns will be desugared to discard patterns
             ast::ParamPattern::Lit(_) => unreachable!(),
@@ -376,6 +417,8 @@ impl Context {
                     };
                     let spec_t = if is_var_params {
                         unknown_len_array_t(spec_t)
+                    } else if is_kw_params {
+                        str_dict_t(spec_t)
                     } else {
                         spec_t
                     };
@@ -582,6 +625,19 @@ impl Context {
                     errs.extend(es);
                 }
             }
+            if let Some(kw_var_params) = &mut params.kw_var_params {
+                if let Some(pt) = &decl_subr_t.kw_var_params {
+                    let pt = pt.clone().map_type(str_dict_t);
+                    if let Err(es) =
+                        self.assign_param(kw_var_params, Some(&pt), ParamKind::KwParams)
+                    {
+                        errs.extend(es);
+                    }
+                } else if let Err(es) = self.assign_param(kw_var_params, None, ParamKind::KwParams)
+                {
+                    errs.extend(es);
+                }
+            }
         } else {
             for non_default in params.non_defaults.iter_mut() {
                 if let Err(es) = self.assign_param(non_default, None, ParamKind::NonDefault) {
@@ -597,6 +653,11 @@ impl Context {
                     errs.extend(es);
                 }
             }
+            if let Some(kw_var_params) = &mut params.kw_var_params {
+                if let Err(es) = self.assign_param(kw_var_params, None, ParamKind::KwParams) {
+                    errs.extend(es);
+                }
+            }
         }
         if errs.is_empty() {
             Ok(())
@@ -613,6 +674,7 @@ impl Context {
         sig: &ast::SubrSignature,
         id: DefId,
         body_t: &Type,
+        is_generator: bool,
     ) -> TyCheckResult<VarInfo> {
         // already defined as const
         if sig.ident.is_const() {
@@ -631,7 +693,20 @@ impl Context {
         let non_default_params = t.non_default_params().unwrap();
         let var_args = t.var_params();
         let default_params = t.default_params().unwrap();
-        let mut errs = if let Some(spec_ret_t) = t.return_t() {
+        let kw_var_args = t.kw_var_params();
+        let is_async = sig.decorators.iter().any(|deco| {
+            matches!(&deco.0, ast::Expr::Accessor(ast::Accessor::Ident(local)) if &local.inspect()[..] == "Async")
+        });
+        // the body of an `@Async` procedure returns `T` of `Coroutine T`,
+        // and the body of a generator function returns `R` of `Generator(Y, S, R)`
+        let spec_ret_t = if is_async || is_generator {
+            t.return_t()
+                .and_then(|ret_t| ret_t.typarams().into_iter().last())
+                .and_then(|tp| Type::try_from(tp).ok())
+        } else {
+            t.return_t().cloned()
+        };
+        let mut errs = if let Some(spec_ret_t) = spec_ret_t.as_ref() {
             let return_t_loc = sig.return_t_spec.as_ref().ok_or(sig);
             self.sub_unify(body_t, spec_ret_t, &return_t_loc, None)
                 .map_err(|errs| {
@@ -660,21 +735,19 @@ impl Context {
             // NOTE: not `body_t.clone()` because the body may contain `return`
             t.return_t().unwrap().clone()
         };
-        let sub_t = if sig.ident.is_procedural() {
-            proc(
-                non_default_params.clone(),
-                var_args.cloned(),
-                default_params.clone(),
-                return_t,
-            )
+        let kind = if sig.ident.is_procedural() {
+            SubrKind::Proc
         } else {
-            func(
-                non_default_params.clone(),
-                var_args.cloned(),
-                default_params.clone(),
-                return_t,
-            )
+            SubrKind::Func
         };
+        let sub_t = subr_t(
+            kind,
+            non_default_params.clone(),
+            var_args.cloned(),
+            default_params.clone(),
+            kw_var_args.cloned(),
+            return_t,
+        );
         sub_t.lift();
         let found_t = self.generalize_t(sub_t);
         let py_name = if let Some(vi) = self.decls.remove(name) {
@@ -775,25 +848,10 @@ impl Context {
 
     // To allow forward references and recursive definitions
     pub(crate) fn preregister(&mut self, block: &ast::Block) -> TyCheckResult<()> {
-        let mut total_errs = TyCheckErrors::empty();
-        for expr in block.iter() {
-            match expr {
-                ast::Expr::Def(def) => {
-                    if let Err(errs) = self.preregister_def(def) {
-                        total_errs.extend(errs.into_iter());
-                    }
-                }
-                ast::Expr::ClassDef(class_def) => {
-                    if let Err(errs) = self.preregister_def(&class_def.def) {
-                        total_errs.extend(errs.into_iter());
-                    }
-                }
-                ast::Expr::PatchDef(patch_def) => {
-                    if let Err(errs) = self.preregister_def(&patch_def.def) {
-                        total_errs.extend(errs.into_iter());
-                    }
-                }
-                _ => {}
+        let (defs, mut total_errs) = self.check_const_cycles(block);
+        for def in defs {
+            if let Err(errs) = self.preregister_def(def) {
+                total_errs.extend(errs);
             }
         }
         if total_errs.is_empty() {
@@ -803,12 +861,105 @@ impl Context {
         }
     }
 
+    /// Collects the definitions in `block` and checks that no constant depends on itself.
+    /// Definitions that form a cycle are reported, excluded and declared as `Failure` to suppress subsequent errors.
+    fn check_const_cycles<'b>(
+        &mut self,
+        block: &'b ast::Block,
+    ) -> (Vec<&'b ast::Def>, TyCheckErrors) {
+        let mut errs = TyCheckErrors::empty();
+        let defs = block
+            .iter()
+            .filter_map(|expr| match expr {
+                ast::Expr::Def(def) => Some(def),
+                ast::Expr::ClassDef(class_def) => Some(&class_def.def),
+                ast::Expr::PatchDef(patch_def) => Some(&patch_def.def),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut names = Dict::new();
+        for (i, def) in defs.iter().enumerate() {
+            if let Some(ident) = def.sig.ident().filter(|ident| ident.is_const()) {
+                if names.get(ident.inspect()).is_none() {
+                    names.insert(ident.inspect().clone(), i);
+                }
+            }
+        }
+        // The bodies of (non-class) subroutines are evaluated lazily, so they do not depend on anything here
+        let deps = defs
+            .iter()
+            .map(|def| match &def.sig {
+                ast::Signature::Subr(_) if !def.def_kind().is_class_or_trait() => set! {},
+                _ => {
+                    let mut refs = set! {};
+                    for chunk in def.body.block.iter() {
+                        collect_const_refs(chunk, &mut refs);
+                    }
+                    refs.iter()
+                        .filter_map(|name| names.get(name).copied())
+                        .collect::<Set<_>>()
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut excluded = set! {};
+        loop {
+            let graph = deps
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !excluded.contains(i))
+                .map(|(i, deps)| {
+                    let deps = deps.iter().filter(|d| !excluded.contains(*d)).copied();
+                    Node::new(i, (), deps.collect())
+                })
+                .collect::<Graph<_, _>>();
+            let Some(cycle) = find_cycle(&graph) else {
+                let defs = defs
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| !excluded.contains(i))
+                    .map(|(_, def)| def)
+                    .collect();
+                return (defs, errs);
+            };
+            let cycle_names = cycle
+                .iter()
+                .map(|i| defs[*i].sig.ident().unwrap().inspect().clone())
+                .collect::<Vec<_>>();
+            errs.push(TyCheckError::cyclic_const_def_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                defs[cycle[0]].sig.loc(),
+                self.caused_by(),
+                &cycle_names,
+            ));
+            for i in cycle.iter() {
+                let ident = defs[*i].sig.ident().unwrap();
+                let vi = VarInfo::new(
+                    Type::Failure,
+                    Const,
+                    ident.vis(),
+                    VarKind::Declared,
+                    None,
+                    self.impl_of(),
+                    None,
+                    self.absolutize(ident.name.loc()),
+                );
+                self.decls.insert(ident.name.clone(), vi);
+            }
+            excluded.extend(cycle);
+        }
+    }
+
     pub(crate) fn preregister_def(&mut self, def: &ast::Def) -> TyCheckResult<()> {
         let id = Some(def.body.id);
         let __name__ = def.sig.ident().map(|i| i.inspect()).unwrap_or(UBAR);
         match &def.sig {
             ast::Signature::Subr(sig) => {
-                if sig.is_const() {
+                let is_generator = def.body.block.contains_yield_of(__name__);
+                if sig.is_const() && !def.def_kind().is_class_or_trait() {
+                    self.declare_sub(sig, id, is_generator)?;
+                    self.register_user_const_subr(sig, &def.body.block);
+                } else if sig.is_const() {
                     let tv_cache = self.instantiate_ty_bounds(&sig.bounds, PreRegister)?;
                     let vis = def.sig.vis();
                     self.grow(__name__, ContextKind::Proc, vis, Some(tv_cache));
@@ -842,7 +993,7 @@ impl Context {
                     self.pop();
                     self.register_gen_const(def.sig.ident().unwrap(), obj)?;
                 } else {
-                    self.declare_sub(sig, id)?;
+                    self.declare_sub(sig, id, is_generator)?;
                 }
             }
             ast::Signature::Var(sig) => {
@@ -1086,6 +1237,24 @@ impl Context {
         }
     }
 
+    /// e.g. `Add1(X: Nat): Nat = X + 1`
+    /// The body is not evaluated here; it is evaluated each time the subroutine is called at compile time.
+    fn register_user_const_subr(&mut self, sig: &ast::SubrSignature, block: &ast::Block) {
+        let Some(vi) = self.decls.get(&sig.ident.name) else {
+            return;
+        };
+        let subr = UserConstSubr::new(
+            sig.ident.inspect().clone(),
+            sig.params.clone(),
+            block.clone(),
+            vi.t.clone(),
+        );
+        self.consts.insert(
+            sig.ident.name.clone(),
+            ValueObj::Subr(ConstSubr::User(subr)),
+        );
+    }
+
     pub(crate) fn register_gen_type(
         &mut self,
         ident: &Identifier,
@@ -1580,17 +1749,39 @@ impl Context {
         if mod_cache.get(&path).is_some() {
             return Ok(path);
         }
+        if let Some(referrer) = self.cfg.input.path() {
+            let graph = &self.shared.as_ref().unwrap().graph;
+            // `path` is being built now (it is an ancestor of the referrer)
+            if let Some(mut cycle) = graph.dep_path(&path, referrer) {
+                cycle.insert(0, referrer.to_path_buf());
+                return self.import_cyclic_erg_mod(mod_name, __name__, path, cycle);
+            }
+        }
         let mut cfg = self.cfg.inherit(path.clone());
         let src = cfg.input.read();
+        // the interfaces are only used when the HIR of the module may not be needed
+        // (separately compiled modules are checked again if their outputs are outdated, see `Compiler::dump_modules_as_pyc`)
+        let use_interface = match self.cfg.mode {
+            ErgMode::FullCheck => true,
+            ErgMode::Compile => self.cfg.separate,
+            _ => false,
+        };
+        let hash = get_hash(&src);
+        if use_interface {
+            if let Some(ctx) = self.load_interface(&path, hash, &__name__) {
+                mod_cache.register(path.clone(), None, ctx);
+                return Ok(path);
+            }
+        }
         let mut builder =
             HIRBuilder::new_with_cache(cfg, __name__, self.shared.as_ref().unwrap().clone());
         match builder.build(src, "exec") {
             Ok(artifact) => {
-                mod_cache.register(
-                    path.clone(),
-                    Some(artifact.object),
-                    builder.pop_mod_ctx().unwrap(),
-                );
+                let ctx = builder.pop_mod_ctx().unwrap();
+                if use_interface {
+                    self.save_interface(&path, hash, &ctx.context);
+                }
+                mod_cache.register(path.clone(), Some(artifact.object), ctx);
             }
             Err(artifact) => {
                 if let Some(hir) = artifact.object {
@@ -1602,6 +1793,83 @@ impl Context {
         Ok(path)
     }
 
+    /// Loads the interface of the module at `path` instead of checking the module (see `module::interface`).
+    /// Returns `None` if the interface is outdated or cannot be loaded.
+    fn load_interface(&self, path: &Path, hash: usize, __name__: &Str) -> Option<ModuleContext> {
+        let (header, decl) = read_interface(path, hash)?;
+        let shared = self.shared.as_ref().unwrap();
+        let cfg = self.cfg.inherit(path.to_path_buf());
+        let mut builder = HIRBuilder::new_with_cache(cfg, __name__.clone(), shared.clone());
+        builder.build(decl, "declare").ok()?;
+        // the dependencies are not imported by the interface, but the dependents need them
+        shared.graph.add_node_if_none(path);
+        for (dep, _) in header.deps {
+            shared.graph.add_node_if_none(&dep);
+            shared.graph.inc_ref(path, dep);
+        }
+        builder.pop_mod_ctx()
+    }
+
+    fn save_interface(&self, path: &Path, hash: usize, ctx: &Context) {
+        let graph = &self.shared.as_ref().unwrap().graph;
+        let mut deps = vec![];
+        for dep in graph.dependencies(path) {
+            // modules imported cyclically are checked together with the importer
+            if dep == path {
+                return;
+            }
+            let Some(dep_hash) = source_hash(&dep) else {
+                return;
+            };
+            deps.push((dep, dep_hash));
+        }
+        deps.sort();
+        write_interface(path, InterfaceHeader::new(hash, deps), ctx);
+    }
+
+    /// Registers only the declarations of a module that is imported cyclically.
+    /// The module is fully checked by the importer that is building it now,
+    /// so top-level variables (other than subroutines, types and modules) are not allowed.
+    fn import_cyclic_erg_mod(
+        &self,
+        mod_name: &Literal,
+        __name__: Str,
+        path: PathBuf,
+        cycle: Vec<PathBuf>,
+    ) -> CompileResult<PathBuf> {
+        let mut cfg = self.cfg.inherit(path.clone());
+        let src = cfg.input.read();
+        let ast = ASTBuilder::new(cfg.copy())
+            .build(src)
+            .map_err(CompileErrors::from)?;
+        let ast = Reorderer::new(cfg.clone()).reorder(ast, "exec")?;
+        // the variables are registered to the index when the module is checked by the importer
+        let shared = SharedCompilerResource {
+            index: SharedModuleIndex::new(),
+            ..self.shared.as_ref().unwrap().clone()
+        };
+        let mut lowerer = ASTLowerer::new_with_cache(cfg, __name__, shared);
+        // errors will also be reported then
+        let _ = lowerer.module.context.preregister(ast.module.block());
+        self.mod_cache()
+            .unwrap()
+            .register(path.clone(), None, lowerer.pop_mod_ctx().unwrap());
+        if !ast.module.iter().all(is_cyclic_importable) {
+            let cycle = cycle
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>();
+            return Err(CompileErrors::from(CompileError::cyclic_import_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                mod_name.loc(),
+                self.caused_by(),
+                &cycle,
+            )));
+        }
+        Ok(path)
+    }
+
     fn similar_builtin_py_mod_name(&self, name: &Str) -> Option<Str> {
         get_similar_name(BUILTIN_PYTHON_MODS.into_iter(), name).map(Str::rc)
     }
@@ -1696,32 +1964,49 @@ impl Context {
     }
 
     fn try_gen_py_decl_file(&self, __name__: &Str) -> Result<PathBuf, ()> {
-        if let Ok(path) = self.cfg.input.local_py_resolve(Path::new(&__name__[..])) {
-            let (out, err) = if self.cfg.quiet_repl {
-                (Stdio::null(), Stdio::null())
-            } else {
-                (Stdio::inherit(), Stdio::inherit())
-            };
-            // pylyzer is a static analysis tool for Python (https://github.com/mtshiba/pylyzer).
-            // It can convert a Python script to an Erg AST for code analysis.
-            // There is also an option to output the analysis result as `d.er`. Use this if the system have pylyzer installed.
-            // A type definition file may be generated even if not all type checks succeed.
-            if let Ok(_status) = Command::new("pylyzer")
-                .arg("--dump-decl")
-                .arg(path.to_str().unwrap())
-                .stdout(out)
-                .stderr(err)
-                .spawn()
-                .and_then(|mut child| child.wait())
-            {
-                if let Some(path) = Self::resolve_decl_path(&self.cfg, Path::new(&__name__[..])) {
+        let path = Path::new(&__name__[..]);
+        if let Ok(py_path) = self.cfg.input.local_py_resolve(path) {
+            if self.run_pylyzer(&py_path) {
+                if let Some(path) = Self::resolve_decl_path(&self.cfg, path) {
                     return Ok(path);
                 }
             }
+        } else if let Some(venv) = self
+            .cfg
+            .venv
+            .as_ref()
+            .filter(|_| !BUILTIN_PYTHON_MODS.contains(&&__name__[..]))
+        {
+            // the declaration files of the packages installed in the virtual environment are cached per environment
+            if let Some(py_path) = venv.resolve_site_package(path) {
+                if self.run_pylyzer(&py_path) {
+                    return venv.cache_decl(&py_path, path).ok_or(());
+                }
+            }
         }
         Err(())
     }
 
+    fn run_pylyzer(&self, py_path: &Path) -> bool {
+        let (out, err) = if self.cfg.quiet_repl {
+            (Stdio::null(), Stdio::null())
+        } else {
+            (Stdio::inherit(), Stdio::inherit())
+        };
+        // pylyzer is a static analysis tool for Python (https://github.com/mtshiba/pylyzer).
+        // It can convert a Python script to an Erg AST for code analysis.
+        // There is also an option to output the analysis result as `d.er`. Use this if the system have pylyzer installed.
+        // A type definition file may be generated even if not all type checks succeed.
+        Command::new("pylyzer")
+            .arg("--dump-decl")
+            .arg(py_path.to_str().unwrap())
+            .stdout(out)
+            .stderr(err)
+            .spawn()
+            .and_then(|mut child| child.wait())
+            .is_ok()
+    }
+
     fn import_py_mod(&self, mod_name: &Literal) -> CompileResult<PathBuf> {
         let ValueObj::Str(__name__) = mod_name.value.clone() else { todo!("{mod_name}") };
         let py_mod_cache = self.py_mod_cache().unwrap();
@@ -1891,3 +2176,109 @@ impl Context {
             .inc_ref(vi, self.absolutize(name.loc()));
     }
 }
+
+/// Can the chunk be in a module that is imported cyclically?
+/// Top-level variables are not allowed because they may be accessed before initialization.
+fn is_cyclic_importable(chunk: &ast::Expr) -> bool {
+    match chunk {
+        ast::Expr::Def(def) => match &def.sig {
+            ast::Signature::Subr(_) => true,
+            ast::Signature::Var(_) => {
+                def.def_kind().is_class_or_trait()
+                    || def.def_kind().is_import()
+                    || matches!(def.body.block.first(), Some(ast::Expr::Lambda(_)))
+            }
+        },
+        ast::Expr::ReDef(_) => false,
+        _ => true,
+    }
+}
+
+fn collect_args_refs(args: &ast::Args, refs: &mut Set<Str>) {
+    for arg in args.pos_args() {
+        collect_const_refs(&arg.expr, refs);
+    }
+    for arg in args.kw_args() {
+        collect_const_refs(&arg.expr, refs);
+    }
+}
+
+/// Collects the names referred to when `expr` is evaluated eagerly.
+/// Lambda bodies are not collected because they are evaluated lazily.
+fn collect_const_refs(expr: &ast::Expr, refs: &mut Set<Str>) {
+    match expr {
+        ast::Expr::Accessor(acc) => match acc {
+            ast::Accessor::Ident(ident) => {
+                refs.insert(ident.inspect().clone());
+            }
+            ast::Accessor::Attr(attr) => collect_const_refs(&attr.obj, refs),
+            ast::Accessor::TupleAttr(attr) => collect_const_refs(&attr.obj, refs),
+            ast::Accessor::Subscr(subscr) => {
+                collect_const_refs(&subscr.obj, refs);
+                collect_const_refs(&subscr.index, refs);
+            }
+            ast::Accessor::TypeApp(app) => {
+                collect_const_refs(&app.obj, refs);
+                collect_args_refs(&app.type_args.args, refs);
+            }
+        },
+        ast::Expr::Array(ast::Array::Normal(arr)) => collect_args_refs(&arr.elems, refs),
+        ast::Expr::Array(ast::Array::WithLength(arr)) => {
+            collect_const_refs(&arr.elem.expr, refs);
+            collect_const_refs(&arr.len, refs);
+        }
+        ast::Expr::Array(ast::Array::Comprehension(arr)) => {
+            for (_, gen) in arr.generators.iter() {
+                collect_const_refs(gen, refs);
+            }
+        }
+        ast::Expr::Tuple(ast::Tuple::Normal(tup)) => collect_args_refs(&tup.elems, refs),
+        ast::Expr::Dict(ast::Dict::Normal(dict)) => {
+            for kv in dict.kvs.iter() {
+                collect_const_refs(&kv.key, refs);
+                collect_const_refs(&kv.value, refs);
+            }
+        }
+        ast::Expr::Set(ast::Set::Normal(set)) => collect_args_refs(&set.elems, refs),
+        ast::Expr::Set(ast::Set::WithLength(set)) => {
+            collect_const_refs(&set.elem.expr, refs);
+            collect_const_refs(&set.len, refs);
+        }
+        ast::Expr::Record(ast::Record::Normal(rec)) => {
+            for attr in rec.attrs.iter() {
+                for chunk in attr.body.block.iter() {
+                    collect_const_refs(chunk, refs);
+                }
+            }
+        }
+        ast::Expr::Record(ast::Record::Mixed(rec)) => {
+            for attr in rec.attrs.iter() {
+                match attr {
+                    ast::RecordAttrOrIdent::Attr(def) => {
+                        for chunk in def.body.block.iter() {
+                            collect_const_refs(chunk, refs);
+                        }
+                    }
+                    ast::RecordAttrOrIdent::Ident(ident) => {
+                        refs.insert(ident.inspect().clone());
+                    }
+                }
+            }
+        }
+        ast::Expr::BinOp(bin) => {
+            collect_const_refs(&bin.args[0], refs);
+            collect_const_refs(&bin.args[1], refs);
+        }
+        ast::Expr::UnaryOp(unary) => collect_const_refs(&unary.args[0], refs),
+        ast::Expr::Call(call) => {
+            collect_const_refs(&call.obj, refs);
+            collect_args_refs(&call.args, refs);
+        }
+        ast::Expr::DataPack(pack) => {
+            collect_const_refs(&pack.class, refs);
+            collect_const_refs(&ast::Expr::Record(pack.args.clone()), refs);
+        }
+        ast::Expr::TypeAscription(tasc) => collect_const_refs(&tasc.expr, refs),
+        _ => {}
+    }
+}
@@ -28,6 +28,7 @@ impl Context {
     // occur(?T, ?T -> X) ==> Error
     // occur(?T, Option(?T)) ==> Error
     // occur(?T, ?T.Output) ==> Error
+    // occur(?T(:> Array!(?U)), ?U) ==> Error (e.g. `a.push! b; b.push! a`)
     fn occur(
         &self,
         maybe_sub: &Type,
@@ -36,7 +37,14 @@ impl Context {
     ) -> TyCheckResult<()> {
         match (maybe_sub, maybe_sup) {
             (Type::FreeVar(sub), Type::FreeVar(sup)) => {
-                if sub.is_unbound() && sup.is_unbound() && sub == sup {
+                let occurs_in_sub_bound = || {
+                    let Some(name) = sup.unbound_name() else {
+                        return false;
+                    };
+                    sub.get_sub()
+                        .is_some_and(|sub_bound| sub_bound.contains_tvar(&name))
+                };
+                if sub.is_unbound() && sup.is_unbound() && (sub == sup || occurs_in_sub_bound()) {
                     Err(TyCheckErrors::from(TyCheckError::subtyping_error(
                         self.cfg.input.clone(),
                         line!() as usize,
@@ -56,6 +64,9 @@ impl Context {
                 if let Some(var_params) = subr.var_params.as_ref() {
                     self.occur(var_params.typ(), maybe_sup, loc)?;
                 }
+                if let Some(kw_var_params) = subr.kw_var_params.as_ref() {
+                    self.occur(kw_var_params.typ(), maybe_sup, loc)?;
+                }
                 for non_default_t in subr.non_default_params.iter().map(|pt| pt.typ()) {
                     self.occur(non_default_t, maybe_sup, loc)?;
                 }
@@ -69,6 +80,9 @@ impl Context {
                 if let Some(var_params) = subr.var_params.as_ref() {
                     self.occur(maybe_sub, var_params.typ(), loc)?;
                 }
+                if let Some(kw_var_params) = subr.kw_var_params.as_ref() {
+                    self.occur(maybe_sub, kw_var_params.typ(), loc)?;
+                }
                 for non_default_t in subr.non_default_params.iter().map(|pt| pt.typ()) {
                     self.occur(maybe_sub, non_default_t, loc)?;
                 }
@@ -735,6 +749,10 @@ impl Context {
                         // contravariant
                         self.sub_unify(r.typ(), l.typ(), loc, param_name)
                     })?;
+                if let Some((l, r)) = lsub.kw_var_params.as_ref().zip(rsub.kw_var_params.as_ref()) {
+                    // contravariant
+                    self.sub_unify(r.typ(), l.typ(), loc, param_name)?;
+                }
                 // covariant
                 self.sub_unify(&lsub.return_t, &rsub.return_t, loc, param_name)?;
                 Ok(())
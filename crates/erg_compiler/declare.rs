@@ -84,6 +84,10 @@ impl ASTLowerer {
     /// allowed: alias, import, const functions (e.g. Class)
     fn declare_def(&mut self, def: ast::Def) -> LowerResult<hir::Def> {
         log!(info "entered {}({})", fn_name!(), def.sig);
+        if def.def_kind().is_class_or_trait() {
+            self.module.context.preregister_def(&def)?;
+            return self.lower_def(def);
+        }
         let name = if let Some(name) = def.sig.name_as_str() {
             name.clone()
         } else {
@@ -153,7 +157,7 @@ impl ASTLowerer {
     }
 
     fn fake_lower_args(&self, args: ast::Args) -> LowerResult<hir::Args> {
-        let (pos_args_, var_args_, kw_args_, paren) = args.deconstruct();
+        let (pos_args_, var_args_, kw_args_, kw_var_args_, paren) = args.deconstruct();
         let mut pos_args = vec![];
         for arg in pos_args_.into_iter() {
             let arg = self.fake_lower_expr(arg.expr)?;
@@ -171,7 +175,14 @@ impl ASTLowerer {
             let expr = self.fake_lower_expr(kw_arg.expr)?;
             kw_args.push(hir::KwArg::new(kw_arg.keyword, expr));
         }
-        let args = hir::Args::new(pos_args, var_args, kw_args, paren);
+        let kw_var_args = match kw_var_args_ {
+            Some(kw_var_args) => {
+                let kw_var_args = self.fake_lower_expr(kw_var_args.expr)?;
+                Some(hir::PosArg::new(kw_var_args))
+            }
+            None => None,
+        };
+        let args = hir::Args::new(pos_args, var_args, kw_args, kw_var_args, paren);
         Ok(args)
     }
 
@@ -211,7 +222,7 @@ impl ASTLowerer {
                     let elem = self.fake_lower_expr(elem.expr)?;
                     elems.push(hir::PosArg::new(elem));
                 }
-                let elems = hir::Args::new(elems, None, vec![], None);
+                let elems = hir::Args::new(elems, None, vec![], None, None);
                 Ok(hir::Array::Normal(hir::NormalArray::new(
                     arr.l_sqbr,
                     arr.r_sqbr,
@@ -232,12 +243,12 @@ impl ASTLowerer {
         match tup {
             ast::Tuple::Normal(tup) => {
                 let mut elems = Vec::new();
-                let (elems_, _, _, paren) = tup.elems.deconstruct();
+                let (elems_, _, _, _, paren) = tup.elems.deconstruct();
                 for elem in elems_.into_iter() {
                     let elem = self.fake_lower_expr(elem.expr)?;
                     elems.push(hir::PosArg::new(elem));
                 }
-                let elems = hir::Args::new(elems, None, vec![], paren);
+                let elems = hir::Args::new(elems, None, vec![], None, paren);
                 Ok(hir::Tuple::Normal(hir::NormalTuple::new(elems)))
             }
         }
@@ -254,7 +265,7 @@ impl ASTLowerer {
             ast::Signature::Subr(subr) => {
                 let ident = hir::Identifier::bare(subr.ident.dot, subr.ident.name);
                 let params = self.fake_lower_params(subr.params)?;
-                let sig = hir::SubrSignature::new(ident, params, subr.return_t_spec);
+                let sig = hir::SubrSignature::new(ident, params, subr.return_t_spec, false);
                 Ok(hir::Signature::Subr(sig))
             }
         }
@@ -296,7 +307,7 @@ impl ASTLowerer {
                     let elem = self.fake_lower_expr(elem.expr)?;
                     elems.push(hir::PosArg::new(elem));
                 }
-                let elems = hir::Args::new(elems, None, vec![], None);
+                let elems = hir::Args::new(elems, None, vec![], None, None);
                 Ok(hir::Set::Normal(hir::NormalSet::new(
                     set.l_brace,
                     set.r_brace,
@@ -340,7 +351,7 @@ impl ASTLowerer {
     }
 
     fn fake_lower_params(&self, params: ast::Params) -> LowerResult<hir::Params> {
-        let (non_defaults_, var_params_, defaults_, parens) = params.deconstruct();
+        let (non_defaults_, var_params_, defaults_, kw_var_params_, parens) = params.deconstruct();
         let mut non_defaults = vec![];
         for non_default_ in non_defaults_.into_iter() {
             let non_default =
@@ -361,7 +372,20 @@ impl ASTLowerer {
             let default = hir::DefaultParamSignature::new(sig, default_val);
             defaults.push(default);
         }
-        Ok(hir::Params::new(non_defaults, var_args, defaults, parens))
+        let kw_var_args = kw_var_params_.map(|kw_var_args| {
+            Box::new(hir::NonDefaultParamSignature::new(
+                *kw_var_args,
+                VarInfo::default(),
+                None,
+            ))
+        });
+        Ok(hir::Params::new(
+            non_defaults,
+            var_args,
+            defaults,
+            kw_var_args,
+            parens,
+        ))
     }
 
     fn fake_lower_block(&self, block: ast::Block) -> LowerResult<hir::Block> {
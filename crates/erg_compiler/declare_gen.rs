@@ -0,0 +1,357 @@
+//! Generates the declaration file (`.d.er`) and the Python type stub (`.pyi`) of an Erg module (`--mode declare-gen`).
+//!
+//! The `.d.er` can be `pyimport`ed by other Erg projects without the source (like `lib/pystd/*.d.er`),
+//! and the `.pyi` gives the types of the compiled module to Python tools.
+//!
+//! ```python
+//! # foo.pyi
+//! from typing import Any
+//! class C:
+//!     x: int
+//!     def __init__(self, param: Any) -> None: ...
+//!     def f(self) -> int: ...
+//! def g(x: int) -> C: ...
+//! ```
+use std::fs::File;
+use std::io::Write;
+
+use erg_common::config::ErgConfig;
+use erg_common::dict::Dict;
+use erg_common::error::MultiErrorDisplay;
+use erg_common::traits::Runnable;
+use erg_common::Str;
+
+use crate::build_hir::HIRBuilder;
+use crate::context::Context;
+use crate::error::{CompileError, CompileErrors};
+use crate::module::interface::{is_ident, public_members, py_name, DeclGenerator};
+use crate::py_type::{subr_type, PyTypeMapper};
+use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
+use crate::ty::{SubrType, Type};
+
+/// Generates a Python type stub (`.pyi`) of the public members of a module.
+/// Types that cannot be expressed in Python are converted to `Any`.
+pub struct PyiGenerator<'c> {
+    ctx: &'c Context,
+    /// module name -> (`import` or `pyimport`)
+    modules: Dict<Str, &'static str>,
+    /// names imported from `typing`
+    typing: Vec<&'static str>,
+    /// imports required by the types
+    imports: Vec<String>,
+    /// type variables (e.g. `%3` -> `T3`)
+    type_vars: Dict<Str, String>,
+}
+
+impl<'c> PyiGenerator<'c> {
+    pub fn new(ctx: &'c Context) -> Self {
+        let mut modules = Dict::new();
+        for vi in ctx.locals.values() {
+            if let Some((name, import)) = DeclGenerator::module_of(&vi.t) {
+                modules.insert(name, import);
+            }
+        }
+        Self {
+            ctx,
+            modules,
+            typing: vec![],
+            imports: vec![],
+            type_vars: Dict::new(),
+        }
+    }
+
+    pub fn generate(mut self) -> String {
+        let mut types = String::new();
+        let mut vars = String::new();
+        for (name, vi) in public_members(self.ctx) {
+            let py_name = py_name(name.inspect(), vi);
+            if let Some((module, _)) = DeclGenerator::module_of(&vi.t) {
+                if is_module_name(&module) {
+                    vars += &format!("import {module} as {py_name}\n");
+                }
+            } else if matches!(vi.t, Type::ClassType | Type::TraitType) {
+                types += &self.class_def(name.inspect(), &py_name);
+            } else if let Some(subr) = subr_type(&vi.t) {
+                vars += &self.def(&py_name, &subr, "");
+            } else {
+                vars += &format!("{py_name}: {}\n", self.py_type(&vi.t));
+            }
+        }
+        let mut stub = String::new();
+        if !self.typing.is_empty() {
+            stub += &format!("from typing import {}\n", self.typing.join(", "));
+        }
+        for import in self.imports.iter() {
+            stub += import;
+        }
+        for var in self.type_vars.values() {
+            stub += &format!("{var} = TypeVar(\"{var}\")\n");
+        }
+        stub + &types + &vars
+    }
+
+    fn class_def(&mut self, name: &Str, py_name: &str) -> String {
+        let mut attrs = vec![];
+        let (bases, init_param) = match self.ctx.consts.get(name) {
+            Some(ValueObj::Type(TypeObj::Generated(GenTypeObj::Class(class)))) => {
+                let base = class.base.as_deref().map(|base| base.typ().clone());
+                (vec![], base)
+            }
+            Some(ValueObj::Type(TypeObj::Generated(GenTypeObj::Subclass(subclass)))) => {
+                let sup = self.py_type(subclass.sup.typ());
+                let additional = subclass.additional.as_deref();
+                (vec![sup], additional.map(|add| add.typ().clone()))
+            }
+            Some(ValueObj::Type(TypeObj::Generated(GenTypeObj::Trait(trait_)))) => {
+                let protocol = self.load_typing("Protocol").to_string();
+                if let Type::Record(rec) = trait_.requires.typ() {
+                    for (field, t) in rec.iter().filter(|(field, _)| field.vis.is_public()) {
+                        attrs.push((field.symbol.to_string(), t.clone()));
+                    }
+                }
+                let mut code = format!("class {py_name}({protocol}):\n");
+                code += &self.class_body(name, attrs, None);
+                return code;
+            }
+            _ => (vec![], None),
+        };
+        if let Some(Type::Record(rec)) = &init_param {
+            for (field, t) in rec.iter().filter(|(field, _)| field.vis.is_public()) {
+                attrs.push((field.symbol.to_string(), t.clone()));
+            }
+        }
+        let mut code = if bases.is_empty() {
+            format!("class {py_name}:\n")
+        } else {
+            format!("class {py_name}({}):\n", bases.join(", "))
+        };
+        // `C.new {x = 1}` => `C({x = 1})`
+        let init = match init_param {
+            Some(Type::Record(_)) => format!("param: {}", self.any()),
+            Some(t) => format!("param: {}", self.py_type(&t)),
+            None => "".into(),
+        };
+        code += &self.class_body(name, attrs, Some(init));
+        code
+    }
+
+    fn class_body(
+        &mut self,
+        name: &Str,
+        attrs: Vec<(String, Type)>,
+        init: Option<String>,
+    ) -> String {
+        let mut body = String::new();
+        for (attr, t) in attrs {
+            body += &self.member(&attr, &t);
+        }
+        if let Some(init) = init {
+            let sep = if init.is_empty() { "" } else { ", " };
+            body += &format!("    def __init__(self{sep}{init}) -> None: ...\n");
+        }
+        if let Some((_, type_ctx)) = self.ctx.mono_types.get(name) {
+            // including the methods of trait implementations
+            for (_, methods) in type_ctx.methods_list.iter() {
+                for (attr, vi) in public_members(methods) {
+                    let py_name = py_name(attr.inspect(), vi);
+                    body += &self.member(&py_name, &vi.t);
+                }
+            }
+        }
+        if body.is_empty() {
+            body += "    ...\n";
+        }
+        body
+    }
+
+    /// A method (with `self`), a static method, or a class attribute.
+    fn member(&mut self, name: &str, t: &Type) -> String {
+        let Some(subr) = subr_type(t) else {
+            return format!("    {name}: {}\n", self.py_type(t));
+        };
+        let is_method = subr
+            .non_default_params
+            .first()
+            .and_then(|param| param.name())
+            .is_some_and(|param| &param[..] == "self");
+        if is_method {
+            self.def(name, &subr, "    ")
+        } else {
+            format!("    @staticmethod\n{}", self.def(name, &subr, "    "))
+        }
+    }
+
+    fn def(&mut self, name: &str, subr: &SubrType, indent: &str) -> String {
+        let mut params = vec![];
+        for (i, param) in subr.non_default_params.iter().enumerate() {
+            let name = match param.name() {
+                Some(name) if &name[..] == "self" && i == 0 => {
+                    params.push("self".to_string());
+                    continue;
+                }
+                Some(name) if is_ident(name) => name.to_string(),
+                _ => format!("_{i}"),
+            };
+            params.push(format!("{name}: {}", self.py_type(param.typ())));
+        }
+        if let Some(var_params) = subr.var_params.as_deref() {
+            let name = var_params
+                .name()
+                .map_or("args", |name| &name[..])
+                .to_string();
+            params.push(format!("*{name}: {}", self.py_type(var_params.typ())));
+        }
+        for param in subr.default_params.iter() {
+            let Some(name) = param.name() else {
+                continue;
+            };
+            params.push(format!("{name}: {} = ...", self.py_type(param.typ())));
+        }
+        if let Some(kw_var_params) = subr.kw_var_params.as_deref() {
+            let name = kw_var_params
+                .name()
+                .map_or("kwargs", |name| &name[..])
+                .to_string();
+            params.push(format!("**{name}: {}", self.py_type(kw_var_params.typ())));
+        }
+        format!(
+            "{indent}def {name}({}) -> {}: ...\n",
+            params.join(", "),
+            self.py_type(&subr.return_t)
+        )
+    }
+}
+
+impl PyTypeMapper for PyiGenerator<'_> {
+    fn load_typing(&mut self, name: &'static str) -> &'static str {
+        if !self.typing.contains(&name) {
+            self.typing.push(name);
+        }
+        name
+    }
+
+    fn type_var(&mut self, name: Str) -> String {
+        if let Some(var) = self.type_vars.get(&name) {
+            return var.clone();
+        }
+        let var = if is_ident(&name) {
+            name.to_string()
+        } else {
+            format!("T{}", self.type_vars.len())
+        };
+        self.load_typing("TypeVar");
+        self.type_vars.insert(name, var.clone());
+        var
+    }
+
+    /// Types defined in this module are referred to by their names,
+    /// and types defined in the other modules are referred to through their imports.
+    fn type_name(&mut self, name: &Str) -> Option<String> {
+        let own = &self.ctx.name;
+        let local = name
+            .strip_prefix(&format!("{own}."))
+            .or_else(|| name.strip_prefix(&format!("{own}::")));
+        if let Some(local) = local {
+            let vi = self.ctx.locals.get(local)?;
+            return (is_ident(local) && vi.vis.is_public()).then(|| py_name(local, vi));
+        }
+        let (module, local) = name.rsplit_once('.')?;
+        if !is_ident(local) || !is_module_name(module) || !self.modules.contains_key(module) {
+            return None;
+        }
+        let alias = format!("_{}", module.replace('.', "_"));
+        let import = format!("import {module} as {alias}\n");
+        if !self.imports.contains(&import) {
+            self.imports.push(import);
+        }
+        Some(format!("{alias}.{local}"))
+    }
+}
+
+/// e.g. `foo.bar` (not `./foo`)
+fn is_module_name(name: &str) -> bool {
+    name.split('.').all(is_ident)
+}
+
+/// Generates `foo.d.er` and `foo.pyi` from `foo.er`.
+#[derive(Debug)]
+pub struct DeclFileGenerator {
+    pub cfg: ErgConfig,
+    builder: HIRBuilder,
+}
+
+impl Default for DeclFileGenerator {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for DeclFileGenerator {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg declaration file generator";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self {
+            builder: HIRBuilder::new(cfg.copy()),
+            cfg,
+        }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {
+        self.builder.initialize();
+    }
+
+    fn clear(&mut self) {
+        self.builder.clear();
+    }
+
+    fn exec(&mut self) -> Result<i32, Self::Errs> {
+        let src = self.cfg.input.read();
+        let (decl, stub) = self.generate(src)?;
+        let mut path = self.cfg.dump_path();
+        path.set_extension("d.er");
+        let mut f = File::create(&path).unwrap();
+        f.write_all(decl.as_bytes()).unwrap();
+        path.set_extension("");
+        path.set_extension("pyi");
+        let mut f = File::create(&path).unwrap();
+        f.write_all(stub.as_bytes()).unwrap();
+        Ok(0)
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        let (decl, _) = self.generate(src)?;
+        Ok(decl)
+    }
+}
+
+impl DeclFileGenerator {
+    /// Returns the declarations (`.d.er`) and the Python type stub (`.pyi`) of the module.
+    pub fn generate(&mut self, src: String) -> Result<(String, String), CompileErrors> {
+        let artifact = self.builder.build(src, "exec").map_err(|eart| {
+            eart.warns.fmt_all_stderr();
+            eart.errors
+        })?;
+        artifact.warns.fmt_all_stderr();
+        let ctx = self.builder.pop_mod_ctx().unwrap();
+        let (decl, skipped) = DeclGenerator::new(&ctx.context).generate_partial();
+        for name in skipped {
+            eprintln!("warning: `{name}` cannot be declared and is skipped");
+        }
+        let stub = PyiGenerator::new(&ctx.context).generate();
+        Ok((decl, stub))
+    }
+}
@@ -4,6 +4,7 @@
 
 use erg_common::config::ErgConfig;
 use erg_common::log;
+use erg_common::set::Set;
 use erg_common::traits::{Locational, Stream};
 use erg_common::vis::Visibility;
 use erg_common::Str;
@@ -13,7 +14,7 @@ use Visibility::*;
 use crate::ty::HasType;
 
 use crate::error::{EffectError, EffectErrors};
-use crate::hir::{Array, Def, Dict, Expr, Params, Set, Signature, Tuple, HIR};
+use crate::hir::{self, Accessor, Array, Block, Def, Dict, Expr, Params, Signature, Tuple, HIR};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum BlockKind {
@@ -38,6 +39,8 @@ pub struct SideEffectChecker {
     cfg: ErgConfig,
     path_stack: Vec<(Str, Visibility)>,
     block_stack: Vec<BlockKind>,
+    /// variables defined in each block
+    local_vars: Vec<Set<Str>>,
     errs: EffectErrors,
 }
 
@@ -47,6 +50,7 @@ impl SideEffectChecker {
             cfg,
             path_stack: vec![],
             block_stack: vec![],
+            local_vars: vec![],
             errs: EffectErrors::empty(),
         }
     }
@@ -86,120 +90,12 @@ impl SideEffectChecker {
 
     pub fn check(mut self, hir: HIR) -> Result<HIR, (HIR, EffectErrors)> {
         self.path_stack.push((hir.name.clone(), Private));
-        self.block_stack.push(Module);
+        self.push_block(Module);
         log!(info "the side-effects checking process has started.{RESET}");
         // At the top level, there is no problem with side effects, only check for purity violations.
         // トップレベルでは副作用があっても問題なく、純粋性違反がないかのみチェックする
         for expr in hir.module.iter() {
-            match expr {
-                Expr::Def(def) => {
-                    self.check_def(def);
-                }
-                Expr::ClassDef(class_def) => {
-                    if let Some(req_sup) = &class_def.require_or_sup {
-                        self.check_expr(req_sup);
-                    }
-                    // TODO: grow
-                    for def in class_def.methods.iter() {
-                        self.check_expr(def);
-                    }
-                }
-                Expr::PatchDef(patch_def) => {
-                    self.check_expr(patch_def.base.as_ref());
-                    // TODO: grow
-                    for def in patch_def.methods.iter() {
-                        self.check_expr(def);
-                    }
-                }
-                Expr::Call(call) => {
-                    for parg in call.args.pos_args.iter() {
-                        self.check_expr(&parg.expr);
-                    }
-                    for kwarg in call.args.kw_args.iter() {
-                        self.check_expr(&kwarg.expr);
-                    }
-                }
-                Expr::BinOp(bin) => {
-                    self.check_expr(&bin.lhs);
-                    self.check_expr(&bin.rhs);
-                }
-                Expr::UnaryOp(unary) => {
-                    self.check_expr(&unary.expr);
-                }
-                Expr::Accessor(_) | Expr::Lit(_) => {}
-                Expr::Array(array) => match array {
-                    Array::Normal(arr) => {
-                        for elem in arr.elems.pos_args.iter() {
-                            self.check_expr(&elem.expr);
-                        }
-                    }
-                    Array::WithLength(arr) => {
-                        self.check_expr(&arr.elem);
-                        self.check_expr(&arr.len);
-                    }
-                    Array::Comprehension(arr) => {
-                        self.check_expr(&arr.elem);
-                        self.check_expr(&arr.guard);
-                    }
-                },
-                Expr::Tuple(tuple) => match tuple {
-                    Tuple::Normal(tuple) => {
-                        for elem in tuple.elems.pos_args.iter() {
-                            self.check_expr(&elem.expr);
-                        }
-                    }
-                },
-                Expr::Record(rec) => {
-                    self.path_stack.push((Str::ever("<record>"), Private));
-                    self.block_stack.push(Instant);
-                    for attr in rec.attrs.iter() {
-                        self.check_def(attr);
-                    }
-                    self.path_stack.pop();
-                    self.block_stack.pop();
-                }
-                Expr::Set(set) => match set {
-                    Set::Normal(set) => {
-                        for elem in set.elems.pos_args.iter() {
-                            self.check_expr(&elem.expr);
-                        }
-                    }
-                    Set::WithLength(set) => {
-                        self.check_expr(&set.elem);
-                        self.check_expr(&set.len);
-                    }
-                },
-                Expr::Dict(dict) => match dict {
-                    Dict::Normal(dict) => {
-                        for kv in dict.kvs.iter() {
-                            self.check_expr(&kv.key);
-                            self.check_expr(&kv.value);
-                        }
-                    }
-                    other => todo!("{other}"),
-                },
-                Expr::TypeAsc(tasc) => {
-                    self.check_expr(&tasc.expr);
-                }
-                Expr::Lambda(lambda) => {
-                    let is_proc = lambda.is_procedural();
-                    if is_proc {
-                        self.path_stack.push((Str::ever("<lambda!>"), Private));
-                        self.block_stack.push(Proc);
-                    } else {
-                        self.path_stack.push((Str::ever("<lambda>"), Private));
-                        self.block_stack.push(Func);
-                    }
-                    lambda.body.iter().for_each(|chunk| self.check_expr(chunk));
-                    self.path_stack.pop();
-                    self.block_stack.pop();
-                }
-                Expr::ReDef(_)
-                | Expr::Code(_)
-                | Expr::Compound(_)
-                | Expr::Import(_)
-                | Expr::Dummy(_) => {}
-            }
+            self.check_expr(expr);
         }
         log!(info "the side-effects checking process has completed, found errors: {}{RESET}", self.errs.len());
         if self.errs.is_empty() {
@@ -209,8 +105,76 @@ impl SideEffectChecker {
         }
     }
 
+    fn push_block(&mut self, kind: BlockKind) {
+        self.block_stack.push(kind);
+        self.local_vars.push(Set::new());
+    }
+
+    fn pop_block(&mut self) {
+        self.block_stack.pop();
+        self.local_vars.pop();
+    }
+
+    fn register_local(&mut self, name: Option<&Str>) {
+        if let Some(name) = name {
+            self.local_vars.last_mut().unwrap().insert(name.clone());
+        }
+    }
+
+    /// Is `name` defined in the current subroutine (not captured from the outside)?
+    fn is_local(&self, name: &str) -> bool {
+        for (kind, locals) in self.block_stack.iter().zip(self.local_vars.iter()).rev() {
+            if locals.contains(name) {
+                return true;
+            }
+            if matches!(kind, Func | ConstFunc | Proc) {
+                return false;
+            }
+        }
+        false
+    }
+
+    /// `x.y.z` -> `x`
+    fn root_name(expr: &Expr) -> Option<&Str> {
+        match expr {
+            Expr::Accessor(Accessor::Ident(ident)) => Some(ident.inspect()),
+            Expr::Accessor(Accessor::Attr(attr)) => Self::root_name(&attr.obj),
+            _ => None,
+        }
+    }
+
+    /// Mutating a local object (e.g. `arr = ![]; arr.push! 1`) is not a side-effect.
+    fn is_local_receiver(&self, receiver: &Expr) -> bool {
+        Self::root_name(receiver)
+            .map(|name| self.is_local(name))
+            .unwrap_or(false)
+    }
+
+    /// Procedural methods can change the internal state of the receiver,
+    /// so they can be defined only in mutable classes (e.g. `C!`).
+    fn check_immut_class_methods(&mut self, class_name: &Str, methods: &Block) {
+        if class_name.ends_with('!') {
+            return;
+        }
+        for method in methods.iter() {
+            if let Expr::Def(def) = method {
+                if def.sig.is_subr() && def.sig.is_procedural() {
+                    self.errs
+                        .push(EffectError::proc_method_in_immut_class_error(
+                            self.cfg.input.clone(),
+                            line!() as usize,
+                            def.sig.loc(),
+                            self.full_path(),
+                            class_name,
+                        ));
+                }
+            }
+        }
+    }
+
     fn check_params(&mut self, params: &Params) {
         for nd_param in params.non_defaults.iter() {
+            self.register_local(nd_param.inspect());
             if nd_param.vi.t.is_procedure() && !nd_param.inspect().unwrap().ends_with('!') {
                 self.errs.push(EffectError::proc_assign_error(
                     self.cfg.input.clone(),
@@ -221,6 +185,7 @@ impl SideEffectChecker {
             }
         }
         if let Some(var_arg) = params.var_params.as_deref() {
+            self.register_local(var_arg.inspect());
             if var_arg.vi.t.is_procedure() && !var_arg.inspect().unwrap().ends_with('!') {
                 self.errs.push(EffectError::proc_assign_error(
                     self.cfg.input.clone(),
@@ -231,6 +196,7 @@ impl SideEffectChecker {
             }
         }
         for d_param in params.defaults.iter() {
+            self.register_local(d_param.inspect());
             if d_param.sig.vi.t.is_procedure() && !d_param.inspect().unwrap().ends_with('!') {
                 self.errs.push(EffectError::proc_assign_error(
                     self.cfg.input.clone(),
@@ -241,6 +207,9 @@ impl SideEffectChecker {
             }
             self.check_expr(&d_param.default_val);
         }
+        if let Some(kw_var_arg) = params.kw_var_params.as_deref() {
+            self.register_local(kw_var_arg.inspect());
+        }
     }
 
     fn check_def(&mut self, def: &Def) {
@@ -248,6 +217,7 @@ impl SideEffectChecker {
             Signature::Var(var) => (var.inspect().clone(), var.vis()),
             Signature::Subr(subr) => (subr.ident.inspect().clone(), subr.ident.vis()),
         };
+        self.register_local(Some(&name_and_vis.0));
         self.path_stack.push(name_and_vis);
         let is_procedural = def.sig.is_procedural();
         let is_subr = def.sig.is_subr();
@@ -257,19 +227,19 @@ impl SideEffectChecker {
                 panic!("user-defined constant procedures are not allowed");
             }
             (true, true, false) => {
-                self.block_stack.push(Proc);
+                self.push_block(Proc);
             }
             (_, false, false) => {
-                self.block_stack.push(Instant);
+                self.push_block(Instant);
             }
             (false, true, true) => {
-                self.block_stack.push(ConstFunc);
+                self.push_block(ConstFunc);
             }
             (false, true, false) => {
-                self.block_stack.push(Func);
+                self.push_block(Func);
             }
             (_, false, true) => {
-                self.block_stack.push(ConstInstant);
+                self.push_block(ConstInstant);
             }
         }
         if let Signature::Subr(sig) = &def.sig {
@@ -293,7 +263,7 @@ impl SideEffectChecker {
             }
         }
         self.path_stack.pop();
-        self.block_stack.pop();
+        self.pop_block();
     }
 
     /// check if `expr` has side-effects / purity violations.
@@ -316,6 +286,12 @@ impl SideEffectChecker {
     /// purity violation:
     /// ```python
     /// for iter, i -> print! i
+    /// arr = ![]
+    /// f x = arr.push! x # mutating a captured object
+    /// g x =
+    ///     local = ![]
+    ///     local.push! x # OK
+    ///     local
     /// ```
     fn check_expr(&mut self, expr: &Expr) {
         match expr {
@@ -327,6 +303,7 @@ impl SideEffectChecker {
                 if let Some(req_sup) = &class_def.require_or_sup {
                     self.check_expr(req_sup);
                 }
+                self.check_immut_class_methods(class_def.sig.ident().inspect(), &class_def.methods);
                 for def in class_def.methods.iter() {
                     self.check_expr(def);
                 }
@@ -361,20 +338,20 @@ impl SideEffectChecker {
             },
             Expr::Record(record) => {
                 self.path_stack.push((Str::ever("<record>"), Private));
-                self.block_stack.push(Instant);
+                self.push_block(Instant);
                 for attr in record.attrs.iter() {
                     self.check_def(attr);
                 }
                 self.path_stack.pop();
-                self.block_stack.pop();
+                self.pop_block();
             }
             Expr::Set(set) => match set {
-                Set::Normal(set) => {
+                hir::Set::Normal(set) => {
                     for elem in set.elems.pos_args.iter() {
                         self.check_expr(&elem.expr);
                     }
                 }
-                Set::WithLength(set) => {
+                hir::Set::WithLength(set) => {
                     self.check_expr(&set.elem);
                     self.check_expr(&set.len);
                 }
@@ -386,15 +363,21 @@ impl SideEffectChecker {
                         self.check_expr(&kv.value);
                     }
                 }
-                other => todo!("{other}"),
+                Dict::Comprehension(dict) => {
+                    self.check_expr(&dict.key);
+                    self.check_expr(&dict.value);
+                    self.check_expr(&dict.guard);
+                }
             },
             Expr::Call(call) => {
-                if (call.obj.t().is_procedure()
-                    || call
-                        .attr_name
-                        .as_ref()
-                        .map(|name| name.is_procedural())
-                        .unwrap_or(false))
+                let is_proc_method = call
+                    .attr_name
+                    .as_ref()
+                    .map(|name| name.is_procedural())
+                    .unwrap_or(false);
+                // e.g. `captured_arr.push! 1`
+                let mutates_outer = is_proc_method && !self.is_local_receiver(&call.obj);
+                if (call.obj.t().is_procedure() || mutates_outer)
                     && !self.in_context_effects_allowed()
                 {
                     self.errs.push(EffectError::has_effect(
@@ -403,15 +386,23 @@ impl SideEffectChecker {
                         expr,
                         self.full_path(),
                     ));
+                } else {
+                    self.check_expr(&call.obj);
                 }
                 call.args
                     .pos_args
                     .iter()
                     .for_each(|parg| self.check_expr(&parg.expr));
+                if let Some(var_args) = call.args.var_args.as_ref() {
+                    self.check_expr(&var_args.expr);
+                }
                 call.args
                     .kw_args
                     .iter()
                     .for_each(|kwarg| self.check_expr(&kwarg.expr));
+                if let Some(kw_var_args) = call.args.kw_var_args.as_ref() {
+                    self.check_expr(&kw_var_args.expr);
+                }
             }
             Expr::UnaryOp(unary) => {
                 self.check_expr(&unary.expr);
@@ -434,21 +425,24 @@ impl SideEffectChecker {
                 let is_proc = lambda.is_procedural();
                 if is_proc {
                     self.path_stack.push((Str::ever("<lambda!>"), Private));
-                    self.block_stack.push(Proc);
+                    self.push_block(Proc);
                 } else {
                     self.path_stack.push((Str::ever("<lambda>"), Private));
-                    self.block_stack.push(Func);
+                    self.push_block(Func);
                 }
                 self.check_params(&lambda.params);
                 lambda.body.iter().for_each(|chunk| self.check_expr(chunk));
                 self.path_stack.pop();
-                self.block_stack.pop();
+                self.pop_block();
             }
             Expr::TypeAsc(type_asc) => {
                 self.check_expr(&type_asc.expr);
             }
             Expr::Accessor(acc) => {
-                if !self.in_context_effects_allowed() && acc.ref_t().is_mut_type() {
+                if !self.in_context_effects_allowed()
+                    && acc.ref_t().is_mut_type()
+                    && !self.is_local_receiver(expr)
+                {
                     self.errs.push(EffectError::touch_mut_error(
                         self.cfg.input.clone(),
                         line!() as usize,
@@ -457,11 +451,22 @@ impl SideEffectChecker {
                     ));
                 }
             }
-            Expr::ReDef(_)
-            | Expr::Code(_)
-            | Expr::Compound(_)
-            | Expr::Import(_)
-            | Expr::Dummy(_) => {}
+            Expr::ReDef(redef) => {
+                for chunk in redef.block.iter() {
+                    self.check_expr(chunk);
+                }
+            }
+            Expr::Code(chunks) | Expr::Compound(chunks) => {
+                for chunk in chunks.iter() {
+                    self.check_expr(chunk);
+                }
+            }
+            Expr::Dummy(dummy) => {
+                for chunk in dummy.iter() {
+                    self.check_expr(chunk);
+                }
+            }
+            Expr::Import(_) => {}
         }
     }
 }
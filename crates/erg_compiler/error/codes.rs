@@ -0,0 +1,1861 @@
+//! The registry of the stable error/warning codes (`erg --explain <code>`).
+//!
+//! Each diagnostic (i.e. each error constructor, e.g. `TyCheckError::type_mismatch_error`) has its own code,
+//! e.g. `E0025` (the type of an expression does not match the expected type) and `W0004` (a variable is not used).
+//! The codes are attached to the errors with `ErrorCore::with_code`.
+//! Since the codes do not depend on where the diagnostics are raised in the compiler,
+//! the existing codes must not be changed or reused; a new diagnostic is given a new code.
+use erg_common::error::ErrorKind::{self, *};
+use erg_common::switch_lang;
+
+/// A diagnostic reported by the compiler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// e.g. `E0025`
+    pub code: &'static str,
+    pub kind: ErrorKind,
+    /// what the diagnostic reports (one sentence)
+    pub summary: &'static str,
+}
+
+/// The errors and warnings reported by the compiler.
+pub const COMPILE_DIAGNOSTICS: [Diagnostic; 92] = [
+    Diagnostic {
+        code: "E0001",
+        kind: CompilerSystemError,
+        summary: switch_lang!(
+            "japanese" => "Ergの内部エラー(バグ)です。",
+            "simplified_chinese" => "Erg的内部错误(bug)。",
+            "traditional_chinese" => "Erg的內部錯誤(bug)。",
+            "english" => "An internal error of Erg (a bug).",
+        ),
+    },
+    Diagnostic {
+        code: "E0002",
+        kind: CompilerSystemError,
+        summary: switch_lang!(
+            "japanese" => "パーサーの内部エラー(バグ)です。",
+            "simplified_chinese" => "解析器的内部错误(bug)。",
+            "traditional_chinese" => "解析器的內部錯誤(bug)。",
+            "english" => "An internal error of the parser (a bug).",
+        ),
+    },
+    Diagnostic {
+        code: "E0003",
+        kind: FeatureError,
+        summary: switch_lang!(
+            "japanese" => "構文がまだ実装されていません。",
+            "simplified_chinese" => "该语法尚未实现。",
+            "traditional_chinese" => "該語法尚未實現。",
+            "english" => "The syntax is not implemented yet.",
+        ),
+    },
+    Diagnostic {
+        code: "E0004",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "コードが構文として正しくありません。",
+            "simplified_chinese" => "代码的语法无效。",
+            "traditional_chinese" => "代碼的語法無效。",
+            "english" => "The code is not valid syntax.",
+        ),
+    },
+    Diagnostic {
+        code: "E0005",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "構文規則に違反しています(詳細はエラーメッセージを参照)。",
+            "simplified_chinese" => "违反了语法规则(详情请参阅错误信息)。",
+            "traditional_chinese" => "違反了語法規則(詳情請參閱錯誤信息)。",
+            "english" => "The code violates a syntax rule (see the error message for details).",
+        ),
+    },
+    Diagnostic {
+        code: "E0006",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "変数が定義されていません(パーサーが報告)。",
+            "simplified_chinese" => "变量未定义(由解析器报告)。",
+            "traditional_chinese" => "變量未定義(由解析器報告)。",
+            "english" => "A variable is not defined (reported by the parser).",
+        ),
+    },
+    Diagnostic {
+        code: "E0007",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "式が`;`または改行で区切られていません。",
+            "simplified_chinese" => "表达式没有用`;`或换行符分隔。",
+            "traditional_chinese" => "表達式沒有用`;`或換行符分隔。",
+            "english" => "Expressions are not separated by `;` or a newline.",
+        ),
+    },
+    Diagnostic {
+        code: "E0008",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "要素が`,`または改行で区切られていません。",
+            "simplified_chinese" => "元素没有用`,`或换行符分隔。",
+            "traditional_chinese" => "元素沒有用`,`或換行符分隔。",
+            "english" => "Elements are not separated by `,` or a newline.",
+        ),
+    },
+    Diagnostic {
+        code: "E0009",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "ブロックの終端で変数が定義されています(ブロックは式で終わる必要があります)。",
+            "simplified_chinese" => "在块的末尾定义了变量(块必须以表达式结尾)。",
+            "traditional_chinese" => "在塊的末尾定義了變量(塊必須以表達式結尾)。",
+            "english" => "A variable is defined at the end of a block (a block must end with an expression).",
+        ),
+    },
+    Diagnostic {
+        code: "E0010",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "ブロックを解析できません。",
+            "simplified_chinese" => "无法解析块。",
+            "traditional_chinese" => "無法解析塊。",
+            "english" => "A block cannot be parsed.",
+        ),
+    },
+    Diagnostic {
+        code: "E0011",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "可変シンボルの`!`の位置が間違っています。",
+            "simplified_chinese" => "可变符号的`!`位置错误。",
+            "traditional_chinese" => "可變符號的`!`位置錯誤。",
+            "english" => "The `!` of a mutable symbol is in the wrong position.",
+        ),
+    },
+    Diagnostic {
+        code: "E0012",
+        kind: CompilerSystemError,
+        summary: switch_lang!(
+            "japanese" => "コンパイラの内部エラー(バグ)です。",
+            "simplified_chinese" => "编译器的内部错误(bug)。",
+            "traditional_chinese" => "編譯器的內部錯誤(bug)。",
+            "english" => "An internal error of the compiler (a bug).",
+        ),
+    },
+    Diagnostic {
+        code: "E0013",
+        kind: CompilerSystemError,
+        summary: switch_lang!(
+            "japanese" => "生成されたバイトコードのスタックが不整合です(コード生成器のバグ)。",
+            "simplified_chinese" => "生成的字节码的栈不一致(代码生成器的bug)。",
+            "traditional_chinese" => "生成的字節碼的棧不一致(代碼生成器的bug)。",
+            "english" => "The stack of the generated bytecode is inconsistent (a bug of the code generator).",
+        ),
+    },
+    Diagnostic {
+        code: "E0014",
+        kind: FeatureError,
+        summary: switch_lang!(
+            "japanese" => "機能がまだ(または対象のPythonのバージョンで)サポートされていません。",
+            "simplified_chinese" => "该功能尚未(或在目标Python版本上)得到支持。",
+            "traditional_chinese" => "該功能尚未(或在目標Python版本上)得到支持。",
+            "english" => "The feature is not supported yet (or not on the target Python version).",
+        ),
+    },
+    Diagnostic {
+        code: "E0015",
+        kind: HasEffect,
+        summary: switch_lang!(
+            "japanese" => "副作用を持つ式が関数の中で使われています。",
+            "simplified_chinese" => "在函数中使用了有副作用的表达式。",
+            "traditional_chinese" => "在函數中使用了有副作用的表達式。",
+            "english" => "An expression with a side effect is used in a function.",
+        ),
+    },
+    Diagnostic {
+        code: "E0016",
+        kind: HasEffect,
+        summary: switch_lang!(
+            "japanese" => "プロシージャが`!`で終わらない名前の変数に代入されています。",
+            "simplified_chinese" => "过程被赋值给名称不以`!`结尾的变量。",
+            "traditional_chinese" => "過程被賦值給名稱不以`!`結尾的變量。",
+            "english" => "A procedure is assigned to a variable whose name does not end with `!`.",
+        ),
+    },
+    Diagnostic {
+        code: "E0017",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "`await!`が非同期プロシージャの外で使われています。",
+            "simplified_chinese" => "在异步过程之外使用了`await!`。",
+            "traditional_chinese" => "在異步過程之外使用了`await!`。",
+            "english" => "`await!` is used outside of an async procedure.",
+        ),
+    },
+    Diagnostic {
+        code: "E0018",
+        kind: HasEffect,
+        summary: switch_lang!(
+            "japanese" => "不変クラスにプロシージャルメソッドが定義されています。",
+            "simplified_chinese" => "在不可变类中定义了过程方法。",
+            "traditional_chinese" => "在不可變類中定義了過程方法。",
+            "english" => "A procedural method is defined in an immutable class.",
+        ),
+    },
+    Diagnostic {
+        code: "E0019",
+        kind: HasEffect,
+        summary: switch_lang!(
+            "japanese" => "関数の中で可変オブジェクトにアクセスしています。",
+            "simplified_chinese" => "在函数中访问了可变对象。",
+            "traditional_chinese" => "在函數中訪問了可變對象。",
+            "english" => "A mutable object is accessed in a function.",
+        ),
+    },
+    Diagnostic {
+        code: "E0020",
+        kind: MoveError,
+        summary: switch_lang!(
+            "japanese" => "ムーブされた変数が使われています。",
+            "simplified_chinese" => "使用了已被移动的变量。",
+            "traditional_chinese" => "使用了已被移動的變量。",
+            "english" => "A variable is used after its value was moved.",
+        ),
+    },
+    Diagnostic {
+        code: "E0021",
+        kind: CyclicReferenceError,
+        summary: switch_lang!(
+            "japanese" => "可変オブジェクトが互いを参照しています(循環参照)。",
+            "simplified_chinese" => "可变对象相互引用(循环引用)。",
+            "traditional_chinese" => "可變對象相互引用(循環引用)。",
+            "english" => "Mutable objects refer to each other (a cyclic reference).",
+        ),
+    },
+    Diagnostic {
+        code: "E0022",
+        kind: CompilerSystemError,
+        summary: switch_lang!(
+            "japanese" => "型検査器の内部エラー(バグ)です。",
+            "simplified_chinese" => "类型检查器的内部错误(bug)。",
+            "traditional_chinese" => "類型檢查器的內部錯誤(bug)。",
+            "english" => "An internal error of the type checker (a bug).",
+        ),
+    },
+    Diagnostic {
+        code: "E0023",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "変数の型が指定されていません。",
+            "simplified_chinese" => "变量的类型未指定。",
+            "traditional_chinese" => "變量的類型未指定。",
+            "english" => "The type of a variable is not specified.",
+        ),
+    },
+    Diagnostic {
+        code: "E0024",
+        kind: NotImplementedError,
+        summary: switch_lang!(
+            "japanese" => "呼び出されたオブジェクトが与えられた引数を取る`Callable`ではありません。",
+            "simplified_chinese" => "被调用的对象不是接受给定参数的`Callable`。",
+            "traditional_chinese" => "被調用的對象不是接受給定參數的`Callable`。",
+            "english" => "The called object is not a `Callable` that takes the given arguments.",
+        ),
+    },
+    Diagnostic {
+        code: "E0025",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "式の型が期待される型と一致しません。",
+            "simplified_chinese" => "表达式的类型与预期类型不匹配。",
+            "traditional_chinese" => "表達式的類型與預期類型不匹配。",
+            "english" => "The type of an expression does not match the expected type.",
+        ),
+    },
+    Diagnostic {
+        code: "E0026",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "戻り値の型が宣言された戻り値型と一致しません。",
+            "simplified_chinese" => "返回值的类型与声明的返回类型不匹配。",
+            "traditional_chinese" => "返回值的類型與聲明的返回類型不匹配。",
+            "english" => "The type of the return value does not match the declared return type.",
+        ),
+    },
+    Diagnostic {
+        code: "E0027",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "宣言された変数が初期化されていません。",
+            "simplified_chinese" => "已声明的变量未被初始化。",
+            "traditional_chinese" => "已聲明的變量未被初始化。",
+            "english" => "A declared variable is not initialized.",
+        ),
+    },
+    Diagnostic {
+        code: "E0028",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "位置引数の数が一致しません。",
+            "simplified_chinese" => "位置参数的数量不匹配。",
+            "traditional_chinese" => "位置參數的數量不匹配。",
+            "english" => "The number of the positional arguments does not match.",
+        ),
+    },
+    Diagnostic {
+        code: "E0029",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "パラメータの数が一致しません。",
+            "simplified_chinese" => "参数的数量不匹配。",
+            "traditional_chinese" => "參數的數量不匹配。",
+            "english" => "The number of the parameters does not match.",
+        ),
+    },
+    Diagnostic {
+        code: "E0030",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "デフォルト引数を受け付けないサブルーチンにデフォルト引数が与えられています。",
+            "simplified_chinese" => "向不接受默认参数的子程序给出了默认参数。",
+            "traditional_chinese" => "向不接受默認參數的子程序給出了默認參數。",
+            "english" => "Default parameters are given to a subroutine that does not accept them.",
+        ),
+    },
+    Diagnostic {
+        code: "E0031",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "パターンが型のすべての値を網羅していません。",
+            "simplified_chinese" => "模式没有覆盖类型的所有值。",
+            "traditional_chinese" => "模式沒有覆蓋類型的所有值。",
+            "english" => "The patterns do not cover all the values of the type.",
+        ),
+    },
+    Diagnostic {
+        code: "E0032",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "式の型を推論できません。",
+            "simplified_chinese" => "无法推断表达式的类型。",
+            "traditional_chinese" => "無法推斷表達式的類型。",
+            "english" => "The type of an expression cannot be inferred.",
+        ),
+    },
+    Diagnostic {
+        code: "E0033",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "引数が多すぎます。",
+            "simplified_chinese" => "参数过多。",
+            "traditional_chinese" => "參數過多。",
+            "english" => "Too many arguments are passed.",
+        ),
+    },
+    Diagnostic {
+        code: "E0034",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "位置引数が足りません。",
+            "simplified_chinese" => "缺少位置参数。",
+            "traditional_chinese" => "缺少位置參數。",
+            "english" => "Some positional arguments are missing.",
+        ),
+    },
+    Diagnostic {
+        code: "E0035",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "同じ引数が複数回渡されています。",
+            "simplified_chinese" => "同一个参数被传递了多次。",
+            "traditional_chinese" => "同一個參數被傳遞了多次。",
+            "english" => "The same argument is passed multiple times.",
+        ),
+    },
+    Diagnostic {
+        code: "E0036",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "予期しないキーワード引数が渡されています。",
+            "simplified_chinese" => "传递了意外的关键字参数。",
+            "traditional_chinese" => "傳遞了意外的關鍵字參數。",
+            "english" => "An unexpected keyword argument is passed.",
+        ),
+    },
+    Diagnostic {
+        code: "E0037",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "2つの型を単一化できません。",
+            "simplified_chinese" => "无法统一两个类型。",
+            "traditional_chinese" => "無法統一兩個類型。",
+            "english" => "Two types cannot be unified.",
+        ),
+    },
+    Diagnostic {
+        code: "E0038",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "単一化済みの型を再単一化できません。",
+            "simplified_chinese" => "无法重新统一已统一的类型。",
+            "traditional_chinese" => "無法重新統一已統一的類型。",
+            "english" => "Already unified types cannot be unified again.",
+        ),
+    },
+    Diagnostic {
+        code: "E0039",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "部分型制約を満たすことができません。",
+            "simplified_chinese" => "无法满足子类型约束。",
+            "traditional_chinese" => "無法滿足子類型約束。",
+            "english" => "A subtype constraint cannot be satisfied.",
+        ),
+    },
+    Diagnostic {
+        code: "E0040",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "述語(篩型の条件)を単一化できません。",
+            "simplified_chinese" => "无法统一谓词(细化类型的条件)。",
+            "traditional_chinese" => "無法統一謂詞(細化類型的條件)。",
+            "english" => "Predicates (the conditions of refinement types) cannot be unified.",
+        ),
+    },
+    Diagnostic {
+        code: "E0041",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "射影型の候補が見つかりません。",
+            "simplified_chinese" => "找不到投影类型的候选。",
+            "traditional_chinese" => "找不到投影類型的候選。",
+            "english" => "No candidate is found for a projection type.",
+        ),
+    },
+    Diagnostic {
+        code: "E0042",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "クラスがトレイトを実装していません。",
+            "simplified_chinese" => "类没有实现特质。",
+            "traditional_chinese" => "類沒有實現特質。",
+            "english" => "A class does not implement a trait.",
+        ),
+    },
+    Diagnostic {
+        code: "E0043",
+        kind: MethodError,
+        summary: switch_lang!(
+            "japanese" => "メソッドを定義できない型にメソッドが定義されています。",
+            "simplified_chinese" => "在不能定义方法的类型上定义了方法。",
+            "traditional_chinese" => "在不能定義方法的類型上定義了方法。",
+            "english" => "Methods are defined for a type that cannot have methods.",
+        ),
+    },
+    Diagnostic {
+        code: "E0044",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "トレイトのメンバの型が一致しません。",
+            "simplified_chinese" => "特质成员的类型不匹配。",
+            "traditional_chinese" => "特質成員的類型不匹配。",
+            "english" => "The type of a trait member does not match.",
+        ),
+    },
+    Diagnostic {
+        code: "E0045",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "トレイトのメンバが実装されていません。",
+            "simplified_chinese" => "特质的成员没有被实现。",
+            "traditional_chinese" => "特質的成員沒有被實現。",
+            "english" => "A member of a trait is not implemented.",
+        ),
+    },
+    Diagnostic {
+        code: "E0046",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "トレイトで宣言されていないメンバが実装されています。",
+            "simplified_chinese" => "实现了特质中没有声明的成员。",
+            "traditional_chinese" => "實現了特質中沒有聲明的成員。",
+            "english" => "A member not declared in the trait is implemented.",
+        ),
+    },
+    Diagnostic {
+        code: "E0047",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "型変数が定義されていません。",
+            "simplified_chinese" => "类型变量未定义。",
+            "traditional_chinese" => "類型變量未定義。",
+            "english" => "A type variable is not defined.",
+        ),
+    },
+    Diagnostic {
+        code: "E0048",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "型が曖昧です(候補が複数あります)。",
+            "simplified_chinese" => "类型不明确(有多个候选)。",
+            "traditional_chinese" => "類型不明確(有多個候選)。",
+            "english" => "The type is ambiguous (there are multiple candidates).",
+        ),
+    },
+    Diagnostic {
+        code: "E0049",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "配列型の長さが`Nat`ではありません。",
+            "simplified_chinese" => "数组类型的长度不是`Nat`。",
+            "traditional_chinese" => "數組類型的長度不是`Nat`。",
+            "english" => "The length of an array type is not a `Nat`.",
+        ),
+    },
+    Diagnostic {
+        code: "E0050",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "辞書型のキーが型ではありません。",
+            "simplified_chinese" => "字典类型的键不是类型。",
+            "traditional_chinese" => "字典類型的鍵不是類型。",
+            "english" => "A key of a dict type is not a type.",
+        ),
+    },
+    Diagnostic {
+        code: "E0051",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "共通部分型がインスタンスを持ちません。",
+            "simplified_chinese" => "交集类型没有实例。",
+            "traditional_chinese" => "交集類型沒有實例。",
+            "english" => "An intersection type has no instances.",
+        ),
+    },
+    Diagnostic {
+        code: "E0052",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "構文が正しくありません(詳細はエラーメッセージを参照)。",
+            "simplified_chinese" => "语法无效(详情请参阅错误信息)。",
+            "traditional_chinese" => "語法無效(詳情請參閱錯誤信息)。",
+            "english" => "The syntax is invalid (see the error message for details).",
+        ),
+    },
+    Diagnostic {
+        code: "E0053",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "変数が二重に宣言されています。",
+            "simplified_chinese" => "变量被重复声明。",
+            "traditional_chinese" => "變量被重複聲明。",
+            "english" => "A variable is declared twice.",
+        ),
+    },
+    Diagnostic {
+        code: "E0054",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "変数が二重に定義されています。",
+            "simplified_chinese" => "变量被重复定义。",
+            "traditional_chinese" => "變量被重複定義。",
+            "english" => "A variable is defined twice.",
+        ),
+    },
+    Diagnostic {
+        code: "E0055",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "宣言された型と異なる型のオブジェクトが代入されています。",
+            "simplified_chinese" => "赋值了与声明类型不同的对象。",
+            "traditional_chinese" => "賦值了與聲明類型不同的對象。",
+            "english" => "An object of a type different from the declared one is assigned.",
+        ),
+    },
+    Diagnostic {
+        code: "E0056",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "変数が定義されていません。",
+            "simplified_chinese" => "变量未定义。",
+            "traditional_chinese" => "變量未定義。",
+            "english" => "A variable is not defined.",
+        ),
+    },
+    Diagnostic {
+        code: "E0057",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "変数が定義前に参照されています。",
+            "simplified_chinese" => "变量在定义之前被访问。",
+            "traditional_chinese" => "變量在定義之前被訪問。",
+            "english" => "A variable is accessed before its definition.",
+        ),
+    },
+    Diagnostic {
+        code: "E0058",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "削除された変数が参照されています。",
+            "simplified_chinese" => "访问了已删除的变量。",
+            "traditional_chinese" => "訪問了已刪除的變量。",
+            "english" => "A deleted variable is accessed.",
+        ),
+    },
+    Diagnostic {
+        code: "E0059",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "型が定義されていません。",
+            "simplified_chinese" => "类型未定义。",
+            "traditional_chinese" => "類型未定義。",
+            "english" => "A type is not defined.",
+        ),
+    },
+    Diagnostic {
+        code: "E0060",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "型がコンテキストに見つかりません(コンパイラのバグの可能性があります)。",
+            "simplified_chinese" => "在上下文中找不到类型(可能是编译器的bug)。",
+            "traditional_chinese" => "在上下文中找不到類型(可能是編譯器的bug)。",
+            "english" => "A type is not found in the context (this may be a bug of the compiler).",
+        ),
+    },
+    Diagnostic {
+        code: "E0061",
+        kind: AttributeError,
+        summary: switch_lang!(
+            "japanese" => "オブジェクトが属性を持っていません。",
+            "simplified_chinese" => "对象没有该属性。",
+            "traditional_chinese" => "對象沒有該屬性。",
+            "english" => "An object does not have the attribute.",
+        ),
+    },
+    Diagnostic {
+        code: "E0062",
+        kind: AttributeError,
+        summary: switch_lang!(
+            "japanese" => "(シングルトンの)オブジェクトが属性を持っていません。",
+            "simplified_chinese" => "(单例)对象没有该属性。",
+            "traditional_chinese" => "(單例)對象沒有該屬性。",
+            "english" => "A (singleton) object does not have the attribute.",
+        ),
+    },
+    Diagnostic {
+        code: "E0063",
+        kind: AssignError,
+        summary: switch_lang!(
+            "japanese" => "変数に2回以上代入されています。",
+            "simplified_chinese" => "变量被赋值了多次。",
+            "traditional_chinese" => "變量被賦值了多次。",
+            "english" => "A variable is assigned more than once.",
+        ),
+    },
+    Diagnostic {
+        code: "E0064",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "定数または組み込み変数が削除されています。",
+            "simplified_chinese" => "删除了常量或内置变量。",
+            "traditional_chinese" => "刪除了常量或內置變量。",
+            "english" => "A constant or a built-in variable is deleted.",
+        ),
+    },
+    Diagnostic {
+        code: "E0065",
+        kind: VisibilityError,
+        summary: switch_lang!(
+            "japanese" => "非公開の変数に外部からアクセスしています。",
+            "simplified_chinese" => "从外部访问了私有变量。",
+            "traditional_chinese" => "從外部訪問了私有變量。",
+            "english" => "A private variable is accessed from outside.",
+        ),
+    },
+    Diagnostic {
+        code: "E0066",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "`@Override`なしで属性がオーバーライドされています。",
+            "simplified_chinese" => "没有使用`@Override`就覆盖了属性。",
+            "traditional_chinese" => "沒有使用`@Override`就覆蓋了屬性。",
+            "english" => "An attribute is overridden without `@Override`.",
+        ),
+    },
+    Diagnostic {
+        code: "E0067",
+        kind: InheritanceError,
+        summary: switch_lang!(
+            "japanese" => "継承できないクラスが継承されています。",
+            "simplified_chinese" => "继承了不可继承的类。",
+            "traditional_chinese" => "繼承了不可繼承的類。",
+            "english" => "A class that is not inheritable is inherited.",
+        ),
+    },
+    Diagnostic {
+        code: "E0068",
+        kind: IoError,
+        summary: switch_lang!(
+            "japanese" => "ファイルを読み込めません。",
+            "simplified_chinese" => "无法读取文件。",
+            "traditional_chinese" => "無法讀取文件。",
+            "english" => "A file cannot be read.",
+        ),
+    },
+    Diagnostic {
+        code: "E0069",
+        kind: IoError,
+        summary: switch_lang!(
+            "japanese" => "モジュールがお使いの環境をサポートしていません。",
+            "simplified_chinese" => "模块不支持您的环境。",
+            "traditional_chinese" => "模塊不支持您的環境。",
+            "english" => "A module does not support your environment.",
+        ),
+    },
+    Diagnostic {
+        code: "E0070",
+        kind: ImportError,
+        summary: switch_lang!(
+            "japanese" => "インポートするモジュールが見つかりません。",
+            "simplified_chinese" => "找不到要导入的模块。",
+            "traditional_chinese" => "找不到要導入的模塊。",
+            "english" => "The imported module is not found.",
+        ),
+    },
+    Diagnostic {
+        code: "E0071",
+        kind: ImportError,
+        summary: switch_lang!(
+            "japanese" => "モジュールが循環インポートされています。",
+            "simplified_chinese" => "检测到模块的循环导入。",
+            "traditional_chinese" => "檢測到模塊的循環導入。",
+            "english" => "Modules import each other cyclically.",
+        ),
+    },
+    Diagnostic {
+        code: "E0072",
+        kind: NameError,
+        summary: switch_lang!(
+            "japanese" => "定数が循環的に定義されています。",
+            "simplified_chinese" => "常量被循环定义。",
+            "traditional_chinese" => "常量被循環定義。",
+            "english" => "Constants are defined cyclically.",
+        ),
+    },
+    Diagnostic {
+        code: "E0073",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "型がトップレベル以外で定義されています。",
+            "simplified_chinese" => "类型没有在顶层定义。",
+            "traditional_chinese" => "類型沒有在頂層定義。",
+            "english" => "A type is defined outside of the top level.",
+        ),
+    },
+    Diagnostic {
+        code: "E0074",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "宣言がd.erファイルの外で使われています。",
+            "simplified_chinese" => "在d.er文件之外使用了声明。",
+            "traditional_chinese" => "在d.er文件之外使用了聲明。",
+            "english" => "A declaration is used outside of a d.er file.",
+        ),
+    },
+    Diagnostic {
+        code: "E0075",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "変数の型をキャストできません。",
+            "simplified_chinese" => "无法转换变量的类型。",
+            "traditional_chinese" => "無法轉換變量的類型。",
+            "english" => "The type of a variable cannot be cast.",
+        ),
+    },
+    Diagnostic {
+        code: "E0076",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "`yield`がジェネレータ関数の外で使われています。",
+            "simplified_chinese" => "在生成器函数之外使用了`yield`。",
+            "traditional_chinese" => "在生成器函數之外使用了`yield`。",
+            "english" => "`yield` is used outside of a generator function.",
+        ),
+    },
+    Diagnostic {
+        code: "E0077",
+        kind: NotConstExpr,
+        summary: switch_lang!(
+            "japanese" => "定数式でない式が定数の位置で使われています。",
+            "simplified_chinese" => "在需要常量的位置使用了非常量表达式。",
+            "traditional_chinese" => "在需要常量的位置使用了非常量表達式。",
+            "english" => "A non-constant expression is used where a constant is required.",
+        ),
+    },
+    Diagnostic {
+        code: "E0078",
+        kind: SyntaxError,
+        summary: switch_lang!(
+            "japanese" => "リテラルが正しくありません。",
+            "simplified_chinese" => "字面量无效。",
+            "traditional_chinese" => "字面量無效。",
+            "english" => "A literal is invalid.",
+        ),
+    },
+    Diagnostic {
+        code: "E0079",
+        kind: FeatureError,
+        summary: switch_lang!(
+            "japanese" => "コンパイル時評価でまだサポートされていない機能が使われています。",
+            "simplified_chinese" => "编译时求值中使用了尚不支持的功能。",
+            "traditional_chinese" => "編譯時求值中使用了尚不支持的功能。",
+            "english" => "A feature not supported yet is used in the compile-time evaluation.",
+        ),
+    },
+    Diagnostic {
+        code: "E0080",
+        kind: ImportError,
+        summary: switch_lang!(
+            "japanese" => "バイトコードファイルが壊れています。",
+            "simplified_chinese" => "字节码文件已损坏。",
+            "traditional_chinese" => "字節碼文件已損壞。",
+            "english" => "A bytecode file is broken.",
+        ),
+    },
+    Diagnostic {
+        code: "E0081",
+        kind: KeyError,
+        summary: switch_lang!(
+            "japanese" => "コンパイル時関数に必要な引数が渡されていません。",
+            "simplified_chinese" => "没有向编译时函数传递必需的参数。",
+            "traditional_chinese" => "沒有向編譯時函數傳遞必需的參數。",
+            "english" => "A required argument is not passed to a compile-time function.",
+        ),
+    },
+    Diagnostic {
+        code: "E0082",
+        kind: TypeError,
+        summary: switch_lang!(
+            "japanese" => "型が必要なところに型でないオブジェクトが渡されています。",
+            "simplified_chinese" => "在需要类型的位置传递了非类型对象。",
+            "traditional_chinese" => "在需要類型的位置傳遞了非類型對象。",
+            "english" => "A non-type object is passed where a type is required.",
+        ),
+    },
+    Diagnostic {
+        code: "E0083",
+        kind: IndexError,
+        summary: switch_lang!(
+            "japanese" => "コンパイル時のインデックスアクセスが範囲外です。",
+            "simplified_chinese" => "编译时的索引访问越界。",
+            "traditional_chinese" => "編譯時的索引訪問越界。",
+            "english" => "An index is out of range in the compile-time evaluation.",
+        ),
+    },
+    Diagnostic {
+        code: "E0084",
+        kind: IndexError,
+        summary: switch_lang!(
+            "japanese" => "コンパイル時に辞書にキーが見つかりません。",
+            "simplified_chinese" => "编译时在字典中找不到键。",
+            "traditional_chinese" => "編譯時在字典中找不到鍵。",
+            "english" => "A key is not found in a dict in the compile-time evaluation.",
+        ),
+    },
+    Diagnostic {
+        code: "W0001",
+        kind: SyntaxWarning,
+        summary: switch_lang!(
+            "japanese" => "非推奨または紛らわしい構文です。",
+            "simplified_chinese" => "已弃用或容易混淆的语法。",
+            "traditional_chinese" => "已棄用或容易混淆的語法。",
+            "english" => "The syntax is deprecated or confusing.",
+        ),
+    },
+    Diagnostic {
+        code: "W0002",
+        kind: UnusedWarning,
+        summary: switch_lang!(
+            "japanese" => "式の評価結果が使われていません。",
+            "simplified_chinese" => "表达式的求值结果没有被使用。",
+            "traditional_chinese" => "表達式的求值結果沒有被使用。",
+            "english" => "The evaluation result of an expression is not used.",
+        ),
+    },
+    Diagnostic {
+        code: "W0003",
+        kind: UnusedWarning,
+        summary: switch_lang!(
+            "japanese" => "サブルーチンが呼び出されずに捨てられています(`()`の付け忘れ)。",
+            "simplified_chinese" => "子程序没有被调用就被丢弃了(忘记了`()`)。",
+            "traditional_chinese" => "子程序沒有被調用就被丟棄了(忘記了`()`)。",
+            "english" => "A subroutine is discarded without being called (a missing `()`).",
+        ),
+    },
+    Diagnostic {
+        code: "W0004",
+        kind: UnusedWarning,
+        summary: switch_lang!(
+            "japanese" => "変数が使われていません。",
+            "simplified_chinese" => "变量没有被使用。",
+            "traditional_chinese" => "變量沒有被使用。",
+            "english" => "A variable is not used.",
+        ),
+    },
+    Diagnostic {
+        code: "W0005",
+        kind: TypeWarning,
+        summary: switch_lang!(
+            "japanese" => "関数の戻り値型が単一の型ではありません。",
+            "simplified_chinese" => "函数的返回类型不是单一类型。",
+            "traditional_chinese" => "函數的返回類型不是單一類型。",
+            "english" => "The return type of a function is not a single type.",
+        ),
+    },
+    Diagnostic {
+        code: "W0006",
+        kind: NameWarning,
+        summary: switch_lang!(
+            "japanese" => "同名の組み込み関数が既に存在します。",
+            "simplified_chinese" => "已经存在同名的内置函数。",
+            "traditional_chinese" => "已經存在同名的內置函數。",
+            "english" => "A built-in function with the same name already exists.",
+        ),
+    },
+    Diagnostic {
+        code: "W0007",
+        kind: TypeNameWarning,
+        summary: switch_lang!(
+            "japanese" => "レコード型が小文字の名前に束縛されています。",
+            "simplified_chinese" => "记录类型被绑定到小写名称。",
+            "traditional_chinese" => "記錄類型被綁定到小寫名稱。",
+            "english" => "A record type is bound to a lowercase name.",
+        ),
+    },
+    Diagnostic {
+        code: "W0008",
+        kind: Warning,
+        summary: switch_lang!(
+            "japanese" => "ループのブロック内の`.return`が、レシーバではなく外側のサブルーチンから返ります。",
+            "simplified_chinese" => "循环块中的`.return`从外部子程序返回, 而不是从接收者返回。",
+            "traditional_chinese" => "循環塊中的`.return`從外部子程序返回, 而不是從接收者返回。",
+            "english" => "A `.return` in a loop block returns from the outer subroutine, not from its receiver.",
+        ),
+    },
+];
+
+/// The kinds of the errors and warnings raised by Python at runtime.
+pub const RUNTIME_DIAGNOSTICS: [ErrorKind; 52] = [
+    ArithmeticError,
+    AssertionError,
+    BlockingIOError,
+    BrokenPipeError,
+    BufferError,
+    ChildProcessError,
+    ConnectionAbortedError,
+    ConnectionError,
+    ConnectionRefusedError,
+    ConnectionResetError,
+    EOFError,
+    FileExistsError,
+    FileNotFoundError,
+    IndexError,
+    InterruptedError,
+    IoError,
+    IsADirectoryError,
+    KeyError,
+    LookupError,
+    MemoryError,
+    ModuleNotFoundError,
+    NotADirectoryError,
+    OSError,
+    OverflowError,
+    PermissionError,
+    ProcessLookupError,
+    RecursionError,
+    ReferenceError,
+    RuntimeAttributeError,
+    RuntimeError,
+    RuntimeTypeError,
+    RuntimeUnicodeError,
+    TimeoutError,
+    UnicodeError,
+    UserError,
+    ValueError,
+    VMSystemError,
+    WindowsError,
+    ZeroDivisionError,
+    BytesWarning,
+    ResourceWarning,
+    RuntimeWarning,
+    UnicodeWarning,
+    UserWarning,
+    BaseException,
+    Exception,
+    GeneratorExit,
+    KeyboardInterrupt,
+    StopAsyncIteration,
+    StopIteration,
+    SystemExit,
+    UserException,
+];
+
+/// Finds the diagnostic of a code (e.g. `E0025`, `e25`).
+pub fn lookup(code: &str) -> Option<&'static Diagnostic> {
+    let (prefix, digits) = code.split_at(code.find(|c: char| c.is_ascii_digit())?);
+    let num = digits.parse::<u16>().ok()?;
+    let code = format!("{}{num:04}", prefix.to_ascii_uppercase());
+    COMPILE_DIAGNOSTICS.iter().find(|diag| diag.code == code)
+}
+
+/// Finds the kind of a name (e.g. `TypeError`, `ZeroDivisionError`).
+pub fn lookup_kind(name: &str) -> Option<ErrorKind> {
+    COMPILE_DIAGNOSTICS
+        .iter()
+        .map(|diag| diag.kind)
+        .chain(RUNTIME_DIAGNOSTICS)
+        .find(|kind| kind.to_string().eq_ignore_ascii_case(name))
+}
+
+/// The long-form explanation of the diagnostic, e.g.
+/// ```txt
+/// E0025: TypeError
+///
+/// The type of an expression does not match the expected type.
+///
+/// The type of an expression does not match the expected type ...
+/// ```
+pub fn explain(diag: &Diagnostic) -> String {
+    let mut text = format!("{}: {}\n\n{}", diag.code, diag.kind, diag.summary);
+    if let Some(body) = explanation(diag.kind) {
+        text += "\n\n";
+        text += body.trim_end();
+    }
+    text
+}
+
+/// The long-form explanation of the kind and the codes of the kind, e.g.
+/// ```txt
+/// TypeError (E0023, E0025, ...)
+///
+/// The type of an expression does not match the expected type ...
+/// ```
+pub fn explain_kind(kind: ErrorKind) -> String {
+    let codes = COMPILE_DIAGNOSTICS
+        .iter()
+        .filter(|diag| diag.kind == kind)
+        .map(|diag| diag.code)
+        .collect::<Vec<_>>();
+    let body = explanation(kind).map_or_else(|| runtime_explanation(kind), ToString::to_string);
+    if codes.is_empty() {
+        format!("{kind}\n\n{}", body.trim_end())
+    } else {
+        format!("{kind} ({})\n\n{}", codes.join(", "), body.trim_end())
+    }
+}
+
+fn runtime_explanation(kind: ErrorKind) -> String {
+    switch_lang!(
+        "japanese" => format!("{kind}は実行時にPythonが送出する例外です。\n詳細はPythonのドキュメントを参照してください: https://docs.python.org/3/library/exceptions.html"),
+        "simplified_chinese" => format!("{kind}是运行时由Python抛出的异常。\n详情请参阅Python文档: https://docs.python.org/3/library/exceptions.html"),
+        "traditional_chinese" => format!("{kind}是運行時由Python拋出的異常。\n詳情請參閱Python文檔: https://docs.python.org/3/library/exceptions.html"),
+        "english" => format!("{kind} is an exception raised by Python at runtime.\nSee the Python documentation for details: https://docs.python.org/3/library/exceptions.html"),
+    )
+}
+
+fn explanation(kind: ErrorKind) -> Option<&'static str> {
+    let text = match kind {
+        AssignError => switch_lang!(
+            "japanese" => "\
+変数に2回以上代入しようとしました。
+Ergの変数はデフォルトで不変であり、同じスコープで一度しか束縛できません。
+値を変更したい場合は可変変数(`!`)を使ってください。
+
+エラーとなるコードの例:
+
+    x = 1
+    x = 2
+
+修正例:
+
+    x = !1
+    x.update! _ -> 2",
+            "simplified_chinese" => "\
+试图对变量进行两次以上的赋值。
+Erg的变量默认是不可变的, 在同一作用域中只能绑定一次。
+如果想要修改值, 请使用可变变量(`!`)。
+
+错误代码示例:
+
+    x = 1
+    x = 2
+
+修正示例:
+
+    x = !1
+    x.update! _ -> 2",
+            "traditional_chinese" => "\
+試圖對變量進行兩次以上的賦值。
+Erg的變量默認是不可變的, 在同一作用域中只能綁定一次。
+如果想要修改值, 請使用可變變量(`!`)。
+
+錯誤代碼示例:
+
+    x = 1
+    x = 2
+
+修正示例:
+
+    x = !1
+    x.update! _ -> 2",
+            "english" => "\
+A variable is assigned more than once.
+Variables in Erg are immutable by default, and a name can be bound only once in a scope.
+Use a mutable variable (`!`) to change the value.
+
+Erroneous code example:
+
+    x = 1
+    x = 2
+
+Fixed example:
+
+    x = !1
+    x.update! _ -> 2",
+        ),
+        AttributeError => switch_lang!(
+            "japanese" => "\
+オブジェクトにその属性がありません。
+綴りと、オブジェクトの型を確認してください(属性がサブタイプでのみ定義されている可能性があります)。
+
+エラーとなるコードの例:
+
+    s = \"abc\"
+    s.foo",
+            "simplified_chinese" => "\
+对象没有该属性。
+请检查拼写和对象的类型(该属性可能只在子类型中定义)。
+
+错误代码示例:
+
+    s = \"abc\"
+    s.foo",
+            "traditional_chinese" => "\
+對象沒有該屬性。
+請檢查拼寫和對象的類型(該屬性可能只在子類型中定義)。
+
+錯誤代碼示例:
+
+    s = \"abc\"
+    s.foo",
+            "english" => "\
+The object does not have the attribute.
+Check the spelling and the type of the object (the attribute may be defined only in a subtype).
+
+Erroneous code example:
+
+    s = \"abc\"
+    s.foo",
+        ),
+        BytecodeError => switch_lang!(
+            "japanese" => "\
+バイトコードの生成または読み込みに失敗しました。
+.pycファイルが壊れているか、別のバージョンのPython向けに生成された可能性があります。
+`--py-command`や`--target-version`で対象のPythonを確認してください。",
+            "simplified_chinese" => "\
+生成或读取字节码失败。
+.pyc文件可能已损坏, 或者是为其他版本的Python生成的。
+请通过`--py-command`或`--target-version`确认目标Python。",
+            "traditional_chinese" => "\
+生成或讀取字節碼失敗。
+.pyc文件可能已損壞, 或者是為其他版本的Python生成的。
+請通過`--py-command`或`--target-version`確認目標Python。",
+            "english" => "\
+The bytecode could not be generated or read.
+The .pyc file may be broken or generated for another version of Python.
+Check the target Python with `--py-command` or `--target-version`.",
+        ),
+        CompilerSystemError => switch_lang!(
+            "japanese" => "\
+Ergコンパイラの内部エラーです。これはバグです。
+エラーを引き起こしたコードを添えて https://github.com/erg-lang/erg に報告してください。",
+            "simplified_chinese" => "\
+Erg编译器的内部错误。这是一个bug。
+请附上引发错误的代码报告给 https://github.com/erg-lang/erg。",
+            "traditional_chinese" => "\
+Erg編譯器的內部錯誤。這是一個bug。
+請附上引發錯誤的代碼報告給 https://github.com/erg-lang/erg。",
+            "english" => "\
+An internal error of the Erg compiler. This is a bug.
+Please report it to https://github.com/erg-lang/erg with the code that caused it.",
+        ),
+        EnvironmentError => switch_lang!(
+            "japanese" => "\
+コンパイラが必要とする環境(Pythonインタプリタ、標準ライブラリのディレクトリなど)が利用できません。
+Ergのインストールと、`--py-command`で指定したPythonを確認してください。",
+            "simplified_chinese" => "\
+编译器所需的环境(Python解释器、标准库目录等)不可用。
+请检查Erg的安装以及通过`--py-command`指定的Python。",
+            "traditional_chinese" => "\
+編譯器所需的環境(Python解釋器、標準庫目錄等)不可用。
+請檢查Erg的安裝以及通過`--py-command`指定的Python。",
+            "english" => "\
+The environment required by the compiler (e.g. the Python interpreter, the standard library directory) is not available.
+Check the installation of Erg and the Python specified with `--py-command`.",
+        ),
+        FeatureError => switch_lang!(
+            "japanese" => "\
+この機能はこのバージョンのコンパイラではまだ実装されていません。
+この機能を使わずに書き換えるか、最新版を確認してください。",
+            "simplified_chinese" => "\
+此版本的编译器尚未实现该功能。
+请不使用该功能重写代码, 或检查最新版本。",
+            "traditional_chinese" => "\
+此版本的編譯器尚未實現該功能。
+請不使用該功能重寫代碼, 或檢查最新版本。",
+            "english" => "\
+The feature is not implemented yet in this version of the compiler.
+Rewrite the code without it, or check the latest version.",
+        ),
+        ImportError => switch_lang!(
+            "japanese" => "\
+モジュールをインポートできません。ファイルが見つからないか、モジュール同士が循環してインポートしています。
+`import \"foo\"`は、インポート元のファイルからの相対パス(`foo.er`または`foo/__init__.er`)、パッケージの依存関係、標準ライブラリの順に検索します。
+Pythonのモジュールは`pyimport`でインポートします。
+
+エラーとなるコードの例(foo.erが存在しない場合):
+
+    foo = import \"foo\"",
+            "simplified_chinese" => "\
+无法导入模块。文件不存在, 或者模块之间存在循环导入。
+`import \"foo\"`会依次搜索相对于导入文件的路径(`foo.er`或`foo/__init__.er`)、包的依赖和标准库。
+Python模块请使用`pyimport`导入。
+
+错误代码示例(foo.er不存在时):
+
+    foo = import \"foo\"",
+            "traditional_chinese" => "\
+無法導入模塊。文件不存在, 或者模塊之間存在循環導入。
+`import \"foo\"`會依次搜索相對於導入文件的路徑(`foo.er`或`foo/__init__.er`)、包的依賴和標準庫。
+Python模塊請使用`pyimport`導入。
+
+錯誤代碼示例(foo.er不存在時):
+
+    foo = import \"foo\"",
+            "english" => "\
+The module cannot be imported. The file was not found, or the modules import each other cyclically.
+`import \"foo\"` searches the path relative to the importing file (`foo.er` or `foo/__init__.er`), the dependencies of the package, and the standard library in this order.
+Python modules are imported with `pyimport`.
+
+Erroneous code example (when foo.er does not exist):
+
+    foo = import \"foo\"",
+        ),
+        IndentationError => switch_lang!(
+            "japanese" => "\
+インデントが不正です。
+ブロックの本体はその見出しより深くインデントし、同じブロックの行は同じ深さでインデントする必要があります。",
+            "simplified_chinese" => "\
+缩进不正确。
+代码块的主体必须比其开头缩进更深, 同一代码块中的行必须具有相同的缩进。",
+            "traditional_chinese" => "\
+縮進不正確。
+代碼塊的主體必須比其開頭縮進更深, 同一代碼塊中的行必須具有相同的縮進。",
+            "english" => "\
+The indentation is invalid.
+The body of a block must be indented deeper than its header, and the lines of the same block must have the same indentation.",
+        ),
+        NameError => switch_lang!(
+            "japanese" => "\
+その名前はスコープ内で定義されていません。
+綴りを確認してください。変数は使用する前に定義する必要があり、内側のブロックで定義された名前は外側からは見えません。
+
+エラーとなるコードの例:
+
+    print! y",
+            "simplified_chinese" => "\
+该名称在作用域内未定义。
+请检查拼写。变量必须在使用前定义, 在内部代码块中定义的名称在外部不可见。
+
+错误代码示例:
+
+    print! y",
+            "traditional_chinese" => "\
+該名稱在作用域內未定義。
+請檢查拼寫。變量必須在使用前定義, 在內部代碼塊中定義的名稱在外部不可見。
+
+錯誤代碼示例:
+
+    print! y",
+            "english" => "\
+The name is not defined in the scope.
+Check the spelling. Variables must be defined before they are used, and the names defined in an inner block are not visible from outside.
+
+Erroneous code example:
+
+    print! y",
+        ),
+        NotImplementedError => switch_lang!(
+            "japanese" => "\
+オブジェクトは与えられた引数で呼び出すことができません(その引数型を取る呼び出し可能オブジェクトではありません)。
+
+エラーとなるコードの例:
+
+    x = 1
+    x(2)",
+            "simplified_chinese" => "\
+该对象不能以给定的参数调用(它不是以这些参数类型作为参数的可调用对象)。
+
+错误代码示例:
+
+    x = 1
+    x(2)",
+            "traditional_chinese" => "\
+該對象不能以給定的參數調用(它不是以這些參數類型作為參數的可調用對象)。
+
+錯誤代碼示例:
+
+    x = 1
+    x(2)",
+            "english" => "\
+The object cannot be called with the given arguments (it is not a callable object that takes the argument types).
+
+Erroneous code example:
+
+    x = 1
+    x(2)",
+        ),
+        PatternError => switch_lang!(
+            "japanese" => "\
+        このパターンはここでは使えません(変数定義での反駁可能なパターンなど)。",
+            "simplified_chinese" => "\
+        此处不能使用该模式(例如变量定义中的可反驳模式)。",
+            "traditional_chinese" => "\
+        此處不能使用該模式(例如變量定義中的可反駁模式)。",
+            "english" => "\
+        The pattern cannot be used here (e.g. a refutable pattern in a variable definition).",
+        ),
+        SyntaxError => switch_lang!(
+            "japanese" => "\
+コードがErgの文法に従っていません。
+括弧の対応、演算子、定義の形を確認してください。
+
+エラーとなるコードの例:
+
+    print!(1",
+            "simplified_chinese" => "\
+代码不符合Erg的语法。
+请检查括号是否匹配、运算符以及定义的形式。
+
+错误代码示例:
+
+    print!(1",
+            "traditional_chinese" => "\
+代碼不符合Erg的語法。
+請檢查括號是否匹配、運算符以及定義的形式。
+
+錯誤代碼示例:
+
+    print!(1",
+            "english" => "\
+The code does not follow the grammar of Erg.
+Check the brackets, the operators and the form of the definitions.
+
+Erroneous code example:
+
+    print!(1",
+        ),
+        TabError => switch_lang!(
+            "japanese" => "インデントにタブ文字は使えません。空白を使ってください。",
+            "simplified_chinese" => "缩进不能使用制表符。请使用空格。",
+            "traditional_chinese" => "縮進不能使用制表符。請使用空格。",
+            "english" => "Tabs cannot be used for indentation. Use spaces instead.",
+        ),
+        TypeError => switch_lang!(
+            "japanese" => "\
+式の型が期待される型と一致しません。
+呼び出しの引数、戻り値、宣言された型などが対象です。メッセージには期待される型と実際の型が表示されます。
+
+エラーとなるコードの例:
+
+    f x: Int = x + 1
+    f \"a\"",
+            "simplified_chinese" => "\
+表达式的类型与预期的类型不匹配。
+例如调用的参数、返回值、声明的类型等。消息中会显示预期的类型和实际的类型。
+
+错误代码示例:
+
+    f x: Int = x + 1
+    f \"a\"",
+            "traditional_chinese" => "\
+表達式的類型與預期的類型不匹配。
+例如調用的參數、返回值、聲明的類型等。消息中會顯示預期的類型和實際的類型。
+
+錯誤代碼示例:
+
+    f x: Int = x + 1
+    f \"a\"",
+            "english" => "\
+The type of an expression does not match the expected type,
+e.g. of an argument of a call, a return value or a declaration. The message shows the expected and the found types.
+
+Erroneous code example:
+
+    f x: Int = x + 1
+    f \"a\"",
+        ),
+        UnboundLocalError => switch_lang!(
+            "japanese" => "\
+変数が代入される前(または`Del`で削除された後)に参照されています。
+
+エラーとなるコードの例:
+
+    x = 1
+    Del x
+    print! x",
+            "simplified_chinese" => "\
+变量在赋值之前(或用`Del`删除之后)被引用。
+
+错误代码示例:
+
+    x = 1
+    Del x
+    print! x",
+            "traditional_chinese" => "\
+變量在賦值之前(或用`Del`刪除之後)被引用。
+
+錯誤代碼示例:
+
+    x = 1
+    Del x
+    print! x",
+            "english" => "\
+The variable is referenced before it is assigned (or after it is deleted with `Del`).
+
+Erroneous code example:
+
+    x = 1
+    Del x
+    print! x",
+        ),
+        PurityError => switch_lang!(
+            "japanese" => "\
+副作用のある操作が純粋な文脈で使われています。
+関数(名前に`!`がないもの)の中からはプロシージャを呼び出せません。",
+            "simplified_chinese" => "\
+在纯的上下文中使用了有副作用的操作。
+函数(名称中没有`!`)中不能调用过程。",
+            "traditional_chinese" => "\
+在純的上下文中使用了有副作用的操作。
+函數(名稱中沒有`!`)中不能調用過程。",
+            "english" => "\
+A side-effecting operation is used in a pure context.
+Functions (whose names do not end with `!`) cannot call procedures.",
+        ),
+        HasEffect => switch_lang!(
+            "japanese" => "\
+この式は副作用を起こしますが、副作用が許されない場所にあります。
+関数(名前に`!`がないもの)はプロシージャを呼び出せません。プロシージャとして定義してください。
+
+エラーとなるコードの例:
+
+    f x = print! x
+
+修正例:
+
+    f! x = print! x",
+            "simplified_chinese" => "\
+该表达式会产生副作用, 但它位于不允许副作用的地方。
+函数(名称中没有`!`)不能调用过程。请将其定义为过程。
+
+错误代码示例:
+
+    f x = print! x
+
+修正示例:
+
+    f! x = print! x",
+            "traditional_chinese" => "\
+該表達式會產生副作用, 但它位於不允許副作用的地方。
+函數(名稱中沒有`!`)不能調用過程。請將其定義為過程。
+
+錯誤代碼示例:
+
+    f x = print! x
+
+修正示例:
+
+    f! x = print! x",
+            "english" => "\
+This expression causes a side effect, but it is in a place where side effects are not allowed.
+Functions (whose names do not end with `!`) cannot call procedures. Define it as a procedure instead.
+
+Erroneous code example:
+
+    f x = print! x
+
+Fixed example:
+
+    f! x = print! x",
+        ),
+        MoveError => switch_lang!(
+            "japanese" => "\
+値は既にムーブされているため、この変数からは使用できません。
+可変オブジェクトは別の変数に代入されたり引数として渡されたりすると所有権が移動します。",
+            "simplified_chinese" => "\
+该值已被移动, 无法再通过此变量使用。
+可变对象在赋值给其他变量或作为参数传递时, 其所有权会发生转移。",
+            "traditional_chinese" => "\
+該值已被移動, 無法再通過此變量使用。
+可變對象在賦值給其他變量或作為參數傳遞時, 其所有權會發生轉移。",
+            "english" => "\
+The value has been moved, so it can no longer be used through this variable.
+The ownership of a mutable object moves when it is assigned to another variable or passed as an argument.",
+        ),
+        NotConstExpr => switch_lang!(
+            "japanese" => "\
+ここでは定数式が必要ですが、この式はコンパイル時に評価できません。
+型引数や定数(大文字で始まる名前)の定義の右辺などは定数式でなければなりません。",
+            "simplified_chinese" => "\
+此处需要常量表达式, 但该表达式无法在编译时求值。
+类型参数、常量(以大写字母开头的名称)定义的右侧等必须是常量表达式。",
+            "traditional_chinese" => "\
+此處需要常量表達式, 但該表達式無法在編譯時求值。
+類型參數、常量(以大寫字母開頭的名稱)定義的右側等必須是常量表達式。",
+            "english" => "\
+A constant expression is required here, but the expression cannot be evaluated at compile time.
+Type arguments and the right-hand side of a constant (a name starting with an uppercase letter) must be constant expressions.",
+        ),
+        InheritanceError => switch_lang!(
+            "japanese" => "\
+このクラスは継承できません。基底クラスになれるのは`Inheritable`で定義されたクラスのみです。
+
+エラーとなるコードの例:
+
+    C = Class()
+    D = Inherit C
+
+修正例:
+
+    C = Inheritable Class()
+    D = Inherit C",
+            "simplified_chinese" => "\
+该类不可继承。只有用`Inheritable`定义的类才能作为基类。
+
+错误代码示例:
+
+    C = Class()
+    D = Inherit C
+
+修正示例:
+
+    C = Inheritable Class()
+    D = Inherit C",
+            "traditional_chinese" => "\
+該類不可繼承。只有用`Inheritable`定義的類才能作為基類。
+
+錯誤代碼示例:
+
+    C = Class()
+    D = Inherit C
+
+修正示例:
+
+    C = Inheritable Class()
+    D = Inherit C",
+            "english" => "\
+The class cannot be inherited. Only the classes defined with `Inheritable` can be base classes.
+
+Erroneous code example:
+
+    C = Class()
+    D = Inherit C
+
+Fixed example:
+
+    C = Inheritable Class()
+    D = Inherit C",
+        ),
+        VisibilityError => switch_lang!(
+            "japanese" => "\
+この変数はプライベートであり、ここからはアクセスできません。
+公開する属性やモジュールのメンバーは`.`を付けて定義します。
+
+エラーとなるコードの例:
+
+    # foo.er
+    bar = 1
+    # main.er
+    foo = import \"foo\"
+    print! foo.bar
+
+foo.erで`.bar = 1`と定義すればアクセスできます。",
+            "simplified_chinese" => "\
+该变量是私有的, 无法从此处访问。
+公开的属性和模块成员需要以`.`开头定义。
+
+错误代码示例:
+
+    # foo.er
+    bar = 1
+    # main.er
+    foo = import \"foo\"
+    print! foo.bar
+
+在foo.er中定义`.bar = 1`即可访问。",
+            "traditional_chinese" => "\
+該變量是私有的, 無法從此處訪問。
+公開的屬性和模塊成員需要以`.`開頭定義。
+
+錯誤代碼示例:
+
+    # foo.er
+    bar = 1
+    # main.er
+    foo = import \"foo\"
+    print! foo.bar
+
+在foo.er中定義`.bar = 1`即可訪問。",
+            "english" => "\
+The variable is private and cannot be accessed from here.
+Public attributes and members of a module are defined with a leading `.`.
+
+Erroneous code example:
+
+    # foo.er
+    bar = 1
+    # main.er
+    foo = import \"foo\"
+    print! foo.bar
+
+Define `.bar = 1` in foo.er to make it accessible.",
+        ),
+        MethodError => switch_lang!(
+            "japanese" => "\
+        この型にはメソッドを定義できません(クラスでない型など)。メソッドはクラスまたはパッチに定義してください。",
+            "simplified_chinese" => "\
+        不能为该类型定义方法(例如不是类的类型)。请在类或补丁中定义方法。",
+            "traditional_chinese" => "\
+        不能為該類型定義方法(例如不是類的類型)。請在類或補丁中定義方法。",
+            "english" => "\
+        Methods cannot be defined for the type (e.g. a type that is not a class). Define the methods in a class or a patch.",
+        ),
+        DummyError => switch_lang!(
+            "japanese" => "コンパイラ内部で使われるダミーのエラーです。通常は報告されません。",
+            "simplified_chinese" => "编译器内部使用的虚拟错误。通常不会被报告。",
+            "traditional_chinese" => "編譯器內部使用的虛擬錯誤。通常不會被報告。",
+            "english" => "A dummy error used inside the compiler. It is not usually reported.",
+        ),
+        CyclicReferenceError => switch_lang!(
+            "japanese" => "\
+定義が互いに循環して参照しているため、値や型を決定できません。
+
+エラーとなるコードの例:
+
+    X = Y
+    Y = X",
+            "simplified_chinese" => "\
+定义之间存在循环引用, 因此无法确定值或类型。
+
+错误代码示例:
+
+    X = Y
+    Y = X",
+            "traditional_chinese" => "\
+定義之間存在循環引用, 因此無法確定值或類型。
+
+錯誤代碼示例:
+
+    X = Y
+    Y = X",
+            "english" => "\
+The definitions refer to each other cyclically, so their values or types cannot be determined.
+
+Erroneous code example:
+
+    X = Y
+    Y = X",
+        ),
+        AttributeWarning => switch_lang!(
+            "japanese" => "属性の使い方に関する警告です(予約済み)。",
+            "simplified_chinese" => "关于属性用法的警告(保留)。",
+            "traditional_chinese" => "關於屬性用法的警告(保留)。",
+            "english" => "A warning about the usage of an attribute (reserved).",
+        ),
+        CastWarning => switch_lang!(
+            "japanese" => "型のキャストが安全でない可能性があることを示す警告です(予約済み)。",
+            "simplified_chinese" => "表示类型转换可能不安全的警告(保留)。",
+            "traditional_chinese" => "表示類型轉換可能不安全的警告(保留)。",
+            "english" => "A warning that a type cast may be unsafe (reserved).",
+        ),
+        DeprecationWarning => switch_lang!(
+            "japanese" => "非推奨の機能が使われています。将来のバージョンで削除される予定です。",
+            "simplified_chinese" => "使用了已弃用的功能。它将在未来的版本中被删除。",
+            "traditional_chinese" => "使用了已棄用的功能。它將在未來的版本中被刪除。",
+            "english" => "A deprecated feature is used. It will be removed in a future version.",
+        ),
+        FutureWarning => switch_lang!(
+            "japanese" => "将来のバージョンで振る舞いが変わる機能が使われています。",
+            "simplified_chinese" => "使用了在未来版本中行为会发生变化的功能。",
+            "traditional_chinese" => "使用了在未來版本中行為會發生變化的功能。",
+            "english" => "A feature whose behavior will change in a future version is used.",
+        ),
+        ImportWarning => switch_lang!(
+            "japanese" => "モジュールのインポートに関する警告です(予約済み)。",
+            "simplified_chinese" => "关于模块导入的警告(保留)。",
+            "traditional_chinese" => "關於模塊導入的警告(保留)。",
+            "english" => "A warning about importing a module (reserved).",
+        ),
+        PendingDeprecationWarning => switch_lang!(
+            "japanese" => "将来非推奨になる予定の機能が使われています。",
+            "simplified_chinese" => "使用了将来会被弃用的功能。",
+            "traditional_chinese" => "使用了將來會被棄用的功能。",
+            "english" => "A feature that will be deprecated in the future is used.",
+        ),
+        SyntaxWarning => switch_lang!(
+            "japanese" => "文法上は正しいものの、誤りである可能性が高いコードです。",
+            "simplified_chinese" => "语法上正确, 但很可能是错误的代码。",
+            "traditional_chinese" => "語法上正確, 但很可能是錯誤的代碼。",
+            "english" => "The code is syntactically valid, but it is likely to be a mistake.",
+        ),
+        TypeWarning => switch_lang!(
+            "japanese" => "\
+関数の戻り値型が単一の型ではありません(Union型に推論されました)。
+意図した型であれば、戻り値型を明示してください。
+
+警告となるコードの例:
+
+    f x: Int = if x > 0, do \"positive\", do 0
+
+修正例:
+
+    f(x: Int): Str or Int = if x > 0, do \"positive\", do 0",
+            "simplified_chinese" => "\
+函数的返回类型不是单一类型(被推断为Union类型)。
+如果这是有意的, 请显式指定返回类型。
+
+产生警告的代码示例:
+
+    f x: Int = if x > 0, do \"positive\", do 0
+
+修正示例:
+
+    f(x: Int): Str or Int = if x > 0, do \"positive\", do 0",
+            "traditional_chinese" => "\
+函數的返回類型不是單一類型(被推斷為Union類型)。
+如果這是有意的, 請顯式指定返回類型。
+
+產生警告的代碼示例:
+
+    f x: Int = if x > 0, do \"positive\", do 0
+
+修正示例:
+
+    f(x: Int): Str or Int = if x > 0, do \"positive\", do 0",
+            "english" => "\
+The return type of the function is not a single type (it is inferred as a union type).
+If it is intended, specify the return type explicitly.
+
+Code example that causes the warning:
+
+    f x: Int = if x > 0, do \"positive\", do 0
+
+Fixed example:
+
+    f(x: Int): Str or Int = if x > 0, do \"positive\", do 0",
+        ),
+        NameWarning => switch_lang!(
+            "japanese" => "\
+組み込み関数と同じ名前の変数を定義しています。組み込み関数が隠されるので、別の名前を使ってください。
+
+警告となるコードの例:
+
+    print = 1",
+            "simplified_chinese" => "\
+定义了与内置函数同名的变量。内置函数会被隐藏, 请使用其他名称。
+
+产生警告的代码示例:
+
+    print = 1",
+            "traditional_chinese" => "\
+定義了與內置函數同名的變量。內置函數會被隱藏, 請使用其他名稱。
+
+產生警告的代碼示例:
+
+    print = 1",
+            "english" => "\
+The variable has the same name as a built-in function, which is shadowed by it. Use another name.
+
+Code example that causes the warning:
+
+    print = 1",
+        ),
+        UnusedWarning => switch_lang!(
+            "japanese" => "\
+変数、サブルーチン、または式の値が使われていません。
+不要であれば削除してください。値を意図的に捨てる場合は`discard`を使うか、変数名を`_`にしてください。
+
+警告となるコードの例:
+
+    x = 1
+    print! \"hello\"",
+            "simplified_chinese" => "\
+变量、子程序或表达式的值未被使用。
+如果不需要请删除。如果是有意丢弃值, 请使用`discard`或将变量命名为`_`。
+
+产生警告的代码示例:
+
+    x = 1
+    print! \"hello\"",
+            "traditional_chinese" => "\
+變量、子程序或表達式的值未被使用。
+如果不需要請刪除。如果是有意丟棄值, 請使用`discard`或將變量命名為`_`。
+
+產生警告的代碼示例:
+
+    x = 1
+    print! \"hello\"",
+            "english" => "\
+A variable, a subroutine or the value of an expression is not used.
+Remove it if it is unnecessary. To discard a value intentionally, use `discard` or name the variable `_`.
+
+Code example that causes the warning:
+
+    x = 1
+    print! \"hello\"",
+        ),
+        Warning => switch_lang!(
+            "japanese" => "その他の警告です。",
+            "simplified_chinese" => "其他警告。",
+            "traditional_chinese" => "其他警告。",
+            "english" => "A general warning.",
+        ),
+        TypeNameWarning => switch_lang!(
+            "japanese" => "\
+レコード型が定数でない(小文字で始まる)名前に束縛されています。型指定に使えるのは定数だけなので、大文字で始まる名前にしてください。
+
+警告となるコードの例:
+
+    t = {x = Int; y = Int}",
+            "simplified_chinese" => "\
+记录类型被绑定到非常量(以小写字母开头)的名称。只有常量可以用于类型指定, 请使用以大写字母开头的名称。
+
+产生警告的代码示例:
+
+    t = {x = Int; y = Int}",
+            "traditional_chinese" => "\
+記錄類型被綁定到非常量(以小寫字母開頭)的名稱。只有常量可以用於類型指定, 請使用以大寫字母開頭的名稱。
+
+產生警告的代碼示例:
+
+    t = {x = Int; y = Int}",
+            "english" => "\
+A record type is bound to a non-constant (lowercase) name. Only constants can be used in type specifications, so use a name that starts with an uppercase letter.
+
+Code example that causes the warning:
+
+    t = {x = Int; y = Int}",
+        ),
+        _ => return None,
+    };
+    Some(text)
+}
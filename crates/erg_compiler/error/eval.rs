@@ -23,7 +23,8 @@ impl EvalError {
                 errno,
                 NotConstExpr,
                 loc,
-            ),
+            )
+            .with_code("E0077"),
             input,
             caused_by,
         )
@@ -42,7 +43,8 @@ impl EvalError {
                 errno,
                 SyntaxError,
                 loc,
-            ),
+            )
+            .with_code("E0078"),
             input,
             caused_by,
         )
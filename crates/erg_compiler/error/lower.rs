@@ -32,7 +32,8 @@ impl LowerError {
                 errno,
                 SyntaxError,
                 loc,
-            ),
+            )
+            .with_code("E0052"),
             input,
             caused_by,
         )
@@ -59,7 +60,8 @@ impl LowerError {
                 errno,
                 UnusedWarning,
                 expr.loc(),
-            ),
+            )
+            .with_code("W0002"),
             input,
             caused_by,
         )
@@ -90,7 +92,8 @@ impl LowerError {
                 errno,
                 UnusedWarning,
                 expr.loc(),
-            ),
+            )
+            .with_code("W0003"),
             input,
             caused_by,
         )
@@ -116,7 +119,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_code("E0053"),
             input,
             caused_by,
         )
@@ -142,7 +146,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_code("E0054"),
             input,
             caused_by,
         )
@@ -172,7 +177,8 @@ impl LowerError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0055"),
             input,
             caused_by,
         )
@@ -209,7 +215,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_code("E0056"),
             input,
             caused_by,
         )
@@ -247,7 +254,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_code("E0057"),
             input,
             caused_by,
         )
@@ -285,7 +293,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_code("E0058"),
             input,
             caused_by,
         )
@@ -322,7 +331,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_code("E0059"),
             input,
             caused_by,
         )
@@ -354,7 +364,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_code("E0060"),
             input,
             caused_by,
         )
@@ -390,7 +401,8 @@ impl LowerError {
                 errno,
                 AttributeError,
                 loc,
-            ),
+            )
+            .with_code("E0061"),
             input,
             caused_by,
         )
@@ -429,7 +441,8 @@ impl LowerError {
                 errno,
                 AttributeError,
                 loc,
-            ),
+            )
+            .with_code("E0062"),
             input,
             caused_by,
         )
@@ -455,7 +468,8 @@ impl LowerError {
                 errno,
                 AssignError,
                 loc,
-            ),
+            )
+            .with_code("E0063"),
             input,
             caused_by,
         )
@@ -496,7 +510,8 @@ impl LowerError {
                 errno,
                 NameError,
                 ident.loc(),
-            ),
+            )
+            .with_code("E0064"),
             input,
             caused_by,
         )
@@ -538,7 +553,8 @@ impl LowerError {
                 errno,
                 VisibilityError,
                 loc,
-            ),
+            )
+            .with_code("E0065"),
             input,
             caused_by,
         )
@@ -616,7 +632,8 @@ impl LowerError {
                 errno,
                 NameError,
                 name_loc,
-            ),
+            )
+            .with_code("E0066"),
             input,
             caused_by.into(),
         )
@@ -641,7 +658,8 @@ impl LowerError {
                 errno,
                 InheritanceError,
                 loc,
-            ),
+            )
+            .with_code("E0067"),
             input,
             caused_by,
         )
@@ -662,7 +680,8 @@ impl LowerError {
                 errno,
                 IoError,
                 loc,
-            ),
+            )
+            .with_code("E0068"),
             input,
             caused_by,
         )
@@ -681,7 +700,9 @@ impl LowerError {
             "traditional_chinese" => format!("{mod_name}模塊不支持您的環境"),
             "english" => format!("module {mod_name} is not supported in your environment"),
         );
-        Self::file_error(input, errno, desc, loc, caused_by, None)
+        let mut err = Self::file_error(input, errno, desc, loc, caused_by, None);
+        err.core.code = Some("E0069");
+        err
     }
 
     pub fn import_error(
@@ -844,7 +865,81 @@ impl LowerError {
                 errno,
                 ImportError,
                 loc,
-            ),
+            )
+            .with_code("E0070"),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn cyclic_import_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        cycle: &[String],
+    ) -> Self {
+        let cycle = StyledString::new(cycle.join(" -> "), Some(ERR), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => "循環参照しているモジュールのトップレベルでは、サブルーチン・型・モジュール以外の変数を定義できません",
+            "simplified_chinese" => "循环引用的模块的顶层不能定义子程序、类型和模块以外的变量",
+            "traditional_chinese" => "循環引用的模塊的頂層不能定義子程序、類型和模塊以外的變量",
+            "english" => "variables other than subroutines, types and modules cannot be defined at the top level of cyclically imported modules",
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(
+                    loc,
+                    vec![cycle.to_string()],
+                    Some(hint.into()),
+                )],
+                switch_lang!(
+                    "japanese" => "モジュールが循環参照しています",
+                    "simplified_chinese" => "模块存在循环引用",
+                    "traditional_chinese" => "模塊存在循環引用",
+                    "english" => "cyclic module import detected",
+                ),
+                errno,
+                ImportError,
+                loc,
+            )
+            .with_code("E0071"),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn cyclic_const_def_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        cycle: &[Str],
+    ) -> Self {
+        let cycle = cycle
+            .iter()
+            .map(|name| readable_name(name))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        let cycle = StyledString::new(cycle, Some(ERR), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(
+                    loc,
+                    vec![cycle.to_string()],
+                    None,
+                )],
+                switch_lang!(
+                    "japanese" => "定数の定義が循環しています",
+                    "simplified_chinese" => "常量的定义存在循环",
+                    "traditional_chinese" => "常量的定義存在循環",
+                    "english" => "cyclic constant definition detected",
+                ),
+                errno,
+                NameError,
+                loc,
+            )
+            .with_code("E0072"),
             input,
             caused_by,
         )
@@ -868,7 +963,8 @@ impl LowerError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0073"),
             input,
             caused_by,
         )
@@ -887,7 +983,8 @@ impl LowerError {
                 errno,
                 SyntaxError,
                 loc,
-            ),
+            )
+            .with_code("E0074"),
             input,
             caused_by,
         )
@@ -917,7 +1014,35 @@ impl LowerError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0075"),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn yield_outside_generator_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        name: &str,
+    ) -> Self {
+        let name = StyledStr::new(readable_name(name), Some(WARN), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("{name}はジェネレータ関数ではありません(yieldは自身の本体の中でのみ呼び出せます)"),
+                    "simplified_chinese" => format!("{name}不是生成器函数(yield只能在其自身的函数体中调用)"),
+                    "traditional_chinese" => format!("{name}不是生成器函數(yield只能在其自身的函數體中調用)"),
+                    "english" => format!("{name} is not a generator function (yield can only be called in its own body)"),
+                ),
+                errno,
+                SyntaxError,
+                loc,
+            )
+            .with_code("E0076"),
             input,
             caused_by,
         )
@@ -945,7 +1070,8 @@ impl LowerWarning {
                 errno,
                 UnusedWarning,
                 loc,
-            ),
+            )
+            .with_code("W0004"),
             input,
             caused_by,
         )
@@ -977,7 +1103,8 @@ impl LowerWarning {
                 errno,
                 TypeWarning,
                 loc,
-            ),
+            )
+            .with_code("W0005"),
             input,
             caused_by,
         )
@@ -1003,7 +1130,123 @@ impl LowerWarning {
                 errno,
                 NameWarning,
                 loc,
-            ),
+            )
+            .with_code("W0006"),
+            input,
+            caused_by,
+        )
+    }
+
+    /// Reports the warning as an error (`-D <lint>`).
+    pub fn deny(mut self, lint: &str) -> LowerError {
+        let note = switch_lang!(
+            "japanese" => format!("`-D {lint}`によりエラーとして扱われます"),
+            "simplified_chinese" => format!("由于`-D {lint}`而被视为错误"),
+            "traditional_chinese" => format!("由於`-D {lint}`而被視為錯誤"),
+            "english" => format!("reported as an error because of `-D {lint}`"),
+        );
+        if let Some(sub) = self.core.sub_messages.first_mut() {
+            sub.msg.push(note);
+        } else {
+            let loc = self.core.loc;
+            self.core
+                .sub_messages
+                .push(SubMessage::ambiguous_new(loc, vec![note], None));
+        }
+        self.core.denied = true;
+        self
+    }
+
+    pub fn lowercase_type_warning(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        name: &str,
+    ) -> Self {
+        let mut chars = name.chars();
+        let capitalized = chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default();
+        let hint = switch_lang!(
+            "japanese" => format!("型指定に使うには定数にしてください: `{capitalized} = ...`"),
+            "simplified_chinese" => format!("要在类型指定中使用, 请将其定义为常量: `{capitalized} = ...`"),
+            "traditional_chinese" => format!("要在類型指定中使用, 請將其定義為常量: `{capitalized} = ...`"),
+            "english" => format!("define it as a constant to use it as a type: `{capitalized} = ...`"),
+        );
+        let name = StyledStr::new(readable_name(name), Some(WARN), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint))],
+                switch_lang!(
+                    "japanese" => format!("レコード型が変数{name}に束縛されています"),
+                    "simplified_chinese" => format!("记录类型被绑定到变量{name}"),
+                    "traditional_chinese" => format!("記錄類型被綁定到變量{name}"),
+                    "english" => format!("a record type is bound to the variable {name}"),
+                ),
+                errno,
+                TypeNameWarning,
+                loc,
+            )
+            .with_code("W0007"),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn return_in_loop_warning(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        loop_name: &str,
+        receiver: &str,
+        outer: Option<&str>,
+    ) -> Self {
+        let hint = if let Some(outer) = outer {
+            let outer = StyledStr::new(readable_name(outer), Some(HINT), Some(ATTR));
+            switch_lang!(
+                "japanese" => format!("{outer}から返るなら明示してください: `{outer}.return ...`"),
+                "simplified_chinese" => format!("如果要从{outer}返回, 请明确指定: `{outer}.return ...`"),
+                "traditional_chinese" => format!("如果要從{outer}返回, 請明確指定: `{outer}.return ...`"),
+                "english" => format!("to return from {outer}, say so explicitly: `{outer}.return ...`"),
+            )
+        } else {
+            switch_lang!(
+                "japanese" => "ループをサブルーチンの中に移し、`<サブルーチン>.return ...`を使ってください".to_string(),
+                "simplified_chinese" => "请将循环移到子程序中, 并使用`<子程序>.return ...`".to_string(),
+                "traditional_chinese" => "請將循環移到子程序中, 並使用`<子程序>.return ...`".to_string(),
+                "english" => "move the loop into a subroutine and use `<subroutine>.return ...`".to_string(),
+            )
+        };
+        let loop_name = StyledStr::new(loop_name, Some(WARN), Some(ATTR));
+        let receiver = StyledStr::new(readable_name(receiver), Some(WARN), Some(ATTR));
+        let outer = outer.map(|outer| StyledStr::new(readable_name(outer), Some(WARN), Some(ATTR)));
+        let desc = if let Some(outer) = outer {
+            switch_lang!(
+                "japanese" => format!("{loop_name}ブロック内の`{receiver}.return`は{receiver}ではなく{outer}から返ります"),
+                "simplified_chinese" => format!("{loop_name}块中的`{receiver}.return`从{outer}返回, 而不是从{receiver}返回"),
+                "traditional_chinese" => format!("{loop_name}塊中的`{receiver}.return`從{outer}返回, 而不是從{receiver}返回"),
+                "english" => format!("`{receiver}.return` in a {loop_name} block returns from {outer}, not from {receiver}"),
+            )
+        } else {
+            switch_lang!(
+                "japanese" => format!("{loop_name}ブロック内の`{receiver}.return`は{receiver}ではなく外側のブロックから返ります"),
+                "simplified_chinese" => format!("{loop_name}块中的`{receiver}.return`从外部块返回, 而不是从{receiver}返回"),
+                "traditional_chinese" => format!("{loop_name}塊中的`{receiver}.return`從外部塊返回, 而不是從{receiver}返回"),
+                "english" => format!("`{receiver}.return` in a {loop_name} block returns from the outer block, not from {receiver}"),
+            )
+        };
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint))],
+                desc,
+                errno,
+                Warning,
+                loc,
+            )
+            .with_code("W0008"),
             input,
             caused_by,
         )
@@ -1,3 +1,4 @@
+pub mod codes;
 pub mod eval;
 pub mod lower;
 pub mod tycheck;
@@ -10,7 +11,7 @@ use erg_common::error::{
 };
 use erg_common::style::{Attribute, Color, StyledStr, StyledString, StyledStrings, Theme, THEME};
 use erg_common::traits::{Locational, Stream};
-use erg_common::{impl_display_and_error, impl_stream, switch_lang};
+use erg_common::{impl_display_and_error, impl_stream, switch_lang, Str};
 
 use erg_parser::error::{ParserRunnerError, ParserRunnerErrors};
 
@@ -247,7 +248,8 @@ impl CompileError {
                 errno,
                 CompilerSystemError,
                 loc,
-            ),
+            )
+            .with_code("E0012"),
             input,
             "".to_owned(),
         )
@@ -284,7 +286,8 @@ caused from: {fn_name}"),
                 0,
                 CompilerSystemError,
                 loc,
-            ),
+            )
+            .with_code("E0013"),
             input,
             "".to_owned(),
         )
@@ -303,7 +306,8 @@ caused from: {fn_name}"),
                 0,
                 FeatureError,
                 loc,
-            ),
+            )
+            .with_code("E0014"),
             input,
             caused_by,
         )
@@ -346,7 +350,8 @@ impl EffectError {
                 errno,
                 HasEffect,
                 expr.loc(),
-            ),
+            )
+            .with_code("E0015"),
             input,
             caused_by,
         )
@@ -397,7 +402,99 @@ impl EffectError {
                 errno,
                 HasEffect,
                 loc,
-            ),
+            )
+            .with_code("E0016"),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn await_outside_async_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+    ) -> Self {
+        let hint = Some(
+            switch_lang!(
+                "japanese" => {
+                let mut s = StyledStrings::default();
+                s.push_str("プロシージャに");
+                s.push_str_with_color_and_attribute("@Async", WARN, ATTR);
+                s.push_str("デコレータをつけてください");
+                s
+                },
+                "simplified_chinese" => {
+                let mut s = StyledStrings::default();
+                s.push_str("请给过程加上");
+                s.push_str_with_color_and_attribute("@Async", WARN, ATTR);
+                s.push_str("装饰器");
+                s
+                },
+                "traditional_chinese" => {
+                let mut s = StyledStrings::default();
+                s.push_str("請給過程加上");
+                s.push_str_with_color_and_attribute("@Async", WARN, ATTR);
+                s.push_str("裝飾器");
+                s
+                },
+                "english" => {
+                let mut s = StyledStrings::default();
+                s.push_str("add the ");
+                s.push_str_with_color_and_attribute("@Async", WARN, ATTR);
+                s.push_str(" decorator to the procedure");
+                s
+                },
+            )
+            .to_string(),
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], hint)],
+                switch_lang!(
+                    "japanese" => "await!は非同期プロシージャの中でのみ使用できます",
+                    "simplified_chinese" => "await!只能在异步过程中使用",
+                    "traditional_chinese" => "await!只能在異步過程中使用",
+                    "english" => "await! can only be used in an async procedure",
+                ),
+                errno,
+                SyntaxError,
+                loc,
+            )
+            .with_code("E0017"),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn proc_method_in_immut_class_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        class_name: &str,
+    ) -> Self {
+        let class_name = StyledStr::new(class_name, Some(WARN), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => format!("{class_name}!のような可変クラスを定義してください"),
+            "simplified_chinese" => format!("请定义像{class_name}!这样的可变类"),
+            "traditional_chinese" => format!("請定義像{class_name}!這樣的可變類"),
+            "english" => format!("define a mutable class like {class_name}!"),
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint))],
+                switch_lang!(
+                    "japanese" => format!("不変クラス{class_name}にはプロシージャルメソッドを定義できません"),
+                    "simplified_chinese" => format!("不能在不可变类{class_name}中定义过程方法"),
+                    "traditional_chinese" => format!("不能在不可變類{class_name}中定義過程方法"),
+                    "english" => format!("procedural methods cannot be defined in the immutable class {class_name}"),
+                ),
+                errno,
+                HasEffect,
+                loc,
+            )
+            .with_code("E0018"),
             input,
             caused_by,
         )
@@ -416,7 +513,8 @@ impl EffectError {
                 errno,
                 HasEffect,
                 expr.loc(),
-            ),
+            )
+            .with_code("E0019"),
             input,
             caused_by,
         )
@@ -460,7 +558,44 @@ impl OwnershipError {
                 errno,
                 MoveError,
                 name_loc,
-            ),
+            )
+            .with_code("E0020"),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn cyclic_reference_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        cycle: &[Str],
+    ) -> Self {
+        let cycle = cycle
+            .iter()
+            .map(|name| &name[..])
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        let cycle = StyledString::new(cycle, Some(ERR), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(
+                    loc,
+                    vec![cycle.to_string()],
+                    None,
+                )],
+                switch_lang!(
+                    "japanese" => "循環参照が検出されました",
+                    "simplified_chinese" => "检测到循环引用",
+                    "traditional_chinese" => "檢測到循環引用",
+                    "english" => "cyclic reference detected",
+                ),
+                errno,
+                CyclicReferenceError,
+                loc,
+            )
+            .with_code("E0021"),
             input,
             caused_by,
         )
@@ -810,4 +945,65 @@ mod test {
             print!("{err}");
         }
     }
+
+    #[test]
+    fn error_codes() {
+        use crate::error::codes::{explain, lookup, lookup_kind, COMPILE_DIAGNOSTICS};
+        use erg_common::error::ErrorKind;
+        use std::path::Path;
+
+        for diag in COMPILE_DIAGNOSTICS.iter() {
+            let prefix = if diag.kind.is_warning() { "W" } else { "E" };
+            assert!(diag.code.starts_with(prefix), "{}", diag.code);
+            assert_eq!(lookup(diag.code), Some(diag), "{}", diag.code);
+            assert!(explain(diag).starts_with(&format!("{}: {}\n\n", diag.code, diag.kind)));
+        }
+        let mut codes = COMPILE_DIAGNOSTICS
+            .iter()
+            .map(|diag| diag.code)
+            .collect::<Vec<_>>();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), COMPILE_DIAGNOSTICS.len());
+        assert_eq!(lookup("e25").map(|diag| diag.code), Some("E0025"));
+        assert_eq!(lookup("W0025"), None);
+        assert_eq!(lookup("Foo"), None);
+        assert_eq!(lookup_kind("typeerror"), Some(ErrorKind::TypeError));
+        assert_eq!(
+            lookup_kind("ZeroDivisionError"),
+            Some(ErrorKind::ZeroDivisionError)
+        );
+        // every code attached in the sources is registered, and every registered code is attached somewhere
+        fn collect_codes(dir: &Path, used: &mut Vec<String>) {
+            for entry in std::fs::read_dir(dir).unwrap().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    collect_codes(&path, used);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    let src = std::fs::read_to_string(&path).unwrap();
+                    for pat in ["with_code(\"", "code = Some(\""] {
+                        for (i, _) in src.match_indices(pat) {
+                            let code = &src[i + pat.len()..][..5];
+                            used.push(code.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut used = vec![];
+        for dir in [
+            root,
+            &root.join("../erg_parser"),
+            &root.join("../erg_common"),
+        ] {
+            collect_codes(dir, &mut used);
+        }
+        for code in used.iter() {
+            assert!(lookup(code).is_some(), "{code} is not registered");
+        }
+        for code in codes {
+            assert!(used.iter().any(|used| used == code), "{code} is not used");
+        }
+    }
 }
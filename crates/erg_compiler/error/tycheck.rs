@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use erg_common::config::Input;
-use erg_common::error::{ErrorCore, ErrorKind::*, Location, SubMessage};
+use erg_common::error::{ErrorCore, ErrorKind::*, FixIt, Location, SubMessage};
 use erg_common::set::Set;
 use erg_common::style::{StyledStr, StyledString, StyledStrings};
 use erg_common::traits::Locational;
@@ -43,7 +43,8 @@ impl TyCheckError {
                 errno,
                 CompilerSystemError,
                 loc,
-            ),
+            )
+            .with_code("E0022"),
             input,
             "".to_string(),
         )
@@ -69,7 +70,8 @@ impl TyCheckError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0023"),
             input,
             caused_by,
         )
@@ -99,7 +101,8 @@ impl TyCheckError {
                 errno,
                 NotImplementedError,
                 callee.loc(),
-            ),
+            )
+            .with_code("E0024"),
             input,
             caused_by,
         )
@@ -161,7 +164,8 @@ impl TyCheckError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0025"),
             input,
             caused_by,
         )
@@ -210,7 +214,8 @@ impl TyCheckError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0026"),
             input,
             caused_by,
         )
@@ -236,7 +241,8 @@ impl TyCheckError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_code("E0027"),
             input,
             caused_by,
         )
@@ -284,7 +290,8 @@ impl TyCheckError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0028"),
             input,
             caused_by,
         )
@@ -332,7 +339,8 @@ impl TyCheckError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0029"),
             input,
             caused_by,
         )
@@ -357,7 +365,8 @@ impl TyCheckError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0030"),
             input,
             caused_by,
         )
@@ -382,7 +391,8 @@ impl TyCheckError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0031"),
             input,
             caused_by,
         )
@@ -407,7 +417,8 @@ impl TyCheckError {
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0032"),
             input,
             caused_by,
         )
@@ -470,7 +481,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0033"),
             input,
             caused_by,
         )
@@ -499,7 +511,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0034"),
             input,
             caused_by,
         )
@@ -527,7 +540,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0035"),
             input,
             caused_by,
         )
@@ -555,7 +569,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0036"),
             input,
             caused_by,
         )
@@ -601,7 +616,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0037"),
             input,
             caused_by,
         )
@@ -647,7 +663,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0038"),
             input,
             caused_by,
         )
@@ -700,7 +717,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0039"),
             input,
             caused_by,
         )
@@ -745,7 +763,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 Location::Unknown,
-            ),
+            )
+            .with_code("E0040"),
             input,
             caused_by,
         )
@@ -771,7 +790,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0041"),
             input,
             caused_by,
         )
@@ -798,7 +818,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0042"),
             input,
             caused_by,
         )
@@ -833,7 +854,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 MethodError,
                 loc,
-            ),
+            )
+            .with_code("E0043"),
             input,
             caused_by,
         )
@@ -896,7 +918,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0044"),
             input,
             caused_by,
         )
@@ -925,7 +948,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 Location::Unknown,
-            ),
+            )
+            .with_code("E0045"),
             input,
             caused_by,
         )
@@ -954,7 +978,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 Location::Unknown,
-            ),
+            )
+            .with_code("E0046"),
             input,
             caused_by,
         )
@@ -980,7 +1005,8 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 loc,
-            ),
+            )
+            .with_code("E0047"),
             input,
             caused_by,
         )
@@ -1060,7 +1086,120 @@ passed keyword args:    {kw_args_len}"
                 errno,
                 TypeError,
                 expr.loc(),
-            ),
+            )
+            .with_code("E0048"),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `[Int; Str]` => `(Int, Str)` or `{Int: Str}`
+    pub fn array_len_type_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        elem: &str,
+        len: &str,
+    ) -> Self {
+        let hint = switch_lang!(
+            "japanese" => "型を並べる場合はタプル型、型同士を対応させる場合は辞書型を使ってください",
+            "simplified_chinese" => "要排列类型请使用元组类型, 要将类型相互对应请使用字典类型",
+            "traditional_chinese" => "要排列類型請使用元組類型, 要將類型相互對應請使用字典類型",
+            "english" => "use a tuple type to arrange types, or a dict type to map types to types",
+        );
+        let mut sub = SubMessage::ambiguous_new(loc, vec![], Some(hint.to_string()));
+        sub.push_fix_it(FixIt::new(loc, format!("({elem}, {len})")));
+        sub.push_fix_it(FixIt::new(loc, format!("{{{elem}: {len}}}")));
+        let len = StyledStr::new(len, Some(ERR), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![sub],
+                switch_lang!(
+                    "japanese" => format!("配列型の長さは`Nat`である必要がありますが、型{len}が指定されました"),
+                    "simplified_chinese" => format!("数组类型的长度必须是`Nat`, 但指定了类型{len}"),
+                    "traditional_chinese" => format!("數組類型的長度必須是`Nat`, 但指定了類型{len}"),
+                    "english" => format!("the length of an array type must be a `Nat`, but the type {len} is given"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            )
+            .with_code("E0049"),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `{x: Int}` => `{x = Int}`
+    pub fn dict_key_not_type_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        key: &str,
+        record: &str,
+    ) -> Self {
+        let hint = switch_lang!(
+            "japanese" => "レコード型の属性は`=`で定義します",
+            "simplified_chinese" => "记录类型的属性用`=`定义",
+            "traditional_chinese" => "記錄類型的屬性用`=`定義",
+            "english" => "the attributes of a record type are defined with `=`",
+        );
+        let mut sub = SubMessage::ambiguous_new(loc, vec![], Some(hint.to_string()));
+        sub.push_fix_it(FixIt::new(loc, record));
+        let key = StyledStr::new(key, Some(ERR), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![sub],
+                switch_lang!(
+                    "japanese" => format!("辞書型のキー{key}は型ではありません"),
+                    "simplified_chinese" => format!("字典类型的键{key}不是类型"),
+                    "traditional_chinese" => format!("字典類型的鍵{key}不是類型"),
+                    "english" => format!("the key {key} of the dict type is not a type"),
+                ),
+                errno,
+                NameError,
+                loc,
+            )
+            .with_code("E0050"),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `Int and Str` => `Int or Str`
+    pub fn empty_intersection_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        lhs: &str,
+        rhs: &str,
+    ) -> Self {
+        let hint = switch_lang!(
+            "japanese" => "多重継承はできません。どちらかの型を表すには`or`を使ってください",
+            "simplified_chinese" => "不允许多重继承。要表示其中一种类型请使用`or`",
+            "traditional_chinese" => "不允許多重繼承。要表示其中一種類型請使用`or`",
+            "english" => "multiple inheritance is not allowed. Use `or` for either of the types",
+        );
+        let mut sub = SubMessage::ambiguous_new(loc, vec![], Some(hint.to_string()));
+        sub.push_fix_it(FixIt::new(loc, format!("{lhs} or {rhs}")));
+        let intersec = StyledString::new(format!("{lhs} and {rhs}"), Some(ERR), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![sub],
+                switch_lang!(
+                    "japanese" => format!("{intersec}のインスタンスは存在しません"),
+                    "simplified_chinese" => format!("{intersec}没有实例"),
+                    "traditional_chinese" => format!("{intersec}沒有實例"),
+                    "english" => format!("{intersec} has no instances"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            )
+            .with_code("E0051"),
             input,
             caused_by,
         )
@@ -166,6 +166,7 @@ pub struct Args {
     pub pos_args: Vec<PosArg>,
     pub var_args: Option<Box<PosArg>>,
     pub kw_args: Vec<KwArg>,
+    pub kw_var_args: Option<Box<PosArg>>,
     pub paren: Option<(Token, Token)>,
 }
 
@@ -181,6 +182,10 @@ impl NestedDisplay for Args {
         if !self.kw_args.is_empty() {
             fmt_lines(self.kw_args.iter(), f, level)?;
         }
+        if let Some(kw_var_args) = &self.kw_var_args {
+            writeln!(f, "**")?;
+            kw_var_args.fmt_nest(f, level)?;
+        }
         Ok(())
     }
 }
@@ -205,6 +210,9 @@ impl NoTypeDisplay for Args {
                 .map(|x| x.to_string_notype())
                 .fold("".to_string(), |acc, s| acc + &s + ", ");
         }
+        if let Some(kw_var_args) = &self.kw_var_args {
+            s += &format!(", **{}", kw_var_args.to_string_notype());
+        }
         s
     }
 }
@@ -221,6 +229,17 @@ impl Locational for Args {
                 return loc;
             }
         }
+        if let Some(kw_var) = self.kw_var_args.as_deref() {
+            return if let Some(l) = self.pos_args.first() {
+                Location::concat(l, kw_var)
+            } else if let Some(l) = self.var_args.as_deref() {
+                Location::concat(l, kw_var)
+            } else if let Some(l) = self.kw_args.first() {
+                Location::concat(l, kw_var)
+            } else {
+                kw_var.loc()
+            };
+        }
         match (
             self.pos_args.first(),
             self.var_args.as_ref(),
@@ -241,12 +260,14 @@ impl Args {
         pos_args: Vec<PosArg>,
         var_args: Option<PosArg>,
         kw_args: Vec<KwArg>,
+        kw_var_args: Option<PosArg>,
         paren: Option<(Token, Token)>,
     ) -> Self {
         Self {
             pos_args,
             var_args: var_args.map(Box::new),
             kw_args,
+            kw_var_args: kw_var_args.map(Box::new),
             paren,
         }
     }
@@ -256,24 +277,30 @@ impl Args {
             exprs.into_iter().map(PosArg::new).collect(),
             None,
             vec![],
+            None,
             paren,
         )
     }
 
     pub fn empty() -> Self {
-        Self::new(vec![], None, vec![], None)
+        Self::new(vec![], None, vec![], None, None)
     }
 
     #[inline]
     pub fn len(&self) -> usize {
         #[allow(clippy::bool_to_int_with_if)]
         let var_argc = if self.var_args.is_none() { 0 } else { 1 };
-        self.pos_args.len() + var_argc + self.kw_args.len()
+        #[allow(clippy::bool_to_int_with_if)]
+        let kw_var_argc = if self.kw_var_args.is_none() { 0 } else { 1 };
+        self.pos_args.len() + var_argc + self.kw_args.len() + kw_var_argc
     }
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.pos_args.is_empty() && self.var_args.is_none() && self.kw_args.is_empty()
+        self.pos_args.is_empty()
+            && self.var_args.is_none()
+            && self.kw_args.is_empty()
+            && self.kw_var_args.is_none()
     }
 
     #[inline]
@@ -1417,6 +1444,7 @@ impl Call {
                     match self.attr_name.as_ref().map(|i| &i.inspect()[..]) {
                         Some("return") => Some(OperationKind::Return),
                         Some("yield") => Some(OperationKind::Yield),
+                        Some("yield_from") => Some(OperationKind::YieldFrom),
                         _ => None,
                     }
                 } else {
@@ -1670,6 +1698,7 @@ pub struct Params {
     pub non_defaults: Vec<NonDefaultParamSignature>,
     pub var_params: Option<Box<NonDefaultParamSignature>>,
     pub defaults: Vec<DefaultParamSignature>,
+    pub kw_var_params: Option<Box<NonDefaultParamSignature>>,
     pub parens: Option<(Token, Token)>,
 }
 
@@ -1677,10 +1706,11 @@ impl fmt::Display for Params {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "({}, {}, {})",
+            "({}, {}, {}, {})",
             fmt_vec(&self.non_defaults),
             fmt_option!(pre "*", &self.var_params),
-            fmt_vec(&self.defaults)
+            fmt_vec(&self.defaults),
+            fmt_option!(pre "**", &self.kw_var_params),
         )
     }
 }
@@ -1688,13 +1718,14 @@ impl fmt::Display for Params {
 impl NoTypeDisplay for Params {
     fn to_string_notype(&self) -> String {
         format!(
-            "({}, {}, {})",
+            "({}, {}, {}, {})",
             fmt_vec(&self.non_defaults),
             fmt_option!(pre "*", &self.var_params),
             self.defaults
                 .iter()
                 .map(|p| p.to_string_notype())
-                .fold("".to_string(), |acc, e| acc + &e + ", ")
+                .fold("".to_string(), |acc, e| acc + &e + ", "),
+            fmt_option!(pre "**", &self.kw_var_params),
         )
     }
 }
@@ -1707,6 +1738,17 @@ impl Locational for Params {
                 return loc;
             }
         }
+        if let Some(kw_var) = self.kw_var_params.as_deref() {
+            return if let Some(l) = self.non_defaults.first() {
+                Location::concat(l, kw_var)
+            } else if let Some(l) = self.var_params.as_deref() {
+                Location::concat(l, kw_var)
+            } else if let Some(l) = self.defaults.first() {
+                Location::concat(l, kw_var)
+            } else {
+                kw_var.loc()
+            };
+        }
         match (
             self.non_defaults.first(),
             self.var_params.as_ref(),
@@ -1727,6 +1769,7 @@ type RawParams = (
     Vec<NonDefaultParamSignature>,
     Option<Box<NonDefaultParamSignature>>,
     Vec<DefaultParamSignature>,
+    Option<Box<NonDefaultParamSignature>>,
     Option<(Token, Token)>,
 );
 
@@ -1734,6 +1777,7 @@ type RefRawParams<'a> = (
     &'a Vec<NonDefaultParamSignature>,
     &'a Option<Box<NonDefaultParamSignature>>,
     &'a Vec<DefaultParamSignature>,
+    &'a Option<Box<NonDefaultParamSignature>>,
     &'a Option<(Token, Token)>,
 );
 
@@ -1742,12 +1786,14 @@ impl Params {
         non_defaults: Vec<NonDefaultParamSignature>,
         var_args: Option<Box<NonDefaultParamSignature>>,
         defaults: Vec<DefaultParamSignature>,
+        kw_var_params: Option<Box<NonDefaultParamSignature>>,
         parens: Option<(Token, Token)>,
     ) -> Self {
         Self {
             non_defaults,
             var_params: var_args,
             defaults,
+            kw_var_params,
             parens,
         }
     }
@@ -1757,6 +1803,7 @@ impl Params {
             &self.non_defaults,
             &self.var_params,
             &self.defaults,
+            &self.kw_var_params,
             &self.parens,
         )
     }
@@ -1766,6 +1813,7 @@ impl Params {
             self.non_defaults,
             self.var_params,
             self.defaults,
+            self.kw_var_params,
             self.parens,
         )
     }
@@ -1786,6 +1834,8 @@ pub struct SubrSignature {
     pub ident: Identifier,
     pub params: Params,
     pub return_t_spec: Option<TypeSpec>,
+    /// contains `yield` in the body
+    pub is_generator: bool,
 }
 
 impl NestedDisplay for SubrSignature {
@@ -1833,17 +1883,41 @@ impl HasType for SubrSignature {
 }
 
 impl SubrSignature {
-    pub const fn new(ident: Identifier, params: Params, return_t_spec: Option<TypeSpec>) -> Self {
+    pub const fn new(
+        ident: Identifier,
+        params: Params,
+        return_t_spec: Option<TypeSpec>,
+        is_generator: bool,
+    ) -> Self {
         Self {
             ident,
             params,
             return_t_spec,
+            is_generator,
         }
     }
 
     pub fn is_procedural(&self) -> bool {
         self.ident.is_procedural()
     }
+
+    /// decorated with `@Async`
+    pub fn is_async(&self) -> bool {
+        self.ident
+            .vi
+            .comptime_decos
+            .as_ref()
+            .is_some_and(|decos| decos.contains("Async"))
+    }
+
+    /// decorated with `@Test`
+    pub fn is_test(&self) -> bool {
+        self.ident
+            .vi
+            .comptime_decos
+            .as_ref()
+            .is_some_and(|decos| decos.contains("Test"))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
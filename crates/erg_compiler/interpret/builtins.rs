@@ -0,0 +1,1941 @@
+//! defines the builtin functions, methods and operators of the interpreter.
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+
+use erg_common::error::Location;
+use erg_common::shared::Shared;
+use erg_common::traits::Locational;
+use erg_common::Str;
+
+use erg_parser::token::{Token, TokenKind};
+
+use crate::hir::Expr;
+
+use super::value::{ClassKind, ExceptionObj, RangeObj, Value};
+use super::{CallArgs, EvalResult, Interpreter, Scope, Signal};
+
+const BUILTIN_FUNCS: [&str; 53] = [
+    "abs",
+    "all",
+    "any",
+    "assert",
+    "bin",
+    "bool",
+    "chr",
+    "classof",
+    "discard",
+    "enumerate",
+    "exit",
+    "filter",
+    "float",
+    "for",
+    "for!",
+    "hex",
+    "if",
+    "if!",
+    "input!",
+    "int",
+    "isinstance",
+    "issubclass",
+    "len",
+    "log",
+    "map",
+    "match",
+    "match!",
+    "max",
+    "min",
+    "nat",
+    "not",
+    "oct",
+    "ord",
+    "panic",
+    "pow",
+    "print!",
+    "quit",
+    "raise",
+    "range",
+    "repr",
+    "reversed",
+    "round",
+    "sorted",
+    "str",
+    "sum",
+    "try!",
+    "while!",
+    "zip",
+    "dict",
+    "array",
+    "set",
+    "tuple",
+    "id",
+];
+
+const COMMON_METHODS: [&str; 4] = ["to_str", "__str__", "__repr__", "__eq__"];
+const MUT_METHODS: [&str; 3] = ["update!", "inc!", "dec!"];
+const NUM_METHODS: [&str; 5] = ["abs", "times!", "is_integer", "real", "imag"];
+const STR_METHODS: [&str; 23] = [
+    "__getitem__",
+    "capitalize",
+    "count",
+    "endswith",
+    "find",
+    "format",
+    "isalnum",
+    "isalpha",
+    "isdecimal",
+    "isdigit",
+    "islower",
+    "isnumeric",
+    "isupper",
+    "join",
+    "lower",
+    "lstrip",
+    "replace",
+    "rstrip",
+    "split",
+    "splitlines",
+    "startswith",
+    "strip",
+    "upper",
+];
+const ARRAY_METHODS: [&str; 15] = [
+    "__getitem__",
+    "clear!",
+    "copy",
+    "count",
+    "extend!",
+    "index",
+    "insert!",
+    "pop!",
+    "push!",
+    "remove!",
+    "reverse!",
+    "sort!",
+    "update!",
+    "dedup!",
+    "sum",
+];
+const TUPLE_METHODS: [&str; 4] = ["__getitem__", "__Tuple_getitem__", "count", "index"];
+const DICT_METHODS: [&str; 11] = [
+    "__getitem__",
+    "copy",
+    "get",
+    "insert!",
+    "items",
+    "keys",
+    "pop!",
+    "remove!",
+    "update!",
+    "values",
+    "concat",
+];
+const SET_METHODS: [&str; 8] = [
+    "add!",
+    "copy",
+    "difference",
+    "discard!",
+    "intersection",
+    "remove!",
+    "union",
+    "update!",
+];
+
+/// `name` -> the parent class of the builtin exception class `name`
+fn exception_base(name: &str) -> Option<&'static str> {
+    match name {
+        "Exception" | "SystemExit" | "KeyboardInterrupt" | "GeneratorExit" => Some("BaseException"),
+        "ArithmeticError" | "AssertionError" | "AttributeError" | "BufferError" | "EOFError"
+        | "ImportError" | "LookupError" | "MemoryError" | "NameError" | "OSError"
+        | "ReferenceError" | "RuntimeError" | "StopIteration" | "SyntaxError" | "TypeError"
+        | "ValueError" => Some("Exception"),
+        "FloatingPointError" | "OverflowError" | "ZeroDivisionError" => Some("ArithmeticError"),
+        "IndexError" | "KeyError" => Some("LookupError"),
+        "ModuleNotFoundError" => Some("ImportError"),
+        "UnboundLocalError" => Some("NameError"),
+        "NotImplementedError" | "RecursionError" => Some("RuntimeError"),
+        "UnicodeError" => Some("ValueError"),
+        "FileExistsError" | "FileNotFoundError" | "IsADirectoryError" | "NotADirectoryError"
+        | "PermissionError" | "TimeoutError" => Some("OSError"),
+        _ => None,
+    }
+}
+
+fn is_exception_class(name: &str) -> bool {
+    name == "BaseException" || exception_base(name).is_some()
+}
+
+fn exception_is_subclass(mut class: &str, sup: &str) -> bool {
+    loop {
+        if class == sup {
+            return true;
+        }
+        match exception_base(class) {
+            Some(base) => class = base,
+            None => return false,
+        }
+    }
+}
+
+/// Numeric operands (`Bool` is treated as `Int`, as in Python)
+#[derive(Debug, Clone, Copy)]
+enum Num {
+    Int(i128),
+    Float(f64),
+}
+
+impl Num {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(b) => Some(Self::Int(*b as i128)),
+            Value::Int(i) => Some(Self::Int(*i)),
+            Value::Float(f) => Some(Self::Float(*f)),
+            _ => None,
+        }
+    }
+
+    fn as_f64(self) -> f64 {
+        match self {
+            Self::Int(i) => i as f64,
+            Self::Float(f) => f,
+        }
+    }
+}
+
+fn binop_dunder(kind: TokenKind) -> Option<&'static str> {
+    use TokenKind::*;
+    let name = match kind {
+        Plus => "__add__",
+        Minus => "__sub__",
+        Star => "__mul__",
+        Slash => "__div__",
+        FloorDiv => "__floordiv__",
+        Mod => "__mod__",
+        Pow => "__pow__",
+        DblEq => "__eq__",
+        NotEq => "__ne__",
+        Less => "__lt__",
+        LessEq => "__le__",
+        Gre => "__gt__",
+        GreEq => "__ge__",
+        _ => return None,
+    };
+    Some(name)
+}
+
+impl Interpreter {
+    /// Returns the builtin object named `name`.
+    /// Capitalized names that are not defined by the user are regarded as builtin types.
+    pub(super) fn builtin_value(name: &str) -> Option<Value> {
+        if BUILTIN_FUNCS.contains(&name) {
+            Some(Value::Builtin(Str::rc(name)))
+        } else if name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            Some(Value::Type(Str::rc(name)))
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn is_mut_method(name: &str) -> bool {
+        MUT_METHODS.contains(&name)
+    }
+
+    pub(super) fn has_builtin_method(obj: &Value, name: &str) -> bool {
+        if COMMON_METHODS.contains(&name) {
+            return true;
+        }
+        let methods: &[&str] = match obj {
+            Value::Mut(_) => &MUT_METHODS,
+            Value::Bool(_) | Value::Int(_) | Value::Float(_) => &NUM_METHODS,
+            Value::Str(_) => &STR_METHODS,
+            Value::Array(_) => &ARRAY_METHODS,
+            Value::Tuple(_) => &TUPLE_METHODS,
+            Value::Dict(_) => &DICT_METHODS,
+            Value::Set(_) => &SET_METHODS,
+            _ => &[],
+        };
+        methods.contains(&name)
+    }
+
+    /// `x in ty`
+    pub(super) fn contains(&mut self, ty: &Value, x: &Value, loc: Location) -> EvalResult<bool> {
+        let x = x.deref();
+        let res = match ty.deref() {
+            Value::Type(name) => Self::type_contains(&name, &x),
+            Value::Union(union) => {
+                self.contains(&union.0, &x, loc)? || self.contains(&union.1, &x, loc)?
+            }
+            Value::Intersection(isec) => {
+                self.contains(&isec.0, &x, loc)? && self.contains(&isec.1, &x, loc)?
+            }
+            Value::Class(class) if class.kind == ClassKind::Patch => {
+                let base = class.base.clone().unwrap_or_default();
+                self.contains(&base, &x, loc)?
+            }
+            Value::Class(class) => match &x {
+                Value::Instance(inst) => inst.class.is_subclass_of(&class),
+                _ => false,
+            },
+            Value::Range(range) => range.contains(&x),
+            Value::Set(elems) => elems.borrow().iter().any(|e| e.structural_eq(&x)),
+            Value::Dict(kvs) => kvs.borrow().iter().any(|(k, _)| k.structural_eq(&x)),
+            Value::Str(s) => match &x {
+                Value::Str(sub) => s.contains(&sub[..]),
+                _ => false,
+            },
+            ty @ (Value::Array(_) | Value::Tuple(_)) if ty.is_type() => {
+                let types = self.iterate(&ty, loc)?;
+                let elems = match &x {
+                    Value::Array(_) | Value::Tuple(_) => self.iterate(&x, loc)?,
+                    _ => return Ok(false),
+                };
+                if types.len() != elems.len() {
+                    return Ok(false);
+                }
+                for (ty, elem) in types.iter().zip(elems.iter()) {
+                    if !self.contains(ty, elem, loc)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Value::Array(elems) => elems.borrow().iter().any(|e| e.structural_eq(&x)),
+            Value::Tuple(elems) => elems.iter().any(|e| e.structural_eq(&x)),
+            Value::Record(fields) if fields.iter().all(|(_, v)| v.is_type()) => {
+                for (name, ty) in fields.iter() {
+                    let field = match &x {
+                        Value::Record(xs) => {
+                            xs.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+                        }
+                        Value::Instance(inst) => inst.attrs.borrow().get(name).cloned(),
+                        _ => None,
+                    };
+                    match field {
+                        Some(field) if self.contains(ty, &field, loc)? => {}
+                        _ => return Ok(false),
+                    }
+                }
+                true
+            }
+            other => other.structural_eq(&x),
+        };
+        Ok(res)
+    }
+
+    fn type_contains(name: &str, x: &Value) -> bool {
+        match name.trim_end_matches('!') {
+            "Obj" | "Object" => true,
+            "Never" => false,
+            "Int" => matches!(x, Value::Int(_) | Value::Bool(_)),
+            "Nat" => matches!(x, Value::Int(i) if *i >= 0) || matches!(x, Value::Bool(_)),
+            "Float" => matches!(x, Value::Float(_)),
+            "Ratio" | "Complex" => Num::from_value(x).is_some(),
+            "Bool" => matches!(x, Value::Bool(_)),
+            "Str" => matches!(x, Value::Str(_)),
+            "NoneType" => matches!(x, Value::None),
+            "Array" | "List" => matches!(x, Value::Array(_)),
+            "Tuple" => matches!(x, Value::Tuple(_)),
+            "Dict" => matches!(x, Value::Dict(_)),
+            "Set" => matches!(x, Value::Set(_)),
+            "Range" => matches!(x, Value::Range(_)),
+            "Record" => matches!(x, Value::Record(_)),
+            "Type" | "ClassType" | "TraitType" => x.is_type(),
+            "Module" => matches!(x, Value::Module(_)),
+            "Subroutine" | "Func" | "Proc" => matches!(
+                x,
+                Value::Subr(_)
+                    | Value::BoundMethod(_)
+                    | Value::Builtin(_)
+                    | Value::BuiltinMethod(_)
+                    | Value::Constructor(_)
+            ),
+            exc if is_exception_class(exc) => match x {
+                Value::Exception(obj) => exception_is_subclass(&obj.class, exc),
+                _ => false,
+            },
+            // traits, etc.
+            _ => true,
+        }
+    }
+
+    pub(super) fn iterate(&mut self, value: &Value, loc: Location) -> EvalResult<Vec<Value>> {
+        match value.deref() {
+            Value::Array(elems) | Value::Set(elems) => Ok(elems.borrow().clone()),
+            Value::Tuple(elems) => Ok(elems.to_vec()),
+            Value::Dict(kvs) => Ok(kvs.borrow().iter().map(|(k, _)| k.clone()).collect()),
+            Value::Str(s) => Ok(s.chars().map(|c| Value::str(c.to_string())).collect()),
+            Value::Range(range) => Ok((range.first()..range.stop()).map(Value::Int).collect()),
+            other => self.raise(
+                "TypeError",
+                format!("'{}' object is not iterable", other.class_name()),
+                loc,
+            ),
+        }
+    }
+
+    pub(super) fn expect_index(&mut self, value: &Value, loc: Location) -> EvalResult<i128> {
+        match value.deref() {
+            Value::Int(i) => Ok(i),
+            Value::Bool(b) => Ok(b as i128),
+            other => self.raise(
+                "TypeError",
+                format!(
+                    "'{}' object cannot be interpreted as an integer",
+                    other.class_name()
+                ),
+                loc,
+            ),
+        }
+    }
+
+    fn expect_str(&mut self, value: &Value, loc: Location) -> EvalResult<Str> {
+        match value.deref() {
+            Value::Str(s) => Ok(s),
+            other => self.raise(
+                "TypeError",
+                format!("expected Str, but found {}", other.class_name()),
+                loc,
+            ),
+        }
+    }
+
+    fn take_arg(
+        &mut self,
+        args: &mut CallArgs,
+        nth: usize,
+        name: &str,
+        subr: &str,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        match args.take_nth_or_kw(nth, name) {
+            Some(value) => Ok(value),
+            None => self.raise(
+                "TypeError",
+                format!("{subr}() missing required argument: '{name}'"),
+                loc,
+            ),
+        }
+    }
+
+    /// Python's `str`
+    pub(super) fn stringify(&mut self, value: &Value, loc: Location) -> EvalResult<String> {
+        match value {
+            Value::Instance(inst) if inst.class.get_attr("__str__").is_some() => {
+                let method = self.get_attr(value.clone(), "__str__", loc)?;
+                let res = self.call_value(method, CallArgs::default(), loc)?;
+                Ok(res.to_string())
+            }
+            Value::Instance(_) => self.repr(value, loc),
+            Value::Mut(inner) => {
+                let inner = inner.borrow().clone();
+                self.stringify(&inner, loc)
+            }
+            other => Ok(other.to_string()),
+        }
+    }
+
+    /// Python's `repr`
+    pub(super) fn repr(&mut self, value: &Value, loc: Location) -> EvalResult<String> {
+        match value {
+            Value::Instance(inst) if inst.class.get_attr("__repr__").is_some() => {
+                let method = self.get_attr(value.clone(), "__repr__", loc)?;
+                let res = self.call_value(method, CallArgs::default(), loc)?;
+                Ok(res.to_string())
+            }
+            other => Ok(other.repr()),
+        }
+    }
+
+    fn compare(&mut self, lhs: &Value, rhs: &Value, loc: Location) -> EvalResult<Option<Ordering>> {
+        let (lhs, rhs) = (lhs.deref(), rhs.deref());
+        match (&lhs, &rhs) {
+            (Value::Str(l), Value::Str(r)) => return Ok(Some(l.cmp(r))),
+            (Value::Array(_) | Value::Tuple(_), Value::Array(_) | Value::Tuple(_)) => {
+                let (l, r) = (self.iterate(&lhs, loc)?, self.iterate(&rhs, loc)?);
+                for (x, y) in l.iter().zip(r.iter()) {
+                    match self.compare(x, y, loc)? {
+                        Some(Ordering::Equal) => {}
+                        other => return Ok(other),
+                    }
+                }
+                return Ok(Some(l.len().cmp(&r.len())));
+            }
+            _ => {}
+        }
+        match (Num::from_value(&lhs), Num::from_value(&rhs)) {
+            (Some(Num::Int(l)), Some(Num::Int(r))) => Ok(Some(l.cmp(&r))),
+            (Some(l), Some(r)) => Ok(l.as_f64().partial_cmp(&r.as_f64())),
+            _ => self.raise(
+                "TypeError",
+                format!(
+                    "comparison not supported between instances of '{}' and '{}'",
+                    lhs.class_name(),
+                    rhs.class_name()
+                ),
+                loc,
+            ),
+        }
+    }
+
+    fn equals(&mut self, lhs: &Value, rhs: &Value, loc: Location) -> EvalResult<bool> {
+        if let Value::Instance(inst) = lhs {
+            if inst.class.get_attr("__eq__").is_some() {
+                let method = self.get_attr(lhs.clone(), "__eq__", loc)?;
+                let res = self.call_value(method, CallArgs::pos(vec![rhs.clone()]), loc)?;
+                return Ok(res.is_truthy());
+            }
+        }
+        Ok(lhs.structural_eq(rhs))
+    }
+
+    pub(super) fn eval_binop(
+        &mut self,
+        op: &Token,
+        lhs: Value,
+        rhs: &Expr,
+        scope: &Rc<Scope>,
+    ) -> EvalResult<Value> {
+        // short-circuit evaluation
+        match (op.kind, lhs.deref()) {
+            (TokenKind::AndOp, Value::Bool(false)) => return Ok(Value::Bool(false)),
+            (TokenKind::OrOp, Value::Bool(true)) => return Ok(Value::Bool(true)),
+            _ => {}
+        }
+        let loc = Location::concat(op, rhs);
+        let rhs = self.eval_expr(rhs, scope)?;
+        self.binop(op.kind, lhs, rhs, loc)
+    }
+
+    fn binop(
+        &mut self,
+        kind: TokenKind,
+        lhs: Value,
+        rhs: Value,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        use TokenKind::*;
+        if let (Value::Instance(inst), Some(dunder)) = (&lhs, binop_dunder(kind)) {
+            if inst.class.get_attr(dunder).is_some() {
+                let method = self.get_attr(lhs.clone(), dunder, loc)?;
+                return self.call_value(method, CallArgs::pos(vec![rhs]), loc);
+            }
+        }
+        let (l, r) = (lhs.deref(), rhs.deref());
+        match kind {
+            DblEq => return Ok(Value::Bool(self.equals(&l, &r, loc)?)),
+            NotEq => return Ok(Value::Bool(!self.equals(&l, &r, loc)?)),
+            IsOp => return Ok(Value::Bool(lhs.is(&rhs))),
+            IsNotOp => return Ok(Value::Bool(!lhs.is(&rhs))),
+            InOp => return Ok(Value::Bool(self.contains(&r, &l, loc)?)),
+            NotInOp => return Ok(Value::Bool(!self.contains(&r, &l, loc)?)),
+            Less | LessEq | Gre | GreEq => {
+                let ord = self.compare(&l, &r, loc)?;
+                let res = match (kind, ord) {
+                    (_, None) => false,
+                    (Less, Some(ord)) => ord.is_lt(),
+                    (LessEq, Some(ord)) => ord.is_le(),
+                    (Gre, Some(ord)) => ord.is_gt(),
+                    (_, Some(ord)) => ord.is_ge(),
+                };
+                return Ok(Value::Bool(res));
+            }
+            Closed | RightOpen | LeftOpen | Open => {
+                let start = self.expect_index(&l, loc)?;
+                let end = self.expect_index(&r, loc)?;
+                return Ok(Value::Range(RangeObj {
+                    start,
+                    end,
+                    left_open: matches!(kind, LeftOpen | Open),
+                    right_open: matches!(kind, RightOpen | Open),
+                }));
+            }
+            AndOp | OrOp => return self.logical_op(kind, l, r, loc),
+            _ => {}
+        }
+        match (&l, &r) {
+            (Value::Str(x), Value::Str(y)) if kind == Plus => {
+                return Ok(Value::str(format!("{x}{y}")));
+            }
+            (Value::Str(s), Value::Int(n)) | (Value::Int(n), Value::Str(s)) if kind == Star => {
+                return Ok(Value::str(s.repeat((*n).max(0) as usize)));
+            }
+            (Value::Array(x), Value::Array(y)) if kind == Plus => {
+                let elems = [x.borrow().clone(), y.borrow().clone()].concat();
+                return Ok(Value::array(elems));
+            }
+            (Value::Array(x), Value::Int(n)) if kind == Star => {
+                let elems = (0..(*n).max(0)).flat_map(|_| x.borrow().clone()).collect();
+                return Ok(Value::array(elems));
+            }
+            (Value::Tuple(x), Value::Tuple(y)) if kind == Plus => {
+                return Ok(Value::tuple([x.to_vec(), y.to_vec()].concat()));
+            }
+            (Value::Set(x), Value::Set(y)) if matches!(kind, BitOr | BitAnd | Minus) => {
+                let (x, y) = (x.borrow(), y.borrow());
+                let elems = match kind {
+                    BitOr => {
+                        let mut elems = x.clone();
+                        for e in y.iter() {
+                            if !elems.iter().any(|x| x.structural_eq(e)) {
+                                elems.push(e.clone());
+                            }
+                        }
+                        elems
+                    }
+                    BitAnd => x
+                        .iter()
+                        .filter(|e| y.iter().any(|y| y.structural_eq(e)))
+                        .cloned()
+                        .collect(),
+                    _ => x
+                        .iter()
+                        .filter(|e| !y.iter().any(|y| y.structural_eq(e)))
+                        .cloned()
+                        .collect(),
+                };
+                return Ok(Value::Set(Shared::new(elems)));
+            }
+            _ => {}
+        }
+        let (Some(x), Some(y)) = (Num::from_value(&l), Num::from_value(&r)) else {
+            return self.unsupported_operands(kind, &l, &r, loc);
+        };
+        match (x, y) {
+            (Num::Int(x), Num::Int(y)) => self.int_op(kind, x, y, &l, &r, loc),
+            (x, y) => self.float_op(kind, x.as_f64(), y.as_f64(), &l, &r, loc),
+        }
+    }
+
+    fn unsupported_operands<T>(
+        &mut self,
+        kind: TokenKind,
+        l: &Value,
+        r: &Value,
+        loc: Location,
+    ) -> EvalResult<T> {
+        self.raise(
+            "TypeError",
+            format!(
+                "unsupported operand type(s) for {kind:?}: '{}' and '{}'",
+                l.class_name(),
+                r.class_name()
+            ),
+            loc,
+        )
+    }
+
+    fn logical_op(
+        &mut self,
+        kind: TokenKind,
+        l: Value,
+        r: Value,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        let is_and = kind == TokenKind::AndOp;
+        match (&l, &r) {
+            (Value::Bool(x), Value::Bool(y)) => {
+                Ok(Value::Bool(if is_and { *x && *y } else { *x || *y }))
+            }
+            (x, y) if x.is_type() || y.is_type() => {
+                let pair = Rc::new((l.clone(), r.clone()));
+                Ok(if is_and {
+                    Value::Intersection(pair)
+                } else {
+                    Value::Union(pair)
+                })
+            }
+            (Value::Set(_), Value::Set(_)) | (Value::Int(_), Value::Int(_)) => {
+                let kind = if is_and {
+                    TokenKind::BitAnd
+                } else {
+                    TokenKind::BitOr
+                };
+                self.binop(kind, l, r, loc)
+            }
+            // the same as Python's `and`/`or`
+            _ => Ok(if l.is_truthy() == is_and { r } else { l }),
+        }
+    }
+
+    fn int_op(
+        &mut self,
+        kind: TokenKind,
+        x: i128,
+        y: i128,
+        l: &Value,
+        r: &Value,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        use TokenKind::*;
+        let res = match kind {
+            Plus => x.checked_add(y),
+            Minus => x.checked_sub(y),
+            Star => x.checked_mul(y),
+            Slash => {
+                if y == 0 {
+                    return self.raise("ZeroDivisionError", "division by zero", loc);
+                }
+                return Ok(Value::Float(x as f64 / y as f64));
+            }
+            FloorDiv | Mod => {
+                if y == 0 {
+                    return self.raise(
+                        "ZeroDivisionError",
+                        "integer division or modulo by zero",
+                        loc,
+                    );
+                }
+                // rounds towards negative infinity, as in Python
+                let (mut q, mut m) = (x / y, x % y);
+                if m != 0 && (m < 0) != (y < 0) {
+                    q -= 1;
+                    m += y;
+                }
+                Some(if kind == FloorDiv { q } else { m })
+            }
+            Pow if y < 0 => return Ok(Value::Float((x as f64).powf(y as f64))),
+            Pow => u32::try_from(y).ok().and_then(|y| x.checked_pow(y)),
+            BitAnd | BitOr | BitXor if matches!((l, r), (Value::Bool(_), Value::Bool(_))) => {
+                let res = match kind {
+                    BitAnd => x & y,
+                    BitOr => x | y,
+                    _ => x ^ y,
+                };
+                return Ok(Value::Bool(res != 0));
+            }
+            BitAnd => Some(x & y),
+            BitOr => Some(x | y),
+            BitXor => Some(x ^ y),
+            Shl => u32::try_from(y).ok().and_then(|y| x.checked_shl(y)),
+            Shr => u32::try_from(y).ok().and_then(|y| x.checked_shr(y)),
+            _ => return self.unsupported_operands(kind, l, r, loc),
+        };
+        match res {
+            Some(res) => Ok(Value::Int(res)),
+            None => self.raise("OverflowError", "integer overflow", loc),
+        }
+    }
+
+    fn float_op(
+        &mut self,
+        kind: TokenKind,
+        x: f64,
+        y: f64,
+        l: &Value,
+        r: &Value,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        use TokenKind::*;
+        let res = match kind {
+            Plus => x + y,
+            Minus => x - y,
+            Star => x * y,
+            Slash if y == 0.0 => {
+                return self.raise("ZeroDivisionError", "float division by zero", loc);
+            }
+            Slash => x / y,
+            FloorDiv if y == 0.0 => {
+                return self.raise("ZeroDivisionError", "float floor division by zero", loc);
+            }
+            FloorDiv => (x / y).floor(),
+            Mod if y == 0.0 => {
+                return self.raise("ZeroDivisionError", "float modulo", loc);
+            }
+            Mod => {
+                let m = x % y;
+                if m != 0.0 && (m < 0.0) != (y < 0.0) {
+                    m + y
+                } else {
+                    m
+                }
+            }
+            Pow => x.powf(y),
+            _ => return self.unsupported_operands(kind, l, r, loc),
+        };
+        Ok(Value::Float(res))
+    }
+
+    pub(super) fn eval_unaryop(&mut self, op: &Token, value: Value) -> EvalResult<Value> {
+        let loc = op.loc();
+        match op.kind {
+            TokenKind::Mutate => Ok(match value {
+                Value::Array(elems) => Value::array(elems.borrow().clone()),
+                Value::Dict(kvs) => Value::Dict(Shared::new(kvs.borrow().clone())),
+                Value::Set(elems) => Value::Set(Shared::new(elems.borrow().clone())),
+                mutable @ (Value::Mut(_) | Value::Instance(_)) => mutable,
+                other => Value::Mut(Shared::new(other)),
+            }),
+            TokenKind::PrePlus => match value.deref() {
+                Value::Bool(b) => Ok(Value::Int(b as i128)),
+                num @ (Value::Int(_) | Value::Float(_)) => Ok(num),
+                other => self.raise(
+                    "TypeError",
+                    format!("bad operand type for unary +: '{}'", other.class_name()),
+                    loc,
+                ),
+            },
+            TokenKind::PreMinus => match value.deref() {
+                Value::Bool(b) => Ok(Value::Int(-(b as i128))),
+                Value::Int(i) => match i.checked_neg() {
+                    Some(i) => Ok(Value::Int(i)),
+                    None => self.raise("OverflowError", "integer overflow", loc),
+                },
+                Value::Float(f) => Ok(Value::Float(-f)),
+                other => self.raise(
+                    "TypeError",
+                    format!("bad operand type for unary -: '{}'", other.class_name()),
+                    loc,
+                ),
+            },
+            TokenKind::PreBitNot => {
+                let i = self.expect_index(&value, loc)?;
+                Ok(Value::Int(!i))
+            }
+            other => self.unsupported(&format!("the unary operator {other:?}"), loc),
+        }
+    }
+
+    /// Converts an evaluated value to an exception object for `raise`.
+    fn conv_exception(&mut self, value: Value, loc: Location) -> EvalResult<Value> {
+        match value {
+            exc @ Value::Exception(_) => Ok(exc),
+            Value::Type(name) if is_exception_class(&name) => Ok(Value::exception(name, "")),
+            other => self.raise(
+                "TypeError",
+                format!(
+                    "exceptions must derive from BaseException, not {}",
+                    other.class_name()
+                ),
+                loc,
+            ),
+        }
+    }
+
+    fn print(&mut self, mut args: CallArgs, loc: Location) -> EvalResult<Value> {
+        let sep = match args.take_kw("sep") {
+            Some(sep) => self.expect_str(&sep, loc)?.to_string(),
+            None => " ".to_string(),
+        };
+        let end = match args.take_kw("end") {
+            Some(end) => self.expect_str(&end, loc)?.to_string(),
+            None => "\n".to_string(),
+        };
+        let mut strs = vec![];
+        for value in args.pos.iter() {
+            strs.push(self.stringify(value, loc)?);
+        }
+        let mut stdout = io::stdout().lock();
+        let _ = write!(stdout, "{}{end}", strs.join(&sep));
+        let _ = stdout.flush();
+        Ok(Value::None)
+    }
+
+    fn sort_values(
+        &mut self,
+        values: &mut [Value],
+        key: Option<Value>,
+        loc: Location,
+    ) -> EvalResult<()> {
+        let keys = match key {
+            Some(key) => {
+                let mut keys = vec![];
+                for value in values.iter() {
+                    keys.push(self.call_value(
+                        key.clone(),
+                        CallArgs::pos(vec![value.clone()]),
+                        loc,
+                    )?);
+                }
+                keys
+            }
+            None => values.to_vec(),
+        };
+        let mut indices = (0..values.len()).collect::<Vec<_>>();
+        let mut err = None;
+        indices.sort_by(|&i, &j| {
+            if err.is_some() {
+                return Ordering::Equal;
+            }
+            match self.compare(&keys[i], &keys[j], loc) {
+                Ok(ord) => ord.unwrap_or(Ordering::Equal),
+                Err(e) => {
+                    err = Some(e);
+                    Ordering::Equal
+                }
+            }
+        });
+        if let Some(err) = err {
+            return Err(err);
+        }
+        let sorted = indices
+            .into_iter()
+            .map(|i| values[i].clone())
+            .collect::<Vec<_>>();
+        values.clone_from_slice(&sorted);
+        Ok(())
+    }
+
+    fn min_max(&mut self, name: &str, mut args: CallArgs, loc: Location) -> EvalResult<Value> {
+        let values = if args.pos.len() == 1 {
+            let iterable = args.pos.remove(0);
+            self.iterate(&iterable, loc)?
+        } else {
+            args.pos
+        };
+        let mut iter = values.into_iter();
+        let Some(mut res) = iter.next() else {
+            return self.raise(
+                "ValueError",
+                format!("{name}() arg is an empty sequence"),
+                loc,
+            );
+        };
+        for value in iter {
+            let ord = self.compare(&value, &res, loc)?;
+            let replace = match ord {
+                Some(Ordering::Less) => name == "min",
+                Some(Ordering::Greater) => name == "max",
+                _ => false,
+            };
+            if replace {
+                res = value;
+            }
+        }
+        Ok(res)
+    }
+
+    fn conv_int(&mut self, value: &Value, loc: Location) -> EvalResult<Value> {
+        match value.deref() {
+            Value::Int(i) => Ok(Value::Int(i)),
+            Value::Bool(b) => Ok(Value::Int(b as i128)),
+            Value::Float(f) if f.is_finite() => Ok(Value::Int(f.trunc() as i128)),
+            Value::Str(s) => match s.trim().replace('_', "").parse::<i128>() {
+                Ok(i) => Ok(Value::Int(i)),
+                Err(_) => self.raise(
+                    "ValueError",
+                    format!(
+                        "invalid literal for int() with base 10: {}",
+                        Value::Str(s).repr()
+                    ),
+                    loc,
+                ),
+            },
+            other => self.raise(
+                "TypeError",
+                format!(
+                    "int() argument must be a string or a number, not '{}'",
+                    other.class_name()
+                ),
+                loc,
+            ),
+        }
+    }
+
+    fn conv_float(&mut self, value: &Value, loc: Location) -> EvalResult<Value> {
+        match value.deref() {
+            Value::Float(f) => Ok(Value::Float(f)),
+            Value::Int(i) => Ok(Value::Float(i as f64)),
+            Value::Bool(b) => Ok(Value::Float(b as i128 as f64)),
+            Value::Str(s) => match &s.trim().to_lowercase()[..] {
+                "inf" | "infinity" => Ok(Value::Float(f64::INFINITY)),
+                "-inf" | "-infinity" => Ok(Value::Float(f64::NEG_INFINITY)),
+                "nan" => Ok(Value::Float(f64::NAN)),
+                trimmed => match trimmed.parse::<f64>() {
+                    Ok(f) => Ok(Value::Float(f)),
+                    Err(_) => self.raise(
+                        "ValueError",
+                        format!(
+                            "could not convert string to float: {}",
+                            Value::Str(s).repr()
+                        ),
+                        loc,
+                    ),
+                },
+            },
+            other => self.raise(
+                "TypeError",
+                format!(
+                    "float() argument must be a string or a number, not '{}'",
+                    other.class_name()
+                ),
+                loc,
+            ),
+        }
+    }
+
+    /// Calls the control flow builtins with evaluated arguments (e.g. `i = if; i cond, ...`).
+    fn call_control(&mut self, name: &str, mut args: CallArgs, loc: Location) -> EvalResult<Value> {
+        match name {
+            "if" | "if!" => {
+                let cond = self.take_arg(&mut args, 0, "cond", name, loc)?;
+                let block = if cond.is_truthy() {
+                    args.take_nth_or_kw(1, "then")
+                } else {
+                    args.take_nth_or_kw(2, "else")
+                };
+                match block {
+                    Some(block) => self.call_value(block, CallArgs::default(), loc),
+                    None => Ok(Value::None),
+                }
+            }
+            "for" | "for!" => {
+                let iterable = self.take_arg(&mut args, 0, "iterable", name, loc)?;
+                let block = self.take_arg(&mut args, 1, "proc!", name, loc)?;
+                for elem in self.iterate(&iterable, loc)? {
+                    self.call_value(block.clone(), CallArgs::pos(vec![elem]), loc)?;
+                }
+                Ok(Value::None)
+            }
+            "while!" => {
+                let cond = self.take_arg(&mut args, 0, "cond!", name, loc)?;
+                let block = self.take_arg(&mut args, 1, "proc!", name, loc)?;
+                while self
+                    .call_value(cond.clone(), CallArgs::default(), loc)?
+                    .is_truthy()
+                {
+                    self.call_value(block.clone(), CallArgs::default(), loc)?;
+                }
+                Ok(Value::None)
+            }
+            "match" | "match!" => {
+                let target = self.take_arg(&mut args, 0, "obj", name, loc)?;
+                let arms = args.pos.split_off(1.min(args.pos.len()));
+                let len = arms.len();
+                for (i, arm) in arms.into_iter().enumerate() {
+                    if i + 1 < len && !self.match_subr_pattern(&arm, &target, loc)? {
+                        continue;
+                    }
+                    return self.call_value(arm, CallArgs::pos(vec![target]), loc);
+                }
+                Ok(Value::None)
+            }
+            "try!" => {
+                let body = self.take_arg(&mut args, 0, "proc!", name, loc)?;
+                let exc = match self.call_value(body, CallArgs::default(), loc) {
+                    Err(Signal::Raise(exc)) => exc,
+                    other => return other,
+                };
+                for arm in args.pos.split_off(1.min(args.pos.len())) {
+                    if self.match_subr_pattern(&arm, &exc.value, loc)? {
+                        return self.call_value(arm, CallArgs::pos(vec![exc.value]), loc);
+                    }
+                }
+                Err(Signal::Raise(exc))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Checks the type specification of the first parameter of `arm`.
+    fn match_subr_pattern(
+        &mut self,
+        arm: &Value,
+        target: &Value,
+        loc: Location,
+    ) -> EvalResult<bool> {
+        let Value::Subr(subr) = arm else {
+            return Ok(true);
+        };
+        let spec = subr
+            .params
+            .non_defaults
+            .first()
+            .and_then(|param| param.t_spec_as_expr.as_ref());
+        match spec {
+            Some(spec) => {
+                let env = subr.env.clone();
+                let ty = self.eval_expr(spec, &env)?;
+                self.contains(&ty, target, loc)
+            }
+            None => Ok(true),
+        }
+    }
+
+    pub(super) fn call_builtin(
+        &mut self,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        match name {
+            "if" | "if!" | "for" | "for!" | "while!" | "match" | "match!" | "try!" => {
+                self.call_control(name, args, loc)
+            }
+            "print!" | "log" => self.print(args, loc),
+            "str" => {
+                let value = args.take_nth_or_kw(0, "object").unwrap_or(Value::str(""));
+                Ok(Value::str(self.stringify(&value, loc)?))
+            }
+            "repr" => {
+                let value = self.take_arg(&mut args, 0, "object", name, loc)?;
+                Ok(Value::str(self.repr(&value, loc)?))
+            }
+            "int" => match args.take_nth_or_kw(0, "obj") {
+                Some(value) => self.conv_int(&value, loc),
+                None => Ok(Value::Int(0)),
+            },
+            "nat" => {
+                let value = self.take_arg(&mut args, 0, "obj", name, loc)?;
+                match self.conv_int(&value, loc)? {
+                    Value::Int(i) if i >= 0 => Ok(Value::Int(i)),
+                    _ => Ok(Value::None),
+                }
+            }
+            "float" => match args.take_nth_or_kw(0, "obj") {
+                Some(value) => self.conv_float(&value, loc),
+                None => Ok(Value::Float(0.0)),
+            },
+            "bool" => Ok(Value::Bool(
+                args.take_nth_or_kw(0, "obj")
+                    .is_some_and(|value| value.is_truthy()),
+            )),
+            "not" => {
+                let value = self.take_arg(&mut args, 0, "b", name, loc)?;
+                Ok(Value::Bool(!value.is_truthy()))
+            }
+            "discard" => Ok(Value::None),
+            "id" => {
+                let value = self.take_arg(&mut args, 0, "obj", name, loc)?;
+                Ok(Value::Int(match &value {
+                    Value::Array(x) | Value::Set(x) => x.as_ptr() as usize as i128,
+                    Value::Mut(x) => x.as_ptr() as usize as i128,
+                    Value::Instance(x) => Rc::as_ptr(x) as usize as i128,
+                    _ => 0,
+                }))
+            }
+            "len" => {
+                let value = self.take_arg(&mut args, 0, "s", name, loc)?;
+                let len = match value.deref() {
+                    Value::Range(range) => range.len(),
+                    Value::Str(s) => s.chars().count(),
+                    other => self.iterate(&other, loc)?.len(),
+                };
+                Ok(Value::Int(len as i128))
+            }
+            "abs" => {
+                let value = self.take_arg(&mut args, 0, "x", name, loc)?;
+                self.call_builtin_method(value, "abs", CallArgs::default(), loc)
+            }
+            "sum" => {
+                let iterable = self.take_arg(&mut args, 0, "iterable", name, loc)?;
+                let mut res = args.take_nth_or_kw(1, "start").unwrap_or(Value::Int(0));
+                for value in self.iterate(&iterable, loc)? {
+                    res = self.binop(TokenKind::Plus, res, value, loc)?;
+                }
+                Ok(res)
+            }
+            "min" | "max" => self.min_max(name, args, loc),
+            "any" | "all" => {
+                let iterable = self.take_arg(&mut args, 0, "iterable", name, loc)?;
+                let values = self.iterate(&iterable, loc)?;
+                Ok(Value::Bool(if name == "any" {
+                    values.iter().any(|v| v.is_truthy())
+                } else {
+                    values.iter().all(|v| v.is_truthy())
+                }))
+            }
+            "map" | "filter" => {
+                let func = self.take_arg(&mut args, 0, "func", name, loc)?;
+                let iterable = self.take_arg(&mut args, 1, "iterable", name, loc)?;
+                let mut res = vec![];
+                for value in self.iterate(&iterable, loc)? {
+                    let ret =
+                        self.call_value(func.clone(), CallArgs::pos(vec![value.clone()]), loc)?;
+                    if name == "map" {
+                        res.push(ret);
+                    } else if ret.is_truthy() {
+                        res.push(value);
+                    }
+                }
+                Ok(Value::array(res))
+            }
+            "sorted" => {
+                let iterable = self.take_arg(&mut args, 0, "iterable", name, loc)?;
+                let mut values = self.iterate(&iterable, loc)?;
+                let key = args
+                    .take_kw("key")
+                    .filter(|key| !matches!(key, Value::None));
+                self.sort_values(&mut values, key, loc)?;
+                if args.take_kw("reverse").is_some_and(|rev| rev.is_truthy()) {
+                    values.reverse();
+                }
+                Ok(Value::array(values))
+            }
+            "reversed" => {
+                let iterable = self.take_arg(&mut args, 0, "seq", name, loc)?;
+                let mut values = self.iterate(&iterable, loc)?;
+                values.reverse();
+                Ok(Value::array(values))
+            }
+            "zip" => {
+                let mut columns = vec![];
+                for value in args.pos.iter() {
+                    columns.push(self.iterate(value, loc)?);
+                }
+                let len = columns.iter().map(|c| c.len()).min().unwrap_or(0);
+                let rows = (0..len)
+                    .map(|i| Value::tuple(columns.iter().map(|c| c[i].clone()).collect()))
+                    .collect();
+                Ok(Value::array(rows))
+            }
+            "enumerate" => {
+                let iterable = self.take_arg(&mut args, 0, "iterable", name, loc)?;
+                let start = match args.take_nth_or_kw(1, "start") {
+                    Some(start) => self.expect_index(&start, loc)?,
+                    None => 0,
+                };
+                let rows = self
+                    .iterate(&iterable, loc)?
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, v)| Value::tuple(vec![Value::Int(start + i as i128), v]))
+                    .collect();
+                Ok(Value::array(rows))
+            }
+            "range" => {
+                let mut bounds = vec![];
+                for value in args.pos.iter() {
+                    bounds.push(self.expect_index(value, loc)?);
+                }
+                let (start, stop, step) = match bounds[..] {
+                    [stop] => (0, stop, 1),
+                    [start, stop] => (start, stop, 1),
+                    [start, stop, step] if step != 0 => (start, stop, step),
+                    [_, _, _] => {
+                        return self.raise("ValueError", "range() arg 3 must not be zero", loc);
+                    }
+                    _ => return self.raise("TypeError", "range expected 1 to 3 arguments", loc),
+                };
+                let mut values = vec![];
+                let mut i = start;
+                while (step > 0 && i < stop) || (step < 0 && i > stop) {
+                    values.push(Value::Int(i));
+                    i += step;
+                }
+                Ok(Value::array(values))
+            }
+            "chr" => {
+                let code = self.take_arg(&mut args, 0, "i", name, loc)?;
+                let code = self.expect_index(&code, loc)?;
+                match u32::try_from(code).ok().and_then(char::from_u32) {
+                    Some(c) => Ok(Value::str(c.to_string())),
+                    None => self.raise("ValueError", "chr() arg not in range(0x110000)", loc),
+                }
+            }
+            "ord" => {
+                let c = self.take_arg(&mut args, 0, "c", name, loc)?;
+                let s = self.expect_str(&c, loc)?;
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Value::Int(c as i128)),
+                    _ => self.raise(
+                        "TypeError",
+                        format!(
+                            "ord() expected a character, but string of length {} found",
+                            s.chars().count()
+                        ),
+                        loc,
+                    ),
+                }
+            }
+            "bin" | "oct" | "hex" => {
+                let value = self.take_arg(&mut args, 0, "x", name, loc)?;
+                let i = self.expect_index(&value, loc)?;
+                let sign = if i < 0 { "-" } else { "" };
+                let abs = i.unsigned_abs();
+                let digits = match name {
+                    "bin" => format!("0b{abs:b}"),
+                    "oct" => format!("0o{abs:o}"),
+                    _ => format!("0x{abs:x}"),
+                };
+                Ok(Value::str(format!("{sign}{digits}")))
+            }
+            "pow" => {
+                let base = self.take_arg(&mut args, 0, "base", name, loc)?;
+                let exp = self.take_arg(&mut args, 1, "exp", name, loc)?;
+                self.binop(TokenKind::Pow, base, exp, loc)
+            }
+            "round" => {
+                let value = self.take_arg(&mut args, 0, "number", name, loc)?;
+                let ndigits = args
+                    .take_nth_or_kw(1, "ndigits")
+                    .filter(|n| !matches!(n, Value::None));
+                let Some(Num::Float(f)) = Num::from_value(&value.deref()) else {
+                    return Ok(value.deref());
+                };
+                match ndigits {
+                    Some(n) => {
+                        let n = self.expect_index(&n, loc)?;
+                        let scale = 10f64.powi(n as i32);
+                        Ok(Value::Float(round_half_even(f * scale) / scale))
+                    }
+                    None => Ok(Value::Int(round_half_even(f) as i128)),
+                }
+            }
+            "classof" => {
+                let value = self.take_arg(&mut args, 0, "obj", name, loc)?;
+                Ok(match value {
+                    Value::Instance(inst) => Value::Class(inst.class.clone()),
+                    other => Value::Type(Str::rc(other.class_name().trim_end_matches('!'))),
+                })
+            }
+            "isinstance" => {
+                let obj = self.take_arg(&mut args, 0, "obj", name, loc)?;
+                let ty = self.take_arg(&mut args, 1, "classinfo", name, loc)?;
+                Ok(Value::Bool(self.contains(&ty, &obj, loc)?))
+            }
+            "issubclass" => {
+                let sub = self.take_arg(&mut args, 0, "cls", name, loc)?;
+                let sup = self.take_arg(&mut args, 1, "classinfo", name, loc)?;
+                let res = match (&sub, &sup) {
+                    (Value::Class(sub), Value::Class(sup)) => sub.is_subclass_of(sup),
+                    (Value::Type(sub), Value::Type(sup)) => {
+                        sub == sup || exception_is_subclass(sub, sup)
+                    }
+                    _ => false,
+                };
+                Ok(Value::Bool(res))
+            }
+            "exit" | "quit" => {
+                let (code, msg) = match args.take_nth_or_kw(0, "code") {
+                    None | Some(Value::None) => (0, Str::ever("")),
+                    Some(Value::Int(code)) => (code as i32, Str::from(code.to_string())),
+                    Some(other) => {
+                        eprintln!("{}", self.stringify(&other, loc)?);
+                        (1, Str::from(other.to_string()))
+                    }
+                };
+                let exc = Value::Exception(Rc::new(ExceptionObj {
+                    class: Str::ever("SystemExit"),
+                    msg,
+                    code,
+                }));
+                self.raise_value(exc, loc)
+            }
+            "panic" => {
+                let msg = self.take_arg(&mut args, 0, "msg", name, loc)?;
+                let msg = self.stringify(&msg, loc)?;
+                self.raise("Exception", msg, loc)
+            }
+            "raise" => {
+                let exc = self.take_arg(&mut args, 0, "exc", name, loc)?;
+                let exc = self.conv_exception(exc, loc)?;
+                self.raise_value(exc, loc)
+            }
+            "assert" => {
+                let test = self.take_arg(&mut args, 0, "test", name, loc)?;
+                if test.is_truthy() {
+                    return Ok(Value::None);
+                }
+                let msg = match args.take_nth_or_kw(1, "msg") {
+                    Some(msg) => self.stringify(&msg, loc)?,
+                    None => String::new(),
+                };
+                self.raise("AssertionError", msg, loc)
+            }
+            "input!" => {
+                if let Some(prompt) = args.take_nth_or_kw(0, "msg") {
+                    print!("{}", self.stringify(&prompt, loc)?);
+                    let _ = io::stdout().flush();
+                }
+                let mut line = String::new();
+                match io::stdin().lock().read_line(&mut line) {
+                    Ok(0) | Err(_) => self.raise("EOFError", "EOF when reading a line", loc),
+                    Ok(_) => Ok(Value::str(line.trim_end_matches(['\n', '\r']).to_string())),
+                }
+            }
+            "array" | "dict" | "set" | "tuple" => {
+                let ty = match name {
+                    "array" => "Array",
+                    "dict" => "Dict",
+                    "set" => "Set",
+                    _ => "Tuple",
+                };
+                self.call_type(ty, args, loc)
+            }
+            _ => self.unsupported(&format!("built-in `{name}`"), loc),
+        }
+    }
+
+    /// Calls a builtin type (conversion or exception construction).
+    pub(super) fn call_type(
+        &mut self,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        if is_exception_class(name) {
+            let msg = match &args.pos[..] {
+                [] => String::new(),
+                [msg] => self.stringify(msg, loc)?,
+                msgs => Value::tuple(msgs.to_vec()).repr(),
+            };
+            return Ok(Value::exception(Str::rc(name), msg));
+        }
+        let arg = args.take_nth_or_kw(0, "obj");
+        match (name.trim_end_matches('!'), arg) {
+            ("Int", Some(value)) => self.conv_int(&value, loc),
+            ("Nat", Some(value)) => self.call_builtin("nat", CallArgs::pos(vec![value]), loc),
+            ("Float", Some(value)) => self.conv_float(&value, loc),
+            ("Str", Some(value)) => Ok(Value::str(self.stringify(&value, loc)?)),
+            ("Bool", Some(value)) => Ok(Value::Bool(value.is_truthy())),
+            ("Array" | "List", Some(value)) if !value.is_type() => {
+                Ok(Value::array(self.iterate(&value, loc)?))
+            }
+            ("Tuple", Some(value)) if !value.is_type() => {
+                Ok(Value::tuple(self.iterate(&value, loc)?))
+            }
+            ("Set", Some(value)) if !value.is_type() => {
+                let mut uniq: Vec<Value> = vec![];
+                for elem in self.iterate(&value, loc)? {
+                    if !uniq.iter().any(|e| e.structural_eq(&elem)) {
+                        uniq.push(elem);
+                    }
+                }
+                Ok(Value::Set(Shared::new(uniq)))
+            }
+            ("Dict", Some(Value::Dict(kvs))) => Ok(Value::Dict(Shared::new(kvs.borrow().clone()))),
+            ("Int", None) => Ok(Value::Int(0)),
+            ("Float", None) => Ok(Value::Float(0.0)),
+            ("Str", None) => Ok(Value::str("")),
+            ("Bool", None) => Ok(Value::Bool(false)),
+            ("Array" | "List", None) => Ok(Value::array(vec![])),
+            ("Dict", None) => Ok(Value::Dict(Shared::new(vec![]))),
+            ("Set", None) => Ok(Value::Set(Shared::new(vec![]))),
+            // type constructors (e.g. `Array(Int, 3)`) are evaluated to the type itself
+            _ => Ok(Value::Type(Str::rc(name))),
+        }
+    }
+
+    fn index(&mut self, len: usize, index: &Value, loc: Location, kind: &str) -> EvalResult<usize> {
+        let i = self.expect_index(index, loc)?;
+        let idx = if i < 0 { i + len as i128 } else { i };
+        if (0..len as i128).contains(&idx) {
+            Ok(idx as usize)
+        } else {
+            self.raise("IndexError", format!("{kind} index out of range"), loc)
+        }
+    }
+
+    pub(super) fn call_builtin_method(
+        &mut self,
+        receiver: Value,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        match name {
+            "to_str" | "__str__" => return Ok(Value::str(self.stringify(&receiver, loc)?)),
+            "__repr__" => return Ok(Value::str(self.repr(&receiver, loc)?)),
+            "__eq__" => {
+                let other = self.take_arg(&mut args, 0, "other", name, loc)?;
+                return Ok(Value::Bool(receiver.structural_eq(&other)));
+            }
+            _ => {}
+        }
+        match receiver {
+            Value::Mut(inner) => self.call_mut_method(inner, name, args, loc),
+            Value::Bool(_) | Value::Int(_) | Value::Float(_) => {
+                self.call_num_method(receiver, name, args, loc)
+            }
+            Value::Str(s) => self.call_str_method(s, name, args, loc),
+            Value::Array(elems) => self.call_array_method(elems, name, args, loc),
+            Value::Tuple(elems) => match name {
+                "__getitem__" | "__Tuple_getitem__" => {
+                    let index = self.take_arg(&mut args, 0, "index", name, loc)?;
+                    let idx = self.index(elems.len(), &index, loc, "tuple")?;
+                    Ok(elems[idx].clone())
+                }
+                _ => self.call_seq_method(&elems, name, args, loc),
+            },
+            Value::Dict(kvs) => self.call_dict_method(kvs, name, args, loc),
+            Value::Set(elems) => self.call_set_method(elems, name, args, loc),
+            other => self.raise(
+                "AttributeError",
+                format!("'{}' object has no attribute '{name}'", other.class_name()),
+                loc,
+            ),
+        }
+    }
+
+    fn call_mut_method(
+        &mut self,
+        inner: Shared<Value>,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        let current = inner.borrow().clone();
+        let new = match name {
+            "update!" => {
+                let f = self.take_arg(&mut args, 0, "f", name, loc)?;
+                self.call_value(f, CallArgs::pos(vec![current]), loc)?
+            }
+            "inc!" | "dec!" => {
+                let by = args.take_nth_or_kw(0, "value").unwrap_or(Value::Int(1));
+                let kind = if name == "inc!" {
+                    TokenKind::Plus
+                } else {
+                    TokenKind::Minus
+                };
+                self.binop(kind, current, by, loc)?
+            }
+            _ => return self.call_builtin_method(current, name, args, loc),
+        };
+        *inner.borrow_mut() = new;
+        Ok(Value::None)
+    }
+
+    fn call_num_method(
+        &mut self,
+        receiver: Value,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        match (name, &receiver) {
+            ("abs", Value::Float(f)) => Ok(Value::Float(f.abs())),
+            ("abs", _) => {
+                let i = self.expect_index(&receiver, loc)?;
+                match i.checked_abs() {
+                    Some(i) => Ok(Value::Int(i)),
+                    None => self.raise("OverflowError", "integer overflow", loc),
+                }
+            }
+            ("times!", _) => {
+                let n = self.expect_index(&receiver, loc)?;
+                let block = self.take_arg(&mut args, 0, "p!", name, loc)?;
+                for _ in 0..n.max(0) {
+                    self.call_value(block.clone(), CallArgs::default(), loc)?;
+                }
+                Ok(Value::None)
+            }
+            ("is_integer", Value::Float(f)) => Ok(Value::Bool(f.fract() == 0.0)),
+            ("is_integer", _) => Ok(Value::Bool(true)),
+            ("real", _) => Ok(receiver),
+            ("imag", Value::Float(_)) => Ok(Value::Float(0.0)),
+            ("imag", _) => Ok(Value::Int(0)),
+            _ => self.raise(
+                "AttributeError",
+                format!(
+                    "'{}' object has no attribute '{name}'",
+                    receiver.class_name()
+                ),
+                loc,
+            ),
+        }
+    }
+
+    fn call_str_method(
+        &mut self,
+        s: Str,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        let str_arg =
+            |this: &mut Self, args: &mut CallArgs, nth: usize, key: &str| -> EvalResult<Str> {
+                let value = this.take_arg(args, nth, key, name, loc)?;
+                this.expect_str(&value, loc)
+            };
+        let res = match name {
+            "__getitem__" => {
+                let index = self.take_arg(&mut args, 0, "index", name, loc)?;
+                let chars = s.chars().collect::<Vec<_>>();
+                let idx = self.index(chars.len(), &index, loc, "string")?;
+                Value::str(chars[idx].to_string())
+            }
+            "upper" => Value::str(s.to_uppercase()),
+            "lower" => Value::str(s.to_lowercase()),
+            "capitalize" => {
+                let mut chars = s.chars();
+                let capitalized = match chars.next() {
+                    Some(c) => c
+                        .to_uppercase()
+                        .chain(chars.as_str().to_lowercase().chars())
+                        .collect(),
+                    None => String::new(),
+                };
+                Value::str(capitalized)
+            }
+            "strip" | "lstrip" | "rstrip" => {
+                let chars = match args.take_nth_or_kw(0, "chars") {
+                    Some(Value::Str(chars)) => Some(chars),
+                    _ => None,
+                };
+                let pat = |c: char| match &chars {
+                    Some(chars) => chars.contains(c),
+                    None => c.is_whitespace(),
+                };
+                Value::str(match name {
+                    "strip" => s.trim_matches(pat),
+                    "lstrip" => s.trim_start_matches(pat),
+                    _ => s.trim_end_matches(pat),
+                })
+            }
+            "replace" => {
+                let old = str_arg(self, &mut args, 0, "old")?;
+                let new = str_arg(self, &mut args, 1, "new")?;
+                Value::str(s.replace(&old[..], &new))
+            }
+            "split" => {
+                let parts: Vec<Value> = match args.take_nth_or_kw(0, "sep") {
+                    Some(Value::Str(sep)) if !sep.is_empty() => {
+                        s.split(&sep[..]).map(Value::str).collect()
+                    }
+                    Some(Value::Str(_)) => return self.raise("ValueError", "empty separator", loc),
+                    _ => s.split_whitespace().map(Value::str).collect(),
+                };
+                Value::array(parts)
+            }
+            "splitlines" => Value::array(s.lines().map(Value::str).collect()),
+            "join" => {
+                let iterable = self.take_arg(&mut args, 0, "iterable", name, loc)?;
+                let mut strs = vec![];
+                for value in self.iterate(&iterable, loc)? {
+                    strs.push(self.expect_str(&value, loc)?.to_string());
+                }
+                Value::str(strs.join(&s))
+            }
+            "startswith" => Value::Bool(s.starts_with(&str_arg(self, &mut args, 0, "prefix")?[..])),
+            "endswith" => Value::Bool(s.ends_with(&str_arg(self, &mut args, 0, "suffix")?[..])),
+            "find" => {
+                let sub = str_arg(self, &mut args, 0, "sub")?;
+                Value::Int(match s.find(&sub[..]) {
+                    Some(byte_idx) => s[..byte_idx].chars().count() as i128,
+                    None => -1,
+                })
+            }
+            "count" => {
+                let sub = str_arg(self, &mut args, 0, "sub")?;
+                Value::Int(if sub.is_empty() {
+                    s.chars().count() as i128 + 1
+                } else {
+                    s.matches(&sub[..]).count() as i128
+                })
+            }
+            "format" => {
+                let mut res = String::new();
+                let mut rest = &s[..];
+                let mut nth = 0;
+                while let Some(start) = rest.find("{}") {
+                    res.push_str(&rest[..start]);
+                    match args.pos.get(nth).cloned() {
+                        Some(value) => res.push_str(&self.stringify(&value, loc)?),
+                        None => {
+                            return self.raise("IndexError", "Replacement index out of range", loc)
+                        }
+                    }
+                    nth += 1;
+                    rest = &rest[start + 2..];
+                }
+                res.push_str(rest);
+                Value::str(res)
+            }
+            "isalnum" | "isalpha" | "isdecimal" | "isdigit" | "isnumeric" => {
+                let pred = match name {
+                    "isalnum" => char::is_alphanumeric,
+                    "isalpha" => char::is_alphabetic,
+                    "isnumeric" => char::is_numeric,
+                    _ => |c: char| c.is_ascii_digit(),
+                };
+                Value::Bool(!s.is_empty() && s.chars().all(pred))
+            }
+            "islower" => {
+                Value::Bool(s.chars().any(char::is_lowercase) && !s.chars().any(char::is_uppercase))
+            }
+            "isupper" => {
+                Value::Bool(s.chars().any(char::is_uppercase) && !s.chars().any(char::is_lowercase))
+            }
+            _ => {
+                return self.raise(
+                    "AttributeError",
+                    format!("'Str' object has no attribute '{name}'"),
+                    loc,
+                );
+            }
+        };
+        Ok(res)
+    }
+
+    /// The methods common to `Array` and `Tuple`
+    fn call_seq_method(
+        &mut self,
+        elems: &[Value],
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        match name {
+            "count" => {
+                let x = self.take_arg(&mut args, 0, "x", name, loc)?;
+                Ok(Value::Int(
+                    elems.iter().filter(|e| e.structural_eq(&x)).count() as i128,
+                ))
+            }
+            "index" => {
+                let x = self.take_arg(&mut args, 0, "x", name, loc)?;
+                match elems.iter().position(|e| e.structural_eq(&x)) {
+                    Some(idx) => Ok(Value::Int(idx as i128)),
+                    None => self.raise("ValueError", format!("{} is not in list", x.repr()), loc),
+                }
+            }
+            "sum" => self.call_builtin(
+                "sum",
+                CallArgs::pos(vec![Value::array(elems.to_vec())]),
+                loc,
+            ),
+            _ => self.raise(
+                "AttributeError",
+                format!("object has no attribute '{name}'"),
+                loc,
+            ),
+        }
+    }
+
+    fn call_array_method(
+        &mut self,
+        elems: Shared<Vec<Value>>,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        match name {
+            "__getitem__" => {
+                let index = self.take_arg(&mut args, 0, "index", name, loc)?;
+                let len = elems.borrow().len();
+                let idx = self.index(len, &index, loc, "list")?;
+                let elem = elems.borrow()[idx].clone();
+                Ok(elem)
+            }
+            "push!" => {
+                let elem = self.take_arg(&mut args, 0, "elem", name, loc)?;
+                elems.borrow_mut().push(elem);
+                Ok(Value::None)
+            }
+            "extend!" => {
+                let iterable = self.take_arg(&mut args, 0, "iterable", name, loc)?;
+                let values = self.iterate(&iterable, loc)?;
+                elems.borrow_mut().extend(values);
+                Ok(Value::None)
+            }
+            "insert!" => {
+                let index = self.take_arg(&mut args, 0, "index", name, loc)?;
+                let elem = self.take_arg(&mut args, 1, "elem", name, loc)?;
+                let len = elems.borrow().len() as i128;
+                let i = self.expect_index(&index, loc)?;
+                let idx = if i < 0 { (i + len).max(0) } else { i.min(len) };
+                elems.borrow_mut().insert(idx as usize, elem);
+                Ok(Value::None)
+            }
+            "remove!" => {
+                let x = self.take_arg(&mut args, 0, "x", name, loc)?;
+                let pos = elems.borrow().iter().position(|e| e.structural_eq(&x));
+                match pos {
+                    Some(idx) => {
+                        elems.borrow_mut().remove(idx);
+                        Ok(Value::None)
+                    }
+                    None => self.raise("ValueError", "list.remove(x): x not in list", loc),
+                }
+            }
+            "pop!" => {
+                let len = elems.borrow().len();
+                if len == 0 {
+                    return self.raise("IndexError", "pop from empty list", loc);
+                }
+                let idx = match args.take_nth_or_kw(0, "index") {
+                    Some(index) => self.index(len, &index, loc, "pop")?,
+                    None => len - 1,
+                };
+                let elem = elems.borrow_mut().remove(idx);
+                Ok(elem)
+            }
+            "clear!" => {
+                elems.borrow_mut().clear();
+                Ok(Value::None)
+            }
+            "sort!" => {
+                let key = args
+                    .take_kw("key")
+                    .filter(|key| !matches!(key, Value::None));
+                let mut values = elems.borrow().clone();
+                self.sort_values(&mut values, key, loc)?;
+                if args.take_kw("reverse").is_some_and(|rev| rev.is_truthy()) {
+                    values.reverse();
+                }
+                *elems.borrow_mut() = values;
+                Ok(Value::None)
+            }
+            "reverse!" => {
+                elems.borrow_mut().reverse();
+                Ok(Value::None)
+            }
+            "dedup!" => {
+                elems.borrow_mut().dedup_by(|a, b| a.structural_eq(b));
+                Ok(Value::None)
+            }
+            "update!" => {
+                let f = self.take_arg(&mut args, 0, "f", name, loc)?;
+                let current = Value::array(elems.borrow().clone());
+                let new = self.call_value(f, CallArgs::pos(vec![current]), loc)?;
+                let new = self.iterate(&new, loc)?;
+                *elems.borrow_mut() = new;
+                Ok(Value::None)
+            }
+            "copy" => Ok(Value::array(elems.borrow().clone())),
+            _ => {
+                let elems = elems.borrow().clone();
+                self.call_seq_method(&elems, name, args, loc)
+            }
+        }
+    }
+
+    fn call_dict_method(
+        &mut self,
+        kvs: Shared<Vec<(Value, Value)>>,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        let find = |kvs: &Shared<Vec<(Value, Value)>>, key: &Value| {
+            kvs.borrow().iter().position(|(k, _)| k.structural_eq(key))
+        };
+        match name {
+            "__getitem__" | "get" => {
+                let key = self.take_arg(&mut args, 0, "key", name, loc)?;
+                match find(&kvs, &key) {
+                    Some(idx) => Ok(kvs.borrow()[idx].1.clone()),
+                    None if name == "get" => {
+                        Ok(args.take_nth_or_kw(1, "default").unwrap_or_default())
+                    }
+                    None => self.raise("KeyError", key.repr(), loc),
+                }
+            }
+            "insert!" => {
+                let key = self.take_arg(&mut args, 0, "key", name, loc)?;
+                let value = self.take_arg(&mut args, 1, "value", name, loc)?;
+                match find(&kvs, &key) {
+                    Some(idx) => kvs.borrow_mut()[idx].1 = value,
+                    None => kvs.borrow_mut().push((key, value)),
+                }
+                Ok(Value::None)
+            }
+            "remove!" | "pop!" => {
+                let key = self.take_arg(&mut args, 0, "key", name, loc)?;
+                match find(&kvs, &key) {
+                    Some(idx) => Ok(kvs.borrow_mut().remove(idx).1),
+                    None if name == "remove!" => Ok(Value::None),
+                    None => match args.take_nth_or_kw(1, "default") {
+                        Some(default) => Ok(default),
+                        None => self.raise("KeyError", key.repr(), loc),
+                    },
+                }
+            }
+            "keys" => Ok(Value::array(
+                kvs.borrow().iter().map(|(k, _)| k.clone()).collect(),
+            )),
+            "values" => Ok(Value::array(
+                kvs.borrow().iter().map(|(_, v)| v.clone()).collect(),
+            )),
+            "items" => Ok(Value::array(
+                kvs.borrow()
+                    .iter()
+                    .map(|(k, v)| Value::tuple(vec![k.clone(), v.clone()]))
+                    .collect(),
+            )),
+            "copy" => Ok(Value::Dict(Shared::new(kvs.borrow().clone()))),
+            "concat" | "update!" => {
+                let other = self.take_arg(&mut args, 0, "other", name, loc)?;
+                let Value::Dict(other) = other.deref() else {
+                    return self.raise("TypeError", "expected Dict", loc);
+                };
+                let target = if name == "concat" {
+                    Shared::new(kvs.borrow().clone())
+                } else {
+                    kvs
+                };
+                for (k, v) in other.borrow().iter() {
+                    match find(&target, k) {
+                        Some(idx) => target.borrow_mut()[idx].1 = v.clone(),
+                        None => target.borrow_mut().push((k.clone(), v.clone())),
+                    }
+                }
+                Ok(if name == "concat" {
+                    Value::Dict(target)
+                } else {
+                    Value::None
+                })
+            }
+            _ => self.raise(
+                "AttributeError",
+                format!("'Dict' object has no attribute '{name}'"),
+                loc,
+            ),
+        }
+    }
+
+    fn call_set_method(
+        &mut self,
+        elems: Shared<Vec<Value>>,
+        name: &str,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        match name {
+            "add!" => {
+                let elem = self.take_arg(&mut args, 0, "elem", name, loc)?;
+                if !elems.borrow().iter().any(|e| e.structural_eq(&elem)) {
+                    elems.borrow_mut().push(elem);
+                }
+                Ok(Value::None)
+            }
+            "remove!" | "discard!" => {
+                let elem = self.take_arg(&mut args, 0, "elem", name, loc)?;
+                let pos = elems.borrow().iter().position(|e| e.structural_eq(&elem));
+                match pos {
+                    Some(idx) => {
+                        elems.borrow_mut().remove(idx);
+                        Ok(Value::None)
+                    }
+                    None if name == "discard!" => Ok(Value::None),
+                    None => self.raise("KeyError", elem.repr(), loc),
+                }
+            }
+            "copy" => Ok(Value::Set(Shared::new(elems.borrow().clone()))),
+            "union" | "intersection" | "difference" | "update!" => {
+                let other = self.take_arg(&mut args, 0, "other", name, loc)?;
+                let other = self.call_type("Set", CallArgs::pos(vec![other]), loc)?;
+                let kind = match name {
+                    "intersection" => TokenKind::BitAnd,
+                    "difference" => TokenKind::Minus,
+                    _ => TokenKind::BitOr,
+                };
+                let res = self.binop(kind, Value::Set(elems.clone()), other, loc)?;
+                if name == "update!" {
+                    if let Value::Set(res) = res {
+                        *elems.borrow_mut() = res.borrow().clone();
+                    }
+                    Ok(Value::None)
+                } else {
+                    Ok(res)
+                }
+            }
+            _ => self.raise(
+                "AttributeError",
+                format!("'Set' object has no attribute '{name}'"),
+                loc,
+            ),
+        }
+    }
+}
+
+/// Rounds half to even, as in Python's `round`.
+fn round_half_even(f: f64) -> f64 {
+    let rounded = f.round();
+    if (f - f.trunc()).abs() == 0.5 {
+        2.0 * (f / 2.0).round()
+    } else {
+        rounded
+    }
+}
@@ -0,0 +1,1243 @@
+//! defines `Interpreter`, a tree-walking interpreter that evaluates HIR directly.
+//!
+//! Unlike `DummyVM`, the interpreter does not need a Python interpreter.
+//! It only supports pure-Erg programs; Python modules (`pyimport`) and code-object-level features
+//! (generators, async procedures, `with!`) are reported as `FeatureError`s.
+mod builtins;
+pub mod value;
+
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::thread;
+
+use erg_common::config::{ErgConfig, Input};
+use erg_common::dict::Dict;
+use erg_common::error::{ErrorCore, ErrorKind, Location, MultiErrorDisplay, SubMessage};
+use erg_common::shared::Shared;
+use erg_common::traits::{ExitStatus, Locational, Runnable, Stream};
+use erg_common::{log, normalize_path, Str};
+
+use erg_parser::ast::{OperationKind, VarName};
+
+use crate::artifact::{CompleteArtifact, ErrorArtifact};
+use crate::build_hir::HIRBuilder;
+use crate::context::{Context, ContextProvider};
+use crate::desugar_hir::HIRDesugarer;
+use crate::error::{CompileError, CompileErrors};
+use crate::hir::{
+    Accessor, Args, Array, Block, Call, ClassDef, Def, Dict as HirDict, Expr, Identifier, Lambda,
+    Params, PatchDef, ReDef, Record, Set as HirSet, Signature, Tuple, HIR,
+};
+use crate::module::SharedCompilerResource;
+use crate::ty::typaram::TyParam;
+use crate::ty::value::{GenTypeObj, ValueObj};
+use crate::ty::{HasType, Type};
+use crate::varinfo::{VarInfo, VarKind};
+
+use value::{ClassKind, ClassObj, InstanceObj, ModuleObj, SubrObj, Value};
+
+/// The maximum depth of subroutine calls (the same as Python's default recursion limit)
+const MAX_RECURSION_DEPTH: usize = 1000;
+/// The stack size of the interpreter thread.
+/// Each Erg call consumes several Rust frames, so the default main thread stack is not enough for `MAX_RECURSION_DEPTH`.
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// A variable namespace. Subroutines hold the scope in which they are defined.
+#[derive(Debug, Default)]
+pub struct Scope {
+    vars: Shared<Dict<Str, Value>>,
+    parent: Option<Rc<Scope>>,
+}
+
+impl Scope {
+    pub fn new(parent: Option<Rc<Scope>>) -> Rc<Self> {
+        Rc::new(Self {
+            vars: Shared::new(Dict::new()),
+            parent,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.get_local(name)
+            .or_else(|| self.parent.as_ref().and_then(|parent| parent.get(name)))
+    }
+
+    pub fn get_local(&self, name: &str) -> Option<Value> {
+        self.vars.borrow().get(name).cloned()
+    }
+
+    pub fn insert(&self, name: Str, value: Value) {
+        self.vars.borrow_mut().insert(name, value);
+    }
+
+    /// Reassigns the variable in the nearest scope that has it.
+    pub fn assign(&self, name: Str, value: Value) {
+        if self.vars.borrow().contains_key(&name) || self.parent.is_none() {
+            self.insert(name, value);
+        } else if let Some(parent) = self.parent.as_ref().filter(|p| p.get(&name).is_some()) {
+            parent.assign(name, value);
+        } else {
+            self.insert(name, value);
+        }
+    }
+
+    pub fn remove(&self, name: &str) -> Option<Value> {
+        self.vars.borrow_mut().remove(name)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Frame {
+    name: Str,
+    input: Input,
+    /// the location currently being evaluated
+    loc: Location,
+}
+
+/// An exception that is propagating up the call stack.
+#[derive(Debug)]
+pub struct RuntimeException {
+    pub value: Value,
+    traceback: Vec<Frame>,
+}
+
+impl RuntimeException {
+    fn class_name(&self) -> Str {
+        self.value.class_name()
+    }
+
+    pub fn is_system_exit(&self) -> bool {
+        &self.class_name()[..] == "SystemExit"
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match &self.value {
+            Value::Exception(exc) => exc.code,
+            _ => 1,
+        }
+    }
+
+    /// Converts the exception to an error in the same format as the errors reported by `DummyVM`.
+    /// Frames of other modules than the one where the exception occurred are shown in the hint.
+    pub fn into_error(self) -> CompileError {
+        let input = self
+            .traceback
+            .last()
+            .map(|frame| frame.input.clone())
+            .unwrap_or(Input::Dummy);
+        let mut loc = Location::Unknown;
+        let mut sub_msgs = vec![];
+        let mut extern_frames = vec![];
+        for frame in self.traceback.iter() {
+            if frame.input == input {
+                loc = frame.loc;
+                sub_msgs.push(SubMessage::ambiguous_new(
+                    loc,
+                    vec![format!("in {}", frame.name)],
+                    None,
+                ));
+            } else {
+                extern_frames.push(format!(
+                    "File \"{}\", line {}, in {}",
+                    frame.input.enclosed_name(),
+                    frame.loc.ln_begin().unwrap_or(0),
+                    frame.name
+                ));
+            }
+        }
+        if let Some(first) = sub_msgs.first_mut() {
+            if !extern_frames.is_empty() {
+                first.set_hint(extern_frames.join("\n"));
+            }
+        } else {
+            sub_msgs.push(SubMessage::only_loc(loc));
+        }
+        let class = self.class_name();
+        let msg = match &self.value {
+            Value::Exception(exc) if !exc.msg.is_empty() => exc.msg.to_string(),
+            Value::Exception(_) => class.to_string(),
+            other => other.to_string(),
+        };
+        CompileError::new(
+            ErrorCore::new(sub_msgs, msg, 0, ErrorKind::from(&class[..]), loc),
+            input,
+            "<module>".to_owned(),
+        )
+    }
+}
+
+/// Non-local exits
+#[derive(Debug)]
+pub enum Signal {
+    /// `f::return x`
+    Return(Value),
+    Raise(Box<RuntimeException>),
+    /// The program uses a feature that the interpreter does not support. This cannot be caught.
+    Abort(Box<CompileError>),
+}
+
+pub type EvalResult<T> = Result<T, Signal>;
+
+/// Evaluated arguments
+#[derive(Debug, Default)]
+pub struct CallArgs {
+    pub pos: Vec<Value>,
+    pub kw: Vec<(Str, Value)>,
+}
+
+impl CallArgs {
+    pub fn pos(pos: Vec<Value>) -> Self {
+        Self { pos, kw: vec![] }
+    }
+
+    pub fn take_kw(&mut self, name: &str) -> Option<Value> {
+        let idx = self.kw.iter().position(|(k, _)| &k[..] == name)?;
+        Some(self.kw.remove(idx).1)
+    }
+
+    /// Takes the `nth` positional argument or the keyword argument named `name`.
+    pub fn take_nth_or_kw(&mut self, nth: usize, name: &str) -> Option<Value> {
+        if nth < self.pos.len() {
+            Some(mem::take(&mut self.pos[nth]))
+        } else {
+            self.take_kw(name)
+        }
+    }
+}
+
+/// Returns the `nth` positional argument or the keyword argument named `name`.
+fn nth_or_key<'a>(args: &'a Args, nth: usize, name: &str) -> Option<&'a Expr> {
+    args.pos_args.get(nth).map(|arg| &arg.expr).or_else(|| {
+        args.kw_args
+            .iter()
+            .find(|arg| &arg.keyword.inspect()[..] == name)
+            .map(|arg| &arg.expr)
+    })
+}
+
+/// Evaluates HIR without compiling it to Python bytecode.
+#[derive(Debug)]
+pub struct Interpreter {
+    cfg: ErgConfig,
+    builder: HIRBuilder,
+    shared: SharedCompilerResource,
+    /// the config of the module being evaluated (imports are resolved relative to it)
+    mod_cfg: ErgConfig,
+    globals: Rc<Scope>,
+    patches: Vec<Rc<ClassObj>>,
+    /// evaluated Erg modules; a module is registered before it is evaluated (for cyclic imports)
+    modules: Dict<PathBuf, Value>,
+    frames: Vec<Frame>,
+}
+
+/// Executes `run` in a new thread whose stack is large enough for `MAX_RECURSION_DEPTH`.
+pub fn exec_new_interpreter_thread<F, T>(run: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    thread::Builder::new()
+        .name("erg-interpreter".into())
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for Interpreter {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg interpreter";
+
+    fn new(cfg: ErgConfig) -> Self {
+        let shared = SharedCompilerResource::new(cfg.copy());
+        Self {
+            builder: HIRBuilder::new_with_cache(cfg.copy(), "<module>", shared.clone()),
+            shared,
+            mod_cfg: cfg.copy(),
+            globals: Scope::new(None),
+            patches: vec![],
+            modules: Dict::new(),
+            frames: vec![],
+            cfg,
+        }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {
+        self.builder.initialize();
+        self.globals = Scope::new(None);
+        self.patches.clear();
+        self.modules.clear();
+    }
+
+    fn clear(&mut self) {
+        self.builder.clear();
+    }
+
+    fn exec(&mut self) -> Result<i32, Self::Errs> {
+        let src = self.cfg.input.read();
+        let hir = self.build(src, "exec").map_err(|eart| {
+            eart.warns.fmt_all_stderr();
+            eart.errors
+        })?;
+        hir.warns.fmt_all_stderr();
+        match self.eval_main(&hir.object) {
+            Ok(_) => Ok(0),
+            Err(Signal::Raise(exc)) if exc.is_system_exit() => Ok(exc.exit_code()),
+            Err(Signal::Raise(exc)) => Err(CompileErrors::from(exc.into_error())),
+            Err(Signal::Abort(err)) => Err(CompileErrors::from(*err)),
+            Err(Signal::Return(_)) => Ok(0),
+        }
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        let hir = self.build(src, "eval").map_err(|eart| {
+            eart.warns.fmt_all_stderr();
+            eart.errors
+        })?;
+        let mut res = hir.warns.to_string();
+        match self.eval_main(&hir.object) {
+            Ok(Value::None) => {}
+            Ok(value) => {
+                let repr = self
+                    .repr(&value, Location::Unknown)
+                    .map_err(|sig| CompileErrors::from(self.signal_to_error(sig)))?;
+                res.push_str(&repr);
+            }
+            Err(Signal::Raise(exc)) if exc.is_system_exit() => {
+                return Err(CompileErrors::from(CompileError::system_exit()));
+            }
+            Err(sig) => {
+                print!("{res}");
+                return Err(CompileErrors::from(self.signal_to_error(sig)));
+            }
+        }
+        if self.cfg.show_type {
+            res.push_str(": ");
+            res.push_str(
+                &hir.object
+                    .module
+                    .last()
+                    .map(|last| last.t())
+                    .unwrap_or_default()
+                    .to_string(),
+            );
+        }
+        Ok(res)
+    }
+}
+
+impl ContextProvider for Interpreter {
+    fn dir(&self) -> Vec<(&VarName, &VarInfo)> {
+        self.builder.dir()
+    }
+
+    fn get_receiver_ctx(&self, receiver_name: &str) -> Option<&Context> {
+        self.builder.get_receiver_ctx(receiver_name)
+    }
+
+    fn get_var_info(&self, name: &str) -> Option<(&VarName, &VarInfo)> {
+        self.builder.get_var_info(name)
+    }
+}
+
+impl Interpreter {
+    /// Runs the interpreter in a new thread with a large stack.
+    pub fn run_in_new_thread(cfg: ErgConfig) -> ExitStatus {
+        exec_new_interpreter_thread(move || Self::run(cfg))
+    }
+
+    fn build(&mut self, src: String, mode: &str) -> Result<CompleteArtifact, ErrorArtifact> {
+        log!(info "the interpreting process has started.");
+        let artifact = self.builder.build(src, mode)?;
+        let desugared = HIRDesugarer::desugar(artifact.object);
+        Ok(CompleteArtifact::new(desugared, artifact.warns))
+    }
+
+    fn eval_main(&mut self, hir: &HIR) -> EvalResult<Value> {
+        self.mod_cfg = self.cfg.copy();
+        self.frames = vec![Frame {
+            name: Str::ever("<module>"),
+            input: self.cfg.input.clone(),
+            loc: Location::Unknown,
+        }];
+        let globals = self.globals.clone();
+        let res = self.eval_chunks(hir.module.iter(), &globals);
+        log!(info "the interpreting process has completed.");
+        res
+    }
+
+    fn signal_to_error(&self, sig: Signal) -> CompileError {
+        match sig {
+            Signal::Raise(exc) => exc.into_error(),
+            Signal::Abort(err) => *err,
+            // `return` outside of a subroutine is rejected by the compiler
+            Signal::Return(_) => CompileError::system_exit(),
+        }
+    }
+
+    fn cur_frame_name(&self) -> String {
+        self.frames
+            .last()
+            .map(|frame| frame.name.to_string())
+            .unwrap_or_else(|| "<module>".into())
+    }
+
+    /// Raises `class(msg)` at `loc`.
+    pub(crate) fn raise<T>(
+        &mut self,
+        class: &'static str,
+        msg: impl Into<Str>,
+        loc: Location,
+    ) -> EvalResult<T> {
+        self.raise_value(Value::exception(class, msg), loc)
+    }
+
+    pub(crate) fn raise_value<T>(&mut self, value: Value, loc: Location) -> EvalResult<T> {
+        let mut traceback = self.frames.clone();
+        if let Some(last) = traceback.last_mut() {
+            if loc != Location::Unknown {
+                last.loc = loc;
+            }
+        }
+        Err(Signal::Raise(Box::new(RuntimeException {
+            value,
+            traceback,
+        })))
+    }
+
+    pub(crate) fn unsupported<T>(&self, name: &str, loc: Location) -> EvalResult<T> {
+        let input = self
+            .frames
+            .last()
+            .map(|frame| frame.input.clone())
+            .unwrap_or_else(|| self.cfg.input.clone());
+        Err(Signal::Abort(Box::new(CompileError::feature_error(
+            input,
+            loc,
+            name,
+            self.cur_frame_name(),
+        ))))
+    }
+
+    fn eval_chunks<'e>(
+        &mut self,
+        chunks: impl Iterator<Item = &'e Expr>,
+        scope: &Rc<Scope>,
+    ) -> EvalResult<Value> {
+        let mut last = Value::None;
+        for chunk in chunks {
+            last = self.eval_expr(chunk, scope)?;
+        }
+        Ok(last)
+    }
+
+    fn eval_block(&mut self, block: &Block, scope: &Rc<Scope>) -> EvalResult<Value> {
+        self.eval_chunks(block.iter(), scope)
+    }
+
+    pub(crate) fn eval_expr(&mut self, expr: &Expr, scope: &Rc<Scope>) -> EvalResult<Value> {
+        match expr {
+            Expr::Lit(lit) => match Value::from_value_obj(&lit.value) {
+                Some(value) => Ok(value),
+                None => self.unsupported(&format!("literal `{}`", lit.value), lit.loc()),
+            },
+            Expr::Accessor(Accessor::Ident(ident)) => self.eval_ident(ident, scope),
+            Expr::Accessor(Accessor::Attr(attr)) => {
+                let obj = self.eval_expr(&attr.obj, scope)?;
+                self.get_attr(obj, attr.ident.inspect(), attr.loc())
+            }
+            Expr::Array(array) => self.eval_array(array, scope),
+            Expr::Tuple(Tuple::Normal(tuple)) => {
+                let elems = self.eval_args(&tuple.elems, scope)?;
+                Ok(Value::tuple(elems.pos))
+            }
+            Expr::Set(set) => self.eval_set(set, scope),
+            Expr::Dict(dict) => self.eval_dict(dict, scope),
+            Expr::Record(record) => self.eval_record(record, scope),
+            Expr::BinOp(bin) => {
+                let lhs = self.eval_expr(&bin.lhs, scope)?;
+                self.eval_binop(&bin.op, lhs, &bin.rhs, scope)
+            }
+            Expr::UnaryOp(unary) => {
+                let operand = self.eval_expr(&unary.expr, scope)?;
+                self.eval_unaryop(&unary.op, operand)
+            }
+            Expr::Call(call) => self.eval_call(call, scope),
+            Expr::Lambda(lambda) => Ok(self.eval_lambda(lambda, scope)),
+            Expr::Def(def) => self.eval_def(def, scope),
+            Expr::ClassDef(class_def) => self.eval_class_def(class_def, scope),
+            Expr::PatchDef(patch_def) => self.eval_patch_def(patch_def, scope),
+            Expr::ReDef(redef) => self.eval_redef(redef, scope),
+            Expr::TypeAsc(tasc) => self.eval_expr(&tasc.expr, scope),
+            Expr::Compound(block) => self.eval_block(block, scope),
+            Expr::Dummy(dummy) => self.eval_chunks(dummy.iter(), scope),
+            Expr::Code(block) => self.unsupported("code objects", block.loc()),
+            Expr::Import(acc) => self.unsupported("declaration files", acc.loc()),
+        }
+    }
+
+    fn eval_ident(&mut self, ident: &Identifier, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let name = ident.inspect();
+        if let Some(value) = scope.get(name) {
+            return Ok(value);
+        }
+        if let Some(value) = Self::builtin_value(name) {
+            return Ok(value);
+        }
+        if ident.vi.kind == VarKind::Builtin {
+            self.unsupported(&format!("built-in `{name}`"), ident.loc())
+        } else {
+            self.raise(
+                "NameError",
+                format!("name '{name}' is not defined"),
+                ident.loc(),
+            )
+        }
+    }
+
+    fn eval_array(&mut self, array: &Array, scope: &Rc<Scope>) -> EvalResult<Value> {
+        match array {
+            Array::Normal(arr) => {
+                let elems = self.eval_args(&arr.elems, scope)?;
+                Ok(Value::array(elems.pos))
+            }
+            Array::WithLength(arr) => {
+                let elem = self.eval_expr(&arr.elem, scope)?;
+                let len = self.eval_expr(&arr.len, scope)?;
+                let len = self.expect_index(&len, arr.len.loc())?;
+                Ok(Value::array(vec![elem; len.max(0) as usize]))
+            }
+            Array::Comprehension(arr) => self.unsupported("array comprehensions", arr.loc()),
+        }
+    }
+
+    fn eval_set(&mut self, set: &HirSet, scope: &Rc<Scope>) -> EvalResult<Value> {
+        match set {
+            HirSet::Normal(set) => {
+                let elems = self.eval_args(&set.elems, scope)?;
+                let mut uniq: Vec<Value> = vec![];
+                for elem in elems.pos {
+                    if !uniq.iter().any(|e| e.structural_eq(&elem)) {
+                        uniq.push(elem);
+                    }
+                }
+                Ok(Value::Set(Shared::new(uniq)))
+            }
+            HirSet::WithLength(set) => {
+                let elem = self.eval_expr(&set.elem, scope)?;
+                Ok(Value::Set(Shared::new(vec![elem])))
+            }
+        }
+    }
+
+    fn eval_dict(&mut self, dict: &HirDict, scope: &Rc<Scope>) -> EvalResult<Value> {
+        match dict {
+            HirDict::Normal(dict) => {
+                let mut kvs: Vec<(Value, Value)> = vec![];
+                for kv in dict.kvs.iter() {
+                    let key = self.eval_expr(&kv.key, scope)?;
+                    let value = self.eval_expr(&kv.value, scope)?;
+                    if let Some(entry) = kvs.iter_mut().find(|(k, _)| k.structural_eq(&key)) {
+                        entry.1 = value;
+                    } else {
+                        kvs.push((key, value));
+                    }
+                }
+                Ok(Value::Dict(Shared::new(kvs)))
+            }
+            HirDict::Comprehension(dict) => self.unsupported("dict comprehensions", dict.loc()),
+        }
+    }
+
+    /// The fields can refer to the preceding fields.
+    fn eval_record(&mut self, record: &Record, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let rec_scope = Scope::new(Some(scope.clone()));
+        let mut fields = vec![];
+        for def in record.attrs.iter() {
+            let value = self.eval_def_body(def, &rec_scope)?;
+            let name = def.sig.ident().inspect().clone();
+            rec_scope.insert(name.clone(), value.clone());
+            fields.push((name, value));
+        }
+        Ok(Value::Record(Rc::from(fields)))
+    }
+
+    fn eval_lambda(&mut self, lambda: &Lambda, scope: &Rc<Scope>) -> Value {
+        Value::Subr(Rc::new(SubrObj {
+            name: Str::ever("<lambda>"),
+            params: lambda.params.clone(),
+            body: lambda.body.clone(),
+            env: scope.clone(),
+            input: self.mod_cfg.input.clone(),
+        }))
+    }
+
+    fn eval_def_body(&mut self, def: &Def, scope: &Rc<Scope>) -> EvalResult<Value> {
+        match &def.sig {
+            Signature::Var(_) => self.eval_block(&def.body.block, scope),
+            Signature::Subr(sig) => {
+                if sig.is_generator {
+                    return self.unsupported("generators", sig.loc());
+                } else if sig.is_async() {
+                    return self.unsupported("async procedures", sig.loc());
+                }
+                Ok(Value::Subr(Rc::new(SubrObj {
+                    name: sig.ident.inspect().clone(),
+                    params: sig.params.clone(),
+                    body: def.body.block.clone(),
+                    env: scope.clone(),
+                    input: self.mod_cfg.input.clone(),
+                })))
+            }
+        }
+    }
+
+    fn eval_def(&mut self, def: &Def, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let value = self.eval_def_body(def, scope)?;
+        let name = def.sig.ident().inspect();
+        if &name[..] != "_" {
+            scope.insert(name.clone(), value);
+        }
+        Ok(Value::None)
+    }
+
+    /// Evaluates the definitions in a class or patch body.
+    fn eval_methods(
+        &mut self,
+        methods: &Block,
+        class: &Rc<ClassObj>,
+        scope: &Rc<Scope>,
+    ) -> EvalResult<()> {
+        for chunk in methods.iter() {
+            match chunk {
+                Expr::Def(def) => {
+                    let value = self.eval_def_body(def, scope)?;
+                    let name = def.sig.ident().inspect().clone();
+                    class.attrs.borrow_mut().insert(name, value);
+                }
+                other => {
+                    self.eval_expr(other, scope)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn eval_class_def(&mut self, class_def: &ClassDef, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let base = match (&class_def.obj, &class_def.require_or_sup) {
+            (GenTypeObj::Subclass(_), Some(sup)) => Some(self.eval_expr(sup, scope)?),
+            _ => None,
+        };
+        let name = class_def.sig.ident().inspect().clone();
+        let class = Rc::new(ClassObj {
+            name: name.clone(),
+            kind: ClassKind::Class,
+            base,
+            new_t: Some(class_def.__new__.clone()),
+            attrs: Shared::new(Dict::new()),
+        });
+        if class_def.need_to_gen_new {
+            class
+                .attrs
+                .borrow_mut()
+                .insert(Str::ever("new"), Value::Constructor(class.clone()));
+        }
+        self.eval_methods(&class_def.methods, &class, scope)?;
+        scope.insert(name, Value::Class(class));
+        Ok(Value::None)
+    }
+
+    fn eval_patch_def(&mut self, patch_def: &PatchDef, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let base = self.eval_expr(&patch_def.base, scope)?;
+        let name = patch_def.sig.ident().inspect().clone();
+        let patch = Rc::new(ClassObj {
+            name: name.clone(),
+            kind: ClassKind::Patch,
+            base: Some(base),
+            new_t: None,
+            attrs: Shared::new(Dict::new()),
+        });
+        // the methods may refer to the patch itself
+        scope.insert(name, Value::Class(patch.clone()));
+        self.eval_methods(&patch_def.methods, &patch, scope)?;
+        self.patches.push(patch);
+        Ok(Value::None)
+    }
+
+    fn eval_redef(&mut self, redef: &ReDef, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let value = self.eval_block(&redef.block, scope)?;
+        match &redef.attr {
+            Accessor::Ident(ident) => {
+                scope.assign(ident.inspect().clone(), value);
+            }
+            Accessor::Attr(attr) => {
+                let obj = self.eval_expr(&attr.obj, scope)?;
+                self.set_attr(obj, attr.ident.inspect().clone(), value, attr.loc())?;
+            }
+        }
+        Ok(Value::None)
+    }
+
+    fn set_attr(&mut self, obj: Value, name: Str, value: Value, loc: Location) -> EvalResult<()> {
+        match obj {
+            Value::Instance(inst) => {
+                inst.attrs.borrow_mut().insert(name, value);
+            }
+            Value::Class(class) => {
+                class.attrs.borrow_mut().insert(name, value);
+            }
+            Value::Module(module) => module.scope.insert(name, value),
+            other => {
+                return self.raise(
+                    "AttributeError",
+                    format!("'{}' object has no attribute '{name}'", other.class_name()),
+                    loc,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_attr(&mut self, obj: Value, name: &str, loc: Location) -> EvalResult<Value> {
+        match &obj {
+            Value::Instance(inst) => {
+                if let Some(value) = inst.attrs.borrow().get(name) {
+                    return Ok(value.clone());
+                }
+                if let Some(value) = inst.class.get_attr(name) {
+                    return Ok(Self::bind(obj.clone(), value));
+                }
+            }
+            Value::Class(class) => {
+                if name == "__new__" && class.kind == ClassKind::Class {
+                    return Ok(Value::Constructor(class.clone()));
+                }
+                if let Some(value) = class.get_attr(name) {
+                    return Ok(value);
+                }
+            }
+            Value::Record(fields) => {
+                if let Some((_, value)) = fields.iter().find(|(k, _)| &k[..] == name) {
+                    return Ok(value.clone());
+                }
+            }
+            Value::Module(module) => {
+                if let Some(value) = module.scope.get_local(name) {
+                    return Ok(value);
+                }
+                return self.raise(
+                    "AttributeError",
+                    format!("module '{}' has no attribute '{name}'", module.name),
+                    loc,
+                );
+            }
+            // `update!`, etc. mutate the box itself
+            Value::Mut(_) if !Self::is_mut_method(name) => {
+                return self.get_attr(obj.deref(), name, loc);
+            }
+            _ => {}
+        }
+        for patch in self.patches.clone() {
+            let Some(value) = patch.attrs.borrow().get(name).cloned() else {
+                continue;
+            };
+            let base = patch.base.clone().unwrap_or_default();
+            if self.contains(&base, &obj, loc)? {
+                return Ok(Self::bind(obj, value));
+            }
+        }
+        if Self::has_builtin_method(&obj, name) {
+            return Ok(Value::BuiltinMethod(Rc::new((obj, Str::rc(name)))));
+        }
+        self.raise(
+            "AttributeError",
+            format!("'{}' object has no attribute '{name}'", obj.class_name()),
+            loc,
+        )
+    }
+
+    /// Binds `value` to `receiver` if `value` is a method.
+    fn bind(receiver: Value, value: Value) -> Value {
+        match &value {
+            Value::Subr(subr) if subr.is_method() => Value::BoundMethod(Rc::new((receiver, value))),
+            _ => value,
+        }
+    }
+
+    fn eval_args(&mut self, args: &Args, scope: &Rc<Scope>) -> EvalResult<CallArgs> {
+        let mut pos = vec![];
+        for arg in args.pos_args.iter() {
+            pos.push(self.eval_expr(&arg.expr, scope)?);
+        }
+        if let Some(var_args) = &args.var_args {
+            let values = self.eval_expr(&var_args.expr, scope)?;
+            pos.extend(self.iterate(&values, var_args.expr.loc())?);
+        }
+        let mut kw = vec![];
+        for arg in args.kw_args.iter() {
+            kw.push((
+                arg.keyword.inspect().clone(),
+                self.eval_expr(&arg.expr, scope)?,
+            ));
+        }
+        if let Some(kw_var_args) = &args.kw_var_args {
+            let loc = kw_var_args.expr.loc();
+            let Value::Dict(kvs) = self.eval_expr(&kw_var_args.expr, scope)?.deref() else {
+                return self.raise("TypeError", "argument after ** must be a mapping", loc);
+            };
+            for (key, value) in kvs.borrow().iter() {
+                let Value::Str(key) = key.deref() else {
+                    return self.raise("TypeError", "keywords must be strings", loc);
+                };
+                kw.push((key, value.clone()));
+            }
+        }
+        Ok(CallArgs { pos, kw })
+    }
+
+    fn eval_call(&mut self, call: &Call, scope: &Rc<Scope>) -> EvalResult<Value> {
+        match call.additional_operation() {
+            Some(OperationKind::Import) => return self.eval_import(call),
+            Some(OperationKind::PyImport) => {
+                return self.unsupported("importing Python modules", call.loc());
+            }
+            Some(OperationKind::Del) => {
+                for arg in call.args.pos_args.iter() {
+                    if let Expr::Accessor(Accessor::Ident(ident)) = &arg.expr {
+                        scope.remove(ident.inspect());
+                    }
+                }
+                return Ok(Value::None);
+            }
+            Some(OperationKind::Return) => {
+                let value = match call.args.pos_args.first() {
+                    Some(arg) => self.eval_expr(&arg.expr, scope)?,
+                    None => Value::None,
+                };
+                return Err(Signal::Return(value));
+            }
+            Some(OperationKind::Yield | OperationKind::YieldFrom) => {
+                return self.unsupported("generators", call.loc());
+            }
+            _ => {}
+        }
+        if call.attr_name.is_none() {
+            if let Expr::Accessor(Accessor::Ident(ident)) = call.obj.as_ref() {
+                if let Some(res) = self.eval_control(ident, &call.args, scope, call.loc()) {
+                    return res;
+                }
+            }
+        }
+        let callee = self.eval_expr(&call.obj, scope)?;
+        let callee = match &call.attr_name {
+            Some(attr) => self.get_attr(callee, attr.inspect(), call.loc())?,
+            None => callee,
+        };
+        let args = self.eval_args(&call.args, scope)?;
+        self.call_value(callee, args, call.loc())
+    }
+
+    /// Evaluates the control flow builtins (`if`, `for!`, `match`, etc.) in place.
+    /// The lambdas passed to them do not create frames, so `return` in them exits the enclosing subroutine.
+    fn eval_control(
+        &mut self,
+        ident: &Identifier,
+        args: &Args,
+        scope: &Rc<Scope>,
+        loc: Location,
+    ) -> Option<EvalResult<Value>> {
+        if ident.vi.kind != VarKind::Builtin
+            || scope.get(ident.inspect()).is_some()
+            || args.var_args.is_some()
+            || args.kw_var_args.is_some()
+        {
+            return None;
+        }
+        let res = match &ident.inspect()[..] {
+            "if" | "if!" => self.eval_if(args, scope),
+            "for" | "for!" => self.eval_for(args, scope, loc),
+            "while!" => self.eval_while(args, scope),
+            "match" | "match!" => self.eval_match(args, scope),
+            "try!" => self.eval_try(args, scope),
+            "with!" => self.unsupported("with!", loc),
+            "await!" => self.unsupported("async procedures", loc),
+            _ => return None,
+        };
+        Some(res)
+    }
+
+    /// Calls a block argument of a control flow builtin.
+    fn call_block(
+        &mut self,
+        block: &Expr,
+        args: Vec<Value>,
+        scope: &Rc<Scope>,
+    ) -> EvalResult<Value> {
+        match block {
+            Expr::Lambda(lambda) => {
+                let local = Scope::new(Some(scope.clone()));
+                self.bind_params(&lambda.params, &local, CallArgs::pos(args), lambda.loc())?;
+                self.eval_block(&lambda.body, &local)
+            }
+            other => {
+                let callee = self.eval_expr(other, scope)?;
+                self.call_value(callee, CallArgs::pos(args), other.loc())
+            }
+        }
+    }
+
+    fn eval_if(&mut self, args: &Args, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let Some(cond) = nth_or_key(args, 0, "cond") else {
+            return Ok(Value::None);
+        };
+        let cond = self.eval_expr(cond, scope)?;
+        let block = if cond.is_truthy() {
+            nth_or_key(args, 1, "then")
+        } else {
+            nth_or_key(args, 2, "else")
+        };
+        match block {
+            Some(block) => self.call_block(block, vec![], scope),
+            None => Ok(Value::None),
+        }
+    }
+
+    fn eval_for(&mut self, args: &Args, scope: &Rc<Scope>, loc: Location) -> EvalResult<Value> {
+        let (Some(iterable), Some(block)) = (
+            nth_or_key(args, 0, "iterable"),
+            nth_or_key(args, 1, "proc!"),
+        ) else {
+            return self.raise("TypeError", "for! takes 2 arguments", loc);
+        };
+        let iterable = self.eval_expr(iterable, scope)?;
+        for elem in self.iterate(&iterable, loc)? {
+            self.call_block(block, vec![elem], scope)?;
+        }
+        Ok(Value::None)
+    }
+
+    fn eval_while(&mut self, args: &Args, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let (Some(cond), Some(block)) =
+            (nth_or_key(args, 0, "cond!"), nth_or_key(args, 1, "proc!"))
+        else {
+            return Ok(Value::None);
+        };
+        while self.call_block(cond, vec![], scope)?.is_truthy() {
+            self.call_block(block, vec![], scope)?;
+        }
+        Ok(Value::None)
+    }
+
+    /// As with the code generator, the pattern of the last arm is not checked.
+    fn eval_match(&mut self, args: &Args, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let Some(target) = args.pos_args.first() else {
+            return Ok(Value::None);
+        };
+        let target = self.eval_expr(&target.expr, scope)?;
+        let arms = &args.pos_args[1..];
+        for (i, arm) in arms.iter().enumerate() {
+            let is_last_arm = i + 1 == arms.len();
+            if let Expr::Lambda(lambda) = &arm.expr {
+                let spec = lambda
+                    .params
+                    .non_defaults
+                    .first()
+                    .and_then(|param| param.t_spec_as_expr.as_ref());
+                if let Some(spec) = spec.filter(|_| !is_last_arm) {
+                    if !self.match_pattern(spec, &target, scope)? {
+                        continue;
+                    }
+                }
+            }
+            return self.call_block(&arm.expr, vec![target], scope);
+        }
+        Ok(Value::None)
+    }
+
+    fn match_pattern(
+        &mut self,
+        spec: &Expr,
+        target: &Value,
+        scope: &Rc<Scope>,
+    ) -> EvalResult<bool> {
+        use erg_parser::token::TokenKind::{AndOp, OrOp};
+        match spec {
+            Expr::BinOp(bin) if bin.op.kind == OrOp => Ok(self
+                .match_pattern(&bin.lhs, target, scope)?
+                || self.match_pattern(&bin.rhs, target, scope)?),
+            Expr::BinOp(bin) if bin.op.kind == AndOp => Ok(self
+                .match_pattern(&bin.lhs, target, scope)?
+                && self.match_pattern(&bin.rhs, target, scope)?),
+            _ => {
+                let ty = self.eval_expr(spec, scope)?;
+                self.contains(&ty, target, spec.loc())
+            }
+        }
+    }
+
+    fn eval_try(&mut self, args: &Args, scope: &Rc<Scope>) -> EvalResult<Value> {
+        let Some(body) = args.pos_args.first() else {
+            return Ok(Value::None);
+        };
+        let exc = match self.call_block(&body.expr, vec![], scope) {
+            Err(Signal::Raise(exc)) => exc,
+            other => return other,
+        };
+        for arm in args.pos_args[1..].iter() {
+            let spec = match &arm.expr {
+                Expr::Lambda(lambda) => lambda
+                    .params
+                    .non_defaults
+                    .first()
+                    .and_then(|param| param.t_spec_as_expr.as_ref()),
+                _ => None,
+            };
+            if let Some(spec) = spec {
+                if !self.match_pattern(spec, &exc.value, scope)? {
+                    continue;
+                }
+            }
+            return self.call_block(&arm.expr, vec![exc.value], scope);
+        }
+        Err(Signal::Raise(exc))
+    }
+
+    fn eval_import(&mut self, call: &Call) -> EvalResult<Value> {
+        let loc = call.loc();
+        let Some(TyParam::Value(ValueObj::Str(mod_name))) =
+            call.ref_t().typarams().first().cloned()
+        else {
+            return self.unsupported("importing modules dynamically", loc);
+        };
+        let Some(path) = Context::resolve_real_path(&self.mod_cfg, Path::new(&mod_name[..])) else {
+            return self.unsupported("importing Python modules", loc);
+        };
+        let path = normalize_path(path);
+        if let Some(module) = self.modules.get(&path) {
+            return Ok(module.clone());
+        }
+        let Some((hir, cfg)) = self
+            .shared
+            .mod_cache
+            .get(path.as_path())
+            .and_then(|entry| entry.hir.clone().map(|hir| (hir, entry.cfg().copy())))
+        else {
+            return self.raise(
+                "ImportError",
+                format!("cannot import module '{mod_name}'"),
+                loc,
+            );
+        };
+        let scope = Scope::new(None);
+        let module = Value::Module(Rc::new(ModuleObj {
+            name: mod_name,
+            scope: scope.clone(),
+        }));
+        self.modules.insert(path, module.clone());
+        let hir = HIRDesugarer::desugar(hir);
+        if let Some(caller) = self.frames.last_mut() {
+            caller.loc = loc;
+        }
+        self.frames.push(Frame {
+            name: Str::ever("<module>"),
+            input: cfg.input.clone(),
+            loc: Location::Unknown,
+        });
+        let prev_cfg = mem::replace(&mut self.mod_cfg, cfg);
+        let res = self.eval_chunks(hir.module.iter(), &scope);
+        self.mod_cfg = prev_cfg;
+        self.frames.pop();
+        res?;
+        Ok(module)
+    }
+
+    /// Binds the arguments to the parameters in `scope`.
+    /// Default values are evaluated in `scope`, so they can refer to the preceding parameters.
+    fn bind_params(
+        &mut self,
+        params: &Params,
+        scope: &Rc<Scope>,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<()> {
+        let mut pos = mem::take(&mut args.pos).into_iter();
+        for param in params.non_defaults.iter() {
+            let name = param.inspect();
+            let value = match pos.next() {
+                Some(value) => value,
+                None => match name.and_then(|name| args.take_kw(name)) {
+                    Some(value) => value,
+                    None => {
+                        return self.raise(
+                            "TypeError",
+                            format!(
+                                "missing a required argument: '{}'",
+                                name.map_or("_", |s| &s[..])
+                            ),
+                            loc,
+                        );
+                    }
+                },
+            };
+            if let Some(name) = name {
+                scope.insert(name.clone(), value);
+            }
+        }
+        for param in params.defaults.iter() {
+            let name = param.sig.inspect();
+            let value = match pos.next() {
+                Some(value) => value,
+                None => match name.and_then(|name| args.take_kw(name)) {
+                    Some(value) => value,
+                    None => self.eval_expr(&param.default_val, scope)?,
+                },
+            };
+            if let Some(name) = name {
+                scope.insert(name.clone(), value);
+            }
+        }
+        let rest = pos.collect::<Vec<_>>();
+        if let Some(var_params) = &params.var_params {
+            if let Some(name) = var_params.inspect() {
+                scope.insert(name.clone(), Value::array(rest));
+            }
+        } else if !rest.is_empty() {
+            return self.raise(
+                "TypeError",
+                format!("{} extra positional argument(s) were given", rest.len()),
+                loc,
+            );
+        }
+        if let Some(kw_var_params) = &params.kw_var_params {
+            if let Some(name) = kw_var_params.inspect() {
+                let kvs = mem::take(&mut args.kw)
+                    .into_iter()
+                    .map(|(k, v)| (Value::Str(k), v))
+                    .collect();
+                scope.insert(name.clone(), Value::Dict(Shared::new(kvs)));
+            }
+        } else if let Some((key, _)) = args.kw.first() {
+            return self.raise(
+                "TypeError",
+                format!("got an unexpected keyword argument '{key}'"),
+                loc,
+            );
+        }
+        Ok(())
+    }
+
+    fn call_subr(&mut self, subr: &SubrObj, args: CallArgs, loc: Location) -> EvalResult<Value> {
+        if self.frames.len() >= MAX_RECURSION_DEPTH {
+            return self.raise("RecursionError", "maximum recursion depth exceeded", loc);
+        }
+        if let Some(caller) = self.frames.last_mut() {
+            caller.loc = loc;
+        }
+        self.frames.push(Frame {
+            name: subr.name.clone(),
+            input: subr.input.clone(),
+            loc: subr.body.loc(),
+        });
+        let scope = Scope::new(Some(subr.env.clone()));
+        let res = self
+            .bind_params(&subr.params, &scope, args, loc)
+            .and_then(|_| self.eval_block(&subr.body, &scope));
+        self.frames.pop();
+        match res {
+            Err(Signal::Return(value)) => Ok(value),
+            other => other,
+        }
+    }
+
+    pub(crate) fn call_value(
+        &mut self,
+        callee: Value,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        match callee {
+            Value::Subr(subr) => self.call_subr(&subr, args, loc),
+            Value::BoundMethod(bound) => {
+                let (receiver, method) = bound.as_ref().clone();
+                args.pos.insert(0, receiver);
+                self.call_value(method, args, loc)
+            }
+            Value::Builtin(name) => self.call_builtin(&name, args, loc),
+            Value::BuiltinMethod(bound) => {
+                let (receiver, name) = bound.as_ref().clone();
+                self.call_builtin_method(receiver, &name, args, loc)
+            }
+            Value::Type(name) => self.call_type(&name, args, loc),
+            Value::Class(class) | Value::Constructor(class) if class.kind == ClassKind::Class => {
+                self.construct(class, args, loc)
+            }
+            Value::Mut(inner) => {
+                let inner = inner.borrow().clone();
+                self.call_value(inner, args, loc)
+            }
+            other => self.raise(
+                "TypeError",
+                format!("'{}' object is not callable", other.class_name()),
+                loc,
+            ),
+        }
+    }
+
+    /// Creates an instance in the same way as the `__init__` generated by the code generator.
+    fn construct(
+        &mut self,
+        class: Rc<ClassObj>,
+        mut args: CallArgs,
+        loc: Location,
+    ) -> EvalResult<Value> {
+        let attrs = Shared::new(Dict::new());
+        let first_param_t = class
+            .new_t
+            .as_ref()
+            .and_then(|t| t.non_default_params())
+            .and_then(|params| params.first())
+            .map(|param| param.typ().clone());
+        if let Some(param_t) = first_param_t {
+            let Some(arg) = args.take_nth_or_kw(0, "base") else {
+                return self.raise(
+                    "TypeError",
+                    format!("{}.new takes 1 argument", class.name),
+                    loc,
+                );
+            };
+            match param_t {
+                Type::Record(rec) => {
+                    for field in rec.keys() {
+                        let value = self.get_attr(arg.clone(), &field.symbol, loc)?;
+                        attrs.borrow_mut().insert(field.symbol.clone(), value);
+                    }
+                }
+                _ => {
+                    attrs.borrow_mut().insert(Str::ever("base"), arg);
+                }
+            }
+        }
+        Ok(Value::Instance(Rc::new(InstanceObj { class, attrs })))
+    }
+}
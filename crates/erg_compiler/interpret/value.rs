@@ -0,0 +1,520 @@
+//! defines `Value`, the runtime representation of objects in the interpreter.
+use std::fmt;
+use std::rc::Rc;
+
+use erg_common::config::Input;
+use erg_common::dict::Dict;
+use erg_common::shared::Shared;
+use erg_common::Str;
+
+use crate::hir::{Block, Params};
+use crate::ty::value::ValueObj;
+use crate::ty::Type;
+
+use super::Scope;
+
+/// A user-defined subroutine (function, procedure or method) closed over its defining scope.
+#[derive(Debug)]
+pub struct SubrObj {
+    pub name: Str,
+    pub params: Params,
+    pub body: Block,
+    pub env: Rc<Scope>,
+    /// the module where the subroutine is defined (for tracebacks)
+    pub input: Input,
+}
+
+impl SubrObj {
+    /// Methods are subroutines whose first parameter is `self`.
+    pub fn is_method(&self) -> bool {
+        self.params
+            .non_defaults
+            .first()
+            .and_then(|param| param.inspect())
+            .is_some_and(|name| &name[..] == "self")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassKind {
+    Class,
+    /// `Patch(Base)`, the methods are looked up from the instances of `Base`
+    Patch,
+}
+
+/// A user-defined class or patch
+#[derive(Debug)]
+pub struct ClassObj {
+    pub name: Str,
+    pub kind: ClassKind,
+    /// the superclass of a class, or the base type of a patch
+    pub base: Option<Value>,
+    /// the type of `__new__` (e.g. `{x = Int} -> C`)
+    pub new_t: Option<Type>,
+    pub attrs: Shared<Dict<Str, Value>>,
+}
+
+impl ClassObj {
+    pub fn get_attr(&self, name: &str) -> Option<Value> {
+        if let Some(value) = self.attrs.borrow().get(name) {
+            return Some(value.clone());
+        }
+        match &self.base {
+            Some(Value::Class(sup)) if self.kind == ClassKind::Class => sup.get_attr(name),
+            _ => None,
+        }
+    }
+
+    pub fn is_subclass_of(self: &Rc<Self>, other: &Rc<Self>) -> bool {
+        if Rc::ptr_eq(self, other) {
+            return true;
+        }
+        match &self.base {
+            Some(Value::Class(sup)) => sup.is_subclass_of(other),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InstanceObj {
+    pub class: Rc<ClassObj>,
+    pub attrs: Shared<Dict<Str, Value>>,
+}
+
+#[derive(Debug)]
+pub struct ModuleObj {
+    pub name: Str,
+    pub scope: Rc<Scope>,
+}
+
+/// An exception object. `class` is the name of a builtin exception class (e.g. `ValueError`).
+#[derive(Debug)]
+pub struct ExceptionObj {
+    pub class: Str,
+    pub msg: Str,
+    /// the exit status (only used by `SystemExit`)
+    pub code: i32,
+}
+
+/// `start..end`, `start<..end`, `start..<end`, `start<..<end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeObj {
+    pub start: i128,
+    pub end: i128,
+    pub left_open: bool,
+    pub right_open: bool,
+}
+
+impl fmt::Display for RangeObj {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let l = if self.left_open { "<" } else { "" };
+        let r = if self.right_open { "<" } else { "" };
+        write!(f, "{}{l}..{r}{}", self.start, self.end)
+    }
+}
+
+impl RangeObj {
+    pub const fn first(&self) -> i128 {
+        if self.left_open {
+            self.start + 1
+        } else {
+            self.start
+        }
+    }
+
+    /// the exclusive upper bound
+    pub const fn stop(&self) -> i128 {
+        if self.right_open {
+            self.end
+        } else {
+            self.end + 1
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        (self.stop() - self.first()).max(0) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, value: &Value) -> bool {
+        let lower_ok = |x: f64| {
+            if self.left_open {
+                (self.start as f64) < x
+            } else {
+                (self.start as f64) <= x
+            }
+        };
+        let upper_ok = |x: f64| {
+            if self.right_open {
+                x < (self.end as f64)
+            } else {
+                x <= (self.end as f64)
+            }
+        };
+        match value.deref() {
+            Value::Int(i) => self.first() <= i && i < self.stop(),
+            Value::Bool(b) => (self.first()..self.stop()).contains(&(b as i128)),
+            Value::Float(f) => lower_ok(f) && upper_ok(f),
+            _ => false,
+        }
+    }
+}
+
+/// Runtime values.
+/// Mutable containers are shared, so the mutation through `push!`, etc. is visible from all references.
+#[derive(Debug, Clone, Default)]
+pub enum Value {
+    #[default]
+    None,
+    Ellipsis,
+    NotImplemented,
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    Str(Str),
+    Array(Shared<Vec<Value>>),
+    Tuple(Rc<[Value]>),
+    /// the insertion order is preserved
+    Dict(Shared<Vec<(Value, Value)>>),
+    Set(Shared<Vec<Value>>),
+    Record(Rc<[(Str, Value)]>),
+    Range(RangeObj),
+    /// `!x` for an immutable object (e.g. `Int!`)
+    Mut(Shared<Value>),
+    Subr(Rc<SubrObj>),
+    /// a method bound to the receiver
+    BoundMethod(Rc<(Value, Value)>),
+    /// a builtin function (e.g. `print!`)
+    Builtin(Str),
+    /// a builtin method bound to the receiver (e.g. `[1].push!`)
+    BuiltinMethod(Rc<(Value, Str)>),
+    /// a builtin type, trait or exception class (e.g. `Int`)
+    Type(Str),
+    /// `L or R`
+    Union(Rc<(Value, Value)>),
+    /// `L and R`
+    Intersection(Rc<(Value, Value)>),
+    Class(Rc<ClassObj>),
+    /// `C.new` or `C::__new__` generated by the interpreter
+    Constructor(Rc<ClassObj>),
+    Instance(Rc<InstanceObj>),
+    Module(Rc<ModuleObj>),
+    Exception(Rc<ExceptionObj>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(s) => write!(f, "{s}"),
+            Self::Mut(inner) => write!(f, "{}", inner.borrow()),
+            Self::Exception(exc) => write!(f, "{}", exc.msg),
+            other => write!(f, "{}", other.repr()),
+        }
+    }
+}
+
+impl Value {
+    pub fn array(elems: Vec<Value>) -> Self {
+        Self::Array(Shared::new(elems))
+    }
+
+    pub fn tuple(elems: Vec<Value>) -> Self {
+        Self::Tuple(Rc::from(elems))
+    }
+
+    pub fn str(s: impl Into<String>) -> Self {
+        Self::Str(Str::from(s.into()))
+    }
+
+    pub fn exception(class: impl Into<Str>, msg: impl Into<Str>) -> Self {
+        Self::Exception(Rc::new(ExceptionObj {
+            class: class.into(),
+            msg: msg.into(),
+            code: 1,
+        }))
+    }
+
+    /// Converts a literal to a runtime value.
+    /// Returns `None` if the value cannot exist at runtime (e.g. code objects).
+    pub fn from_value_obj(value: &ValueObj) -> Option<Self> {
+        match value {
+            ValueObj::Int(i) => Some(Self::Int(*i as i128)),
+            ValueObj::Nat(n) => Some(Self::Int(*n as i128)),
+            ValueObj::Float(f) => Some(Self::Float(*f)),
+            ValueObj::Str(s) => Some(Self::Str(s.clone())),
+            ValueObj::Bool(b) => Some(Self::Bool(*b)),
+            ValueObj::None => Some(Self::None),
+            ValueObj::Ellipsis => Some(Self::Ellipsis),
+            ValueObj::NotImplemented => Some(Self::NotImplemented),
+            ValueObj::Inf => Some(Self::Float(f64::INFINITY)),
+            ValueObj::NegInf => Some(Self::Float(f64::NEG_INFINITY)),
+            ValueObj::Array(elems) => elems
+                .iter()
+                .map(Self::from_value_obj)
+                .collect::<Option<Vec<_>>>()
+                .map(Self::array),
+            ValueObj::Tuple(elems) => elems
+                .iter()
+                .map(Self::from_value_obj)
+                .collect::<Option<Vec<_>>>()
+                .map(Self::tuple),
+            ValueObj::Set(elems) => elems
+                .iter()
+                .map(Self::from_value_obj)
+                .collect::<Option<Vec<_>>>()
+                .map(|elems| Self::Set(Shared::new(elems))),
+            ValueObj::Dict(dict) => dict
+                .iter()
+                .map(|(k, v)| Some((Self::from_value_obj(k)?, Self::from_value_obj(v)?)))
+                .collect::<Option<Vec<_>>>()
+                .map(|kvs| Self::Dict(Shared::new(kvs))),
+            ValueObj::Record(rec) => rec
+                .iter()
+                .map(|(k, v)| Some((k.symbol.clone(), Self::from_value_obj(v)?)))
+                .collect::<Option<Vec<_>>>()
+                .map(|fields| Self::Record(Rc::from(fields))),
+            ValueObj::Type(t) => Some(Self::Type(t.typ().local_name())),
+            ValueObj::Mut(inner) => Self::from_value_obj(&inner.borrow()),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner value if `self` is a mutable box, otherwise a clone of `self`.
+    pub fn deref(&self) -> Self {
+        match self {
+            Self::Mut(inner) => inner.borrow().clone(),
+            other => other.clone(),
+        }
+    }
+
+    pub fn class_name(&self) -> Str {
+        match self {
+            Self::None => Str::ever("NoneType"),
+            Self::Ellipsis => Str::ever("Ellipsis"),
+            Self::NotImplemented => Str::ever("NotImplementedType"),
+            Self::Bool(_) => Str::ever("Bool"),
+            Self::Int(i) if *i >= 0 => Str::ever("Nat"),
+            Self::Int(_) => Str::ever("Int"),
+            Self::Float(_) => Str::ever("Float"),
+            Self::Str(_) => Str::ever("Str"),
+            Self::Array(_) => Str::ever("Array!"),
+            Self::Tuple(_) => Str::ever("Tuple"),
+            Self::Dict(_) => Str::ever("Dict!"),
+            Self::Set(_) => Str::ever("Set!"),
+            Self::Record(_) => Str::ever("Record"),
+            Self::Range(_) => Str::ever("Range"),
+            Self::Mut(inner) => Str::from(format!("{}!", inner.borrow().class_name())),
+            Self::Subr(_) | Self::BoundMethod(_) | Self::Constructor(_) => Str::ever("Subroutine"),
+            Self::Builtin(_) | Self::BuiltinMethod(_) => Str::ever("BuiltinFunction"),
+            Self::Type(_) | Self::Union(_) | Self::Intersection(_) | Self::Class(_) => {
+                Str::ever("Type")
+            }
+            Self::Instance(inst) => inst.class.name.clone(),
+            Self::Module(_) => Str::ever("Module"),
+            Self::Exception(exc) => exc.class.clone(),
+        }
+    }
+
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::None => false,
+            Self::Bool(b) => *b,
+            Self::Int(i) => *i != 0,
+            Self::Float(f) => *f != 0.0,
+            Self::Str(s) => !s.is_empty(),
+            Self::Array(elems) | Self::Set(elems) => !elems.borrow().is_empty(),
+            Self::Tuple(elems) => !elems.is_empty(),
+            Self::Dict(kvs) => !kvs.borrow().is_empty(),
+            Self::Range(range) => !range.is_empty(),
+            Self::Mut(inner) => inner.borrow().is_truthy(),
+            _ => true,
+        }
+    }
+
+    /// Returns `true` if `self` is an object that can be used as a type (e.g. `Int`, `{1, 2}`, `[Int; 2]`).
+    pub fn is_type(&self) -> bool {
+        match self {
+            Self::Type(_) | Self::Union(_) | Self::Intersection(_) | Self::Class(_) => true,
+            Self::Array(elems) => elems.borrow().first().is_some_and(|e| e.is_type()),
+            Self::Record(fields) => fields.iter().all(|(_, v)| v.is_type()),
+            _ => false,
+        }
+    }
+
+    /// Structural equality. Equality defined by the user (`__eq__`) is handled by the interpreter.
+    pub fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Mut(l), _) => l.borrow().structural_eq(other),
+            (_, Self::Mut(r)) => self.structural_eq(&r.borrow()),
+            (Self::None, Self::None)
+            | (Self::Ellipsis, Self::Ellipsis)
+            | (Self::NotImplemented, Self::NotImplemented) => true,
+            (Self::Bool(l), Self::Bool(r)) => l == r,
+            (Self::Int(l), Self::Int(r)) => l == r,
+            (Self::Float(l), Self::Float(r)) => l == r,
+            (Self::Int(i), Self::Float(f)) | (Self::Float(f), Self::Int(i)) => *i as f64 == *f,
+            (Self::Bool(b), Self::Int(i)) | (Self::Int(i), Self::Bool(b)) => *b as i128 == *i,
+            (Self::Str(l), Self::Str(r)) => l == r,
+            (Self::Array(l), Self::Array(r)) => Self::seq_eq(&l.borrow()[..], &r.borrow()[..]),
+            (Self::Tuple(l), Self::Tuple(r)) => Self::seq_eq(l, r),
+            (Self::Set(l), Self::Set(r)) => {
+                let (l, r) = (l.borrow(), r.borrow());
+                l.len() == r.len() && l.iter().all(|x| r.iter().any(|y| x.structural_eq(y)))
+            }
+            (Self::Dict(l), Self::Dict(r)) => {
+                let (l, r) = (l.borrow(), r.borrow());
+                l.len() == r.len()
+                    && l.iter().all(|(k, v)| {
+                        r.iter()
+                            .any(|(k2, v2)| k.structural_eq(k2) && v.structural_eq(v2))
+                    })
+            }
+            (Self::Record(l), Self::Record(r)) => {
+                l.len() == r.len()
+                    && l.iter()
+                        .zip(r.iter())
+                        .all(|((lk, lv), (rk, rv))| lk == rk && lv.structural_eq(rv))
+            }
+            (Self::Range(l), Self::Range(r)) => l == r,
+            (Self::Builtin(l), Self::Builtin(r)) | (Self::Type(l), Self::Type(r)) => l == r,
+            _ => self.is(other),
+        }
+    }
+
+    fn seq_eq(l: &[Value], r: &[Value]) -> bool {
+        l.len() == r.len() && l.iter().zip(r.iter()).all(|(x, y)| x.structural_eq(y))
+    }
+
+    /// Identity (`is` operator)
+    pub fn is(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Array(l), Self::Array(r)) | (Self::Set(l), Self::Set(r)) => {
+                l.as_ptr() == r.as_ptr()
+            }
+            (Self::Dict(l), Self::Dict(r)) => l.as_ptr() == r.as_ptr(),
+            (Self::Mut(l), Self::Mut(r)) => l.as_ptr() == r.as_ptr(),
+            (Self::Tuple(l), Self::Tuple(r)) => Rc::ptr_eq(l, r),
+            (Self::Record(l), Self::Record(r)) => Rc::ptr_eq(l, r),
+            (Self::Subr(l), Self::Subr(r)) => Rc::ptr_eq(l, r),
+            (Self::Class(l), Self::Class(r)) | (Self::Constructor(l), Self::Constructor(r)) => {
+                Rc::ptr_eq(l, r)
+            }
+            (Self::Instance(l), Self::Instance(r)) => Rc::ptr_eq(l, r),
+            (Self::Module(l), Self::Module(r)) => Rc::ptr_eq(l, r),
+            (Self::Exception(l), Self::Exception(r)) => Rc::ptr_eq(l, r),
+            (Self::Mut(_), _) | (_, Self::Mut(_)) => false,
+            (Self::Str(_) | Self::Range(_), _) => false,
+            _ => self.structural_eq(other),
+        }
+    }
+
+    /// The same format as Python's `repr`
+    pub fn repr(&self) -> String {
+        match self {
+            Self::None => "None".into(),
+            Self::Ellipsis => "Ellipsis".into(),
+            Self::NotImplemented => "NotImplemented".into(),
+            Self::Bool(true) => "True".into(),
+            Self::Bool(false) => "False".into(),
+            Self::Int(i) => i.to_string(),
+            Self::Float(f) => fmt_float(*f),
+            Self::Str(s) => repr_str(s),
+            Self::Array(elems) => format!("[{}]", repr_seq(&elems.borrow())),
+            Self::Tuple(elems) if elems.len() == 1 => format!("({},)", elems[0].repr()),
+            Self::Tuple(elems) => format!("({})", repr_seq(elems)),
+            Self::Set(elems) if elems.borrow().is_empty() => "set()".into(),
+            Self::Set(elems) => format!("{{{}}}", repr_seq(&elems.borrow())),
+            Self::Dict(kvs) => {
+                let kvs = kvs
+                    .borrow()
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", k.repr(), v.repr()))
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", kvs.join(", "))
+            }
+            Self::Record(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(k, v)| format!("{k}={}", v.repr()))
+                    .collect::<Vec<_>>();
+                format!("Record({})", fields.join(", "))
+            }
+            Self::Range(range) => range.to_string(),
+            Self::Mut(inner) => inner.borrow().repr(),
+            Self::Subr(subr) => format!("<function {}>", subr.name),
+            Self::BoundMethod(bound) => format!("<bound method {}>", bound.1.repr()),
+            Self::Builtin(name) => format!("<built-in function {name}>"),
+            Self::BuiltinMethod(bound) => {
+                format!("<built-in method {} of {}>", bound.1, bound.0.class_name())
+            }
+            Self::Type(name) => format!("<class '{name}'>"),
+            Self::Union(union) => format!("{} or {}", union.0.repr(), union.1.repr()),
+            Self::Intersection(isec) => format!("{} and {}", isec.0.repr(), isec.1.repr()),
+            Self::Class(class) => format!("<class '{}'>", class.name),
+            Self::Constructor(class) => format!("<function {}.new>", class.name),
+            Self::Instance(inst) => format!("<{} object>", inst.class.name),
+            Self::Module(module) => format!("<module '{}'>", module.name),
+            Self::Exception(exc) => format!("{}({})", exc.class, repr_str(&exc.msg)),
+        }
+    }
+}
+
+fn repr_seq(elems: &[Value]) -> String {
+    elems
+        .iter()
+        .map(|e| e.repr())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Quotes a string in the same way as Python's `repr`.
+pub fn repr_str(s: &str) -> String {
+    let quote = if s.contains('\'') && !s.contains('"') {
+        '"'
+    } else {
+        '\''
+    };
+    let mut repr = String::with_capacity(s.len() + 2);
+    repr.push(quote);
+    for c in s.chars() {
+        match c {
+            '\\' => repr.push_str("\\\\"),
+            '\n' => repr.push_str("\\n"),
+            '\r' => repr.push_str("\\r"),
+            '\t' => repr.push_str("\\t"),
+            c if c == quote => {
+                repr.push('\\');
+                repr.push(c);
+            }
+            c if c.is_control() => repr.push_str(&format!("\\x{:02x}", c as u32)),
+            c => repr.push(c),
+        }
+    }
+    repr.push(quote);
+    repr
+}
+
+/// Formats a float in the same way as Python's `repr`.
+pub fn fmt_float(f: f64) -> String {
+    if f.is_nan() {
+        return "nan".into();
+    } else if f.is_infinite() {
+        return if f > 0.0 { "inf" } else { "-inf" }.into();
+    }
+    let abs = f.abs();
+    if abs != 0.0 && !(1e-4..1e16).contains(&abs) {
+        let exp = format!("{f:e}");
+        let (mantissa, exp) = exp.split_once('e').unwrap_or((&exp, "0"));
+        let exp = exp.parse::<i32>().unwrap_or(0);
+        let sign = if exp < 0 { '-' } else { '+' };
+        format!("{mantissa}e{sign}{:02}", exp.abs())
+    } else if f.fract() == 0.0 {
+        format!("{f:.1}")
+    } else {
+        format!("{f}")
+    }
+}
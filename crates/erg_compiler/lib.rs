@@ -11,17 +11,21 @@ pub use compile::*;
 mod codegen;
 pub mod context;
 pub mod declare;
+pub mod declare_gen;
 pub mod desugar_hir;
 pub mod effectcheck;
 pub mod error;
 pub mod hir;
+pub mod interpret;
 pub mod link;
 pub mod lint;
 pub mod lower;
 pub mod module;
 pub mod optimize;
 pub mod ownercheck;
+mod py_type;
 pub mod reorder;
+pub mod source_map;
 pub mod transpile;
 pub mod ty;
 pub mod varinfo;
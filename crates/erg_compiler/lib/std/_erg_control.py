@@ -13,13 +13,19 @@ def while__(cond_block, body):
         body()
 
 def with__(obj, body):
-    obj.__enter__()
-    body(e)
-    obj.__exit__()
+    with obj as e:
+        return body(e)
 
 def discard__(obj):
     pass
 
+def raise__(exc):
+    raise exc
+
+def panic__(msg):
+    from _erg_result import Error
+    raise Error(msg)
+
 def then__(x, f):
     if x == None or x == NotImplemented:
         return x
@@ -24,7 +24,7 @@ class Int(int):
     def __mul__(self, other):
         return then__(int.__mul__(self, other), Int)
     def __rmul__(self, other):
-        return then__(int.__mul__(other, self), Int)
+        return then__(int.__rmul__(self, other), Int)
     def __div__(self, other):
         return then__(int.__div__(self, other), Int)
     def __rdiv__(self, other):
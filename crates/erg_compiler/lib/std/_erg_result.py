@@ -1,7 +1,8 @@
 # from typing import TypeVar, Union, _SpecialForm, _type_check
 
-class Error:
+class Error(Exception):
     def __init__(self, message):
+        super().__init__(message)
         self.message = message
 
 # T = TypeVar("T")
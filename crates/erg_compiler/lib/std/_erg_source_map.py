@@ -0,0 +1,135 @@
+# Rewrites the locations in Python tracebacks into the Erg source locations,
+# using the source maps emitted by `erg --source-map` (`foo.pyc.map`, `foo.py.map`).
+#
+# Usage:
+#   python _erg_source_map.py foo.py [args...]  # runs foo.py (or foo.pyc) with the hook
+# or:
+#   import _erg_source_map
+#   _erg_source_map.install("foo.py.map")
+import json
+import linecache
+import os
+import sys
+import traceback
+
+_maps = []
+
+class SourceMap:
+    def __init__(self, path):
+        with open(path, encoding="utf-8") as f:
+            data = json.load(f)
+        self.file = data["file"]
+        self.sources = data["sources"]
+        # [py_line, py_col, source index, line, col]
+        self.mappings = sorted(data["mappings"])
+        # [code name, firstlineno, bytecode offset, source index, line, col] (`.pyc` only)
+        self.offsets = {}
+        for (name, firstlineno, offset, src, line, col) in data.get("offsets", []):
+            self.offsets.setdefault((name, firstlineno), []).append((offset, src, line, col))
+
+    def matches(self, filename):
+        return filename == self.file or os.path.basename(filename) == os.path.basename(self.file)
+
+    def lookup(self, py_line, py_col=None):
+        """Returns (source, line, col) of the nearest mapping at or before the Python position."""
+        found = None
+        for (line, col, src, erg_line, erg_col) in self.mappings:
+            if (line, col) > (py_line, py_col if py_col is not None else sys.maxsize):
+                break
+            found = (self.sources[src], erg_line, erg_col)
+        return found
+
+    def lookup_offset(self, code, lasti):
+        """Returns (source, line, col) of the last mapping at or before the bytecode offset."""
+        found = None
+        for (offset, src, line, col) in self.offsets.get((code.co_name, code.co_firstlineno), []):
+            if offset > lasti:
+                break
+            found = (self.sources[src], line, col)
+        return found
+
+def load(path):
+    """Loads a source map file."""
+    _maps.append(SourceMap(path))
+
+def map_location(filename, py_line, py_col=None):
+    for source_map in _maps:
+        if source_map.matches(filename):
+            return source_map.lookup(py_line, py_col)
+    return None
+
+def _map_tb(tb):
+    code = tb.tb_frame.f_code
+    for source_map in _maps:
+        if source_map.matches(code.co_filename):
+            loc = source_map.lookup_offset(code, tb.tb_lasti)
+            if loc is not None:
+                return loc
+            return source_map.lookup(tb.tb_lineno, _colno(code, tb.tb_lasti))
+    return None
+
+def _colno(code, lasti):
+    # `traceback.extract_tb` cannot be used, since the position tables of `.pyc` may be broken
+    try:
+        return list(code.co_positions())[lasti // 2][2]
+    except Exception:
+        return None
+
+def _is_hook_frame(code):
+    # the frames of this module and `runpy` (see `main`)
+    return code.co_filename == __file__ or "runpy" in code.co_filename
+
+def format_exception(exc):
+    """Formats the exception like `traceback.format_exception`, but with the Erg locations."""
+    lines = ["Traceback (most recent call last):\n"]
+    tb = exc.__traceback__
+    while tb is not None:
+        code = tb.tb_frame.f_code
+        if not _is_hook_frame(code):
+            loc = _map_tb(tb)
+            if loc is None:
+                lines.append(f'  File "{code.co_filename}", line {tb.tb_lineno}, in {code.co_name}\n')
+                line = linecache.getline(code.co_filename, tb.tb_lineno).strip()
+                if line:
+                    lines.append(f"    {line}\n")
+            else:
+                lines.extend(_format_erg_frame(loc, code.co_name))
+        tb = tb.tb_next
+    lines.extend(traceback.format_exception_only(type(exc), exc))
+    return "".join(lines)
+
+def _format_erg_frame(loc, name):
+    (source, line, col) = loc
+    lines = [f'  File "{source}", line {line}, in {name}\n']
+    code = linecache.getline(source, line).rstrip()
+    if code:
+        indent = len(code) - len(code.lstrip())
+        lines.append(f"    {code.lstrip()}\n")
+        if col >= indent:
+            lines.append("    " + " " * (col - indent) + "^\n")
+    return lines
+
+def _excepthook(exc_type, exc, tb):
+    if exc.__traceback__ is None:
+        exc.__traceback__ = tb
+    sys.stderr.write(format_exception(exc))
+
+def install(*paths):
+    """Loads the source maps (`<the main script>.map` by default) and installs the hook."""
+    if not paths:
+        default = sys.argv[0] + ".map"
+        paths = (default,) if os.path.exists(default) else ()
+    for path in paths:
+        load(path)
+    sys.excepthook = _excepthook
+
+def main():
+    import runpy
+    path = sys.argv[1]
+    sys.argv = sys.argv[1:]
+    sys.path[0] = os.path.dirname(os.path.abspath(path))
+    install()
+    runpy.run_path(path, run_name="__main__")
+
+if __name__ == "__main__":
+    main()
@@ -5,7 +5,7 @@ use erg_common::config::{ErgConfig, Input};
 use erg_common::python_util::BUILTIN_PYTHON_MODS;
 use erg_common::traits::Locational;
 use erg_common::Str;
-use erg_common::{enum_unwrap, log};
+use erg_common::{enum_unwrap, get_hash, log, normalize_path};
 
 use erg_parser::ast::{DefId, OperationKind};
 use erg_parser::token::{Token, TokenKind, DOT, EQUAL};
@@ -17,18 +17,47 @@ use crate::ty::HasType;
 use erg_common::fresh::fresh_varname;
 
 use crate::hir::*;
+use crate::module::package::{dependency_module_path, package_module_path};
 use crate::module::SharedModuleCache;
 
 /// Link code using the module cache.
-/// Erg links all non-Python modules into a single pyc file.
+/// Erg links all non-Python modules into a single pyc file,
+/// unless they are compiled separately (`--separate`).
 pub struct Linker<'a> {
     cfg: &'a ErgConfig,
     mod_cache: &'a SharedModuleCache,
+    /// The modules being linked and the variables that hold them (`None` for the main module).
+    /// Importing them again (cyclic import) refers to the module objects instead of inlining.
+    ancestors: Vec<(PathBuf, Option<Identifier>)>,
+    /// If `true`, the linked modules are left in the cache (for the REPL and the compile server).
+    keep_entries: bool,
+    /// If `true`, Erg modules are not inlined but imported from their own files (see `link_modules`).
+    separate: bool,
 }
 
 impl<'a> Linker<'a> {
     pub fn new(cfg: &'a ErgConfig, mod_cache: &'a SharedModuleCache) -> Self {
-        Self { cfg, mod_cache }
+        let ancestors = cfg
+            .input
+            .path()
+            .map(|path| {
+                let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+                vec![(normalize_path(path), None)]
+            })
+            .unwrap_or_default();
+        Self {
+            cfg,
+            mod_cache,
+            separate: cfg.separate && !ancestors.is_empty(),
+            ancestors,
+            keep_entries: cfg.input.is_repl(),
+        }
+    }
+
+    /// The entries are always kept in the REPL.
+    pub fn keep_entries(mut self, keep_entries: bool) -> Self {
+        self.keep_entries |= keep_entries;
+        self
     }
 
     pub fn link(&self, mut main: HIR) -> HIR {
@@ -43,6 +72,86 @@ impl<'a> Linker<'a> {
         main
     }
 
+    /// Links the given modules one by one for separate compilation.
+    /// Returns the configurations and the linked HIRs of the modules,
+    /// and the paths of their output files relative to the main module's directory (without extensions).
+    pub fn link_modules(
+        &self,
+        paths: impl IntoIterator<Item = PathBuf>,
+    ) -> Vec<(ErgConfig, HIR, PathBuf)> {
+        let mut linked = vec![];
+        for path in paths {
+            if self.ancestors.first().map(|(main, _)| main) == Some(&path) {
+                continue;
+            }
+            let hir_cfg = if self.keep_entries {
+                self.mod_cache
+                    .get(path.as_path())
+                    .and_then(|entry| entry.hir.clone().map(|hir| (hir, entry.cfg().clone())))
+            } else {
+                self.mod_cache
+                    .remove(path.as_path())
+                    .and_then(|entry| entry.hir.map(|hir| (hir, entry.module.context.cfg.clone())))
+            };
+            let Some((hir, cfg)) = hir_cfg else {
+                continue;
+            };
+            let linker = Linker {
+                cfg: &cfg,
+                mod_cache: self.mod_cache,
+                ancestors: self.ancestors.clone(),
+                keep_entries: self.keep_entries,
+                separate: true,
+            };
+            let hir = linker.link(hir);
+            let mut output = self.relative_path(&path);
+            output.set_extension("");
+            linked.push((cfg, hir, output));
+        }
+        linked
+    }
+
+    /// The path of the module relative to the main module's directory.
+    /// The modules of a package (and the vendored dependencies) become Python packages named after them,
+    /// and other modules outside of the directory are placed directly under it.
+    /// The names of the latter are suffixed with the hash of their paths, since they may collide
+    /// (e.g. `../a/util.er` and `../b/util.er` -> `util_{hash of ../a/util.er}.er` and `util_{hash of ../b/util.er}.er`).
+    pub(crate) fn relative_path(&self, path: &Path) -> PathBuf {
+        if let Some(rel) = dependency_module_path(path).or_else(|| package_module_path(path)) {
+            return rel;
+        }
+        let root = self.ancestors.first().and_then(|(main, _)| main.parent());
+        if let Some(rel) = root.and_then(|root| path.strip_prefix(root).ok()) {
+            return rel.to_path_buf();
+        }
+        let hash = get_hash(&path);
+        let file_name = PathBuf::from(path.file_name().unwrap_or_default());
+        match path.parent().and_then(|dir| dir.file_name()) {
+            Some(package) if path.file_stem() == Some("__init__".as_ref()) => {
+                PathBuf::from(format!("{}_{hash:x}", package.to_string_lossy())).join(file_name)
+            }
+            _ => {
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                PathBuf::from(format!("{stem}_{hash:x}"))
+                    .with_extension(path.extension().unwrap_or_default())
+            }
+        }
+    }
+
+    /// `foo/bar.er` -> `foo.bar`, `foo/__init__.er` -> `foo`
+    fn module_name(rel: &Path) -> String {
+        let mut rel = rel.to_path_buf();
+        if rel.file_stem() == Some("__init__".as_ref()) {
+            rel.pop();
+        } else {
+            rel.set_extension("");
+        }
+        rel.components()
+            .map(|comp| comp.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
     /// ```erg
     /// urllib = pyimport "urllib"
     /// urllib.request.urlopen! "https://example.com"
@@ -123,9 +232,15 @@ impl<'a> Linker<'a> {
                 for arg in call.args.pos_args.iter_mut() {
                     Self::resolve_pymod_path(&mut arg.expr);
                 }
+                if let Some(var_args) = call.args.var_args.as_mut() {
+                    Self::resolve_pymod_path(&mut var_args.expr);
+                }
                 for arg in call.args.kw_args.iter_mut() {
                     Self::resolve_pymod_path(&mut arg.expr);
                 }
+                if let Some(kw_var_args) = call.args.kw_var_args.as_mut() {
+                    Self::resolve_pymod_path(&mut kw_var_args.expr);
+                }
             }
             Expr::Def(def) => {
                 for chunk in def.body.block.iter_mut() {
@@ -244,9 +359,15 @@ impl<'a> Linker<'a> {
                     for arg in call.args.pos_args.iter_mut() {
                         self.replace_import(&mut arg.expr);
                     }
+                    if let Some(var_args) = call.args.var_args.as_mut() {
+                        self.replace_import(&mut var_args.expr);
+                    }
                     for arg in call.args.kw_args.iter_mut() {
                         self.replace_import(&mut arg.expr);
                     }
+                    if let Some(kw_var_args) = call.args.kw_var_args.as_mut() {
+                        self.replace_import(&mut kw_var_args.expr);
+                    }
                 }
             },
             Expr::Def(def) => {
@@ -303,8 +424,18 @@ impl<'a> Linker<'a> {
             enum_unwrap!(expr.ref_t().typarams().remove(0), TyParam::Value:(ValueObj::Str:(_)));
         let path = Path::new(&path[..]);
         let path = Context::resolve_real_path(self.cfg, path).unwrap();
+        let normalized = normalize_path(path.clone());
+        if self.separate {
+            let mod_name = Self::module_name(&self.relative_path(&normalized));
+            Self::replace_import_name(expr, &mod_name);
+            return;
+        }
+        if let Some((_, module)) = self.ancestors.iter().find(|(anc, _)| anc == &normalized) {
+            *expr = Self::ancestor_module(module.clone(), line);
+            return;
+        }
         // In the case of REPL, entries cannot be used up
-        let hir_cfg = if self.cfg.input.is_repl() {
+        let hir_cfg = if self.keep_entries {
             self.mod_cache
                 .get(path.as_path())
                 .and_then(|entry| entry.hir.clone().map(|hir| (hir, entry.cfg().clone())))
@@ -320,14 +451,28 @@ impl<'a> Linker<'a> {
         // let sig = option_enum_unwrap!(&def.sig, Signature::Var)
         //    .unwrap_or_else(|| todo!("module subroutines are not allowed"));
         if let Some((hir, cfg)) = hir_cfg {
-            let linker = Linker::new(&cfg, self.mod_cache);
+            let tmp = Identifier::private_with_line(Str::from(fresh_varname()), line);
+            let mut ancestors = self.ancestors.clone();
+            ancestors.push((normalized, Some(tmp.clone())));
+            let linker = Linker {
+                cfg: &cfg,
+                mod_cache: self.mod_cache,
+                ancestors,
+                keep_entries: self.keep_entries,
+                separate: false,
+            };
             let hir = linker.link(hir);
             let code = Expr::Code(Block::new(Vec::from(hir.module)));
             let module_type =
                 Expr::Accessor(Accessor::private_with_line(Str::ever("#ModuleType"), line));
-            let args = Args::new(vec![PosArg::new(mod_name.clone())], None, vec![], None);
+            let args = Args::new(
+                vec![PosArg::new(mod_name.clone())],
+                None,
+                vec![],
+                None,
+                None,
+            );
             let block = Block::new(vec![module_type.call_expr(args)]);
-            let tmp = Identifier::private_with_line(Str::from(fresh_varname()), line);
             let mod_def = Expr::Def(Def::new(
                 Signature::Var(VarSignature::new(tmp.clone(), None)),
                 DefBody::new(EQUAL, block, DefId(0)),
@@ -337,7 +482,7 @@ impl<'a> Linker<'a> {
             let m_dict = module.clone().attr_expr(__dict__);
             let locals = Expr::Accessor(Accessor::public_with_line(Str::ever("locals"), line));
             let locals_call = locals.call_expr(Args::empty());
-            let args = Args::new(vec![PosArg::new(locals_call)], None, vec![], None);
+            let args = Args::new(vec![PosArg::new(locals_call)], None, vec![], None, None);
             let mod_update = Expr::Call(Call::new(
                 m_dict.clone(),
                 Some(Identifier::public("update")),
@@ -349,6 +494,7 @@ impl<'a> Linker<'a> {
                 None,
                 vec![],
                 None,
+                None,
             );
             let exec_code = exec.call_expr(args);
             let compound = Block::new(vec![mod_def, mod_update, exec_code, module]);
@@ -356,6 +502,57 @@ impl<'a> Linker<'a> {
         }
     }
 
+    /// ```erg
+    /// x = import "foo/x" # compiled separately
+    /// ```
+    /// ↓
+    /// ```python
+    /// x = __import__("foo.x").x
+    /// ```
+    fn replace_import_name(expr: &mut Expr, mod_name: &str) {
+        let line = expr.ln_begin().unwrap_or(0);
+        let args = &mut enum_unwrap!(expr, Expr::Call).args;
+        let mod_name_lit = enum_unwrap!(args.remove_left_or_key("Path").unwrap(), Expr::Lit);
+        let token = Token::new(
+            TokenKind::StrLit,
+            format!("\"{mod_name}\""),
+            mod_name_lit.ln_begin().unwrap(),
+            mod_name_lit.col_begin().unwrap(),
+        );
+        let mod_name_lit = Expr::Lit(Literal::try_from(token).unwrap());
+        args.insert_pos(0, PosArg::new(mod_name_lit));
+        for attr in mod_name.split('.').skip(1) {
+            *expr = mem::replace(expr, Expr::Code(Block::empty()))
+                .attr_expr(Identifier::public_with_line(DOT, Str::rc(attr), line));
+        }
+    }
+
+    /// The module variables of the ancestors are visible in the inlined modules (see `replace_erg_import`).
+    /// The main module is not inlined, but it can be found in `sys.modules`.
+    /// ```python
+    /// # module: _x
+    /// # main: __import__("sys").modules[__name__]
+    /// ```
+    fn ancestor_module(module: Option<Identifier>, line: u32) -> Expr {
+        if let Some(module) = module {
+            return Expr::Accessor(Accessor::Ident(module));
+        }
+        let __import__ = Expr::Accessor(Accessor::public_with_line(Str::ever("__import__"), line));
+        let sys = Token::new(TokenKind::StrLit, "sys", line, 0);
+        let sys = Expr::Lit(Literal::try_from(sys).unwrap());
+        let args = Args::new(vec![PosArg::new(sys)], None, vec![], None, None);
+        let modules = __import__
+            .call_expr(args)
+            .attr_expr(Identifier::public("modules"));
+        let __name__ = Expr::Accessor(Accessor::public_with_line(Str::ever("__name__"), line));
+        let args = Args::new(vec![PosArg::new(__name__)], None, vec![], None, None);
+        Expr::Call(Call::new(
+            modules,
+            Some(Identifier::public("__getitem__")),
+            args,
+        ))
+    }
+
     /// ```erg
     /// x = pyimport "x" # called from dir "a"
     /// ```
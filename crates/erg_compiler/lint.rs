@@ -2,6 +2,7 @@
 //! What is implemented here affects subsequent optimizations,
 //! and `erg_linter` does linting that does not affect optimizations.
 
+use erg_common::lint::LintLevel;
 #[allow(unused_imports)]
 use erg_common::log;
 use erg_common::traits::{Locational, Runnable, Stream};
@@ -143,6 +144,11 @@ impl ASTLowerer {
                         warns.extend(ws);
                     }
                 }
+                if let Some(kw_var_args) = &call.args.kw_var_args {
+                    if let Err(ws) = self.block_use_check(&kw_var_args.expr) {
+                        warns.extend(ws);
+                    }
+                }
             }
             // TODO: unary, binary, array, ...
             _ => {}
@@ -154,6 +160,184 @@ impl ASTLowerer {
         }
     }
 
+    /// NG: `t = {x = Int; y = Int}` (a type bound to a variable cannot be used in type specifications)
+    /// OK: `T = {x = Int; y = Int}`
+    pub(crate) fn warn_lowercase_types(&mut self, module: &hir::Module, mode: &str) {
+        if mode == "eval" {
+            return;
+        }
+        for chunk in module.iter() {
+            self.lowercase_type_check(chunk);
+        }
+    }
+
+    fn lowercase_type_check(&mut self, expr: &hir::Expr) {
+        match expr {
+            hir::Expr::Def(def) => {
+                if let hir::Signature::Var(sig) = &def.sig {
+                    let name = sig.inspect();
+                    if !sig.ident.is_const()
+                        && &name[..] != "_"
+                        && def.body.block.len() == 1
+                        && is_record_type(def.body.block.first().unwrap())
+                    {
+                        let warn = LowerWarning::lowercase_type_warning(
+                            self.cfg().input.clone(),
+                            line!() as usize,
+                            sig.ident.loc(),
+                            self.module.context.caused_by(),
+                            name,
+                        );
+                        self.warns.push(warn);
+                    }
+                }
+                for chunk in def.body.block.iter() {
+                    self.lowercase_type_check(chunk);
+                }
+            }
+            hir::Expr::ClassDef(class_def) => {
+                for chunk in class_def.methods.iter() {
+                    self.lowercase_type_check(chunk);
+                }
+            }
+            hir::Expr::PatchDef(patch_def) => {
+                for chunk in patch_def.methods.iter() {
+                    self.lowercase_type_check(chunk);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// NG: `g!() = for! 0..<5, i => h!.return i` (returns from `g!`, not from `h!`)
+    /// OK: `g!() = for! 0..<5, i => g!.return i`
+    pub(crate) fn warn_return_in_loop(&mut self, module: &hir::Module, mode: &str) {
+        if mode == "eval" {
+            return;
+        }
+        for chunk in module.iter() {
+            self.return_in_loop_check(chunk, None, None);
+        }
+    }
+
+    /// `outer`: the subroutine that the current block belongs to (`None` in lambdas and at the module level)
+    /// `in_loop`: the loop that the current block belongs to
+    fn return_in_loop_check(
+        &mut self,
+        expr: &hir::Expr,
+        outer: Option<&Str>,
+        in_loop: Option<&str>,
+    ) {
+        match expr {
+            hir::Expr::Def(def) => {
+                let outer = if let hir::Signature::Subr(sig) = &def.sig {
+                    Some(sig.ident.inspect())
+                } else {
+                    outer
+                };
+                for chunk in def.body.block.iter() {
+                    self.return_in_loop_check(chunk, outer, None);
+                }
+            }
+            hir::Expr::ClassDef(class_def) => {
+                for chunk in class_def.methods.iter() {
+                    self.return_in_loop_check(chunk, None, None);
+                }
+            }
+            hir::Expr::PatchDef(patch_def) => {
+                for chunk in patch_def.methods.iter() {
+                    self.return_in_loop_check(chunk, None, None);
+                }
+            }
+            // a lambda that is not inlined returns by itself
+            hir::Expr::Lambda(lambda) => {
+                for chunk in lambda.body.iter() {
+                    self.return_in_loop_check(chunk, None, None);
+                }
+            }
+            hir::Expr::Call(call) => {
+                if let (Some(loop_name), Some("return")) = (
+                    in_loop,
+                    call.attr_name.as_ref().map(|name| &name.inspect()[..]),
+                ) {
+                    let returns_value = call
+                        .args
+                        .pos_args
+                        .first()
+                        .is_some_and(|arg| !arg.expr.ref_t().is_nonelike());
+                    let receiver = match call.obj.as_ref() {
+                        hir::Expr::Accessor(hir::Accessor::Ident(ident)) => Some(ident.inspect()),
+                        hir::Expr::Accessor(hir::Accessor::Attr(attr)) => {
+                            Some(attr.ident.inspect())
+                        }
+                        _ => None,
+                    };
+                    if let Some(receiver) = receiver.filter(|_| returns_value) {
+                        if Some(receiver) != outer {
+                            let warn = LowerWarning::return_in_loop_warning(
+                                self.cfg().input.clone(),
+                                line!() as usize,
+                                call.loc(),
+                                self.module.context.caused_by(),
+                                loop_name,
+                                receiver,
+                                outer.map(|outer| &outer[..]),
+                            );
+                            self.warns.push(warn);
+                        }
+                    }
+                }
+                // the blocks of these are inlined, so `.return` in them returns from the outer subroutine
+                let inlined = match call.obj.as_ref() {
+                    hir::Expr::Accessor(hir::Accessor::Ident(ident)) => {
+                        match &ident.inspect()[..] {
+                            name @ ("for" | "for!" | "while!") => Some(Some(name)),
+                            "if" | "if!" | "match" | "match!" | "with!" => Some(in_loop),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                for arg in call.args.pos_args.iter() {
+                    match (&arg.expr, inlined) {
+                        (hir::Expr::Lambda(lambda), Some(in_loop)) => {
+                            for chunk in lambda.body.iter() {
+                                self.return_in_loop_check(chunk, outer, in_loop);
+                            }
+                        }
+                        (other, _) => self.return_in_loop_check(other, outer, in_loop),
+                    }
+                }
+                if let Some(var_args) = &call.args.var_args {
+                    self.return_in_loop_check(&var_args.expr, outer, in_loop);
+                }
+                for arg in call.args.kw_args.iter() {
+                    self.return_in_loop_check(&arg.expr, outer, in_loop);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Drops the allowed warnings and moves the denied ones to the errors.
+    pub(crate) fn apply_lint_levels(&mut self) {
+        if self.cfg().lint_levels.is_empty() {
+            return;
+        }
+        for warn in self.warns.take_all() {
+            match self.cfg().lint_levels.get(warn.core.kind, warn.core.code) {
+                Some((_, LintLevel::Allow)) => {}
+                Some((lint, LintLevel::Deny)) => {
+                    let err = warn.deny(lint);
+                    self.errs.push(err);
+                }
+                _ => {
+                    self.warns.push(warn);
+                }
+            }
+        }
+    }
+
     pub(crate) fn inc_ref<L: Locational>(&self, vi: &VarInfo, name: &L) {
         self.module.context.inc_ref(vi, name);
     }
@@ -167,7 +351,17 @@ impl ASTLowerer {
                 let code = referee.code();
                 let name = code.as_ref().map(|s| &s[..]).unwrap_or("");
                 let name_is_auto = name == "_"; // || name.starts_with(['%']);
-                if value.referrers.is_empty() && value.vi.vis.is_private() && !name_is_auto {
+                let is_test = value
+                    .vi
+                    .comptime_decos
+                    .as_ref()
+                    .is_some_and(|decos| decos.contains("Test"));
+                if value.referrers.is_empty()
+                    && value.vi.vis.is_private()
+                    && !name_is_auto
+                    // test subroutines are called by `erg test`
+                    && !is_test
+                {
                     let input = referee
                         .module
                         .as_ref()
@@ -185,3 +379,20 @@ impl ASTLowerer {
         }
     }
 }
+
+/// `{x = Int; y = {z = Str}}`
+fn is_record_type(expr: &hir::Expr) -> bool {
+    let hir::Expr::Record(record) = expr else {
+        return false;
+    };
+    !record.attrs.is_empty()
+        && record.attrs.iter().all(|attr| {
+            attr.body.block.len() == 1 && {
+                let value = attr.body.block.first().unwrap();
+                matches!(
+                    value.ref_t(),
+                    Type::ClassType | Type::TraitType | Type::Type
+                ) || is_record_type(value)
+            }
+        })
+}
@@ -22,12 +22,12 @@ use crate::artifact::{CompleteArtifact, IncompleteArtifact};
 use crate::context::instantiate::TyVarCache;
 use crate::module::SharedCompilerResource;
 use crate::ty::constructors::{
-    array_mut, array_t, free_var, func, mono, poly, proc, set_mut, set_t, ty_tp,
+    array_mut, array_t, free_var, mono, poly, set_mut, set_t, subr_t, ty_tp,
 };
 use crate::ty::free::Constraint;
 use crate::ty::typaram::TyParam;
 use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
-use crate::ty::{HasType, ParamTy, Type};
+use crate::ty::{HasType, ParamTy, SubrKind, Type};
 
 use crate::context::{
     ClassDefType, Context, ContextKind, ContextProvider, ModuleContext, RegistrationMode,
@@ -635,9 +635,9 @@ impl ASTLowerer {
     }
 
     fn lower_ident(&mut self, ident: ast::Identifier) -> LowerResult<hir::Identifier> {
-        // `match` is a special form, typing is magic
+        // `match` and `try!` are special forms, typing is magic
         let (vi, __name__) = if ident.vis().is_private()
-            && (&ident.inspect()[..] == "match" || &ident.inspect()[..] == "match!")
+            && matches!(&ident.inspect()[..], "match" | "match!" | "try!")
         {
             (
                 VarInfo {
@@ -701,11 +701,12 @@ impl ASTLowerer {
     }
 
     fn lower_args(&mut self, args: ast::Args, errs: &mut LowerErrors) -> hir::Args {
-        let (pos_args, var_args, kw_args, paren) = args.deconstruct();
+        let (pos_args, var_args, kw_args, kw_var_args, paren) = args.deconstruct();
         let mut hir_args = hir::Args::new(
             Vec::with_capacity(pos_args.len()),
             None,
             Vec::with_capacity(kw_args.len()),
+            None,
             paren,
         );
         for arg in pos_args.into_iter() {
@@ -739,6 +740,16 @@ impl ASTLowerer {
                 }
             }
         }
+        if let Some(kw_var_args) = kw_var_args {
+            match self.lower_expr(kw_var_args.expr) {
+                Ok(expr) => hir_args.kw_var_args = Some(Box::new(hir::PosArg::new(expr))),
+                Err(es) => {
+                    errs.extend(es);
+                    let dummy = hir::Expr::Dummy(hir::Dummy::empty());
+                    hir_args.kw_var_args = Some(Box::new(hir::PosArg::new(dummy)));
+                }
+            }
+        }
         hir_args
     }
 
@@ -836,7 +847,55 @@ impl ASTLowerer {
                     )))
                 }
             },
-            Some(OperationKind::Return | OperationKind::Yield) => {
+            Some(kind @ (OperationKind::Yield | OperationKind::YieldFrom)) => {
+                // (g: () -> Generator(?Y, ?S, ?R)).yield: (self: GenericCallable, arg: ?Y) -> ?S
+                // (g: () -> Generator(?Y, ?S, ?R)).yield_from: (self: GenericCallable, arg: Iterable(?Y)) -> Obj
+                let typarams = call
+                    .obj
+                    .ref_t()
+                    .return_t()
+                    .filter(|t| &t.qual_name()[..] == "Generator")
+                    .map(|t| t.typarams());
+                let Some([yield_t, send_t, _]) = typarams.as_deref() else {
+                    let name = call.obj.show_acc().unwrap_or_default();
+                    return Err(LowerErrors::from(
+                        LowerError::yield_outside_generator_error(
+                            self.input().clone(),
+                            line!() as usize,
+                            call.loc(),
+                            self.module.context.caused_by(),
+                            &name,
+                        ),
+                    ));
+                };
+                let (Ok(yield_t), Ok(send_t)) = (
+                    Type::try_from(yield_t.clone()),
+                    Type::try_from(send_t.clone()),
+                ) else {
+                    return unreachable_error!(LowerErrors, LowerError, self.module.context);
+                };
+                let arg_t = call.args.get(0).unwrap().ref_t();
+                if kind == OperationKind::Yield {
+                    self.module
+                        .context
+                        .sub_unify(arg_t, &yield_t, &call, None)?;
+                    *call.ref_mut_t() = send_t;
+                } else {
+                    let iterable_t = poly("Iterable", vec![ty_tp(yield_t)]);
+                    self.module
+                        .context
+                        .sub_unify(arg_t, &iterable_t, &call, None)?;
+                    // `yield from` returns the return value of the sub-generator
+                    let sub_return_t = (&arg_t.qual_name()[..] == "Generator")
+                        .then(|| arg_t.typarams().pop())
+                        .flatten()
+                        .and_then(|tp| Type::try_from(tp).ok());
+                    if let Some(sub_return_t) = sub_return_t {
+                        *call.ref_mut_t() = sub_return_t;
+                    }
+                }
+            }
+            Some(OperationKind::Return) => {
                 // (f: ?T -> ?U).return: (self: GenericCallable, arg: Obj) -> Never
                 let callable_t = call.obj.ref_t();
                 let ret_t = match callable_t {
@@ -892,7 +951,7 @@ impl ASTLowerer {
             &self.cfg.input,
             &self.module.context.name,
         )?;
-        let args = hir::Args::new(args, None, vec![], None);
+        let args = hir::Args::new(args, None, vec![], None, None);
         let attr_name = hir::Identifier::new(attr_name.dot, attr_name.name, None, vi);
         Ok(hir::Call::new(class, Some(attr_name), args))
     }
@@ -954,6 +1013,16 @@ impl ASTLowerer {
                 Err(es) => errs.extend(es),
             }
         }
+        let hir_kw_var_params = match params.kw_var_params {
+            Some(kw_var_params) => match self.lower_non_default_param(*kw_var_params) {
+                Ok(sig) => Some(Box::new(sig)),
+                Err(es) => {
+                    errs.extend(es);
+                    None
+                }
+            },
+            None => None,
+        };
         if !errs.is_empty() {
             Err(errs)
         } else {
@@ -961,6 +1030,7 @@ impl ASTLowerer {
                 hir_non_defaults,
                 hir_var_params,
                 hir_defaults,
+                hir_kw_var_params,
                 params.parens,
             );
             Ok(hir_params)
@@ -1025,12 +1095,31 @@ impl ASTLowerer {
             for default in params.defaults.iter() {
                 self.inc_ref(&default.sig.vi, &default.sig);
             }
+            if let Some(kw_var_param) = params.kw_var_params.as_deref() {
+                self.inc_ref(&kw_var_param.vi, kw_var_param);
+            }
         }
+        let kw_var_name = params.kw_var_params.as_ref().and_then(|p| p.name());
+        let kw_var_param_ty = params.kw_var_params.as_deref().map(|kw_var| {
+            // `{Str: T}` -> `T`
+            let value_t = match &kw_var.vi.t {
+                Type::Poly { params, .. } => match params.first() {
+                    Some(TyParam::Dict(dict)) => dict
+                        .values()
+                        .next()
+                        .and_then(|tp| Type::try_from(tp.clone()).ok()),
+                    _ => None,
+                },
+                _ => None,
+            };
+            ParamTy::pos(kw_var.inspect().cloned(), value_t.unwrap_or(Type::Obj))
+        });
         let (non_default_params, default_params): (Vec<_>, Vec<_>) = self
             .module
             .context
             .params
             .iter()
+            .filter(|(name, _)| kw_var_name.is_none() || name.as_ref() != kw_var_name)
             .partition(|(_, vi)| !vi.kind.has_default());
         #[cfg(not(feature = "py_compatible"))]
         let non_default_params = non_default_params.into_iter();
@@ -1075,16 +1164,24 @@ impl ASTLowerer {
         } else {
             self.pop_append_errs();
         }
-        let ty = if is_procedural {
-            proc(non_default_param_tys, None, default_param_tys, body.t())
+        let kind = if is_procedural {
+            SubrKind::Proc
         } else {
-            func(non_default_param_tys, None, default_param_tys, body.t())
+            SubrKind::Func
         };
+        let ty = subr_t(
+            kind,
+            non_default_param_tys,
+            None,
+            default_param_tys,
+            kw_var_param_ty,
+            body.t(),
+        );
         let t = if ty.has_qvar() { ty.quantify() } else { ty };
         Ok(hir::Lambda::new(id, params, lambda.op, body, t))
     }
 
-    fn lower_def(&mut self, def: ast::Def) -> LowerResult<hir::Def> {
+    pub(crate) fn lower_def(&mut self, def: ast::Def) -> LowerResult<hir::Def> {
         log!(info "entered {}({})", fn_name!(), def.sig);
         if def.def_kind().is_class_or_trait() && self.module.context.kind != ContextKind::Module {
             self.module
@@ -1248,6 +1345,7 @@ impl ASTLowerer {
             .unwrap_or(Type::Failure);
         match registered_t {
             Type::Subr(subr_t) => {
+                let is_generator = body.block.contains_yield_of(sig.ident.inspect());
                 let mut params = self.lower_params(sig.params.clone())?;
                 if let Err(errs) = self.module.context.assign_params(&mut params, Some(subr_t)) {
                     self.errs.extend(errs);
@@ -1262,6 +1360,7 @@ impl ASTLowerer {
                             &sig,
                             body.id,
                             found_body_t,
+                            is_generator,
                         )?;
                         let return_t = vi.t.return_t().unwrap();
                         if return_t.union_types().is_some() && sig.return_t_spec.is_none() {
@@ -1280,7 +1379,8 @@ impl ASTLowerer {
                             self.warns.push(warn);
                         }
                         let ident = hir::Identifier::new(sig.ident.dot, sig.ident.name, None, vi);
-                        let sig = hir::SubrSignature::new(ident, params, sig.return_t_spec);
+                        let sig =
+                            hir::SubrSignature::new(ident, params, sig.return_t_spec, is_generator);
                         let body = hir::DefBody::new(body.op, block, body.id);
                         Ok(hir::Def::new(hir::Signature::Subr(sig), body))
                     }
@@ -1289,10 +1389,12 @@ impl ASTLowerer {
                             &sig,
                             ast::DefId(0),
                             &Type::Failure,
+                            is_generator,
                         )?;
                         self.errs.extend(errs);
                         let ident = hir::Identifier::new(sig.ident.dot, sig.ident.name, None, vi);
-                        let sig = hir::SubrSignature::new(ident, params, sig.return_t_spec);
+                        let sig =
+                            hir::SubrSignature::new(ident, params, sig.return_t_spec, is_generator);
                         let block =
                             hir::Block::new(vec![hir::Expr::Dummy(hir::Dummy::new(vec![]))]);
                         let body = hir::DefBody::new(body.op, block, body.id);
@@ -1316,7 +1418,7 @@ impl ASTLowerer {
                     .fake_subr_assign(&sig.ident, &sig.decorators, Type::Failure)?;
                 let block = self.lower_block(body.block)?;
                 let ident = hir::Identifier::bare(sig.ident.dot, sig.ident.name);
-                let sig = hir::SubrSignature::new(ident, params, sig.return_t_spec);
+                let sig = hir::SubrSignature::new(ident, params, sig.return_t_spec, false);
                 let body = hir::DefBody::new(body.op, block, body.id);
                 Ok(hir::Def::new(hir::Signature::Subr(sig), body))
             }
@@ -2135,6 +2237,7 @@ impl ASTLowerer {
 
     fn return_incomplete_artifact(&mut self, hir: HIR) -> IncompleteArtifact {
         self.module.context.clear_invalid_vars();
+        self.apply_lint_levels();
         IncompleteArtifact::new(
             Some(hir),
             LowerErrors::from(self.errs.take_all()),
@@ -2156,6 +2259,7 @@ impl ASTLowerer {
             })?;
         if mode == "declare" {
             let hir = self.declare_module(ast);
+            self.apply_lint_levels();
             if self.errs.is_empty() {
                 log!(info "HIR:\n{hir}");
                 log!(info "the declaring process has completed.");
@@ -2201,6 +2305,9 @@ impl ASTLowerer {
         };
         self.warn_unused_expr(&hir.module, mode);
         self.warn_unused_vars(mode);
+        self.warn_lowercase_types(&hir.module, mode);
+        self.warn_return_in_loop(&hir.module, mode);
+        self.apply_lint_levels();
         if self.errs.is_empty() {
             log!(info "the AST lowering process has completed.");
             Ok(CompleteArtifact::new(
@@ -7,6 +7,7 @@ use erg_common::spawn::exec_new_thread;
 use erg_common::traits::{ExitStatus, Runnable};
 
 use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::interpret::Interpreter;
 use erg_compiler::lower::ASTLowerer;
 use erg_compiler::transpile::Transpiler;
 use erg_compiler::ty::deserialize::Deserializer;
@@ -23,6 +24,7 @@ fn run() {
         TypeCheck => ASTLowerer::run(cfg),
         FullCheck => HIRBuilder::run(cfg),
         Transpile => Transpiler::run(cfg),
+        Interpret => Interpreter::run_in_new_thread(cfg),
         Compile | Execute => Compiler::run(cfg),
         Read => Deserializer::run(cfg),
         other => {
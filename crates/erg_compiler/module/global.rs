@@ -1,4 +1,7 @@
+use std::path::Path;
+
 use erg_common::config::ErgConfig;
+use erg_common::normalize_path;
 
 use super::cache::SharedModuleCache;
 use super::graph::SharedModuleGraph;
@@ -22,6 +25,31 @@ impl SharedCompilerResource {
         }
     }
 
+    /// Removes the module at `path` and the modules that depend on it from the caches.
+    /// The removed modules will be checked again when they are imported next time.
+    pub fn remove_module(&self, path: &Path) {
+        let mut paths = self.graph.dependents(path);
+        paths.insert(path.to_path_buf());
+        let cached = self
+            .mod_cache
+            .keys()
+            .chain(self.py_mod_cache.keys())
+            .collect::<Vec<_>>();
+        for path in paths.iter() {
+            self.graph.remove(path);
+            self.index.remove_path(path);
+        }
+        // the keys of the caches are not canonicalized
+        for key in cached {
+            let canonical = normalize_path(key.canonicalize().unwrap_or_else(|_| key.clone()));
+            if paths.contains(&key) || paths.contains(&canonical) {
+                self.mod_cache.remove(&key);
+                self.py_mod_cache.remove(&key);
+                self.index.remove_path(&key);
+            }
+        }
+    }
+
     pub fn clear_all(&self) {
         self.mod_cache.initialize();
         self.py_mod_cache.initialize();
@@ -1,9 +1,14 @@
 use std::path::{Path, PathBuf};
 
+use erg_common::set::Set;
 use erg_common::shared::Shared;
-use erg_common::tsort::{tsort, Graph, Node, TopoSortError};
+use erg_common::tsort::{dep_path, tsort, Graph, Node, TopoSortError};
 use erg_common::{normalize_path, set};
 
+fn canonicalize(path: &Path) -> PathBuf {
+    normalize_path(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()))
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ModuleGraph(Graph<PathBuf, ()>);
 
@@ -22,11 +27,12 @@ impl ModuleGraph {
     }
 
     pub fn get_node(&self, path: &Path) -> Option<&Node<PathBuf, ()>> {
+        let path = canonicalize(path);
         self.0.iter().find(|n| n.id == path)
     }
 
     pub fn add_node_if_none(&mut self, path: &Path) {
-        let path = normalize_path(path.to_path_buf());
+        let path = canonicalize(path);
         if self.0.iter().all(|n| n.id != path) {
             let node = Node::new(path, (), set! {});
             self.0.push(node);
@@ -34,7 +40,8 @@ impl ModuleGraph {
     }
 
     pub fn inc_ref(&mut self, referrer: &Path, depends_on: PathBuf) {
-        let depends_on = normalize_path(depends_on);
+        let referrer = canonicalize(referrer);
+        let depends_on = canonicalize(&depends_on);
         if let Some(node) = self.0.iter_mut().find(|n| n.id == referrer) {
             if referrer == depends_on {
                 return;
@@ -49,14 +56,61 @@ impl ModuleGraph {
         self.0.iter()
     }
 
+    /// returns the modules that depend on `path` directly or indirectly (`path` itself is not included)
+    pub fn dependents(&self, path: &Path) -> Set<PathBuf> {
+        let mut dependents = set! {};
+        let mut pending = vec![canonicalize(path)];
+        while let Some(dep) = pending.pop() {
+            for node in self.0.iter() {
+                if node.depends_on(&dep) && !dependents.contains(&node.id) {
+                    dependents.insert(node.id.clone());
+                    pending.push(node.id.clone());
+                }
+            }
+        }
+        dependents
+    }
+
+    /// returns the modules that `path` depends on directly or indirectly
+    /// (`path` itself is included only if it is imported cyclically)
+    pub fn dependencies(&self, path: &Path) -> Set<PathBuf> {
+        let mut dependencies = set! {};
+        let mut pending = vec![canonicalize(path)];
+        while let Some(referrer) = pending.pop() {
+            let Some(node) = self.0.iter().find(|n| n.id == referrer) else {
+                continue;
+            };
+            for dep in self.0.iter() {
+                if node.depends_on(&dep.id) && !dependencies.contains(&dep.id) {
+                    dependencies.insert(dep.id.clone());
+                    pending.push(dep.id.clone());
+                }
+            }
+        }
+        dependencies
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        let path = canonicalize(path);
+        self.0.retain(|node| node.id != path);
+    }
+
+    /// returns the import path `from -> ... -> to`, if `from` depends on `to`
+    pub fn dep_path(&self, from: &Path, to: &Path) -> Option<Vec<PathBuf>> {
+        let from = canonicalize(from);
+        let to = canonicalize(to);
+        dep_path(&self.0, &from, &to)
+    }
+
     #[allow(clippy::result_unit_err)]
     pub fn sorted(self) -> Result<Self, TopoSortError> {
         tsort(self.0).map(Self)
     }
 
+    /// The graph is left unchanged if it has cyclic references.
     #[allow(clippy::result_unit_err)]
     pub fn sort(&mut self) -> Result<(), TopoSortError> {
-        *self = std::mem::take(self).sorted()?;
+        *self = self.clone().sorted()?;
         Ok(())
     }
 
@@ -102,6 +156,22 @@ impl SharedModuleGraph {
         ref_graph.iter()
     }
 
+    pub fn dep_path(&self, from: &Path, to: &Path) -> Option<Vec<PathBuf>> {
+        self.0.borrow().dep_path(from, to)
+    }
+
+    pub fn dependents(&self, path: &Path) -> Set<PathBuf> {
+        self.0.borrow().dependents(path)
+    }
+
+    pub fn dependencies(&self, path: &Path) -> Set<PathBuf> {
+        self.0.borrow().dependencies(path)
+    }
+
+    pub fn remove(&self, path: &Path) {
+        self.0.borrow_mut().remove(path);
+    }
+
     #[allow(clippy::result_unit_err)]
     pub fn sort(&self) -> Result<(), TopoSortError> {
         self.0.borrow_mut().sort()
@@ -0,0 +1,572 @@
+//! Interface files of Erg modules.
+//!
+//! An interface file is a declaration file (`.d.er`) generated from the context of a checked module.
+//! It is stored in the cache directory of Erg (not in the source tree), and its header records the hashes of the module
+//! and of the modules it depends on. While the hashes match, the interface is loaded instead of checking the module again.
+//!
+//! ```erg
+//! ##[interface] 0.6.10 1234567890
+//! ##[depends] 9876543210 /path/to/dep.er
+//! .C = Class({.x = Int})
+//! .C.
+//!     f: (self: .C) -> Int
+//! .g: (x: Int) -> .C
+//! ```
+use std::fmt;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use erg_common::dict::Dict;
+use erg_common::env::erg_path;
+use erg_common::{get_hash, Str};
+
+use erg_parser::ast::VarName;
+
+use crate::context::{ClassDefType, Context};
+use crate::ty::free::{Constraint, HasLevel};
+use crate::ty::typaram::TyParam;
+use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
+use crate::ty::{Predicate, SubrKind, SubrType, Type};
+use crate::varinfo::{VarInfo, VarKind};
+
+pub const INTERFACE_DIR: &str = "interface";
+
+/// `foo/bar.er` -> `$ERG_PATH/cache/interface/bar-{hash of the path}.d.er`
+pub fn interface_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    erg_path()
+        .join("cache")
+        .join(INTERFACE_DIR)
+        .join(format!("{stem}-{}.d.er", get_hash(&path)))
+}
+
+pub fn source_hash(path: &Path) -> Option<usize> {
+    read_to_string(path).ok().map(|src| get_hash(&src))
+}
+
+/// format:
+/// ```python
+/// ##[interface] 0.6.10 1234567890
+/// ##[depends] 9876543210 foo.er
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InterfaceHeader {
+    pub version: Str,
+    pub hash: usize,
+    pub deps: Vec<(PathBuf, usize)>,
+}
+
+impl fmt::Display for InterfaceHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "##[interface] {} {}", self.version, self.hash)?;
+        for (path, hash) in self.deps.iter() {
+            writeln!(f, "##[depends] {hash} {}", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for InterfaceHeader {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let mut header = lines.next().ok_or("no header")?.split_whitespace();
+        if header.next() != Some("##[interface]") {
+            return Err("not an interface".to_string());
+        }
+        let version = Str::rc(header.next().ok_or("no version")?);
+        let hash = header
+            .next()
+            .ok_or("no hash")?
+            .parse()
+            .map_err(|e| format!("hash parse error: {e}"))?;
+        let mut deps = vec![];
+        for line in lines {
+            let Some(dep) = line.strip_prefix("##[depends] ") else {
+                break;
+            };
+            let (hash, path) = dep.split_once(' ').ok_or("no dependency path")?;
+            let hash = hash.parse().map_err(|e| format!("hash parse error: {e}"))?;
+            deps.push((PathBuf::from(path), hash));
+        }
+        Ok(Self {
+            version,
+            hash,
+            deps,
+        })
+    }
+}
+
+impl InterfaceHeader {
+    pub fn new(hash: usize, deps: Vec<(PathBuf, usize)>) -> Self {
+        Self {
+            version: Str::ever(env!("CARGO_PKG_VERSION")),
+            hash,
+            deps,
+        }
+    }
+
+    /// Returns `true` if neither the compiler, the module nor its dependencies have changed.
+    pub fn is_fresh(&self, hash: usize) -> bool {
+        &self.version[..] == env!("CARGO_PKG_VERSION")
+            && self.hash == hash
+            && self
+                .deps
+                .iter()
+                .all(|(path, hash)| source_hash(path) == Some(*hash))
+    }
+}
+
+/// Reads the interface of the module at `path`.
+/// Returns `None` if there is no interface or it is outdated.
+pub fn read_interface(path: &Path, hash: usize) -> Option<(InterfaceHeader, String)> {
+    let decl = read_to_string(interface_path(path)).ok()?;
+    let header = decl.parse::<InterfaceHeader>().ok()?;
+    header.is_fresh(hash).then_some((header, decl))
+}
+
+/// Writes the interface of the module at `path`.
+/// Nothing is written if the public members of the module cannot be declared.
+pub fn write_interface(path: &Path, header: InterfaceHeader, ctx: &Context) {
+    let Some(decl) = DeclGenerator::new(ctx).generate() else {
+        return;
+    };
+    let path = interface_path(path);
+    if let Some(dir) = path.parent() {
+        let _ = create_dir_all(dir);
+    }
+    let _ = write(path, format!("{header}{decl}"));
+}
+
+/// The public members of the module, in the order of their definitions.
+pub(crate) fn public_members(ctx: &Context) -> Vec<(&VarName, &VarInfo)> {
+    let mut members = ctx
+        .locals
+        .iter()
+        .filter(|(_, vi)| vi.vis.is_public() && DeclGenerator::is_user_defined(vi))
+        .collect::<Vec<_>>();
+    members.sort_by_key(|(_, vi)| (vi.def_loc.loc.ln_begin(), vi.def_loc.loc.col_begin()));
+    members
+}
+
+/// The name of the member in the compiled module (see `codegen::escape_name`).
+pub(crate) fn py_name(name: &str, vi: &VarInfo) -> String {
+    if let Some(py_name) = &vi.py_name {
+        return py_name.to_string();
+    }
+    name.replace('!', "__erg_proc__")
+        .replace('$', "__erg_shared__")
+}
+
+pub(crate) fn is_ident(name: &str) -> bool {
+    let name = name.trim_end_matches('!');
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Generates declarations (`.d.er`) of the public members of a module.
+pub struct DeclGenerator<'c> {
+    ctx: &'c Context,
+    /// module name -> (`import` or `pyimport`)
+    modules: Dict<Str, &'static str>,
+    /// imports required by the declarations
+    imports: Vec<String>,
+    /// renamed type variables (e.g. `%3` -> `T3`)
+    qvars: Dict<Str, Str>,
+    /// members that cannot be declared
+    skipped: Vec<Str>,
+}
+
+impl<'c> DeclGenerator<'c> {
+    pub fn new(ctx: &'c Context) -> Self {
+        let mut modules = Dict::new();
+        for vi in ctx.locals.values() {
+            if let Some((name, import)) = Self::module_of(&vi.t) {
+                modules.insert(name, import);
+            }
+        }
+        Self {
+            ctx,
+            modules,
+            imports: vec![],
+            qvars: Dict::new(),
+            skipped: vec![],
+        }
+    }
+
+    pub(crate) fn module_of(t: &Type) -> Option<(Str, &'static str)> {
+        let Type::Poly { name, params } = t else {
+            return None;
+        };
+        let import = match &name[..] {
+            "Module" => "import",
+            "PyModule" => "pyimport",
+            _ => return None,
+        };
+        match params.first() {
+            Some(TyParam::Value(ValueObj::Str(name))) => Some((name.clone(), import)),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` if some public member cannot be declared.
+    pub fn generate(self) -> Option<String> {
+        let (decl, skipped) = self.generate_members(false);
+        skipped.is_empty().then_some(decl)
+    }
+
+    /// Declares the public members except those that cannot be declared,
+    /// widening the types of variables if needed (e.g. `{3.14}` -> `Float`).
+    /// Returns the declarations and the names of the skipped members.
+    pub fn generate_partial(self) -> (String, Vec<Str>) {
+        self.generate_members(true)
+    }
+
+    fn generate_members(mut self, widen: bool) -> (String, Vec<Str>) {
+        let members = public_members(self.ctx);
+        // skipping a type makes the members referring to it undeclarable, so retry until no type is skipped
+        loop {
+            self.imports.clear();
+            let mut types = String::new();
+            let mut vars = String::new();
+            let mut type_skipped = false;
+            for (name, vi) in members.iter() {
+                let name = name.inspect();
+                if self.skipped.contains(name) {
+                    continue;
+                }
+                let imported = self.imports.len();
+                let is_type = matches!(vi.t, Type::ClassType | Type::TraitType);
+                let decl = if let Some((module, import)) = Self::module_of(&vi.t) {
+                    Some(format!(".{name} = {import} \"{module}\"\n"))
+                } else if is_type {
+                    self.type_def(name)
+                } else {
+                    let py_name = py_name(name, vi);
+                    self.decl_type(&vi.t)
+                        .or_else(|| widen.then(|| self.decl_type(&vi.t.derefine())).flatten())
+                        .map(|t| {
+                            // declared names are referred to without `!` by default
+                            if py_name == name.trim_end_matches('!') {
+                                format!(".{name}: {t}\n")
+                            } else {
+                                format!(".{name} = '{py_name}': {t}\n")
+                            }
+                        })
+                };
+                match decl {
+                    Some(decl) if is_type => types += &decl,
+                    Some(decl) => vars += &decl,
+                    None => {
+                        self.imports.truncate(imported);
+                        self.skipped.push(name.clone());
+                        type_skipped |= is_type;
+                    }
+                }
+            }
+            if !type_skipped {
+                let mut decl = String::new();
+                for import in self.imports.iter() {
+                    decl += import;
+                }
+                return (decl + &types + &vars, self.skipped);
+            }
+        }
+    }
+
+    pub(crate) fn is_user_defined(vi: &VarInfo) -> bool {
+        !matches!(
+            vi.kind,
+            VarKind::Auto | VarKind::FixedAuto | VarKind::Builtin | VarKind::DoesNotExist
+        )
+    }
+
+    fn type_def(&mut self, name: &Str) -> Option<String> {
+        let Some(ValueObj::Type(TypeObj::Generated(gen))) = self.ctx.consts.get(name) else {
+            return None;
+        };
+        let (mut def, impls) = match gen {
+            GenTypeObj::Class(class) => {
+                let base = match class.base.as_deref() {
+                    Some(base) => self.type_spec(base.typ())?,
+                    None => "".into(),
+                };
+                (format!("Class({base}"), class.impls.as_deref())
+            }
+            GenTypeObj::Subclass(subclass) => {
+                let mut def = format!("Inherit({}", self.type_spec(subclass.sup.typ())?);
+                if let Some(additional) = subclass.additional.as_deref() {
+                    def += &format!(", Additional := {}", self.type_spec(additional.typ())?);
+                }
+                (def, subclass.impls.as_deref())
+            }
+            GenTypeObj::Trait(trait_) => (
+                format!("Trait({}", self.type_spec(trait_.requires.typ())?),
+                trait_.impls.as_deref(),
+            ),
+            _ => return None,
+        };
+        // `Inheritable` is recorded as an implementation of `InheritableType`
+        let mut inheritable = false;
+        let impls = match impls.map(|impls| impls.typ()) {
+            Some(Type::And(l, r)) if r.as_ref() == &Type::Mono("InheritableType".into()) => {
+                inheritable = true;
+                Some(l.as_ref())
+            }
+            Some(Type::Mono(name)) if &name[..] == "InheritableType" => {
+                inheritable = true;
+                None
+            }
+            other => other,
+        };
+        if let Some(impls) = impls {
+            def += &format!(", Impl := {}", self.type_spec(impls)?);
+        }
+        def += ")";
+        if inheritable {
+            def = format!("Inheritable({def})");
+        }
+        let mut decl = format!(".{name} = {def}\n");
+        let (_, type_ctx) = self.ctx.mono_types.get(name)?;
+        for (def_t, methods) in type_ctx.methods_list.iter() {
+            if let ClassDefType::ImplTrait { impl_trait, .. } = def_t {
+                decl += &format!(".{name} <: {}\n", self.type_spec(impl_trait)?);
+            }
+            let mut attrs = methods
+                .locals
+                .iter()
+                .filter(|(_, vi)| vi.vis.is_public() && Self::is_user_defined(vi))
+                .collect::<Vec<_>>();
+            if attrs.is_empty() {
+                continue;
+            }
+            attrs.sort_by_key(|(_, vi)| (vi.def_loc.loc.ln_begin(), vi.def_loc.loc.col_begin()));
+            decl += &format!(".{name}.\n");
+            for (attr, vi) in attrs {
+                decl += &format!("    {}: {}\n", attr.inspect(), self.decl_type(&vi.t)?);
+            }
+        }
+        Some(decl)
+    }
+
+    /// e.g. `|T: Type|(x: T) -> T`
+    fn decl_type(&mut self, t: &Type) -> Option<String> {
+        self.qvars.clear();
+        let mut qvars = t.qvars().into_iter().collect::<Vec<_>>();
+        qvars.sort_by(|(l, _), (r, _)| l.cmp(r));
+        for (i, (name, _)) in qvars.iter().enumerate() {
+            let renamed = if is_ident(name) {
+                name.clone()
+            } else {
+                Str::from(format!("T{i}"))
+            };
+            self.qvars.insert(name.clone(), renamed);
+        }
+        let mut bounds = vec![];
+        for (name, constraint) in qvars.iter() {
+            let name = self.qvars.get(name)?.clone();
+            let bound = match constraint {
+                Constraint::Sandwiched { sub, sup } if sub == &Type::Never => {
+                    if sup == &Type::Obj {
+                        format!("{name}: Type")
+                    } else {
+                        format!("{name} <: {}", self.type_spec(sup)?)
+                    }
+                }
+                Constraint::TypeOf(t) => format!("{name}: {}", self.type_spec(t)?),
+                _ => return None,
+            };
+            bounds.push(bound);
+        }
+        let spec = self.type_spec(t)?;
+        if bounds.is_empty() {
+            Some(spec)
+        } else {
+            Some(format!("|{}|{spec}", bounds.join(", ")))
+        }
+    }
+
+    fn type_spec(&mut self, t: &Type) -> Option<String> {
+        match t {
+            Type::Obj
+            | Type::Int
+            | Type::Nat
+            | Type::Ratio
+            | Type::Float
+            | Type::Bool
+            | Type::Str
+            | Type::NoneType
+            | Type::Code
+            | Type::Frame
+            | Type::Error
+            | Type::Type
+            | Type::ClassType
+            | Type::TraitType
+            | Type::Never => Some(t.to_string()),
+            Type::Mono(name) => self.type_name(name),
+            Type::Ref(t) => Some(format!("Ref({})", self.type_spec(t)?)),
+            Type::RefMut {
+                before,
+                after: None,
+            } => Some(format!("RefMut({})", self.type_spec(before)?)),
+            Type::Subr(subr) => self.subr_spec(subr),
+            Type::Record(rec) if rec.is_empty() => Some("{=}".into()),
+            Type::Record(rec) => {
+                let mut fields = vec![];
+                for (field, t) in rec.iter() {
+                    fields.push(format!("{field} = {}", self.type_spec(t)?));
+                }
+                Some(format!("{{{}}}", fields.join("; ")))
+            }
+            Type::Refinement(refine) => {
+                let mut values = vec![];
+                for pred in refine.preds.iter() {
+                    match pred {
+                        Predicate::Equal { lhs, rhs } if lhs == &refine.var => {
+                            values.push(self.typaram_spec(rhs)?);
+                        }
+                        _ => return None,
+                    }
+                }
+                Some(format!("{{{}}}", values.join(", ")))
+            }
+            Type::Quantified(t) => self.type_spec(t),
+            Type::And(l, r) if !matches!(l.as_ref(), Type::Or(_, _)) => {
+                Some(format!("{} and {}", self.type_spec(l)?, self.type_spec(r)?))
+            }
+            Type::Or(l, r) if !matches!(l.as_ref(), Type::And(_, _)) => {
+                Some(format!("{} or {}", self.type_spec(l)?, self.type_spec(r)?))
+            }
+            Type::Poly { name, params } => {
+                let name = self.type_name(name)?;
+                let mut args = vec![];
+                for param in params.iter() {
+                    args.push(self.typaram_spec(param)?);
+                }
+                Some(format!("{name}({})", args.join(", ")))
+            }
+            Type::Proj { lhs, rhs } if is_ident(rhs) => {
+                Some(format!("{}.{rhs}", self.type_spec(lhs)?))
+            }
+            Type::FreeVar(fv) if fv.is_linked() => self.type_spec(&fv.crack()),
+            Type::FreeVar(fv) if fv.is_generalized() => self
+                .qvars
+                .get(&fv.unbound_name()?)
+                .map(|name| name.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Types defined in this module are referred to as `.C`,
+    /// and types defined in the other modules are referred to through their imports.
+    fn type_name(&mut self, name: &Str) -> Option<String> {
+        let own = &self.ctx.name;
+        let local = name
+            .strip_prefix(&format!("{own}."))
+            .or_else(|| name.strip_prefix(&format!("{own}::")));
+        if let Some(local) = local {
+            let is_public_type = self
+                .ctx
+                .locals
+                .get(local)
+                .is_some_and(|vi| vi.vis.is_public())
+                && !self.skipped.iter().any(|skipped| &skipped[..] == local);
+            return (is_ident(local) && is_public_type).then(|| format!(".{local}"));
+        }
+        match name.rsplit_once('.') {
+            Some((module, local)) if is_ident(local) => {
+                let import = *self.modules.get(module)?;
+                let alias = format!("_{}", module.replace(|c: char| !c.is_alphanumeric(), "_"));
+                let line = format!("{alias} = {import} \"{module}\"\n");
+                if !self.imports.contains(&line) {
+                    self.imports.push(line);
+                }
+                Some(format!("{alias}.{local}"))
+            }
+            Some(_) => None,
+            None if is_ident(name) => Some(name.to_string()),
+            None => None,
+        }
+    }
+
+    fn subr_spec(&mut self, subr: &SubrType) -> Option<String> {
+        if subr.kw_var_params.is_some() {
+            return None;
+        }
+        let mut params = vec![];
+        for (i, param) in subr.non_default_params.iter().enumerate() {
+            let name = match param.name() {
+                Some(name) => name.to_string(),
+                None => format!("_{i}"),
+            };
+            params.push(format!("{name}: {}", self.type_spec(param.typ())?));
+        }
+        if let Some(var_params) = subr.var_params.as_deref() {
+            let name = var_params
+                .name()
+                .map_or("args", |name| &name[..])
+                .to_string();
+            params.push(format!("*{name}: {}", self.type_spec(var_params.typ())?));
+        }
+        for param in subr.default_params.iter() {
+            let name = param.name()?.clone();
+            params.push(format!("{name} := {}", self.type_spec(param.typ())?));
+        }
+        let arrow = match subr.kind {
+            SubrKind::Func => "->",
+            SubrKind::Proc => "=>",
+        };
+        Some(format!(
+            "({}) {arrow} {}",
+            params.join(", "),
+            self.type_spec(&subr.return_t)?
+        ))
+    }
+
+    fn typaram_spec(&mut self, tp: &TyParam) -> Option<String> {
+        match tp {
+            TyParam::Value(value) => self.value_spec(value),
+            TyParam::Type(t) => self.type_spec(t),
+            TyParam::Array(tps) => {
+                let mut elems = vec![];
+                for tp in tps.iter() {
+                    elems.push(self.typaram_spec(tp)?);
+                }
+                Some(format!("[{}]", elems.join(", ")))
+            }
+            TyParam::Dict(dict) => {
+                let mut entries = vec![];
+                for (k, v) in dict.iter() {
+                    entries.push(format!(
+                        "{}: {}",
+                        self.typaram_spec(k)?,
+                        self.typaram_spec(v)?
+                    ));
+                }
+                Some(format!("{{{}}}", entries.join(", ")))
+            }
+            TyParam::FreeVar(fv) if fv.is_linked() => self.typaram_spec(&fv.crack()),
+            TyParam::FreeVar(fv) if fv.is_generalized() => self
+                .qvars
+                .get(&fv.unbound_name()?)
+                .map(|name| name.to_string()),
+            _ => None,
+        }
+    }
+
+    fn value_spec(&mut self, value: &ValueObj) -> Option<String> {
+        match value {
+            ValueObj::Nat(_) | ValueObj::Int(_) | ValueObj::Bool(_) | ValueObj::None => {
+                Some(value.to_string())
+            }
+            ValueObj::Str(s) if !s.contains(['"', '\\', '\n', '{']) => Some(value.to_string()),
+            ValueObj::Type(t) => self.type_spec(t.typ()),
+            _ => None,
+        }
+    }
+}
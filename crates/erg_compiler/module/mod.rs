@@ -2,6 +2,8 @@ pub mod cache;
 pub mod global;
 pub mod graph;
 pub mod index;
+pub mod interface;
+pub mod package;
 
 pub use cache::*;
 pub use global::*;
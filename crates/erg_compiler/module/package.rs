@@ -0,0 +1,659 @@
+//! Erg packages and their dependencies.
+//!
+//! A package is a directory that has a manifest file (`package.er`), e.g.
+//!
+//! ```erg
+//! name = "example"
+//! version = "0.1.0"
+//! type = "app" # "app", "lib" or "hybrid"
+//! dependencies = {
+//!     foo = pack("foo", "1.*.*", path := "../foo")
+//!     bar = pack("bar", git := "https://github.com/example/bar")
+//! }
+//! ```
+//!
+//! The dependencies are vendored in `.erg/deps/<alias>` (see `erg pack install`),
+//! and the versions actually installed are recorded in the lockfile (`package.lock.er`).
+use std::cell::RefCell;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use erg_common::config::{ErgConfig, Input};
+use erg_common::dict::Dict;
+use erg_common::error::MultiErrorDisplay;
+use erg_common::normalize_path;
+use erg_common::semver::{Version, VersionReq};
+use erg_common::traits::{Runnable, Stream};
+
+use erg_parser::ast::{Accessor, Array, Expr, Record, RecordAttrOrIdent};
+use erg_parser::token::TokenKind;
+use erg_parser::ParserRunner;
+
+pub const MANIFEST_FILE: &str = "package.er";
+pub const LOCK_FILE: &str = "package.lock.er";
+/// relative to the package root
+pub const DEPS_DIR: &str = ".erg/deps";
+
+thread_local! {
+    /// package root -> (mtime of the manifest, manifest)
+    static MANIFESTS: RefCell<Dict<PathBuf, (SystemTime, Manifest)>> = RefCell::new(Dict::new());
+    /// (root of the dependency, path in the dependency) -> (mtime of the manifest, resolved path)
+    static RESOLVED_DEPS: RefCell<Dict<(PathBuf, PathBuf), (SystemTime, PathBuf)>> =
+        RefCell::new(Dict::new());
+}
+
+fn manifest_mtime(root: &Path) -> Option<SystemTime> {
+    fs::metadata(root.join(MANIFEST_FILE))
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// The values that can be written in `package.er` and `package.lock.er`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestValue {
+    Str(String),
+    Nat(u64),
+    Bool(bool),
+    Array(Vec<ManifestValue>),
+    Record(Vec<(String, ManifestValue)>),
+    /// `pack(name, version, path := ..., git := ..., rev := ...)`
+    Pack(Vec<ManifestValue>, Vec<(String, ManifestValue)>),
+}
+
+impl ManifestValue {
+    fn eval(expr: &Expr) -> Result<Self, String> {
+        match expr {
+            Expr::Literal(lit) => {
+                let content = &lit.token.content;
+                match lit.token.kind {
+                    TokenKind::StrLit => Ok(Self::Str(
+                        content
+                            .trim_start_matches('"')
+                            .trim_end_matches('"')
+                            .to_string(),
+                    )),
+                    TokenKind::NatLit => content
+                        .replace('_', "")
+                        .parse()
+                        .map(Self::Nat)
+                        .map_err(|_| format!("invalid number: {content}")),
+                    TokenKind::BoolLit => Ok(Self::Bool(&content[..] == "True")),
+                    _ => Err(format!("unsupported literal: {content}")),
+                }
+            }
+            Expr::Array(Array::Normal(arr)) => arr
+                .elems
+                .pos_args()
+                .iter()
+                .map(|arg| Self::eval(&arg.expr))
+                .collect::<Result<_, _>>()
+                .map(Self::Array),
+            Expr::Record(record) => {
+                let attrs: Vec<_> = match record {
+                    Record::Normal(rec) => rec.attrs.iter().collect(),
+                    Record::Mixed(rec) => rec
+                        .attrs
+                        .iter()
+                        .filter_map(|attr| match attr {
+                            RecordAttrOrIdent::Attr(def) => Some(def),
+                            RecordAttrOrIdent::Ident(_) => None,
+                        })
+                        .collect(),
+                };
+                let mut fields = vec![];
+                for def in attrs {
+                    let name = def
+                        .sig
+                        .ident()
+                        .ok_or_else(|| format!("invalid field: {}", def.sig))?;
+                    let value = Self::eval_block(def.body.block.ref_payload())?;
+                    fields.push((name.inspect().to_string(), value));
+                }
+                Ok(Self::Record(fields))
+            }
+            Expr::Call(call) => match call.obj.as_ref() {
+                Expr::Accessor(Accessor::Ident(ident)) if &ident.inspect()[..] == "pack" => {
+                    let pos_args = call
+                        .args
+                        .pos_args()
+                        .iter()
+                        .map(|arg| Self::eval(&arg.expr))
+                        .collect::<Result<_, _>>()?;
+                    let mut kw_args = vec![];
+                    for arg in call.args.kw_args() {
+                        kw_args.push((arg.keyword.content.to_string(), Self::eval(&arg.expr)?));
+                    }
+                    Ok(Self::Pack(pos_args, kw_args))
+                }
+                _ => Err(format!("unsupported expression: {expr}")),
+            },
+            _ => Err(format!("unsupported expression: {expr}")),
+        }
+    }
+
+    fn eval_block(block: &[Expr]) -> Result<Self, String> {
+        match block {
+            [expr] => Self::eval(expr),
+            _ => Err("a value must be a single expression".into()),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the top-level definitions of an Erg file as values.
+fn read_values(path: &Path) -> Result<Vec<(String, ManifestValue)>, String> {
+    let src =
+        fs::read_to_string(path).map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+    let cfg = ErgConfig {
+        input: Input::File(path.to_path_buf()),
+        ..ErgConfig::default()
+    };
+    let module = ParserRunner::new(cfg).parse(src).map_err(|errs| {
+        errs.fmt_all_stderr();
+        format!("failed to parse {}", path.display())
+    })?;
+    let mut values = vec![];
+    for chunk in module.iter() {
+        let Expr::Def(def) = chunk else {
+            return Err(format!("{}: only definitions are allowed", path.display()));
+        };
+        let name = def
+            .sig
+            .ident()
+            .ok_or_else(|| format!("{}: invalid definition: {}", path.display(), def.sig))?;
+        let value = ManifestValue::eval_block(def.body.block.ref_payload())
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        values.push((name.inspect().to_string(), value));
+    }
+    Ok(values)
+}
+
+fn expect_str(key: &str, value: &ManifestValue) -> Result<String, String> {
+    value
+        .as_str()
+        .map(ToString::to_string)
+        .ok_or_else(|| format!("`{key}` must be a string"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageType {
+    App,
+    Lib,
+    Hybrid,
+}
+
+impl fmt::Display for PackageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::App => write!(f, "app"),
+            Self::Lib => write!(f, "lib"),
+            Self::Hybrid => write!(f, "hybrid"),
+        }
+    }
+}
+
+impl std::str::FromStr for PackageType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "app" => Ok(Self::App),
+            "lib" => Ok(Self::Lib),
+            "hybrid" => Ok(Self::Hybrid),
+            _ => Err(format!(
+                "invalid package type: {s} (expected app, lib or hybrid)"
+            )),
+        }
+    }
+}
+
+impl PackageType {
+    pub const fn is_app(&self) -> bool {
+        matches!(self, Self::App | Self::Hybrid)
+    }
+
+    pub const fn is_lib(&self) -> bool {
+        matches!(self, Self::Lib | Self::Hybrid)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencySource {
+    /// relative to the package root
+    Path(PathBuf),
+    Git {
+        url: String,
+        rev: Option<String>,
+    },
+}
+
+impl fmt::Display for DependencySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "path+{}", path.display()),
+            Self::Git { url, .. } => write!(f, "git+{url}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    /// the name used in `import`
+    pub alias: String,
+    /// the name of the package
+    pub name: String,
+    pub version: VersionReq,
+    pub source: DependencySource,
+}
+
+impl Dependency {
+    fn from_value(alias: String, value: &ManifestValue) -> Result<Self, String> {
+        let ManifestValue::Pack(pos_args, kw_args) = value else {
+            return Err(format!(
+                "the dependency `{alias}` must be declared with `pack(...)`"
+            ));
+        };
+        let mut pos_args = pos_args.iter();
+        let name = match pos_args.next() {
+            Some(name) => expect_str("name", name)?,
+            None => alias.clone(),
+        };
+        let mut version = match pos_args.next() {
+            Some(version) => expect_str("version", version)?.parse()?,
+            None => VersionReq::ANY,
+        };
+        let (mut path, mut git, mut rev) = (None, None, None);
+        for (key, value) in kw_args.iter() {
+            match &key[..] {
+                "version" => version = expect_str(key, value)?.parse()?,
+                "path" => path = Some(expect_str(key, value)?),
+                "git" => git = Some(expect_str(key, value)?),
+                "rev" => rev = Some(expect_str(key, value)?),
+                _ => return Err(format!("unknown key of `{alias}`: {key}")),
+            }
+        }
+        let source = match (path, git) {
+            (Some(path), None) => DependencySource::Path(PathBuf::from(path)),
+            (None, Some(url)) => DependencySource::Git { url, rev },
+            (None, None) => {
+                return Err(format!(
+                    "the dependency `{alias}` needs `path` or `git` (the package registry is not available yet)"
+                ))
+            }
+            (Some(_), Some(_)) => {
+                return Err(format!(
+                    "the dependency `{alias}` cannot have both `path` and `git`"
+                ))
+            }
+        };
+        Ok(Self {
+            alias,
+            name,
+            version,
+            source,
+        })
+    }
+
+    /// e.g. `foo = pack("foo", "1.*.*", path := "../foo")`
+    pub fn to_erg(&self) -> String {
+        let mut code = format!("{} = pack(\"{}\"", self.alias, self.name);
+        if self.version != VersionReq::ANY {
+            code += &format!(", \"{}\"", self.version);
+        }
+        match &self.source {
+            DependencySource::Path(path) => {
+                code += &format!(", path := \"{}\"", path.display());
+            }
+            DependencySource::Git { url, rev } => {
+                code += &format!(", git := \"{url}\"");
+                if let Some(rev) = rev {
+                    code += &format!(", rev := \"{rev}\"");
+                }
+            }
+        }
+        code + ")"
+    }
+}
+
+/// `package.er`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub name: String,
+    pub version: Version,
+    pub description: String,
+    pub authors: Vec<String>,
+    pub license: String,
+    pub package_type: PackageType,
+    /// the entry point of the application (default: `src/main.er`)
+    pub main: PathBuf,
+    /// the entry point of the library (default: `src/lib.er`)
+    pub lib: PathBuf,
+    pub dependencies: Vec<Dependency>,
+}
+
+impl Manifest {
+    /// Reads `package.er` in `root`.
+    pub fn read(root: &Path) -> Result<Self, String> {
+        let values = read_values(&root.join(MANIFEST_FILE))?;
+        Self::from_values(values).map_err(|err| format!("{}: {err}", root.display()))
+    }
+
+    /// Same as `read`, but the manifest is read again only if it has been modified.
+    pub fn read_cached(root: &Path) -> Result<Self, String> {
+        let mtime = manifest_mtime(root);
+        let cached = MANIFESTS.with(|manifests| {
+            manifests
+                .borrow()
+                .get(root)
+                .filter(|(checked, _)| Some(checked) == mtime.as_ref())
+                .map(|(_, manifest)| manifest.clone())
+        });
+        if let Some(manifest) = cached {
+            return Ok(manifest);
+        }
+        let manifest = Self::read(root)?;
+        if let Some(mtime) = mtime {
+            MANIFESTS.with(|manifests| {
+                manifests
+                    .borrow_mut()
+                    .insert(root.to_path_buf(), (mtime, manifest.clone()))
+            });
+        }
+        Ok(manifest)
+    }
+
+    fn from_values(values: Vec<(String, ManifestValue)>) -> Result<Self, String> {
+        let (mut name, mut version) = (None, None);
+        let mut manifest = Self {
+            name: String::new(),
+            version: Version::new(0, 1, 0, None),
+            description: String::new(),
+            authors: vec![],
+            license: String::new(),
+            package_type: PackageType::App,
+            main: PathBuf::from("src/main.er"),
+            lib: PathBuf::from("src/lib.er"),
+            dependencies: vec![],
+        };
+        for (key, value) in values.iter() {
+            match &key[..] {
+                "name" => name = Some(expect_str(key, value)?),
+                "version" => version = Some(expect_str(key, value)?.parse()?),
+                "description" => manifest.description = expect_str(key, value)?,
+                "license" => manifest.license = expect_str(key, value)?,
+                "type" => manifest.package_type = expect_str(key, value)?.parse()?,
+                "main" => manifest.main = PathBuf::from(expect_str(key, value)?),
+                "lib" => manifest.lib = PathBuf::from(expect_str(key, value)?),
+                "authors" => {
+                    let ManifestValue::Array(authors) = value else {
+                        return Err("`authors` must be an array of strings".into());
+                    };
+                    manifest.authors = authors
+                        .iter()
+                        .map(|author| expect_str(key, author))
+                        .collect::<Result<_, _>>()?;
+                }
+                "dependencies" => {
+                    let ManifestValue::Record(deps) = value else {
+                        return Err("`dependencies` must be a record".into());
+                    };
+                    for (alias, dep) in deps.iter() {
+                        let dep = Dependency::from_value(alias.clone(), dep)?;
+                        manifest.dependencies.push(dep);
+                    }
+                }
+                // reserved for the future use
+                _ => {}
+            }
+        }
+        manifest.name = name.ok_or("`name` is not specified")?;
+        manifest.version = version.ok_or("`version` is not specified")?;
+        Ok(manifest)
+    }
+}
+
+/// An entry of `package.lock.er`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub alias: String,
+    pub version: Version,
+    /// e.g. `path+../foo`, `git+https://github.com/example/foo`
+    pub source: String,
+    /// the commit hash (only for git dependencies)
+    pub rev: Option<String>,
+    /// the installed directory (relative to the package root)
+    pub path: PathBuf,
+}
+
+impl LockedPackage {
+    fn from_value(value: &ManifestValue) -> Result<Self, String> {
+        let ManifestValue::Record(fields) = value else {
+            return Err("a locked package must be a record".into());
+        };
+        let get = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .and_then(|(_, v)| v.as_str())
+                .ok_or_else(|| format!("`{key}` is missing in a locked package"))
+        };
+        let rev = get("rev")?;
+        Ok(Self {
+            name: get("name")?.to_string(),
+            alias: get("alias")?.to_string(),
+            version: get("version")?.parse()?,
+            source: get("source")?.to_string(),
+            rev: (!rev.is_empty()).then(|| rev.to_string()),
+            path: PathBuf::from(get("path")?),
+        })
+    }
+}
+
+/// `package.lock.er`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lockfile {
+    pub packages: Vec<LockedPackage>,
+}
+
+impl fmt::Display for Lockfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "# This file is generated by `erg pack`. Do not edit it manually."
+        )?;
+        writeln!(f, "# Each `package` is an installed dependency.")?;
+        for pack in self.packages.iter() {
+            // the paths are written with `/` on all platforms
+            let path = pack.path.to_string_lossy().replace('\\', "/");
+            writeln!(
+                f,
+                "package = {{name = \"{}\"; alias = \"{}\"; version = \"{}\"; source = \"{}\"; rev = \"{}\"; path = \"{path}\"}}",
+                pack.name,
+                pack.alias,
+                pack.version,
+                pack.source,
+                pack.rev.as_deref().unwrap_or_default(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Lockfile {
+    /// Reads `package.lock.er` in `root`. Returns an empty lockfile if it does not exist.
+    pub fn read(root: &Path) -> Result<Self, String> {
+        let path = root.join(LOCK_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let mut lockfile = Self::default();
+        for (key, value) in read_values(&path)? {
+            if key == "package" {
+                lockfile.packages.push(LockedPackage::from_value(&value)?);
+            }
+        }
+        Ok(lockfile)
+    }
+
+    pub fn write(&self, root: &Path) -> std::io::Result<()> {
+        fs::write(root.join(LOCK_FILE), self.to_string())
+    }
+}
+
+/// Finds the root directory of the package that contains `dir`.
+pub fn find_package_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|dir| dir.join(MANIFEST_FILE).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Resolves `import "foo/bar"` in `input` to a module of the vendored dependency `foo`.
+pub fn resolve_dependency(input: &Input, path: &Path) -> Option<PathBuf> {
+    let dir = input.dir();
+    let dir = if dir.as_os_str().is_empty() {
+        std::env::current_dir().ok()?
+    } else {
+        dir.canonicalize().ok()?
+    };
+    let root = find_package_root(&dir)?;
+    let mut comps = path.components();
+    let Component::Normal(alias) = comps.next()? else {
+        return None;
+    };
+    let dep_root = root.join(DEPS_DIR).join(alias);
+    let rest = comps.as_path();
+    let key = (dep_root, rest.to_path_buf());
+    let mtime = manifest_mtime(&key.0)?;
+    let cached = RESOLVED_DEPS.with(|resolved| {
+        resolved
+            .borrow()
+            .get(&key)
+            .filter(|(checked, _)| checked == &mtime)
+            .map(|(_, path)| path.clone())
+    });
+    if cached.is_some() {
+        return cached;
+    }
+    let manifest = Manifest::read_cached(&key.0).ok()?;
+    let entry = key.0.join(&manifest.lib);
+    let path = if rest.as_os_str().is_empty() {
+        entry.canonicalize().ok()?
+    } else {
+        let dir = entry.parent()?.join(rest);
+        dir.with_extension("er")
+            .canonicalize()
+            .or_else(|_| dir.join("__init__.er").canonicalize())
+            .ok()?
+    };
+    let path = normalize_path(path);
+    RESOLVED_DEPS.with(|resolved| resolved.borrow_mut().insert(key, (mtime, path.clone())));
+    Some(path)
+}
+
+/// The path of a module of a vendored dependency as a Python package, e.g.
+/// * `.erg/deps/foo/src/lib.er` -> `foo/__init__.er`
+/// * `.erg/deps/foo/src/bar.er` -> `foo/bar.er`
+pub fn dependency_module_path(path: &Path) -> Option<PathBuf> {
+    let comps = path.components().collect::<Vec<_>>();
+    let deps = comps
+        .windows(2)
+        .rposition(|w| w[0].as_os_str() == ".erg" && w[1].as_os_str() == "deps")?;
+    let alias = comps.get(deps + 2)?.as_os_str();
+    let dep_root = comps[..deps + 3].iter().collect::<PathBuf>();
+    let manifest = Manifest::read_cached(&dep_root).ok()?;
+    let entry = dep_root.join(&manifest.lib);
+    if entry.canonicalize().ok().map(normalize_path).as_deref() == Some(path) {
+        return Some(PathBuf::from(alias).join("__init__.er"));
+    }
+    let rel = path.strip_prefix(entry.parent()?).ok()?;
+    Some(PathBuf::from(alias).join(rel))
+}
+
+/// The path of a module of a package as a Python package named after it, e.g.
+/// * `src/lib.er` -> `example/__init__.er`
+/// * `src/main.er` -> `example/__main__.er`
+/// * `src/bar.er` -> `example/bar.er`
+///
+/// Modules that are not in the source directory of a package are not mapped.
+pub fn package_module_path(path: &Path) -> Option<PathBuf> {
+    let root = find_package_root(path.parent()?)?;
+    let manifest = Manifest::read_cached(&root).ok()?;
+    let resolve = |entry: &Path| root.join(entry).canonicalize().ok().map(normalize_path);
+    let name = PathBuf::from(&manifest.name);
+    if resolve(&manifest.lib).as_deref() == Some(path) {
+        return Some(name.join("__init__.er"));
+    }
+    if resolve(&manifest.main).as_deref() == Some(path) {
+        return Some(name.join("__main__.er"));
+    }
+    let src_dir = root.join(manifest.lib.parent()?).canonicalize().ok()?;
+    let rel = path.strip_prefix(normalize_path(src_dir)).ok()?;
+    Some(name.join(rel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest() {
+        let values = vec![
+            ("name".into(), ManifestValue::Str("example".into())),
+            ("version".into(), ManifestValue::Str("0.1.0".into())),
+            (
+                "dependencies".into(),
+                ManifestValue::Record(vec![(
+                    "foo".into(),
+                    ManifestValue::Pack(
+                        vec![
+                            ManifestValue::Str("foo".into()),
+                            ManifestValue::Str("1.*.*".into()),
+                        ],
+                        vec![("path".into(), ManifestValue::Str("../foo".into()))],
+                    ),
+                )]),
+            ),
+        ];
+        let manifest = Manifest::from_values(values).unwrap();
+        assert_eq!(manifest.name, "example");
+        assert_eq!(manifest.package_type, PackageType::App);
+        let dep = &manifest.dependencies[0];
+        assert_eq!(dep.source, DependencySource::Path(PathBuf::from("../foo")));
+        assert_eq!(
+            dep.to_erg(),
+            "foo = pack(\"foo\", \"1.*.*\", path := \"../foo\")"
+        );
+        let values = vec![("name".into(), ManifestValue::Str("example".into()))];
+        assert!(Manifest::from_values(values).is_err());
+    }
+
+    #[test]
+    fn manifest_cache() {
+        let root = std::env::temp_dir().join(format!("erg-test-manifest-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join(MANIFEST_FILE);
+        fs::write(&path, "name = \"a\"\nversion = \"0.1.0\"\n").unwrap();
+        assert_eq!(Manifest::read_cached(&root).unwrap().name, "a");
+        let mtime = manifest_mtime(&root).unwrap();
+        let set_mtime = |mtime| {
+            let file = fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(mtime).unwrap();
+        };
+        // not read again while the mtime is the same
+        fs::write(&path, "name = \"b\"\nversion = \"0.1.0\"\n").unwrap();
+        set_mtime(mtime);
+        assert_eq!(Manifest::read_cached(&root).unwrap().name, "a");
+        set_mtime(mtime + std::time::Duration::from_secs(1));
+        assert_eq!(Manifest::read_cached(&root).unwrap().name, "b");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -6,16 +6,20 @@ use erg_common::error::Location;
 use erg_common::set::Set;
 use erg_common::style::colors::DEBUG_MAIN;
 use erg_common::traits::{Locational, Stream};
+use erg_common::tsort::{dep_path, Graph, Node};
 use erg_common::vis::Visibility;
 use erg_common::Str;
-use erg_common::{impl_display_from_debug, log};
+use erg_common::{impl_display_from_debug, log, set};
 use erg_parser::ast::{ParamPattern, VarName};
 use Visibility::*;
 
-use crate::ty::{HasType, Ownership};
+use crate::ty::typaram::TyParam;
+use crate::ty::{ArgsOwnership, HasType, Ownership, Type};
 
 use crate::error::{OwnershipError, OwnershipErrors};
-use crate::hir::{self, Accessor, Array, Block, Def, Expr, Identifier, Signature, Tuple, HIR};
+use crate::hir::{
+    self, Accessor, Array, Block, Call, Def, Expr, Identifier, Params, Signature, Tuple, HIR,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum WrapperKind {
@@ -28,6 +32,8 @@ pub enum WrapperKind {
 struct LocalVars {
     alive_vars: Set<Str>,
     dropped_vars: Dict<Str, Location>,
+    /// `a -> b` means that the mutable object `a` holds a reference to the mutable object `b`
+    references: Graph<Str, ()>,
 }
 
 impl_display_from_debug!(LocalVars);
@@ -35,7 +41,8 @@ impl_display_from_debug!(LocalVars);
 /// Check code ownership.
 /// for example:
 /// * Check if moved variables are not used again.
-/// * Checks whether a mutable reference method is called in an immutable reference method.
+/// * Check if mutable objects do not refer to each other (e.g. `link! a, b; link! b, a` where `link!(x: RefMut(T), y: RefMut(T))`).
+/// * Check if mutable objects do not refer to each other (e.g. `a.push! b; b.push! a`).
 #[derive(Debug)]
 pub struct OwnershipChecker {
     cfg: ErgConfig,
@@ -101,6 +108,9 @@ impl OwnershipChecker {
     fn check_expr(&mut self, expr: &Expr, ownership: Ownership, chunk: bool) {
         match expr {
             Expr::Def(def) => {
+                if let Signature::Subr(subr) = &def.sig {
+                    self.check_defaults(&subr.params);
+                }
                 self.define(def);
                 let name = match &def.sig {
                     Signature::Var(var) => var.inspect().clone(),
@@ -110,22 +120,7 @@ impl OwnershipChecker {
                 self.dict
                     .insert(Str::from(self.full_path()), LocalVars::default());
                 if let Signature::Subr(subr) = &def.sig {
-                    let (nd_params, var_params, d_params, _) = subr.params.ref_deconstruct();
-                    for param in nd_params {
-                        if let ParamPattern::VarName(name) = &param.raw.pat {
-                            self.define_param(name);
-                        }
-                    }
-                    if let Some(var) = var_params {
-                        if let ParamPattern::VarName(name) = &var.raw.pat {
-                            self.define_param(name);
-                        }
-                    }
-                    for param in d_params {
-                        if let ParamPattern::VarName(name) = &param.sig.raw.pat {
-                            self.define_param(name);
-                        }
-                    }
+                    self.define_params(&subr.params);
                 }
                 self.check_block(&def.body.block);
                 self.path_stack.pop();
@@ -146,51 +141,7 @@ impl OwnershipChecker {
             }
             // Access in chunks does not drop variables (e.g., access in REPL)
             Expr::Accessor(acc) => self.check_acc(acc, ownership, chunk),
-            // TODO: referenced
-            Expr::Call(call) => {
-                let args_owns = call.signature_t().unwrap().args_ownership();
-                let non_defaults_len = if call.is_method_call() {
-                    args_owns.non_defaults.len() - 1
-                } else {
-                    args_owns.non_defaults.len()
-                };
-                if call.args.pos_args.len() > non_defaults_len {
-                    let (non_default_args, var_args) =
-                        call.args.pos_args.split_at(non_defaults_len);
-                    for (nd_arg, (_, ownership)) in
-                        non_default_args.iter().zip(args_owns.non_defaults.iter())
-                    {
-                        self.check_expr(&nd_arg.expr, *ownership, false);
-                    }
-                    if let Some((_, ownership)) = args_owns.var_params.as_ref() {
-                        for var_arg in var_args.iter() {
-                            self.check_expr(&var_arg.expr, *ownership, false);
-                        }
-                    } else {
-                        let kw_args = var_args;
-                        for (arg, (_, ownership)) in kw_args.iter().zip(args_owns.defaults.iter()) {
-                            self.check_expr(&arg.expr, *ownership, false);
-                        }
-                    }
-                }
-                for kw_arg in call.args.kw_args.iter() {
-                    if let Some((_, ownership)) = args_owns
-                        .defaults
-                        .iter()
-                        .find(|(k, _)| k == kw_arg.keyword.inspect())
-                    {
-                        self.check_expr(&kw_arg.expr, *ownership, false);
-                    } else if let Some((_, ownership)) = args_owns
-                        .non_defaults
-                        .iter()
-                        .find(|(k, _)| k.as_ref() == Some(kw_arg.keyword.inspect()))
-                    {
-                        self.check_expr(&kw_arg.expr, *ownership, false);
-                    } else {
-                        todo!()
-                    }
-                }
-            }
+            Expr::Call(call) => self.check_call(call),
             // TODO: referenced
             Expr::BinOp(binop) => {
                 self.check_expr(&binop.lhs, ownership, false);
@@ -209,7 +160,10 @@ impl OwnershipChecker {
                     self.check_expr(&arr.elem, ownership, false);
                     self.check_expr(&arr.len, ownership, false);
                 }
-                _ => todo!(),
+                Array::Comprehension(arr) => {
+                    self.check_expr(&arr.elem, ownership, false);
+                    self.check_expr(&arr.guard, Ownership::Ref, false);
+                }
             },
             Expr::Tuple(tuple) => match tuple {
                 Tuple::Normal(arr) => {
@@ -225,7 +179,11 @@ impl OwnershipChecker {
                         self.check_expr(&kv.value, ownership, false);
                     }
                 }
-                other => todo!("{other}"),
+                hir::Dict::Comprehension(dic) => {
+                    self.check_expr(&dic.key, ownership, false);
+                    self.check_expr(&dic.value, ownership, false);
+                    self.check_expr(&dic.guard, Ownership::Ref, false);
+                }
             },
             Expr::Record(rec) => {
                 for def in rec.attrs.iter() {
@@ -247,17 +205,172 @@ impl OwnershipChecker {
             },
             // TODO: capturing
             Expr::Lambda(lambda) => {
+                self.check_defaults(&lambda.params);
                 let name_and_vis = (Str::from(format!("<lambda_{}>", lambda.id)), Private);
                 self.path_stack.push(name_and_vis);
                 self.dict
                     .insert(Str::from(self.full_path()), LocalVars::default());
+                self.define_params(&lambda.params);
                 self.check_block(&lambda.body);
                 self.path_stack.pop();
             }
             Expr::TypeAsc(asc) => {
                 self.check_expr(&asc.expr, ownership, chunk);
             }
-            _ => {}
+            Expr::ReDef(redef) => {
+                for chunk in redef.block.iter() {
+                    self.check_expr(chunk, Ownership::Owned, false);
+                }
+                self.check_acc(&redef.attr, Ownership::Ref, false);
+                if let (Accessor::Attr(attr), Some(value)) = (&redef.attr, redef.block.last()) {
+                    if attr.obj.ref_t().is_mut_type()
+                        && value.ref_t().is_mut_type()
+                        && may_hold(attr.obj.ref_t(), value.ref_t())
+                    {
+                        if let (Some(referrer), Some(referent)) =
+                            (root_ident(&attr.obj), root_ident(value))
+                        {
+                            self.add_reference(referrer, referent, redef.loc());
+                        }
+                    }
+                }
+            }
+            Expr::Code(chunks) | Expr::Compound(chunks) => {
+                for chunk in chunks.iter() {
+                    self.check_expr(chunk, Ownership::Owned, true);
+                }
+            }
+            Expr::Dummy(dummy) => {
+                for chunk in dummy.iter() {
+                    self.check_expr(chunk, Ownership::Owned, true);
+                }
+            }
+            Expr::Lit(_) | Expr::Import(_) => {}
+        }
+    }
+
+    // TODO: referenced
+    fn check_call(&mut self, call: &Call) {
+        // the callee and the receiver are only borrowed
+        self.check_expr(&call.obj, Ownership::Ref, false);
+        let args_owns = call
+            .signature_t()
+            .map(|t| t.args_ownership())
+            .unwrap_or_else(|| {
+                ArgsOwnership::new(
+                    vec![],
+                    Some((None, Ownership::Owned)),
+                    vec![],
+                    Some((None, Ownership::Owned)),
+                )
+            });
+        let non_defaults_len = if call.is_method_call() {
+            args_owns.non_defaults.len().saturating_sub(1)
+        } else {
+            args_owns.non_defaults.len()
+        };
+        if call.args.pos_args.len() > non_defaults_len {
+            let (non_default_args, var_args) = call.args.pos_args.split_at(non_defaults_len);
+            for (nd_arg, (_, ownership)) in
+                non_default_args.iter().zip(args_owns.non_defaults.iter())
+            {
+                self.check_expr(&nd_arg.expr, *ownership, false);
+            }
+            if let Some((_, ownership)) = args_owns.var_params.as_ref() {
+                for var_arg in var_args.iter() {
+                    self.check_expr(&var_arg.expr, *ownership, false);
+                }
+            } else {
+                let kw_args = var_args;
+                for (arg, (_, ownership)) in kw_args.iter().zip(args_owns.defaults.iter()) {
+                    self.check_expr(&arg.expr, *ownership, false);
+                }
+            }
+        }
+        if let Some(var_args) = call.args.var_args.as_ref() {
+            let ownership = args_owns
+                .var_params
+                .as_ref()
+                .map_or(Ownership::Owned, |(_, ownership)| *ownership);
+            self.check_expr(&var_args.expr, ownership, false);
+        }
+        for kw_arg in call.args.kw_args.iter() {
+            if let Some((_, ownership)) = args_owns
+                .defaults
+                .iter()
+                .find(|(k, _)| k == kw_arg.keyword.inspect())
+            {
+                self.check_expr(&kw_arg.expr, *ownership, false);
+            } else if let Some((_, ownership)) = args_owns
+                .non_defaults
+                .iter()
+                .find(|(k, _)| k.as_ref() == Some(kw_arg.keyword.inspect()))
+            {
+                self.check_expr(&kw_arg.expr, *ownership, false);
+            } else {
+                // passed to the variable-length keyword parameter
+                let ownership = args_owns
+                    .kw_var_params
+                    .as_ref()
+                    .map_or(Ownership::Owned, |(_, ownership)| *ownership);
+                self.check_expr(&kw_arg.expr, ownership, false);
+            }
+        }
+        if let Some(kw_var_args) = call.args.kw_var_args.as_ref() {
+            let ownership = args_owns
+                .kw_var_params
+                .as_ref()
+                .map_or(Ownership::Owned, |(_, ownership)| *ownership);
+            self.check_expr(&kw_var_args.expr, ownership, false);
+        }
+        let offset = args_owns.non_defaults.len() - non_defaults_len;
+        let args = call
+            .args
+            .pos_args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                let ownership = args_owns
+                    .non_defaults
+                    .get(offset + i)
+                    .or(args_owns.var_params.as_ref())
+                    .map_or(Ownership::Owned, |(_, ownership)| *ownership);
+                (&arg.expr, ownership)
+            })
+            .collect::<Vec<_>>();
+        self.check_references(call, &args);
+    }
+
+    /// Mutable objects passed to a procedural method (or to a `RefMut` parameter) are regarded as
+    /// being referred to by the receiver (or by the `RefMut` argument).
+    fn check_references(&mut self, call: &Call, args: &[(&Expr, Ownership)]) {
+        // (referrer, its type, the index of the first argument it may hold)
+        let mut referrers = vec![];
+        if matches!(&call.attr_name, Some(name) if name.is_procedural())
+            && call.obj.ref_t().is_mut_type()
+        {
+            referrers.extend(root_ident(&call.obj).map(|ident| (ident, call.obj.ref_t(), 0)));
+        }
+        for (i, (arg, ownership)) in args.iter().enumerate() {
+            if ownership.is_refmut() {
+                referrers.extend(root_ident(arg).map(|ident| (ident, arg.ref_t(), i + 1)));
+            }
+        }
+        for (i, (arg, ownership)) in args.iter().enumerate() {
+            if !(ownership.is_owned() || ownership.is_refmut()) || !arg.ref_t().is_mut_type() {
+                continue;
+            }
+            if let Some(referent) = root_ident(arg) {
+                for (referrer, referrer_t, first) in referrers.iter() {
+                    // `f!(x: RefMut(T), y: RefMut(T))` is assumed to make `x` hold `y`, not vice versa
+                    if ownership.is_refmut() && i < *first {
+                        continue;
+                    }
+                    if may_hold(referrer_t, arg.ref_t()) {
+                        self.add_reference(referrer, referent, call.loc());
+                    }
+                }
+            }
         }
     }
 
@@ -323,6 +436,58 @@ impl OwnershipChecker {
             .insert(name.inspect().clone());
     }
 
+    fn define_params(&mut self, params: &Params) {
+        let var_params = params.var_params.as_deref();
+        let d_params = params.defaults.iter().map(|param| &param.sig);
+        let kw_var_params = params.kw_var_params.as_deref();
+        for param in params
+            .non_defaults
+            .iter()
+            .chain(var_params)
+            .chain(d_params)
+            .chain(kw_var_params)
+        {
+            match &param.raw.pat {
+                ParamPattern::VarName(name)
+                | ParamPattern::Ref(name)
+                | ParamPattern::RefMut(name) => {
+                    self.define_param(name);
+                }
+                // destructuring patterns are desugared into definitions in the body
+                _ => {}
+            }
+        }
+    }
+
+    /// default values are evaluated in the outer scope
+    fn check_defaults(&mut self, params: &Params) {
+        for param in params.defaults.iter() {
+            self.check_expr(&param.default_val, Ownership::Owned, false);
+        }
+    }
+
+    fn add_reference(&mut self, referrer: &Identifier, referent: &Identifier, loc: Location) {
+        let (from, to) = (referrer.inspect(), referent.inspect());
+        log!(info "reference: {from} -> {to}");
+        let refs = &mut self.current_scope().references;
+        if let Some(mut cycle) = dep_path(refs, to, from) {
+            cycle.insert(0, from.clone());
+            self.errs.push(OwnershipError::cyclic_reference_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                loc,
+                self.full_path(),
+                &cycle,
+            ));
+            return;
+        }
+        if let Some(node) = refs.iter_mut().find(|node| &node.id == from) {
+            node.push_dep(to.clone());
+        } else {
+            refs.push(Node::new(from.clone(), (), set! {to.clone()}));
+        }
+    }
+
     fn drop(&mut self, ident: &Identifier) {
         log!("drop: {ident} (in {})", ident.ln_begin().unwrap_or(0));
         for n in 0..self.path_stack.len() {
@@ -333,7 +498,10 @@ impl OwnershipChecker {
                 return;
             }
         }
-        panic!("variable not found: {ident}");
+        // variables defined outside of the module (e.g. builtins)
+        self.current_scope()
+            .dropped_vars
+            .insert(ident.inspect().clone(), ident.loc());
     }
 
     fn check_if_dropped(
@@ -363,3 +531,23 @@ impl Default for OwnershipChecker {
         Self::new(ErgConfig::default())
     }
 }
+
+fn root_ident(expr: &Expr) -> Option<&Identifier> {
+    match expr {
+        Expr::Accessor(Accessor::Ident(ident)) => Some(ident),
+        Expr::Accessor(Accessor::Attr(attr)) => root_ident(&attr.obj),
+        Expr::TypeAsc(asc) => root_ident(&asc.expr),
+        _ => None,
+    }
+}
+
+/// e.g. `Array!(Array!(Int))` may hold `Array!(Int)`, but `Array!(Int)` may not (`a.extend! b`)
+fn may_hold(holder: &Type, t: &Type) -> bool {
+    let typarams = holder.typarams();
+    // user-defined mutable classes may hold anything in their attributes
+    typarams.is_empty()
+        || typarams.iter().any(|tp| match tp {
+            TyParam::Type(elem) => elem.as_ref() == &Type::Obj || elem.qual_name() == t.qual_name(),
+            _ => false,
+        })
+}
@@ -0,0 +1,127 @@
+//! Converts Erg types to PEP 484 type annotations.
+//! This is shared by the transpiler (`transpile.rs`) and the `.pyi` generator (`declare_gen.rs`),
+//! which differ only in how the names (`typing` imports, type variables, user-defined types) are emitted.
+use erg_common::dict::Dict;
+use erg_common::vis::Field;
+use erg_common::Str;
+
+use crate::ty::free::HasLevel;
+use crate::ty::typaram::TyParam;
+use crate::ty::{RefinementType, SubrType, Type};
+
+/// `f: (Int) -> Int` => `(Int) -> Int`
+pub(crate) fn subr_type(t: &Type) -> Option<SubrType> {
+    match t {
+        Type::FreeVar(fv) if fv.is_linked() => subr_type(&fv.crack()),
+        Type::Subr(subr) => Some(subr.clone()),
+        Type::Quantified(t) | Type::Refinement(RefinementType { t, .. }) => subr_type(t),
+        _ => None,
+    }
+}
+
+/// Emits the Python type annotations of Erg types (see the module docs).
+pub(crate) trait PyTypeMapper {
+    /// Imports `name` from `typing` (if not yet) and returns it.
+    fn load_typing(&mut self, name: &'static str) -> &'static str;
+    /// Declares the type variable of a generalized free variable (if not yet) and returns its name.
+    fn type_var(&mut self, name: Str) -> String;
+    /// The name of a user-defined type (`Type::Mono`), or `None` if it cannot be referred to.
+    fn type_name(&mut self, name: &Str) -> Option<String>;
+    /// Record types have no counterpart in `typing`, so they are `Any` by default.
+    fn record_type(&mut self, _fields: &Dict<Field, Type>) -> String {
+        self.any()
+    }
+
+    fn any(&mut self) -> String {
+        self.load_typing("Any").to_string()
+    }
+
+    /// Converts an Erg type to a PEP 484 type annotation.
+    /// Types that cannot be expressed in Python are converted to `Any`.
+    fn py_type(&mut self, t: &Type) -> String {
+        match t {
+            Type::FreeVar(fv) if fv.is_linked() => self.py_type(&fv.crack()),
+            Type::FreeVar(fv) if fv.is_generalized() => match fv.unbound_name() {
+                Some(name) => self.type_var(name),
+                None => self.any(),
+            },
+            Type::Int | Type::Nat => "int".into(),
+            Type::Ratio | Type::Float => "float".into(),
+            Type::Bool => "bool".into(),
+            Type::Str => "str".into(),
+            Type::NoneType => "None".into(),
+            Type::Obj => "object".into(),
+            Type::Never => self.load_typing("NoReturn").into(),
+            Type::Type | Type::ClassType | Type::TraitType => "type".into(),
+            Type::Mono(name) => match &name[..] {
+                "Bytes" => "bytes".into(),
+                "Complex" => "complex".into(),
+                _ => self.type_name(name).unwrap_or_else(|| self.any()),
+            },
+            Type::Ref(t) | Type::RefMut { before: t, .. } => self.py_type(t),
+            Type::Refinement(refine) => self.py_type(&refine.t),
+            Type::Quantified(t) => self.py_type(t),
+            Type::Or(lhs, rhs) => {
+                let union = self.load_typing("Union");
+                format!("{union}[{}, {}]", self.py_type(lhs), self.py_type(rhs))
+            }
+            Type::Record(fields) => self.record_type(fields),
+            Type::Subr(subr) => {
+                let callable = self.load_typing("Callable");
+                let return_t = self.py_type(&subr.return_t);
+                if subr.var_params.is_some()
+                    || !subr.default_params.is_empty()
+                    || subr.kw_var_params.is_some()
+                {
+                    format!("{callable}[..., {return_t}]")
+                } else {
+                    let params = subr
+                        .non_default_params
+                        .iter()
+                        .map(|pt| self.py_type(pt.typ()))
+                        .collect::<Vec<_>>();
+                    format!("{callable}[[{}], {return_t}]", params.join(", "))
+                }
+            }
+            Type::Callable { param_ts, return_t } => {
+                let callable = self.load_typing("Callable");
+                let return_t = self.py_type(return_t);
+                let params = param_ts.iter().map(|t| self.py_type(t)).collect::<Vec<_>>();
+                format!("{callable}[[{}], {return_t}]", params.join(", "))
+            }
+            Type::Poly { name, params } => match (&name[..], &params[..]) {
+                ("Array" | "Array!", [elem, ..]) => format!("list[{}]", self.py_tp(elem)),
+                ("Set" | "Set!", [elem, ..]) => format!("set[{}]", self.py_tp(elem)),
+                ("Dict" | "Dict!", [TyParam::Dict(dict)]) if dict.len() == 1 => {
+                    let (key, value) = dict.iter().next().unwrap();
+                    format!("dict[{}, {}]", self.py_tp(key), self.py_tp(value))
+                }
+                ("Tuple", [TyParam::Array(elems)]) if elems.is_empty() => "tuple[()]".into(),
+                ("Tuple", [TyParam::Array(elems)]) => {
+                    let elems = elems
+                        .iter()
+                        .map(|elem| self.py_tp(elem))
+                        .collect::<Vec<_>>();
+                    format!("tuple[{}]", elems.join(", "))
+                }
+                ("Iterable" | "Iterator", [elem]) => {
+                    let name = if &name[..] == "Iterable" {
+                        self.load_typing("Iterable")
+                    } else {
+                        self.load_typing("Iterator")
+                    };
+                    format!("{name}[{}]", self.py_tp(elem))
+                }
+                _ => self.any(),
+            },
+            _ => self.any(),
+        }
+    }
+
+    fn py_tp(&mut self, tp: &TyParam) -> String {
+        match <&Type>::try_from(tp) {
+            Ok(t) => self.py_type(t),
+            Err(_) => self.any(),
+        }
+    }
+}
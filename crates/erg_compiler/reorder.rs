@@ -36,6 +36,10 @@ impl Reorderer {
         let mut new = vec![];
         for chunk in ast.module.into_iter() {
             match chunk {
+                // in declaration files, type definitions are declared separately from their attributes
+                Expr::Def(def) if mode == "declare" => {
+                    new.push(Expr::Def(def));
+                }
                 Expr::Def(def) => {
                     match def.body.block.first().unwrap() {
                         Expr::Call(call) => {
@@ -0,0 +1,254 @@
+//! Source maps from the generated Python code (`.pyc`, `.py`) back to the Erg source.
+//!
+//! A source map is dumped as a sidecar JSON file (`foo.pyc.map`, `foo.py.map`):
+//!
+//! ```json
+//! {"version": 1, "file": "foo.py", "sources": ["foo.er"], "mappings": [[12, 4, 0, 3, 4]]}
+//! ```
+//!
+//! Each mapping is `[python line, python column, source index, Erg line, Erg column]`.
+//! Lines are 1-origin and columns are 0-origin (the same as Python's `co_positions`).
+//!
+//! The line tables of the generated `.pyc` are not reliable on every Python version,
+//! so the maps of `.pyc` also have `"offsets"`, which map the bytecode offsets (`tb_lasti`):
+//! `[code name, first line number, offset, source index, Erg line, Erg column]`.
+//! `_erg_source_map.py` in the standard library reads them to rewrite tracebacks.
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub const SOURCE_MAP_VERSION: u32 = 1;
+
+/// `foo.pyc` => `foo.pyc.map`
+pub fn source_map_path(generated: &Path) -> PathBuf {
+    let mut path = generated.as_os_str().to_owned();
+    path.push(".map");
+    PathBuf::from(path)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Mapping {
+    pub py_line: u32,
+    pub py_col: u32,
+    /// the index of `SourceMap::sources`
+    pub source: usize,
+    pub line: u32,
+    pub col: u32,
+}
+
+/// Maps the bytecode offset of a code object (`co_name`, `co_firstlineno`) to the Erg position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetMapping {
+    pub code: String,
+    pub firstlineno: u32,
+    pub offset: usize,
+    pub source: usize,
+    pub line: u32,
+    pub col: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// the name of the generated file (`co_filename` of the code objects)
+    pub file: String,
+    pub sources: Vec<String>,
+    pub mappings: Vec<Mapping>,
+    pub offsets: Vec<OffsetMapping>,
+}
+
+impl SourceMap {
+    pub fn new(file: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            file: file.into(),
+            sources: vec![source.into()],
+            mappings: vec![],
+            offsets: vec![],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty() && self.offsets.is_empty()
+    }
+
+    /// Maps the Python position to the Erg position in the first source.
+    pub fn add(&mut self, py_line: u32, py_col: u32, line: u32, col: u32) {
+        self.mappings.push(Mapping {
+            py_line,
+            py_col,
+            source: 0,
+            line,
+            col,
+        });
+    }
+
+    /// Maps the bytecode offset in the code object to the Erg position in the first source.
+    pub fn add_offset(&mut self, code: &str, firstlineno: u32, offset: usize, line: u32, col: u32) {
+        self.offsets.push(OffsetMapping {
+            code: code.to_string(),
+            firstlineno,
+            offset,
+            source: 0,
+            line,
+            col,
+        });
+    }
+
+    /// Shifts the offsets at or after `from` in the code object by `shift` bytes.
+    /// This is needed when instructions (e.g. `EXTENDED_ARG`) are inserted into the code already mapped.
+    pub fn shift_offsets(&mut self, code: &str, firstlineno: u32, from: usize, shift: usize) {
+        for map in self.offsets.iter_mut() {
+            if map.code == code && map.firstlineno == firstlineno && map.offset >= from {
+                map.offset += shift;
+            }
+        }
+    }
+
+    /// Returns the mapping of the nearest position at or before `(py_line, py_col)`.
+    pub fn lookup(&self, py_line: u32, py_col: u32) -> Option<&Mapping> {
+        self.mappings
+            .iter()
+            .filter(|map| (map.py_line, map.py_col) <= (py_line, py_col))
+            .max_by_key(|map| (map.py_line, map.py_col))
+    }
+
+    /// Returns the last mapping of the nearest offset at or before `offset` in the code object.
+    pub fn lookup_offset(
+        &self,
+        code: &str,
+        firstlineno: u32,
+        offset: usize,
+    ) -> Option<&OffsetMapping> {
+        self.offsets
+            .iter()
+            .filter(|map| {
+                map.code == code && map.firstlineno == firstlineno && map.offset <= offset
+            })
+            .max_by_key(|map| map.offset)
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            "{{\"version\": {SOURCE_MAP_VERSION}, \"file\": {}, \"sources\": [",
+            escape_json(&self.file)
+        );
+        let sources = self
+            .sources
+            .iter()
+            .map(|src| escape_json(src))
+            .collect::<Vec<_>>();
+        json += &sources.join(", ");
+        json += "], \"mappings\": [";
+        for (i, map) in self.mappings.iter().enumerate() {
+            if i > 0 {
+                json += ", ";
+            }
+            let _ = write!(
+                json,
+                "[{}, {}, {}, {}, {}]",
+                map.py_line, map.py_col, map.source, map.line, map.col
+            );
+        }
+        json += "]";
+        if !self.offsets.is_empty() {
+            json += ", \"offsets\": [";
+            for (i, map) in self.offsets.iter().enumerate() {
+                if i > 0 {
+                    json += ", ";
+                }
+                let _ = write!(
+                    json,
+                    "[{}, {}, {}, {}, {}, {}]",
+                    escape_json(&map.code),
+                    map.firstlineno,
+                    map.offset,
+                    map.source,
+                    map.line,
+                    map.col
+                );
+            }
+            json += "]";
+        }
+        json += "}\n";
+        json
+    }
+
+    pub fn dump<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut f = File::create(path)?;
+        f.write_all(self.to_json().as_bytes())
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            '\n' => escaped += "\\n",
+            '\r' => escaped += "\\r",
+            '\t' => escaped += "\\t",
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_map_json() {
+        let mut map = SourceMap::new("foo.py", "dir\\foo.er");
+        map.add(3, 0, 1, 0);
+        map.add(4, 4, 2, 8);
+        assert_eq!(
+            map.to_json(),
+            "{\"version\": 1, \"file\": \"foo.py\", \"sources\": [\"dir\\\\foo.er\"], \"mappings\": [[3, 0, 0, 1, 0], [4, 4, 0, 2, 8]]}\n"
+        );
+    }
+
+    #[test]
+    fn source_map_lookup() {
+        let mut map = SourceMap::new("foo.py", "foo.er");
+        map.add(3, 0, 1, 0);
+        map.add(4, 4, 2, 8);
+        assert_eq!(map.lookup(2, 0), None);
+        assert_eq!(map.lookup(3, 10).map(|map| map.line), Some(1));
+        assert_eq!(map.lookup(4, 2).map(|map| map.line), Some(1));
+        assert_eq!(
+            map.lookup(7, 0).map(|map| (map.line, map.col)),
+            Some((2, 8))
+        );
+    }
+
+    #[test]
+    fn source_map_offsets() {
+        let mut map = SourceMap::new("foo.er", "foo.er");
+        map.add_offset("<module>", 1, 0, 1, 0);
+        map.add_offset("f", 1, 2, 2, 4);
+        map.add_offset("f", 1, 2, 2, 8);
+        map.add_offset("<module>", 1, 10, 3, 0);
+        assert!(map.to_json().ends_with(
+            "\"offsets\": [[\"<module>\", 1, 0, 0, 1, 0], [\"f\", 1, 2, 0, 2, 4], [\"f\", 1, 2, 0, 2, 8], [\"<module>\", 1, 10, 0, 3, 0]]}\n"
+        ));
+        assert_eq!(
+            map.lookup_offset("<module>", 1, 8).map(|map| map.line),
+            Some(1)
+        );
+        assert_eq!(map.lookup_offset("f", 1, 20).map(|map| map.col), Some(8));
+        assert_eq!(map.lookup_offset("g", 1, 20), None);
+        map.shift_offsets("f", 1, 2, 6);
+        assert_eq!(map.lookup_offset("f", 1, 7), None);
+        assert_eq!(map.lookup_offset("f", 1, 8).map(|map| map.col), Some(8));
+        assert_eq!(
+            map.lookup_offset("<module>", 1, 10).map(|map| map.line),
+            Some(3)
+        );
+    }
+}
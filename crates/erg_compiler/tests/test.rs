@@ -1,4 +1,11 @@
+use erg_common::config::{ErgConfig, Input};
+use erg_common::error::{FixIt, Location};
+use erg_common::spawn::exec_new_thread;
+use erg_common::style::remove_style;
+use erg_common::traits::Runnable;
+
 use erg_compiler::context::Context;
+use erg_compiler::HIRBuilder;
 
 #[test]
 fn test_subtyping() -> Result<(), ()> {
@@ -39,3 +46,57 @@ fn _test_dir() -> Result<(), ()> {
     }
     Ok(())
 }
+
+/// Returns the fix-its of the errors and the rendered errors
+fn check_fix_its(code: &'static str) -> (Vec<FixIt>, String) {
+    exec_new_thread(move || {
+        let cfg = ErgConfig {
+            input: Input::pipe(code.to_string()),
+            ..ErgConfig::default()
+        };
+        let mut builder = HIRBuilder::new(cfg);
+        let errs = builder.build(code.to_string(), "exec").unwrap_err().errors;
+        let fix_its = errs
+            .iter()
+            .flat_map(|err| err.core.fix_its().cloned())
+            .collect();
+        let rendered = errs
+            .iter()
+            .map(|err| remove_style(&err.to_string()))
+            .collect();
+        (fix_its, rendered)
+    })
+}
+
+#[test]
+fn test_fix_its() {
+    // the locations of the array/dict type specs are extended to the brackets
+    let (fix_its, rendered) = check_fix_its("a: [Int; Str] = (1, \"a\")\n");
+    let loc = Location::range(1, 3, 1, 13);
+    assert_eq!(
+        fix_its,
+        vec![FixIt::new(loc, "(Int, Str)"), FixIt::new(loc, "{Int: Str}")]
+    );
+    assert!(
+        rendered.contains("fix: replace with `(Int, Str)`"),
+        "{rendered}"
+    );
+    assert!(
+        rendered.contains("fix: replace with `{Int: Str}`"),
+        "{rendered}"
+    );
+    let (fix_its, rendered) = check_fix_its("b: {x: Int} = {x = 1}\n");
+    assert_eq!(
+        fix_its,
+        vec![FixIt::new(Location::range(1, 3, 1, 11), "{x = Int}")]
+    );
+    assert!(
+        rendered.contains("fix: replace with `{x = Int}`"),
+        "{rendered}"
+    );
+    let (fix_its, _) = check_fix_its("c: Int and Str = 1\n");
+    assert_eq!(
+        fix_its,
+        vec![FixIt::new(Location::range(1, 3, 1, 14), "Int or Str")]
+    );
+}
@@ -1,14 +1,18 @@
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 
-use erg_common::config::ErgConfig;
+use erg_common::config::{ErgConfig, Input};
+use erg_common::dict::Dict as HashMap;
 use erg_common::error::MultiErrorDisplay;
 use erg_common::log;
-use erg_common::traits::{Runnable, Stream};
+use erg_common::set::Set as HashSet;
+use erg_common::traits::{Locational, Runnable, Stream};
+use erg_common::vis::Field;
 use erg_common::Str;
 
-use erg_parser::ast::{ParamPattern, TypeSpec, VarName};
-use erg_parser::token::TokenKind;
+use erg_parser::ast::{DefKind, OperationKind, ParamPattern, TypeSpec, VarName};
+use erg_parser::token::{TokenKind, DOT};
 
 use crate::artifact::{
     BuildRunnable, Buildable, CompleteArtifact, ErrorArtifact, IncompleteArtifact,
@@ -20,12 +24,14 @@ use crate::desugar_hir::HIRDesugarer;
 use crate::error::{CompileError, CompileErrors};
 use crate::hir::{
     Accessor, Args, Array, BinOp, Block, Call, ClassDef, Def, Dict, Expr, Identifier, Lambda,
-    Literal, Params, PatchDef, ReDef, Record, Set, Signature, Tuple, UnaryOp, HIR,
+    Literal, Module, Params, PatchDef, ReDef, Record, Set, Signature, Tuple, UnaryOp, HIR,
 };
 use crate::link::Linker;
 use crate::module::SharedCompilerResource;
-use crate::ty::value::ValueObj;
-use crate::ty::Type;
+use crate::py_type::{subr_type, PyTypeMapper};
+use crate::source_map::{source_map_path, SourceMap};
+use crate::ty::value::{GenTypeObj, ValueObj};
+use crate::ty::{HasType, Type};
 use crate::varinfo::VarInfo;
 
 /// patch method -> function
@@ -36,11 +42,7 @@ fn debind(ident: &Identifier) -> Option<Str> {
             Some(Str::from(name.replace("Function::", "")))
         }
         Some(patch_method) if patch_method.contains("::") || patch_method.contains('.') => {
-            if ident.vis().is_private() {
-                Some(Str::from(format!("{patch_method}__")))
-            } else {
-                Some(Str::rc(patch_method))
-            }
+            Some(Str::rc(patch_method))
         }
         _ => None,
     }
@@ -65,7 +67,231 @@ fn replace_non_symbolic(name: String) -> String {
         .replace('$', "erg_shared__")
 }
 
-#[derive(Debug)]
+/// Names that cannot be used as-is in the readable output (Python keywords and the names used by the transpiler).
+const PY_RESERVED_NAMES: [&str; 63] = [
+    "False",
+    "None",
+    "True",
+    "and",
+    "as",
+    "assert",
+    "async",
+    "await",
+    "break",
+    "class",
+    "continue",
+    "def",
+    "del",
+    "elif",
+    "else",
+    "except",
+    "finally",
+    "for",
+    "from",
+    "global",
+    "if",
+    "import",
+    "in",
+    "is",
+    "lambda",
+    "nonlocal",
+    "not",
+    "or",
+    "pass",
+    "raise",
+    "return",
+    "try",
+    "while",
+    "with",
+    "yield",
+    "int",
+    "float",
+    "bool",
+    "str",
+    "bytes",
+    "complex",
+    "object",
+    "type",
+    "list",
+    "set",
+    "dict",
+    "tuple",
+    "Int",
+    "Nat",
+    "Bool",
+    "Str",
+    "Float",
+    "Array",
+    "Range",
+    "Error",
+    "Any",
+    "Callable",
+    "Union",
+    "NamedTuple",
+    "TypeVar",
+    "Iterable",
+    "Iterator",
+    "NoReturn",
+];
+
+/// Counts the variables (and parameters) defined in `expr`.
+fn collect_defined_names(expr: &Expr, names: &mut HashMap<Str, usize>) {
+    fn define(name: &Str, names: &mut HashMap<Str, usize>) {
+        let n = names.get(name).copied().unwrap_or(0);
+        names.insert(name.clone(), n + 1);
+    }
+    fn collect_params(params: &Params, names: &mut HashMap<Str, usize>) {
+        let pats = params
+            .non_defaults
+            .iter()
+            .map(|param| &param.raw.pat)
+            .chain(params.defaults.iter().map(|param| &param.sig.raw.pat))
+            .chain(params.var_params.iter().map(|param| &param.raw.pat))
+            .chain(params.kw_var_params.iter().map(|param| &param.raw.pat));
+        for pat in pats {
+            if let ParamPattern::VarName(name) = pat {
+                define(name.inspect(), names);
+            }
+        }
+    }
+    match expr {
+        Expr::Def(def) => {
+            define(def.sig.ident().inspect(), names);
+            if let Signature::Subr(subr) = &def.sig {
+                collect_params(&subr.params, names);
+            }
+            for chunk in def.body.block.iter() {
+                collect_defined_names(chunk, names);
+            }
+        }
+        Expr::Lambda(lambda) => {
+            collect_params(&lambda.params, names);
+            for chunk in lambda.body.iter() {
+                collect_defined_names(chunk, names);
+            }
+        }
+        Expr::ClassDef(classdef) => {
+            define(classdef.sig.ident().inspect(), names);
+            for chunk in classdef.methods.iter() {
+                collect_defined_names(chunk, names);
+            }
+        }
+        Expr::PatchDef(patchdef) => {
+            for chunk in patchdef.methods.iter() {
+                collect_defined_names(chunk, names);
+            }
+        }
+        Expr::ReDef(redef) => {
+            for chunk in redef.block.iter() {
+                collect_defined_names(chunk, names);
+            }
+        }
+        Expr::Call(call) => {
+            collect_defined_names(&call.obj, names);
+            for arg in call.args.pos_args.iter() {
+                collect_defined_names(&arg.expr, names);
+            }
+            for arg in call.args.kw_args.iter() {
+                collect_defined_names(&arg.expr, names);
+            }
+        }
+        Expr::Record(rec) => {
+            for attr in rec.attrs.iter() {
+                for chunk in attr.body.block.iter() {
+                    collect_defined_names(chunk, names);
+                }
+            }
+        }
+        Expr::BinOp(bin) => {
+            collect_defined_names(&bin.lhs, names);
+            collect_defined_names(&bin.rhs, names);
+        }
+        Expr::UnaryOp(unary) => collect_defined_names(&unary.expr, names),
+        Expr::TypeAsc(tasc) => collect_defined_names(&tasc.expr, names),
+        Expr::Compound(comp) => {
+            for chunk in comp.iter() {
+                collect_defined_names(chunk, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `if`, `if!`, `match`, `match!` or `with!` call
+fn is_branch_call(call: &Call) -> bool {
+    call.additional_operation().is_none()
+        && matches!(
+            call.obj.local_name(),
+            Some("if" | "if!" | "match" | "match!" | "with!")
+        )
+}
+
+/// `\x01{line}:{col}\x02` marks the beginning of a statement transpiled from `line:col`
+const POS_START: char = '\u{1}';
+const POS_END: char = '\u{2}';
+
+/// Removes the position markers from the code and records them in a source map.
+fn extract_positions(code: &str) -> (String, SourceMap) {
+    let mut source_map = SourceMap::default();
+    let mut stripped = String::with_capacity(code.len());
+    for (i, line) in code.split_inclusive('\n').enumerate() {
+        let mut rest = line;
+        let mut py_col = 0;
+        while let Some(start) = rest.find(POS_START) {
+            let Some(end) = rest[start..].find(POS_END).map(|end| start + end) else {
+                break;
+            };
+            stripped += &rest[..start];
+            py_col += start;
+            let pos = rest[start + 1..end].split_once(':');
+            if let Some((Ok(ln), Ok(col))) = pos.map(|(ln, col)| (ln.parse(), col.parse())) {
+                source_map.add(i as u32 + 1, py_col as u32, ln, col);
+            }
+            rest = &rest[end + 1..];
+        }
+        stripped += rest;
+    }
+    (stripped, source_map)
+}
+
+/// Whether `expr` is transpiled into a statement, which cannot be used as an expression.
+fn is_statement(expr: &Expr) -> bool {
+    match expr {
+        Expr::Def(_) | Expr::ClassDef(_) | Expr::PatchDef(_) | Expr::ReDef(_) => true,
+        Expr::Call(call) => {
+            matches!(
+                call.attr_name.as_ref().map(|attr| &attr.inspect()[..]),
+                Some("update!" | "return")
+            ) || matches!(
+                call.obj.local_name(),
+                Some("assert" | "Del" | "for" | "for!" | "while" | "while!")
+            )
+        }
+        _ => false,
+    }
+}
+
+/// The body of a block argument: `do: x` => `x`, `f!` => `f!()`
+fn block_body(block: Expr) -> Block {
+    match block {
+        Expr::Lambda(lambda) => lambda.body,
+        other => Block::new(vec![other.call_expr(Args::empty())]),
+    }
+}
+
+/// `if cond, do: a, do: b` => `a if cond else b`
+fn is_simple_if(call: &Call) -> bool {
+    call.args
+        .pos_args
+        .iter()
+        .skip(1)
+        .all(|arg| match &arg.expr {
+            Expr::Lambda(block) => block.body.len() == 1,
+            _ => true,
+        })
+}
+
+#[derive(Debug, Clone)]
 pub enum LastLineOperation {
     Discard,
     Return,
@@ -88,6 +314,8 @@ impl LastLineOperation {
 pub struct PyScript {
     pub filename: Str,
     pub code: String,
+    /// `file` and `sources` are left empty (filled in by `Transpiler`)
+    pub source_map: SourceMap,
 }
 
 /// Generates a `PyScript` from an String or other File inputs.
@@ -115,7 +343,7 @@ impl Runnable for Transpiler {
         Self {
             shared: shared.clone(),
             builder: HIRBuilder::new_with_cache(cfg.copy(), "<module>", shared),
-            script_generator: ScriptGenerator::new(),
+            script_generator: ScriptGenerator::new(cfg.input.clone(), cfg.readable),
             cfg,
         }
     }
@@ -151,8 +379,14 @@ impl Runnable for Transpiler {
             eart.errors
         })?;
         artifact.warns.fmt_all_stderr();
-        let mut f = File::create(path).unwrap();
+        let mut f = File::create(&path).unwrap();
         f.write_all(artifact.object.code.as_bytes()).unwrap();
+        if self.cfg.source_map {
+            Self::dump_source_map(&self.cfg, artifact.object.source_map, &path);
+        }
+        if self.cfg.separate {
+            self.dump_modules_as_py()?;
+        }
         Ok(0)
     }
 
@@ -186,7 +420,7 @@ impl Buildable<PyScript> for Transpiler {
         Self {
             shared: shared.clone(),
             builder: HIRBuilder::new_with_cache(cfg.copy(), mod_name, shared),
-            script_generator: ScriptGenerator::new(),
+            script_generator: ScriptGenerator::new(cfg.input.clone(), cfg.readable),
             cfg,
         }
     }
@@ -216,7 +450,10 @@ impl Transpiler {
     ) -> Result<CompleteArtifact<PyScript>, ErrorArtifact> {
         log!(info "the transpiling process has started.");
         let artifact = self.build_link_desugar(src, mode)?;
-        let script = self.script_generator.transpile(artifact.object);
+        let script = match self.script_generator.transpile(artifact.object) {
+            Ok(script) => script,
+            Err(errs) => return Err(ErrorArtifact::new(errs, artifact.warns)),
+        };
         log!(info "code:\n{}", script.code);
         log!(info "the transpiling process has completed");
         Ok(CompleteArtifact::new(script, artifact.warns))
@@ -234,6 +471,41 @@ impl Transpiler {
         Ok(CompleteArtifact::new(desugared, artifact.warns))
     }
 
+    /// Transpiles the Erg modules imported by the main module into their own .py files (`--separate`).
+    fn dump_modules_as_py(&mut self) -> Result<(), CompileErrors> {
+        let Some(main) = self.cfg.input.path() else {
+            return Ok(());
+        };
+        let deps = self.shared.graph.dependencies(main);
+        let linker = Linker::new(&self.cfg, &self.shared.mod_cache);
+        for (cfg, hir, output) in linker.link_modules(deps) {
+            let mut py_path = self.cfg.dump_dir().join(output);
+            py_path.set_extension("py");
+            if let Some(dir) = py_path.parent() {
+                std::fs::create_dir_all(dir).expect("failed to create the output directory");
+            }
+            let desugared = HIRDesugarer::desugar(hir);
+            let script =
+                ScriptGenerator::new(cfg.input.clone(), self.cfg.readable).transpile(desugared)?;
+            let mut f = File::create(&py_path).unwrap();
+            f.write_all(script.code.as_bytes()).unwrap();
+            if self.cfg.source_map {
+                Self::dump_source_map(&cfg, script.source_map, &py_path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Dumps `foo.py.map` for `foo.py` transpiled from `cfg.input`.
+    fn dump_source_map(cfg: &ErgConfig, mut source_map: SourceMap, py_path: &Path) {
+        let file = py_path.file_name().and_then(|name| name.to_str());
+        source_map.file = file.unwrap_or_default().to_string();
+        source_map.sources = vec![cfg.input.enclosed_name().to_string()];
+        source_map
+            .dump(source_map_path(py_path))
+            .expect("failed to dump a source map (maybe permission denied)");
+    }
+
     pub fn pop_mod_ctx(&mut self) -> Option<ModuleContext> {
         self.builder.pop_mod_ctx()
     }
@@ -251,47 +523,112 @@ impl Transpiler {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct ScriptGenerator {
+    input: Input,
     level: usize,
     fresh_var_n: usize,
-    namedtuple_loaded: bool,
     mutate_op_loaded: bool,
     in_op_loaded: bool,
     range_ops_loaded: bool,
     builtin_types_loaded: bool,
     builtin_control_loaded: bool,
     convertors_loaded: bool,
+    result_loaded: bool,
+    /// names imported from `typing`
+    typing_loaded: Vec<&'static str>,
+    /// declared `TypeVar`s
+    type_vars: Vec<String>,
+    /// (fields, class name) of the `NamedTuple` classes generated for record types
+    record_types: Vec<(String, String)>,
+    /// whether type annotations are emitted in the current script
+    annotated: bool,
+    /// `--readable`: no name mangling, helper functions are defined in place
+    readable: bool,
+    /// the number of definitions of each name in the module (`--readable`)
+    names: HashMap<Str, usize>,
+    /// helper functions to be defined before the current statement
+    pending: String,
     prelude: String,
+    /// the variables defined in each function scope (the first one is the module scope)
+    scopes: Vec<Scope>,
+    /// the expressions that cannot be transpiled
+    errs: CompileErrors,
+}
+
+/// The names defined in a function, and the outer variables reassigned in it
+/// (which need `global`/`nonlocal` declarations).
+#[derive(Debug, Default)]
+struct Scope {
+    defined: HashSet<String>,
+    globals: Vec<String>,
+    nonlocals: Vec<String>,
 }
 
 impl ScriptGenerator {
-    pub const fn new() -> Self {
+    pub fn new(input: Input, readable: bool) -> Self {
         Self {
+            input,
             level: 0,
             fresh_var_n: 0,
-            namedtuple_loaded: false,
             mutate_op_loaded: false,
             in_op_loaded: false,
             range_ops_loaded: false,
             builtin_types_loaded: false,
             builtin_control_loaded: false,
             convertors_loaded: false,
+            result_loaded: false,
+            typing_loaded: vec![],
+            type_vars: vec![],
+            record_types: vec![],
+            annotated: false,
+            readable,
+            names: HashMap::new(),
+            pending: String::new(),
             prelude: String::new(),
+            scopes: vec![Scope::default()],
+            errs: CompileErrors::empty(),
         }
     }
 
-    pub fn transpile(&mut self, hir: HIR) -> PyScript {
+    pub fn transpile(&mut self, hir: HIR) -> Result<PyScript, CompileErrors> {
         let mut code = String::new();
+        if self.readable {
+            for chunk in hir.module.iter() {
+                collect_defined_names(chunk, &mut self.names);
+            }
+        }
         for chunk in hir.module.into_iter() {
-            code += &self.transpile_expr(chunk);
+            let stmt = self.transpile_stmt(chunk, &Discard);
+            code += &std::mem::take(&mut self.pending);
+            code += &stmt;
             code.push('\n');
         }
         code = std::mem::take(&mut self.prelude) + &code;
-        PyScript {
+        // annotations are not evaluated at runtime (e.g. `list[int]` is not available in Python 3.8)
+        if std::mem::take(&mut self.annotated) {
+            code = "from __future__ import annotations\n".to_string() + &code;
+        }
+        if !self.errs.is_empty() {
+            return Err(std::mem::take(&mut self.errs));
+        }
+        let (code, source_map) = extract_positions(&code);
+        Ok(PyScript {
             filename: hir.name,
             code,
-        }
+            source_map,
+        })
+    }
+
+    /// Records an expression that cannot be transpiled (the generated code is discarded).
+    fn feature_error(&mut self, loc: &impl Locational, name: &str) -> String {
+        self.errs.push(CompileError::feature_error(
+            self.input.clone(),
+            loc.loc(),
+            name,
+            String::new(),
+        ));
+        "None".to_string()
     }
 
     // TODO: more smart way
@@ -313,13 +650,6 @@ impl ScriptGenerator {
             .replace("from _erg_control import then__", "")
     }
 
-    fn load_namedtuple_if_not(&mut self) {
-        if !self.namedtuple_loaded {
-            self.prelude += "from collections import namedtuple as NamedTuple__\n";
-            self.namedtuple_loaded = true;
-        }
-    }
-
     // TODO: name escaping
     fn load_range_ops_if_not(&mut self) {
         if !self.range_ops_loaded {
@@ -376,11 +706,18 @@ impl ScriptGenerator {
 
     fn load_builtin_controls_if_not(&mut self) {
         if !self.builtin_control_loaded {
-            self.prelude += include_str!("lib/std/_erg_control.py");
+            self.prelude += &Self::replace_import(include_str!("lib/std/_erg_control.py"));
             self.builtin_control_loaded = true;
         }
     }
 
+    fn load_result_if_not(&mut self) {
+        if !self.result_loaded {
+            self.prelude += &Self::replace_import(include_str!("lib/std/_erg_result.py"));
+            self.result_loaded = true;
+        }
+    }
+
     fn load_convertors_if_not(&mut self) {
         if !self.convertors_loaded {
             self.prelude += &Self::replace_import(include_str!("lib/std/_erg_convertors.py"));
@@ -394,6 +731,8 @@ impl ScriptGenerator {
             .replace('\t', "\\t")
             .replace('\'', "\\'")
             .replace('\0', "\\0")
+            .replace(POS_START, "\\x01")
+            .replace(POS_END, "\\x02")
     }
 
     fn transpile_expr(&mut self, expr: Expr) -> String {
@@ -411,7 +750,13 @@ impl ScriptGenerator {
                     code += "]";
                     code
                 }
-                other => todo!("transpiling {other}"),
+                // [x; n] => [x] * n (same as `PyCodeGenerator::emit_array`)
+                Array::WithLength(arr) => {
+                    let elem = self.transpile_expr(*arr.elem);
+                    let len = self.transpile_expr(*arr.len);
+                    format!("([{elem}] * {len})")
+                }
+                Array::Comprehension(arr) => self.feature_error(&arr, "array comprehension"),
             },
             Expr::Set(set) => match set {
                 Set::Normal(st) => {
@@ -422,7 +767,8 @@ impl ScriptGenerator {
                     code += "}";
                     code
                 }
-                other => todo!("transpiling {other}"),
+                // {x; n} == {x}
+                Set::WithLength(st) => format!("{{{},}}", self.transpile_expr(*st.elem)),
             },
             Expr::Record(rec) => self.transpile_record(rec),
             Expr::Tuple(tuple) => match tuple {
@@ -448,7 +794,7 @@ impl ScriptGenerator {
                     code += "}";
                     code
                 }
-                other => todo!("transpiling {other}"),
+                Dict::Comprehension(dict) => self.feature_error(&dict, "dict comprehension"),
             },
             Expr::Accessor(acc) => self.transpile_acc(acc),
             Expr::Def(def) => self.transpile_def(def),
@@ -470,17 +816,36 @@ impl ScriptGenerator {
                 let root = PyCodeGenerator::get_root(&acc);
                 self.prelude += &format!(
                     "{} = __import__(\"{full_name}\")\n",
-                    Self::transpile_ident(root)
+                    self.transpile_ident(root)
                 );
                 String::new()
             }
             Expr::TypeAsc(tasc) => self.transpile_expr(*tasc.expr),
-            Expr::Code(_) => todo!("transpiling importing user-defined code"),
+            // the code of an inlined module (see `Linker::replace_erg_import`)
+            Expr::Code(block) => {
+                let module = Module::new(Vec::from(block));
+                let mut gen = ScriptGenerator::new(self.input.clone(), self.readable);
+                let script = match gen.transpile(HIR::new(Str::ever("<module>"), module)) {
+                    Ok(script) => script,
+                    Err(errs) => {
+                        self.errs.extend(errs);
+                        return "None".to_string();
+                    }
+                };
+                let escaped = script.code.replace('\\', "\\\\").replace('"', "\\\"");
+                format!("\"{}\"", Self::escape_str(&escaped))
+            }
             Expr::Dummy(_) => "".to_string(),
         }
     }
 
     fn transpile_lit(&mut self, lit: Literal) -> String {
+        // the token of a (multi-line) string may be a fragment of an interpolated string
+        if let ValueObj::Str(s) = &lit.value {
+            self.load_builtin_types_if_not();
+            let escaped = Self::escape_str(&s.replace('\\', "\\\\").replace('"', "\\\""));
+            return format!("Str(\"{escaped}\")");
+        }
         let escaped = Self::escape_str(&lit.token.content);
         if matches!(
             &lit.value,
@@ -494,26 +859,45 @@ impl ScriptGenerator {
     }
 
     fn transpile_record(&mut self, rec: Record) -> String {
-        self.load_namedtuple_if_not();
-        let mut attrs = "[".to_string();
-        let mut values = "(".to_string();
+        let class = self.py_type(rec.ref_t());
+        let mut code = format!("{class}(");
         for mut attr in rec.attrs.into_iter() {
-            attrs += &format!("'{}',", Self::transpile_ident(attr.sig.into_ident()));
+            // the fields of records are not mangled (same as the bytecode backend)
+            code += &format!("{}=", self.mangle(attr.sig.ident().inspect(), true));
             if attr.body.block.len() > 1 {
                 let name = format!("instant_block_{}__", self.fresh_var_n);
                 self.fresh_var_n += 1;
-                let mut code = format!("def {name}():\n");
-                code += &self.transpile_block(attr.body.block, Return);
-                self.prelude += &code;
-                values += &format!("{name}(),");
+                let mut block = format!("def {name}():\n");
+                block += &self.transpile_block(attr.body.block, Return);
+                self.define_helper(block);
+                code += &format!("{name}(),");
             } else {
                 let expr = attr.body.block.remove(0);
-                values += &format!("{},", self.transpile_expr(expr));
+                code += &format!("{},", self.transpile_expr(expr));
             }
         }
-        attrs += "]";
-        values += ")";
-        format!("NamedTuple__('Record', {attrs}){values}")
+        code += ")";
+        code
+    }
+
+    /// `: int`
+    fn transpile_annotation(&mut self, t: Option<&Type>) -> String {
+        if let Some(t) = t {
+            self.annotated = true;
+            format!(": {}", self.py_type(t))
+        } else {
+            "".to_string()
+        }
+    }
+
+    /// ` -> int`
+    fn transpile_return_annotation(&mut self, subr_t: &Type) -> String {
+        if let Some(subr) = subr_type(subr_t) {
+            self.annotated = true;
+            format!(" -> {}", self.py_type(&subr.return_t))
+        } else {
+            "".to_string()
+        }
     }
 
     fn transpile_binop(&mut self, bin: BinOp) -> String {
@@ -577,24 +961,36 @@ impl ScriptGenerator {
                     "Str" | "Bool" | "Nat" | "Array" => {
                         self.load_builtin_types_if_not();
                     }
-                    "if" | "if!" | "for!" | "while" | "discard" => {
+                    "if" | "if!" | "for!" | "while" | "discard" | "raise" => {
+                        self.load_builtin_controls_if_not();
+                    }
+                    "panic" => {
+                        self.load_result_if_not();
                         self.load_builtin_controls_if_not();
                     }
                     "int" | "nat" | "float" | "str" => {
                         self.load_convertors_if_not();
                     }
+                    // used by the inlined modules
+                    "#ModuleType" => {
+                        return "__import__(\"types\").ModuleType".to_string();
+                    }
                     _ => {}
                 }
-                Self::transpile_ident(ident)
+                self.transpile_ident(ident)
             }
-            Accessor::Attr(attr) => {
+            Accessor::Attr(mut attr) => {
+                // the fields of records are not mangled (see `PyCodeGenerator::emit_acc`)
+                if attr.obj.ref_t().is_record() {
+                    attr.ident.dot = Some(DOT);
+                }
                 if let Some(name) = debind(&attr.ident) {
                     demangle(&name)
                 } else {
                     format!(
                         "({}).{}",
                         self.transpile_expr(*attr.obj),
-                        Self::transpile_ident(attr.ident)
+                        self.transpile_ident(attr.ident)
                     )
                 }
             }
@@ -602,6 +998,19 @@ impl ScriptGenerator {
     }
 
     fn transpile_call(&mut self, mut call: Call) -> String {
+        match call.additional_operation() {
+            Some(OperationKind::Yield) => {
+                let value = call
+                    .args
+                    .try_remove(0)
+                    .map_or("None".to_string(), |arg| self.transpile_expr(arg));
+                return format!("(yield {value})");
+            }
+            Some(OperationKind::YieldFrom) => {
+                return format!("(yield from {})", self.transpile_expr(call.args.remove(0)));
+            }
+            _ => {}
+        }
         match call.obj.local_name() {
             Some("assert") => {
                 let mut code = format!("assert {}", self.transpile_expr(call.args.remove(0)));
@@ -611,133 +1020,138 @@ impl ScriptGenerator {
                 code
             }
             Some("not") => format!("(not ({}))", self.transpile_expr(call.args.remove(0))),
+            Some("Del") => format!("del {}", self.transpile_expr(call.args.remove(0))),
             Some("if" | "if!") => self.transpile_if(call),
             Some("for" | "for!") => {
                 let mut code = "for ".to_string();
                 let iter = call.args.remove(0);
-                let Expr::Lambda(block) = call.args.remove(0) else { todo!() };
-                let non_default = block.params.non_defaults.get(0).unwrap();
-                let ParamPattern::VarName(param) = &non_default.raw.pat else { todo!() };
-                code += &format!("{}__ ", &param.token().content);
-                code += &format!("in {}:\n", self.transpile_expr(iter));
-                code += &self.transpile_block(block.body, Discard);
+                match call.args.remove(0) {
+                    Expr::Lambda(mut block) => {
+                        let param = block.params.non_defaults.remove(0);
+                        code += &format!("{} ", self.transpile_param_name(param.raw.pat));
+                        code += &format!("in {}:\n", self.transpile_expr(iter));
+                        code += &self.transpile_block(block.body, Discard);
+                    }
+                    // for! xs, f! => for x in xs: f!(x)
+                    other => {
+                        let tmp = format!("for_tmp_{}__", self.fresh_var_n);
+                        self.fresh_var_n += 1;
+                        code += &format!("{tmp} in {}:\n", self.transpile_expr(iter));
+                        code += &"    ".repeat(self.level + 1);
+                        code += &format!("{}({tmp})\n", self.transpile_expr(other));
+                    }
+                }
                 code
             }
             Some("while" | "while!") => {
                 let mut code = "while ".to_string();
-                let cond = call.args.remove(0);
-                let Expr::Lambda(block) = call.args.remove(0) else { todo!() };
+                let cond = match call.args.remove(0) {
+                    // while! do!(cond), ... => while cond: ...
+                    Expr::Lambda(mut lambda) if lambda.body.len() == 1 => lambda.body.remove(0),
+                    other => other.call_expr(Args::empty()),
+                };
+                let body = block_body(call.args.remove(0));
                 code += &format!("{}:\n", self.transpile_expr(cond));
-                code += &self.transpile_block(block.body, Discard);
+                code += &self.transpile_block(body, Discard);
                 code
             }
             Some("match" | "match!") => self.transpile_match(call),
+            Some("with!") => self.transpile_branch_helper(call, "with"),
+            Some("try!") => self.transpile_try(call),
+            Some("await!") => format!("(await {})", self.transpile_expr(call.args.remove(0))),
             _ => self.transpile_simple_call(call),
         }
     }
 
     fn transpile_if(&mut self, mut call: Call) -> String {
-        let cond = self.transpile_expr(call.args.remove(0));
-        let Expr::Lambda(mut then_block) = call.args.remove(0) else { todo!() };
-        let else_block = call.args.try_remove(0).map(|ex| {
-            if let Expr::Lambda(blk) = ex {
-                blk
-            } else {
-                todo!()
-            }
-        });
-        if then_block.body.len() == 1
-            && else_block
-                .as_ref()
-                .map(|blk| blk.body.len() == 1)
-                .unwrap_or(true)
-        {
-            let then = self.transpile_expr(then_block.body.remove(0));
-            if let Some(mut else_block) = else_block {
-                let els = self.transpile_expr(else_block.body.remove(0));
-                return format!("{then} if {cond} else {els}");
-            } else {
-                return format!("{then} if {cond} else None");
-            }
+        if !is_simple_if(&call) {
+            return self.transpile_branch_helper(call, "if");
         }
-        let tmp = Str::from(format!("if_tmp_{}__", self.fresh_var_n));
-        self.fresh_var_n += 1;
-        let tmp_func = Str::from(format!("if_tmp_func_{}__", self.fresh_var_n));
-        self.fresh_var_n += 1;
-        let mut code = format!("def {tmp_func}():\n");
-        code += &format!("    if {cond}:\n");
-        let level = self.level;
-        self.level = 1;
-        code += &self.transpile_block(then_block.body, StoreTmp(tmp.clone()));
-        self.level = level;
-        if let Some(else_block) = else_block {
-            code += "    else:\n";
-            let level = self.level;
-            self.level = 1;
-            code += &self.transpile_block(else_block.body, StoreTmp(tmp.clone()));
-            self.level = level;
+        let cond = self.transpile_expr(call.args.remove(0));
+        let then = self.transpile_expr(block_body(call.args.remove(0)).remove(0));
+        if let Some(else_block) = call.args.try_remove(0) {
+            let els = self.transpile_expr(block_body(else_block).remove(0));
+            format!("{then} if {cond} else {els}")
         } else {
-            code += "    else:\n";
-            code += &format!("        {tmp} = None\n");
+            format!("{then} if {cond} else None")
         }
-        code += &format!("    return {tmp}\n");
-        self.prelude += &code;
-        // ~~ NOTE: In Python, the variable environment of a function is determined at call time
-        // This is a very bad design, but can be used for this code ~~
-        // FIXME: this trick only works in the global namespace
-        format!("{tmp_func}()")
     }
 
-    fn transpile_match(&mut self, mut call: Call) -> String {
-        let tmp = Str::from(format!("match_tmp_{}__", self.fresh_var_n));
+    fn transpile_match(&mut self, call: Call) -> String {
+        self.transpile_branch_helper(call, "match")
+    }
+
+    fn transpile_try(&mut self, mut call: Call) -> String {
+        let tmp = Str::from(format!("try_tmp_{}__", self.fresh_var_n));
         self.fresh_var_n += 1;
-        let tmp_func = Str::from(format!("match_tmp_func_{}__", self.fresh_var_n));
+        let tmp_func = Str::from(format!("try_tmp_func_{}__", self.fresh_var_n));
         self.fresh_var_n += 1;
-        let mut code = format!("def {tmp_func}():\n");
+        let level = self.level;
         self.level += 1;
-        code += &"    ".repeat(self.level);
-        code += "match ";
-        let cond = call.args.remove(0);
-        code += &format!("{}:\n", self.transpile_expr(cond));
+        let indent = "    ".repeat(self.level);
+        let pending = std::mem::take(&mut self.pending);
+        self.enter_scope();
+        let mut code = format!("{indent}try:\n");
+        match call.args.remove(0) {
+            Expr::Lambda(body) => {
+                code += &self.transpile_block(body.body, StoreTmp(tmp.clone()));
+            }
+            other => {
+                code += &format!("{indent}    {tmp} = ({})()\n", self.transpile_expr(other));
+            }
+        }
         while let Some(Expr::Lambda(arm)) = call.args.try_remove(0) {
-            self.level += 1;
-            code += &"    ".repeat(self.level);
-            let target = arm.params.non_defaults.get(0).unwrap();
-            match &target.raw.pat {
-                ParamPattern::VarName(param) => {
-                    code += &format!("case {}__:\n", &param.token().content);
-                    code += &self.transpile_block(arm.body, StoreTmp(tmp.clone()));
-                    self.level -= 1;
-                }
-                ParamPattern::Discard(_) => {
-                    match target.raw.t_spec.as_ref().map(|t| &t.t_spec) {
-                        Some(TypeSpec::Enum(enum_t)) => {
-                            let values = ValueObj::vec_from_const_args(enum_t.clone());
-                            if values.len() == 1 {
-                                code += &format!("case {}:\n", values[0]);
-                            } else {
-                                todo!()
-                            }
-                        }
-                        Some(_) => todo!(),
-                        None => {
-                            code += "case _:\n";
-                        }
-                    }
-                    code += &self.transpile_block(arm.body, StoreTmp(tmp.clone()));
-                    self.level -= 1;
-                }
-                _ => todo!(),
+            let target = arm.params.non_defaults.first().unwrap();
+            code += &format!("{indent}except");
+            if let Some(t_spec) = target.t_spec_as_expr.clone() {
+                code += &format!(" {}", self.transpile_exc_type_spec(t_spec));
+            }
+            if let ParamPattern::VarName(param) = &target.raw.pat {
+                code += &format!(" as {}", self.mangle(&param.token().content, false));
             }
+            code += ":\n";
+            code += &self.transpile_block(arm.body, StoreTmp(tmp.clone()));
         }
-        code += &"    ".repeat(self.level);
-        code += &format!("return {tmp}\n");
-        self.prelude += &code;
-        self.level -= 1;
+        code += &format!("{indent}return {tmp}\n");
+        self.level = level;
+        let decls = self.exit_scope();
+        let helpers = std::mem::replace(&mut self.pending, pending);
+        self.define_helper(format!("def {tmp_func}():\n{decls}{helpers}{code}"));
         format!("{tmp_func}()")
     }
 
-    fn transpile_simple_call(&mut self, call: Call) -> String {
+    /// `A or B` -> `(A, B)`
+    fn transpile_exc_type_spec(&mut self, t_spec: Expr) -> String {
+        match t_spec {
+            Expr::BinOp(bin) if bin.op.is(TokenKind::OrOp) => {
+                let lhs = self.transpile_exc_type_spec(*bin.lhs);
+                let rhs = self.transpile_exc_type_spec(*bin.rhs);
+                format!("({lhs}, {rhs})")
+            }
+            other => self.transpile_expr(other),
+        }
+    }
+
+    fn transpile_simple_call(&mut self, mut call: Call) -> String {
+        match call.attr_name.as_ref().map(|attr| &attr.inspect()[..]) {
+            // X.update! x -> x + 1 => X = mutate_operator((x -> x + 1)(X))
+            Some("update!") => {
+                self.load_mutate_op_if_not();
+                let acc = self.transpile_expr(*call.obj);
+                self.declare_outer(&acc);
+                let func = self.transpile_expr(call.args.remove(0));
+                return format!("{acc} = mutate_operator(({func})({acc}))");
+            }
+            // f.return x => return x
+            Some("return") if call.obj.ref_t().is_callable() => {
+                return match call.args.try_remove(0) {
+                    Some(value) => format!("return {}", self.transpile_expr(value)),
+                    None => "return None".to_string(),
+                };
+            }
+            _ => {}
+        }
+        let sig_t = call.signature_t().cloned();
         let is_py_api = if let Some(attr) = &call.attr_name {
             let is_py_api = attr.is_py_api();
             if let Some(name) = debind(attr) {
@@ -745,7 +1159,7 @@ impl ScriptGenerator {
                 return format!(
                     "{name}({}, {})",
                     self.transpile_expr(*call.obj),
-                    self.transpile_args(call.args, is_py_api, false)
+                    self.transpile_args(call.args, None, is_py_api, false)
                 );
             }
             is_py_api
@@ -754,13 +1168,19 @@ impl ScriptGenerator {
         };
         let mut code = format!("({})", self.transpile_expr(*call.obj));
         if let Some(attr) = call.attr_name {
-            code += &format!(".{}", Self::transpile_ident(attr));
+            code += &format!(".{}", self.transpile_ident(attr));
         }
-        code += &self.transpile_args(call.args, is_py_api, true);
+        code += &self.transpile_args(call.args, sig_t.as_ref(), is_py_api, true);
         code
     }
 
-    fn transpile_args(&mut self, mut args: Args, is_py_api: bool, paren: bool) -> String {
+    fn transpile_args(
+        &mut self,
+        mut args: Args,
+        sig_t: Option<&Type>,
+        is_py_api: bool,
+        paren: bool,
+    ) -> String {
         let mut code = String::new();
         if paren {
             code.push('(');
@@ -769,13 +1189,30 @@ impl ScriptGenerator {
             code += &self.transpile_expr(arg.expr);
             code.push(',');
         }
+        if let Some(var_args) = args.var_args.take() {
+            code += &format!("*{},", self.transpile_expr(var_args.expr));
+        }
         while let Some(arg) = args.try_remove_kw(0) {
-            let escape = if is_py_api { "" } else { "__" };
-            code += &format!(
-                "{}{escape}={},",
-                arg.keyword.content,
-                self.transpile_expr(arg.expr)
-            );
+            // keyword arguments passed to `**kwargs` are used as the keys of the dict
+            let to_kw_var_params = sig_t
+                .map(|t| {
+                    t.kw_var_params().is_some()
+                        && t.non_default_params()
+                            .into_iter()
+                            .flatten()
+                            .chain(t.default_params().into_iter().flatten())
+                            .all(|pt| pt.name() != Some(arg.keyword.inspect()))
+                })
+                .unwrap_or(false);
+            let keyword = if is_py_api || to_kw_var_params {
+                arg.keyword.content.to_string()
+            } else {
+                self.mangle(&arg.keyword.content, false)
+            };
+            code += &format!("{keyword}={},", self.transpile_expr(arg.expr));
+        }
+        if let Some(kw_var_args) = args.kw_var_args.take() {
+            code += &format!("**{},", self.transpile_expr(kw_var_args.expr));
         }
         if paren {
             code.push(')');
@@ -783,107 +1220,518 @@ impl ScriptGenerator {
         code
     }
 
-    fn transpile_ident(ident: Identifier) -> String {
+    fn transpile_ident(&self, ident: Identifier) -> String {
         if let Some(py_name) = ident.vi.py_name {
             return demangle(&py_name);
         }
-        let name = ident.name.into_token().content.to_string();
-        let name = replace_non_symbolic(name);
-        if ident.dot.is_some() {
-            name
+        let name = ident.name.into_token().content;
+        self.mangle(&name, ident.dot.is_some())
+    }
+
+    /// `x` => `x__` (private), `f!` => `f__erg_proc__`
+    ///
+    /// In the readable mode, names are mangled only if they collide with other names
+    /// (`f!` and `f`, Python keywords, or the names used by the transpiler).
+    fn mangle(&self, name: &str, public: bool) -> String {
+        if self.readable {
+            let stripped = name.trim_end_matches('!');
+            let collides = stripped != name && self.names.contains_key(stripped);
+            let name = replace_non_symbolic(stripped.to_string());
+            if collides || PY_RESERVED_NAMES.contains(&&name[..]) {
+                format!("{name}__")
+            } else {
+                name
+            }
         } else {
-            format!("{name}__")
+            let name = replace_non_symbolic(name.to_string());
+            if public {
+                name
+            } else {
+                format!("{name}__")
+            }
         }
     }
 
-    fn transpile_params(&mut self, params: Params) -> String {
+    /// `subr_t`: the type of the subroutine, used for the type annotations (`None` for `lambda`)
+    fn transpile_params(&mut self, params: Params, subr_t: Option<&Type>) -> String {
+        let subr = subr_t.and_then(subr_type);
+        let subr = subr.as_ref();
         let mut code = String::new();
-        for non_default in params.non_defaults {
-            match non_default.raw.pat {
-                ParamPattern::VarName(param) => {
-                    code += &format!("{}__,", param.into_token().content);
-                }
-                ParamPattern::Discard(_) => {
-                    code += &format!("_{},", self.fresh_var_n);
-                    self.fresh_var_n += 1;
-                }
-                _ => unreachable!(),
-            }
+        for (i, non_default) in params.non_defaults.into_iter().enumerate() {
+            let t = subr.and_then(|subr| subr.non_default_params.get(i));
+            let annot = self.transpile_annotation(t.map(|pt| pt.typ()));
+            let name = self.transpile_param_name(non_default.raw.pat);
+            code += &format!("{name}{annot},");
         }
-        for default in params.defaults {
-            let ParamPattern::VarName(param) = default.sig.raw.pat else { todo!() };
+        for (i, default) in params.defaults.into_iter().enumerate() {
+            let t = subr.and_then(|subr| subr.default_params.get(i));
+            let annot = self.transpile_annotation(t.map(|pt| pt.typ()));
+            let name = self.transpile_param_name(default.sig.raw.pat);
             code += &format!(
-                "{}__ = {},",
-                param.into_token().content,
+                "{name}{annot} = {},",
                 self.transpile_expr(default.default_val)
             );
         }
+        if let Some(var_params) = params.var_params {
+            let t = subr.and_then(|subr| subr.var_params.as_ref());
+            let annot = self.transpile_annotation(t.map(|pt| pt.typ()));
+            code += &format!("*{}{annot},", self.transpile_param_name(var_params.raw.pat));
+        }
+        if let Some(kw_var_params) = params.kw_var_params {
+            let t = subr.and_then(|subr| subr.kw_var_params.as_ref());
+            let annot = self.transpile_annotation(t.map(|pt| pt.typ()));
+            code += &format!(
+                "**{}{annot},",
+                self.transpile_param_name(kw_var_params.raw.pat)
+            );
+        }
         code
     }
 
+    fn transpile_param_name(&mut self, pat: ParamPattern) -> String {
+        match pat {
+            ParamPattern::VarName(param)
+            | ParamPattern::Ref(param)
+            | ParamPattern::RefMut(param) => self.mangle(&param.into_token().content, false),
+            ParamPattern::Discard(_) => {
+                self.fresh_var_n += 1;
+                format!("_{}", self.fresh_var_n - 1)
+            }
+            // tuple/array/record patterns are desugared (see `Desugarer::desugar_nd_param`)
+            other => self.feature_error(&other, &format!("parameter pattern `{other}`")),
+        }
+    }
+
     fn transpile_block(&mut self, block: Block, last_op: LastLineOperation) -> String {
         self.level += 1;
+        // the helper functions defined in this block are emitted in this block
+        let pending = std::mem::take(&mut self.pending);
         let mut code = String::new();
         let last = block.len().saturating_sub(1);
         for (i, chunk) in block.into_iter().enumerate() {
+            let stmt = if i == last {
+                self.transpile_stmt(chunk, &last_op)
+            } else {
+                self.transpile_stmt(chunk, &Discard)
+            };
+            code += &std::mem::take(&mut self.pending);
             code += &"    ".repeat(self.level);
-            if i == last {
-                match last_op {
-                    Return => {
-                        code += "return ";
-                    }
-                    Discard => {}
-                    StoreTmp(ref tmp) => {
-                        code += &format!("{tmp} = ");
-                    }
+            code += &stmt;
+            code.push('\n');
+        }
+        self.pending = pending;
+        self.level -= 1;
+        code
+    }
+
+    /// Transpiles a chunk of a block.
+    /// `if`/`match`/`with!` are transpiled into statements,
+    /// and instant blocks are inlined in the readable mode.
+    fn transpile_stmt(&mut self, chunk: Expr, last_op: &LastLineOperation) -> String {
+        // the position of the statement is recorded in the source map (see `extract_positions`)
+        let marker = match (chunk.ln_begin(), chunk.col_begin()) {
+            (Some(line), Some(col)) => format!("{POS_START}{line}:{col}{POS_END}"),
+            _ => String::new(),
+        };
+        let code = match chunk {
+            // `x = import "foo"` (the module is inlined, see `Linker::replace_erg_import`)
+            Expr::Def(mut def)
+                if matches!(def.sig, Signature::Var(_))
+                    && matches!(def.body.block.first(), Some(Expr::Compound(_))) =>
+            {
+                let Some(Expr::Compound(comp)) = def.body.block.pop() else {
+                    unreachable!()
+                };
+                let name = self.transpile_ident(def.sig.into_ident());
+                self.define_name(&name);
+                self.transpile_assign_stmt(name, comp)
+            }
+            Expr::Call(call) if is_branch_call(&call) => self.transpile_branch_stmt(call, last_op),
+            Expr::Def(def)
+                if matches!(def.sig, Signature::Var(_)) && self.can_inline(&def.body.block) =>
+            {
+                let Signature::Var(var) = def.sig else {
+                    unreachable!()
+                };
+                // local variables are not annotated
+                let annot =
+                    if def.body.block.len() > 1 && self.level == 0 && !var.ident.ref_t().has_qvar()
+                    {
+                        self.transpile_annotation(Some(var.ident.ref_t()))
+                    } else {
+                        "".to_string()
+                    };
+                let name = self.transpile_ident(var.ident);
+                self.define_name(&name);
+                let target = format!("{name}{annot}");
+                self.transpile_assign_stmt(target, def.body.block)
+            }
+            Expr::ReDef(redef) if self.can_inline(&redef.block) => {
+                let target = self.transpile_expr(Expr::Accessor(redef.attr));
+                self.transpile_assign_stmt(target, redef.block)
+            }
+            other => self.transpile_expr_stmt(other, last_op),
+        };
+        marker + &code
+    }
+
+    fn transpile_expr_stmt(&mut self, chunk: Expr, last_op: &LastLineOperation) -> String {
+        let is_stmt = is_statement(&chunk);
+        let code = self.transpile_expr(chunk);
+        self.complete_stmt(code, is_stmt, last_op)
+    }
+
+    /// Applies `last_op` to the code of the last line of a block.
+    fn complete_stmt(&self, code: String, is_stmt: bool, last_op: &LastLineOperation) -> String {
+        match last_op {
+            // the function returns `None` at the end
+            Return if is_stmt => code,
+            Return => format!("return {code}"),
+            Discard => code,
+            StoreTmp(tmp) if is_stmt => {
+                format!("{code}\n{}{tmp} = None", "    ".repeat(self.level))
+            }
+            StoreTmp(tmp) => format!("{tmp} = {code}"),
+        }
+    }
+
+    /// Whether `x = (block)` can be transpiled into statements.
+    /// Instant blocks are inlined only in the readable mode,
+    /// and the variables defined in them must not collide with the others.
+    fn can_inline(&self, block: &Block) -> bool {
+        if block.len() == 1 {
+            return matches!(block.first(), Some(Expr::Call(call)) if is_branch_call(call));
+        }
+        self.readable
+            && block.iter().all(|chunk| match chunk {
+                Expr::Def(def) => self.names.get(def.sig.ident().inspect()) == Some(&1),
+                Expr::ClassDef(classdef) => {
+                    self.names.get(classdef.sig.ident().inspect()) == Some(&1)
                 }
+                _ => true,
+            })
+    }
+
+    /// `x = (block)` => `y = 1; x = y + 1` (readable mode)
+    fn transpile_assign_stmt(&mut self, target: String, block: Block) -> String {
+        let store = StoreTmp(Str::from(target));
+        let indent = "    ".repeat(self.level);
+        let mut code = String::new();
+        let last = block.len().saturating_sub(1);
+        for (i, chunk) in block.into_iter().enumerate() {
+            let stmt = if i == last {
+                self.transpile_stmt(chunk, &store)
+            } else {
+                self.transpile_stmt(chunk, &Discard)
+            };
+            // the helper functions for the first line are defined by the caller
+            if i != 0 {
+                code.push('\n');
+                code += &std::mem::take(&mut self.pending);
+                code += &indent;
+            }
+            code += &stmt;
+        }
+        code
+    }
+
+    fn transpile_branch_stmt(&mut self, call: Call, last_op: &LastLineOperation) -> String {
+        match call.obj.local_name() {
+            Some("if" | "if!") => self.transpile_if_stmt(call, last_op),
+            Some("with!") => self.transpile_with_stmt(call, last_op),
+            _ => self.transpile_match_stmt(call, last_op),
+        }
+    }
+
+    /// `if cond: ... elif cond: ... else: ...`
+    fn transpile_if_stmt(&mut self, mut call: Call, last_op: &LastLineOperation) -> String {
+        let indent = "    ".repeat(self.level);
+        let cond = self.transpile_expr(call.args.remove(0));
+        let then_block = block_body(call.args.remove(0));
+        let mut code = format!("if {cond}:\n");
+        code += &self.transpile_block(then_block, last_op.clone());
+        match call.args.try_remove(0).map(block_body) {
+            Some(mut else_block) => {
+                let is_elif = else_block.len() == 1
+                    && matches!(
+                        else_block.first(),
+                        Some(Expr::Call(call)) if is_branch_call(call)
+                            && matches!(call.obj.local_name(), Some("if" | "if!"))
+                    );
+                if is_elif {
+                    let Expr::Call(elif) = else_block.remove(0) else {
+                        unreachable!()
+                    };
+                    code += &format!("{indent}el{}\n", self.transpile_if_stmt(elif, last_op));
+                } else {
+                    code += &format!("{indent}else:\n");
+                    code += &self.transpile_block(else_block, last_op.clone());
+                }
+            }
+            None => {
+                if let StoreTmp(tmp) = last_op {
+                    code += &format!("{indent}else:\n{indent}    {tmp} = None\n");
+                }
+            }
+        }
+        code.pop(); // \n
+        code
+    }
+
+    /// `match x: case ...`
+    fn transpile_match_stmt(&mut self, mut call: Call, last_op: &LastLineOperation) -> String {
+        let indent = "    ".repeat(self.level);
+        let cond = self.transpile_expr(call.args.remove(0));
+        let mut code = format!("match {cond}:\n");
+        while let Some(Expr::Lambda(arm)) = call.args.try_remove(0) {
+            let target = arm.params.non_defaults.first().unwrap();
+            let mut capture = match &target.raw.pat {
+                ParamPattern::VarName(param) if &param.inspect()[..] != "_" => {
+                    Some(self.mangle(&param.token().content, false))
+                }
+                ParamPattern::VarName(_) | ParamPattern::Discard(_) => None,
+                other => Some(self.transpile_param_name(other.clone())),
+            };
+            let mut guard = String::new();
+            let t_pattern = match target.raw.t_spec.as_ref().map(|t| &t.t_spec) {
+                Some(TypeSpec::Enum(enum_t)) => ValueObj::vec_from_const_args(enum_t.clone())
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                // (i: 0..<10) => case int() as i if 0 <= i < 10
+                Some(TypeSpec::Interval { op, lhs, rhs }) => {
+                    let var = capture.get_or_insert_with(|| {
+                        self.fresh_var_n += 1;
+                        format!("match_tmp_{}__", self.fresh_var_n - 1)
+                    });
+                    let lop = if op.inspect().starts_with('<') {
+                        "<"
+                    } else {
+                        "<="
+                    };
+                    let rop = if op.inspect().ends_with('<') {
+                        "<"
+                    } else {
+                        "<="
+                    };
+                    guard = format!(" if {lhs} {lop} {var} {rop} {rhs}");
+                    self.transpile_type_pattern(&target.vi.t)
+                }
+                Some(_) => self.transpile_type_pattern(&target.vi.t),
+                None => "_".to_string(),
+            };
+            let pattern = match capture {
+                Some(name) if t_pattern == "_" => name,
+                Some(name) => format!("{t_pattern} as {name}{guard}"),
+                None => t_pattern,
+            };
+            code += &format!("{indent}    case {pattern}:\n");
+            self.level += 1;
+            code += &self.transpile_block(arm.body, last_op.clone());
+            self.level -= 1;
+        }
+        code.pop(); // \n
+        code
+    }
+
+    /// `with obj as x: ...`
+    fn transpile_with_stmt(&mut self, mut call: Call, last_op: &LastLineOperation) -> String {
+        let obj = self.transpile_expr(call.args.remove(0));
+        let mut code = format!("with {obj}");
+        match call.args.remove(0) {
+            Expr::Lambda(body) => {
+                if let Some(param) = body.params.non_defaults.into_iter().next() {
+                    code += &format!(" as {}", self.transpile_param_name(param.raw.pat));
+                }
+                code += ":\n";
+                code += &self.transpile_block(body.body, last_op.clone());
+                code.pop(); // \n
+            }
+            // with! obj, f! => with obj as x: f!(x)
+            other => {
+                let tmp = format!("with_tmp_{}__", self.fresh_var_n);
+                self.fresh_var_n += 1;
+                let body = format!("{}({tmp})", self.transpile_expr(other));
+                code += &format!(" as {tmp}:\n{}", "    ".repeat(self.level + 1));
+                code += &self.complete_stmt(body, false, last_op);
             }
-            code += &self.transpile_expr(chunk);
-            code.push('\n');
         }
+        code
+    }
+
+    /// `Int or Str` => `int() | str()`
+    fn transpile_type_pattern(&mut self, t: &Type) -> String {
+        match t {
+            Type::FreeVar(fv) if fv.is_linked() => self.transpile_type_pattern(&fv.crack()),
+            Type::Refinement(refine) => self.transpile_type_pattern(&refine.t),
+            Type::Or(lhs, rhs) => {
+                let lhs = self.transpile_type_pattern(lhs);
+                let rhs = self.transpile_type_pattern(rhs);
+                if lhs == "_" || rhs == "_" {
+                    "_".to_string()
+                } else {
+                    format!("{lhs} | {rhs}")
+                }
+            }
+            Type::NoneType => "None".to_string(),
+            _ => {
+                let annot = self.py_type(t);
+                // list[int] => list()
+                let class = annot.split('[').next().unwrap_or_default();
+                match class {
+                    "Any" | "object" | "Callable" | "Iterable" | "Iterator" | "Union"
+                    | "NoReturn" => "_".to_string(),
+                    _ => format!("{class}()"),
+                }
+            }
+        }
+    }
+
+    /// `if`/`match`/`with!` in an expression => `def if_tmp_func_0__(): if ...`
+    fn transpile_branch_helper(&mut self, call: Call, kind: &str) -> String {
+        let tmp_func = format!("{kind}_tmp_func_{}__", self.fresh_var_n);
+        self.fresh_var_n += 1;
+        let pending = std::mem::take(&mut self.pending);
+        self.enter_scope();
+        self.level += 1;
+        let stmt = self.transpile_branch_stmt(call, &Return);
+        self.level -= 1;
+        let mut code = format!("def {tmp_func}():\n");
+        code += &self.exit_scope();
+        self.level += 1;
+        code += &std::mem::replace(&mut self.pending, pending);
+        code += &"    ".repeat(self.level);
+        code += &stmt;
+        code.push('\n');
         self.level -= 1;
+        self.define_helper(code);
+        format!("{tmp_func}()")
+    }
+
+    /// Defines a helper function (e.g. a multi-line lambda) just before the current statement.
+    /// Since it is defined in the same scope, it can refer to the local variables.
+    fn define_helper(&mut self, code: String) {
+        self.pending += &"    ".repeat(self.level);
+        self.pending += &code;
+    }
+
+    fn define_name(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.defined.insert(name.to_string());
+        }
+    }
+
+    /// `X.update! f` in a function reassigns `X` defined outside it
+    fn declare_outer(&mut self, name: &str) {
+        let Some((cur, outers)) = self.scopes.split_last_mut() else {
+            return;
+        };
+        if cur.defined.contains(name) {
+            return;
+        }
+        let decls = match outers
+            .iter()
+            .rposition(|scope| scope.defined.contains(name))
+        {
+            Some(0) => &mut cur.globals,
+            Some(_) => &mut cur.nonlocals,
+            None => return,
+        };
+        if !decls.iter().any(|decl| decl == name) {
+            decls.push(name.to_string());
+        }
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    /// Returns the `global`/`nonlocal` declarations for the function body
+    fn exit_scope(&mut self) -> String {
+        let scope = self.scopes.pop().unwrap_or_default();
+        let indent = "    ".repeat(self.level + 1);
+        let mut code = String::new();
+        for name in scope.globals.iter() {
+            code += &format!("{indent}global {name}\n");
+        }
+        for name in scope.nonlocals.iter() {
+            code += &format!("{indent}nonlocal {name}\n");
+        }
         code
     }
 
-    fn transpile_lambda(&mut self, lambda: Lambda) -> String {
-        if lambda.body.len() > 1 {
+    /// Transpiles a function body
+    fn transpile_body(&mut self, block: Block, last_op: LastLineOperation) -> String {
+        self.enter_scope();
+        let code = self.transpile_block(block, last_op);
+        self.exit_scope() + &code
+    }
+
+    fn transpile_lambda(&mut self, mut lambda: Lambda) -> String {
+        // a statement cannot be the body of `lambda`
+        if lambda.body.len() > 1 || lambda.body.first().is_some_and(is_statement) {
             let name = format!("lambda_{}__", self.fresh_var_n);
             self.fresh_var_n += 1;
-            let mut code = format!("def {name}({}):\n", self.transpile_params(lambda.params));
-            code += &self.transpile_block(lambda.body, Return);
-            self.prelude += &code;
+            let return_annot = self.transpile_return_annotation(&lambda.t);
+            let mut code = format!(
+                "def {name}({}){return_annot}:\n",
+                self.transpile_params(lambda.params, Some(&lambda.t))
+            );
+            code += &self.transpile_body(lambda.body, Return);
+            self.define_helper(code);
             name
         } else {
-            let mut code = format!("(lambda {}:", self.transpile_params(lambda.params));
-            code += &self.transpile_block(lambda.body, Discard);
-            code.pop(); // \n
-            code.push(')');
-            code
+            let def_params = lambda.params.clone();
+            let params = self.transpile_params(lambda.params, None);
+            let pending = std::mem::take(&mut self.pending);
+            self.level += 1;
+            let body = self.transpile_expr(lambda.body.remove(0));
+            self.level -= 1;
+            let helpers = std::mem::replace(&mut self.pending, pending);
+            if helpers.is_empty() {
+                format!("(lambda {params}:{}{body})", "    ".repeat(self.level + 1))
+            } else {
+                // the helper functions used in the body must be defined in the function
+                let name = format!("lambda_{}__", self.fresh_var_n);
+                self.fresh_var_n += 1;
+                let return_annot = self.transpile_return_annotation(&lambda.t);
+                let mut code = format!(
+                    "def {name}({}){return_annot}:\n",
+                    self.transpile_params(def_params, Some(&lambda.t))
+                );
+                code += &helpers;
+                code += &format!("{}return {body}\n", "    ".repeat(self.level + 1));
+                self.define_helper(code);
+                name
+            }
         }
     }
 
-    // TODO: trait definition
     fn transpile_def(&mut self, mut def: Def) -> String {
-        // HACK: allow reference to local variables in tmp functions
-        let mut code = if self.level == 0 {
-            "".to_string()
-        } else {
-            format!(
-                "global {}\n{}",
-                Self::transpile_ident(def.sig.ident().clone()),
-                "    ".repeat(self.level)
-            )
-        };
+        if def.def_kind().is_trait() {
+            return self.transpile_traitdef(def);
+        }
+        let mut code = String::new();
         match def.sig {
             Signature::Var(var) => {
-                code += &format!("{} = ", Self::transpile_ident(var.ident));
+                // a global variable cannot be annotated in a function
+                let annot = if self.level == 0 && !var.ident.ref_t().has_qvar() {
+                    self.transpile_annotation(Some(var.ident.ref_t()))
+                } else {
+                    "".to_string()
+                };
+                let name = self.transpile_ident(var.ident);
+                self.define_name(&name);
+                code += &format!("{name}{annot} = ");
                 if def.body.block.len() > 1 {
                     let name = format!("instant_block_{}__", self.fresh_var_n);
                     self.fresh_var_n += 1;
-                    let mut code = format!("def {name}():\n");
-                    code += &self.transpile_block(def.body.block, Return);
-                    self.prelude += &code;
-                    format!("{name}()")
+                    let mut block = format!("def {name}():\n");
+                    block += &self.transpile_body(def.body.block, Return);
+                    self.define_helper(block);
+                    code += &format!("{name}()");
+                    code
                 } else {
                     let expr = def.body.block.remove(0);
                     code += &self.transpile_expr(expr);
@@ -891,51 +1739,168 @@ impl ScriptGenerator {
                 }
             }
             Signature::Subr(subr) => {
+                if subr.is_async() {
+                    code += "async ";
+                }
+                // the return types of generators and coroutines are not the same as Erg's
+                let return_annot = if subr.is_generator || subr.is_async() {
+                    "".to_string()
+                } else {
+                    self.transpile_return_annotation(subr.ident.ref_t())
+                };
+                let var_params = subr
+                    .params
+                    .var_params
+                    .as_ref()
+                    .map(|param| self.transpile_param_name(param.raw.pat.clone()));
+                let params = self.transpile_params(subr.params, Some(subr.ident.ref_t()));
                 code += &format!(
-                    "def {}({}):\n",
-                    Self::transpile_ident(subr.ident),
-                    self.transpile_params(subr.params)
+                    "def {}({params}){return_annot}:\n",
+                    self.transpile_ident(subr.ident),
                 );
-                code += &self.transpile_block(def.body.block, Return);
+                // variable-length arguments are passed as an `Array` in Erg
+                // (the annotation of `*args` is a tuple, so mypy needs to be told about the conversion)
+                if let Some(var_params) = var_params {
+                    code += &"    ".repeat(self.level + 1);
+                    code +=
+                        &format!("{var_params} = list({var_params})  # type: ignore[assignment]\n");
+                }
+                code += &self.transpile_body(def.body.block, Return);
                 code
             }
         }
     }
 
+    /// ```erg
+    /// Norm = Trait {.norm = (self: Self) -> Nat; .x = Int}
+    /// ```
+    /// ↓
+    /// ```python
+    /// class Norm(Protocol):
+    ///     def norm(self,) -> int: ...
+    ///     x: int
+    /// ```
+    fn transpile_traitdef(&mut self, mut def: Def) -> String {
+        let kind = def.def_kind();
+        let name = self.transpile_ident(def.sig.into_ident());
+        self.define_name(&name);
+        let Some(Expr::Call(mut call)) = def.body.block.pop() else {
+            unreachable!()
+        };
+        // Sub = Subsume {...}, Super: Sup => class Sub(Sup, Protocol): ...
+        let (sup, req) = match call.args.remove_left_or_key("Super") {
+            Some(sup) if kind == DefKind::Subsume => (
+                format!("{}, ", self.transpile_expr(sup)),
+                call.args.remove_left_or_key("Additional"),
+            ),
+            req => (
+                "".to_string(),
+                req.or_else(|| call.args.remove_left_or_key("Requirement")),
+            ),
+        };
+        let protocol = self.load_typing("Protocol");
+        let mut code = format!("class {name}({sup}{protocol}):\n");
+        let indent = "    ".repeat(self.level + 1);
+        let Some(Expr::Record(req)) = req else {
+            return code + &indent + "pass";
+        };
+        if req.attrs.is_empty() {
+            return code + &indent + "pass";
+        }
+        for mut attr in req.attrs.into_iter() {
+            let attr_name = self.transpile_ident(attr.sig.into_ident());
+            match attr.body.block.remove(0) {
+                Expr::Lambda(mut lambda) => {
+                    let params = self.transpile_params(lambda.params, None);
+                    let return_t = lambda.body.remove(0);
+                    let return_t = self.transpile_type_expr(&return_t);
+                    code += &format!("{indent}def {attr_name}({params}) -> {return_t}: ...\n");
+                }
+                t => {
+                    let t = self.transpile_type_expr(&t);
+                    code += &format!("{indent}{attr_name}: {t}\n");
+                }
+            }
+        }
+        code.pop(); // \n
+        code
+    }
+
+    /// `Nat` => `int`
+    fn transpile_type_expr(&mut self, expr: &Expr) -> String {
+        let name = match expr {
+            Expr::Accessor(Accessor::Ident(ident)) => &ident.inspect()[..],
+            _ => "",
+        };
+        match name {
+            "Int" | "Nat" => "int".to_string(),
+            "Ratio" | "Float" => "float".to_string(),
+            "Bool" => "bool".to_string(),
+            "Str" => "str".to_string(),
+            "NoneType" => "None".to_string(),
+            "Obj" => "object".to_string(),
+            _ => self.load_typing("Any").to_string(),
+        }
+    }
+
     fn transpile_classdef(&mut self, classdef: ClassDef) -> String {
-        let class_name = Self::transpile_ident(classdef.sig.into_ident());
-        let mut code = format!("class {class_name}():\n");
-        let mut init_method = format!(
-            "{}def __init__(self, param__):\n",
-            "    ".repeat(self.level + 1)
-        );
-        match classdef.__new__.non_default_params().unwrap()[0].typ() {
-            Type::Record(rec) => {
+        let class_name = self.transpile_ident(classdef.sig.into_ident());
+        // Y = Inherit X => class Y(X): ...
+        let sup = match (classdef.obj, classdef.require_or_sup) {
+            (GenTypeObj::Subclass(_), Some(sup)) => self.transpile_expr(*sup),
+            _ => "".to_string(),
+        };
+        let mut code = format!("class {class_name}({sup}):\n");
+        let indent = "    ".repeat(self.level + 1);
+        let new_first_param = classdef
+            .__new__
+            .non_default_params()
+            .and_then(|params| params.first().map(|pt| pt.typ().clone()));
+        match &new_first_param {
+            Some(Type::Record(rec)) => {
+                code += &format!("{indent}def __init__(self, param__):\n");
                 for field in rec.keys() {
-                    let vis = if field.vis.is_private() { "__" } else { "" };
-                    init_method += &format!(
-                        "{}self.{}{vis} = param__.{}{vis}\n",
-                        "    ".repeat(self.level + 2),
-                        field.symbol,
-                        field.symbol,
-                    );
+                    let name = self.mangle(&field.symbol, field.vis.is_public());
+                    let field = self.mangle(&field.symbol, true);
+                    code += &format!("{indent}    self.{name} = param__.{field}\n");
                 }
+                if rec.is_empty() {
+                    code += &format!("{indent}    pass\n");
+                }
+            }
+            // C = Class Int => self::base = param
+            Some(_) => {
+                let base = self.mangle("base", false);
+                code += &format!("{indent}def __init__(self, param__):\n");
+                code += &format!("{indent}    self.{base} = param__\n");
+            }
+            None => {
+                code += &format!("{indent}def __init__(self): pass\n");
             }
-            other => todo!("{other}"),
         }
-        code += &init_method;
         if classdef.need_to_gen_new {
-            code += &"    ".repeat(self.level + 1);
-            code += &format!("def new(x): return {class_name}.__call__(x)\n");
+            if new_first_param.is_some() {
+                code += &format!("{indent}def new(x): return {class_name}.__call__(x)\n");
+            } else {
+                code += &format!("{indent}def new(): return {class_name}.__call__()\n");
+            }
         }
+        // the class body has its own namespace
+        self.enter_scope();
         code += &self.transpile_block(classdef.methods, Discard);
+        self.scopes.pop();
         code
     }
 
     fn transpile_patchdef(&mut self, patch_def: PatchDef) -> String {
         let mut code = String::new();
         for chunk in patch_def.methods.into_iter() {
-            let Expr::Def(mut def) = chunk else { todo!() };
+            let Expr::Def(mut def) = chunk else {
+                code += &"    ".repeat(self.level);
+                code += &self.transpile_expr(chunk);
+                code.push('\n');
+                continue;
+            };
             let name = format!(
                 "{}{}",
                 demangle(&patch_def.sig.ident().to_string_notype()),
@@ -954,10 +1919,11 @@ impl ScriptGenerator {
         if redef.block.len() > 1 {
             let name = format!("instant_block_{}__", self.fresh_var_n);
             self.fresh_var_n += 1;
-            let mut code = format!("def {name}():\n");
-            code += &self.transpile_block(redef.block, Return);
-            self.prelude += &code;
-            format!("{name}()")
+            let mut block = format!("def {name}():\n");
+            block += &self.transpile_body(redef.block, Return);
+            self.define_helper(block);
+            code += &format!("{name}()");
+            code
         } else {
             let expr = redef.block.remove(0);
             code += &self.transpile_expr(expr);
@@ -965,3 +1931,67 @@ impl ScriptGenerator {
         }
     }
 }
+
+impl PyTypeMapper for ScriptGenerator {
+    fn load_typing(&mut self, name: &'static str) -> &'static str {
+        if !self.typing_loaded.contains(&name) {
+            self.prelude += &format!("from typing import {name}\n");
+            self.typing_loaded.push(name);
+        }
+        name
+    }
+
+    fn type_var(&mut self, name: Str) -> String {
+        let var = format!("TypeVar__{}", replace_non_symbolic(name.replace('%', "v")));
+        if !self.type_vars.contains(&var) {
+            self.load_typing("TypeVar");
+            self.prelude += &format!("{var} = TypeVar('{var}')\n");
+            self.type_vars.push(var.clone());
+        }
+        var
+    }
+
+    /// Only the classes defined in this module can be referred to.
+    fn type_name(&mut self, name: &Str) -> Option<String> {
+        match (
+            name.strip_prefix("<module>::"),
+            name.strip_prefix("<module>."),
+        ) {
+            (Some(class), _) if !class.contains([':', '.']) => Some(self.mangle(class, false)),
+            (_, Some(class)) if !class.contains([':', '.']) => Some(self.mangle(class, true)),
+            _ => None,
+        }
+    }
+
+    /// `{x = Int; y = Str}` => `class Record0__(NamedTuple): x: int; y: str`
+    fn record_type(&mut self, fields: &HashMap<Field, Type>) -> String {
+        let mut attrs = vec![];
+        for (field, t) in fields.iter() {
+            let name = self.mangle(&field.symbol, true);
+            // type variables cannot be bound in the class body
+            let t = if t.has_qvar() {
+                self.any()
+            } else {
+                self.py_type(t)
+            };
+            attrs.push(format!("{name}: {t}"));
+        }
+        attrs.sort();
+        let key = attrs.join("; ");
+        if let Some((_, class)) = self.record_types.iter().find(|(k, _)| k == &key) {
+            return class.clone();
+        }
+        self.load_typing("NamedTuple");
+        let class = format!("Record{}__", self.record_types.len());
+        let mut code = format!("class {class}(NamedTuple):\n");
+        if attrs.is_empty() {
+            code += "    pass\n";
+        }
+        for attr in attrs {
+            code += &format!("    {attr}\n");
+        }
+        self.prelude += &code;
+        self.record_types.push((key, class.clone()));
+        class
+    }
+}
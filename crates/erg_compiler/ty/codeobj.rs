@@ -131,6 +131,53 @@ impl From<u8> for MakeFunctionFlags {
     }
 }
 
+/// An exception handler of a range of instructions (Python 3.11~).
+/// `start`, `end` and `target` are byte offsets, `end` is exclusive.
+/// See Objects/exception_handling_notes.txt in CPython for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExceptionTableEntry {
+    pub start: usize,
+    pub end: usize,
+    pub target: usize,
+    /// the stack depth to unwind to before the exception is pushed
+    pub depth: u32,
+    /// whether to push the offset of the raising instruction
+    pub lasti: bool,
+}
+
+impl ExceptionTableEntry {
+    pub const fn new(start: usize, end: usize, target: usize, depth: u32, lasti: bool) -> Self {
+        Self {
+            start,
+            end,
+            target,
+            depth,
+            lasti,
+        }
+    }
+
+    /// e.g. 300 (0b100101100) -> [0b01000100, 0b00101100]
+    fn encode_item(value: usize, mut msb: u8, bytes: &mut Vec<u8>) {
+        const CONTINUATION_BIT: u8 = 64;
+        for shift in [24, 18, 12, 6] {
+            if value >= 1 << shift {
+                bytes.push(((value >> shift) & 0x3f) as u8 | CONTINUATION_BIT | msb);
+                msb = 0;
+            }
+        }
+        bytes.push((value & 0x3f) as u8 | msb);
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        let depth_lasti = ((self.depth as usize) << 1) | self.lasti as usize;
+        // offsets are represented in code units (2 bytes)
+        Self::encode_item(self.start / 2, 1 << 7, bytes);
+        Self::encode_item((self.end - self.start) / 2, 0, bytes);
+        Self::encode_item(self.target / 2, 0, bytes);
+        Self::encode_item(depth_lasti, 0, bytes);
+    }
+}
+
 /// Implementation of `PyCodeObject`, see Include/cpython/code.h in CPython for details.
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct CodeObj {
@@ -220,8 +267,9 @@ impl CodeObj {
     ) -> Self {
         let name = name.into();
         let var_args_defined = (flags & CodeObjFlags::VarArgs as u32 != 0) as u32;
+        let kw_var_args_defined = (flags & CodeObjFlags::VarKeywords as u32 != 0) as u32;
         Self {
-            argcount: params.len() as u32 - var_args_defined,
+            argcount: params.len() as u32 - var_args_defined - kw_var_args_defined,
             posonlyargcount: 0,
             kwonlyargcount: 0,
             nlocals: params.len() as u32,
@@ -347,6 +395,54 @@ impl CodeObj {
         bytes
     }
 
+    /// Removes the line number tables of this and the nested code objects (for release builds).
+    pub fn strip_lnotab(&mut self) {
+        self.lnotab.clear();
+        for cons in self.consts.iter_mut() {
+            if let ValueObj::Code(code) = cons {
+                code.strip_lnotab();
+            }
+        }
+    }
+
+    /// The handlers may be nested.
+    /// CPython requires the entries to be sorted and not to overlap,
+    /// so the table is flattened so that each instruction belongs to its innermost (narrowest) handler.
+    pub fn set_exception_table(&mut self, handlers: &[ExceptionTableEntry]) {
+        let mut entries: Vec<ExceptionTableEntry> = vec![];
+        for offset in (0..self.code.len()).step_by(2) {
+            let Some(handler) = handlers
+                .iter()
+                .filter(|h| h.start <= offset && offset < h.end)
+                .min_by_key(|h| h.end - h.start)
+            else {
+                continue;
+            };
+            match entries.last_mut() {
+                Some(last)
+                    if last.end == offset
+                        && (last.target, last.depth, last.lasti)
+                            == (handler.target, handler.depth, handler.lasti) =>
+                {
+                    last.end += 2;
+                }
+                _ => {
+                    entries.push(ExceptionTableEntry::new(
+                        offset,
+                        offset + 2,
+                        handler.target,
+                        handler.depth,
+                        handler.lasti,
+                    ));
+                }
+            }
+        }
+        self.exceptiontable.clear();
+        for entry in entries.iter() {
+            entry.encode(&mut self.exceptiontable);
+        }
+    }
+
     fn dump_locals(
         varnames: Vec<Str>,
         freevars: Vec<Str>,
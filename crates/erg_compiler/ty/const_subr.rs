@@ -15,10 +15,10 @@ use crate::context::Context;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct UserConstSubr {
-    name: Str,
-    params: Params,
-    block: Block,
-    sig_t: Type,
+    pub name: Str,
+    pub params: Params,
+    pub block: Block,
+    pub sig_t: Type,
 }
 
 impl UserConstSubr {
@@ -146,11 +146,13 @@ impl ConstSubr {
                         if let Predicate::Equal { rhs, .. } = pred {
                             let return_t = Type::try_from(rhs).ok()?;
                             let var_params = subr.var_params.as_ref().map(|t| t.as_ref());
+                            let kw_var_params = subr.kw_var_params.as_ref().map(|t| t.as_ref());
                             return Some(subr_t(
                                 subr.kind,
                                 subr.non_default_params.clone(),
                                 var_params.cloned(),
                                 subr.default_params.clone(),
+                                kw_var_params.cloned(),
                                 return_t,
                             ));
                         }
@@ -1,5 +1,7 @@
 use std::convert::TryInto;
 
+use erg_common::dict;
+
 use crate::ty::*;
 
 #[inline]
@@ -58,6 +60,11 @@ pub fn dict_t(dict: TyParam) -> Type {
     poly("Dict", vec![dict])
 }
 
+/// `{Str: T}`, the type of variable-length keyword parameters
+pub fn str_dict_t(value: Type) -> Type {
+    dict_t(dict! { Type::Str => value }.into())
+}
+
 #[inline]
 pub fn range(t: Type) -> Type {
     poly("Range", vec![TyParam::t(t)])
@@ -171,6 +178,7 @@ pub fn subr_t(
     non_default_params: Vec<ParamTy>,
     var_params: Option<ParamTy>,
     default_params: Vec<ParamTy>,
+    kw_var_params: Option<ParamTy>,
     return_t: Type,
 ) -> Type {
     Type::Subr(SubrType::new(
@@ -178,6 +186,7 @@ pub fn subr_t(
         non_default_params,
         var_params,
         default_params,
+        kw_var_params,
         return_t,
     ))
 }
@@ -193,6 +202,7 @@ pub fn func(
         non_default_params,
         var_params,
         default_params,
+        None,
         return_t,
     ))
 }
@@ -240,6 +250,7 @@ pub fn proc(
         non_default_params,
         var_params,
         default_params,
+        None,
         return_t,
     ))
 }
@@ -274,6 +285,7 @@ pub fn fn_met(
         non_default_params,
         var_params,
         default_params,
+        None,
         return_t,
     ))
 }
@@ -309,6 +321,7 @@ pub fn pr_met(
         non_default_params,
         var_params,
         default_params,
+        None,
         return_t,
     ))
 }
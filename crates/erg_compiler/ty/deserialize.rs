@@ -45,6 +45,7 @@ impl From<DeserializeError> for ErrorCore {
             ErrorKind::ImportError,
             Location::Unknown,
         )
+        .with_code("E0080")
     }
 }
 
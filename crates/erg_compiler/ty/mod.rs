@@ -230,7 +230,7 @@ pub struct SubrType {
     pub non_default_params: Vec<ParamTy>,
     pub var_params: Option<Box<ParamTy>>, // TODO: need to have a position (var_params can be specified after default_params)
     pub default_params: Vec<ParamTy>,
-    // var_kw_params: Option<(Str, Box<Type>)>,
+    pub kw_var_params: Option<Box<ParamTy>>,
     pub return_t: Box<Type>,
 }
 
@@ -264,6 +264,16 @@ impl LimitedDisplay for SubrType {
             write!(f, ", {} := ", pt.name().unwrap())?;
             pt.typ().limited_fmt(f, limit - 1)?;
         }
+        if let Some(kw_var_params) = &self.kw_var_params {
+            if !self.non_default_params.is_empty()
+                || self.var_params.is_some()
+                || !self.default_params.is_empty()
+            {
+                write!(f, ", ")?;
+            }
+            write!(f, "**")?;
+            kw_var_params.typ().limited_fmt(f, limit - 1)?;
+        }
         write!(f, ") {} ", self.kind.arrow())?;
         self.return_t.limited_fmt(f, limit - 1)
     }
@@ -275,6 +285,7 @@ impl SubrType {
         non_default_params: Vec<ParamTy>,
         var_params: Option<ParamTy>,
         default_params: Vec<ParamTy>,
+        kw_var_params: Option<ParamTy>,
         return_t: Type,
     ) -> Self {
         Self {
@@ -282,6 +293,7 @@ impl SubrType {
             non_default_params,
             var_params: var_params.map(Box::new),
             default_params,
+            kw_var_params: kw_var_params.map(Box::new),
             return_t: Box::new(return_t),
         }
     }
@@ -299,6 +311,11 @@ impl SubrType {
                 .default_params
                 .iter()
                 .any(|pt| pt.typ().contains_tvar(name))
+            || self
+                .kw_var_params
+                .as_ref()
+                .map(|pt| pt.typ().contains_tvar(name))
+                .unwrap_or(false)
             || self.return_t.contains_tvar(name)
     }
 
@@ -313,6 +330,9 @@ impl SubrType {
         for pt in self.default_params.iter() {
             qvars.extend(pt.typ().qvars());
         }
+        if let Some(kw_var_params) = &self.kw_var_params {
+            qvars.extend(kw_var_params.typ().qvars());
+        }
         qvars.extend(self.return_t.qvars());
         qvars
     }
@@ -325,6 +345,11 @@ impl SubrType {
                 .map(|pt| pt.typ().has_qvar())
                 .unwrap_or(false)
             || self.default_params.iter().any(|pt| pt.typ().has_qvar())
+            || self
+                .kw_var_params
+                .as_ref()
+                .map(|pt| pt.typ().has_qvar())
+                .unwrap_or(false)
             || self.return_t.has_qvar()
     }
 
@@ -343,6 +368,11 @@ impl SubrType {
                 .iter()
                 .map(|pt| TyParam::t(pt.typ().clone()))
                 .collect(),
+            self.kw_var_params
+                .as_ref()
+                .map(|pt| TyParam::t(pt.typ().clone()))
+                .into_iter()
+                .collect(),
         ]
         .concat()
     }
@@ -522,6 +552,7 @@ pub struct ArgsOwnership {
     pub non_defaults: Vec<(Option<Str>, Ownership)>,
     pub var_params: Option<(Option<Str>, Ownership)>,
     pub defaults: Vec<(Str, Ownership)>,
+    pub kw_var_params: Option<(Option<Str>, Ownership)>,
 }
 
 impl fmt::Display for ArgsOwnership {
@@ -548,6 +579,14 @@ impl fmt::Display for ArgsOwnership {
         for (name, o) in self.defaults.iter() {
             write!(f, ", {name} := {o:?}")?;
         }
+        if let Some((name, o)) = self.kw_var_params.as_ref() {
+            write!(f, ", **")?;
+            if let Some(name) = name {
+                write!(f, "{name}: {o:?}")?;
+            } else {
+                write!(f, "{o:?}")?;
+            }
+        }
         write!(f, ")")?;
         Ok(())
     }
@@ -558,11 +597,13 @@ impl ArgsOwnership {
         non_defaults: Vec<(Option<Str>, Ownership)>,
         var_params: Option<(Option<Str>, Ownership)>,
         defaults: Vec<(Str, Ownership)>,
+        kw_var_params: Option<(Option<Str>, Ownership)>,
     ) -> Self {
         Self {
             non_defaults,
             var_params,
             defaults,
+            kw_var_params,
         }
     }
 }
@@ -1006,8 +1047,13 @@ impl HasLevel for Type {
                     .iter()
                     .filter_map(|p| p.typ().level())
                     .min();
+                let kv_min = subr
+                    .kw_var_params
+                    .iter()
+                    .filter_map(|p| p.typ().level())
+                    .min();
                 let ret_min = subr.return_t.level();
-                [nd_min, v_min, d_min, ret_min]
+                [nd_min, v_min, d_min, kv_min, ret_min]
                     .iter()
                     .filter_map(|o| *o)
                     .min()
@@ -1087,6 +1133,9 @@ impl HasLevel for Type {
                 for pt in subr.default_params.iter() {
                     pt.typ().set_level(level);
                 }
+                if let Some(pt) = subr.kw_var_params.as_ref() {
+                    pt.typ().set_level(level);
+                }
                 subr.return_t.set_level(level);
             }
             Self::And(lhs, rhs) | Self::Or(lhs, rhs) => {
@@ -1358,9 +1407,24 @@ impl Type {
                     };
                     d_args.push((d_param.name().unwrap().clone(), ownership));
                 }
-                ArgsOwnership::new(nd_args, var_args, d_args)
-            }
-            _ => todo!(),
+                let kw_var_args = subr
+                    .kw_var_params
+                    .as_ref()
+                    .map(|t| (t.name().cloned(), t.typ().ownership()));
+                ArgsOwnership::new(nd_args, var_args, d_args, kw_var_args)
+            }
+            Self::Quantified(quant) => quant.args_ownership(),
+            Self::Callable { param_ts, .. } => {
+                let nd_args = param_ts.iter().map(|t| (None, t.ownership())).collect();
+                ArgsOwnership::new(nd_args, None, vec![], None)
+            }
+            // unknown signature: all arguments are regarded as moved
+            _ => ArgsOwnership::new(
+                vec![],
+                Some((None, Ownership::Owned)),
+                vec![],
+                Some((None, Ownership::Owned)),
+            ),
         }
     }
 
@@ -1685,6 +1749,11 @@ impl Type {
                         .default_params
                         .iter()
                         .any(|pt| pt.typ().has_unbound_var())
+                    || subr
+                        .kw_var_params
+                        .as_ref()
+                        .map(|pt| pt.typ().has_unbound_var())
+                        .unwrap_or(false)
                     || subr.return_t.has_unbound_var()
             }
             Self::Record(r) => r.values().any(|t| t.has_unbound_var()),
@@ -1714,6 +1783,7 @@ impl Type {
                 subr.non_default_params.len()
                     + subr.var_params.as_ref().map(|_| 1).unwrap_or(0)
                     + subr.default_params.len()
+                    + subr.kw_var_params.as_ref().map(|_| 1).unwrap_or(0)
                     + 1,
             ),
             Self::Callable { param_ts, .. } => Some(param_ts.len() + 1),
@@ -1803,6 +1873,19 @@ impl Type {
         }
     }
 
+    pub fn kw_var_params(&self) -> Option<&ParamTy> {
+        match self {
+            Self::FreeVar(fv) if fv.is_linked() => unsafe { fv.as_ptr().as_ref() }
+                .unwrap()
+                .linked()
+                .and_then(|t| t.kw_var_params()),
+            Self::Refinement(refine) => refine.t.kw_var_params(),
+            Self::Subr(SubrType { kw_var_params, .. }) => kw_var_params.as_deref(),
+            Self::Quantified(quant) => quant.kw_var_params(),
+            _ => None,
+        }
+    }
+
     pub fn default_params(&self) -> Option<&Vec<ParamTy>> {
         match self {
             Self::FreeVar(fv) if fv.is_linked() => unsafe { fv.as_ptr().as_ref() }
@@ -1949,6 +2032,10 @@ impl Type {
                 for d in subr.default_params.iter_mut() {
                     *d.typ_mut() = std::mem::take(d.typ_mut()).replace(target, to);
                 }
+                if let Some(kw_var) = subr.kw_var_params.as_mut() {
+                    *kw_var.as_mut().typ_mut() =
+                        std::mem::take(kw_var.as_mut().typ_mut()).replace(target, to);
+                }
                 subr.return_t = Box::new(subr.return_t.replace(target, to));
                 Self::Subr(subr)
             }
@@ -46,13 +46,16 @@ impl From<EvalValueError> for ErrorCore {
 
 impl EvalValueError {
     pub fn feature_error(_input: Input, loc: Location, name: &str, caused_by: String) -> Self {
-        Self::from(ErrorCore::new(
-            vec![],
-            format!("{name} is not supported yet: {caused_by}"),
-            0,
-            ErrorKind::FeatureError,
-            loc,
-        ))
+        Self::from(
+            ErrorCore::new(
+                vec![],
+                format!("{name} is not supported yet: {caused_by}"),
+                0,
+                ErrorKind::FeatureError,
+                loc,
+            )
+            .with_code("E0079"),
+        )
     }
 }
 
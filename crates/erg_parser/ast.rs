@@ -31,6 +31,7 @@ pub enum OperationKind {
     Subsume,
     Return,
     Yield,
+    YieldFrom,
 }
 
 impl OperationKind {
@@ -161,6 +162,7 @@ pub struct Args {
     pos_args: Vec<PosArg>,
     pub(crate) var_args: Option<Box<PosArg>>,
     kw_args: Vec<KwArg>,
+    pub(crate) kw_var_args: Option<Box<PosArg>>,
     pub paren: Option<(Token, Token)>,
 }
 
@@ -198,22 +200,24 @@ impl Args {
         pos_args: Vec<PosArg>,
         var_args: Option<PosArg>,
         kw_args: Vec<KwArg>,
+        kw_var_args: Option<PosArg>,
         paren: Option<(Token, Token)>,
     ) -> Self {
         Self {
             pos_args,
             var_args: var_args.map(Box::new),
             kw_args,
+            kw_var_args: kw_var_args.map(Box::new),
             paren,
         }
     }
 
     pub fn pos_only(pos_args: Vec<PosArg>, paren: Option<(Token, Token)>) -> Self {
-        Self::new(pos_args, None, vec![], paren)
+        Self::new(pos_args, None, vec![], None, paren)
     }
 
     pub fn empty() -> Self {
-        Self::new(vec![], None, vec![], None)
+        Self::new(vec![], None, vec![], None, None)
     }
 
     // for replacing to hir::Args
@@ -224,12 +228,14 @@ impl Args {
         Vec<PosArg>,
         Option<PosArg>,
         Vec<KwArg>,
+        Option<PosArg>,
         Option<(Token, Token)>,
     ) {
         (
             self.pos_args,
             self.var_args.map(|x| *x),
             self.kw_args,
+            self.kw_var_args.map(|x| *x),
             self.paren,
         )
     }
@@ -290,6 +296,10 @@ impl Args {
         self.var_args = Some(Box::new(arg));
     }
 
+    pub fn set_kw_var_args(&mut self, arg: PosArg) {
+        self.kw_var_args = Some(Box::new(arg));
+    }
+
     pub fn push_kw(&mut self, arg: KwArg) {
         self.kw_args.push(arg);
     }
@@ -1217,6 +1227,12 @@ impl Locational for Block {
 
 impl_stream!(Block, Expr);
 
+impl Block {
+    pub fn contains_yield_of(&self, name: &str) -> bool {
+        self.0.iter().any(|expr| expr.contains_yield_of(name))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Dummy {
     pub loc: Option<Location>,
@@ -1816,6 +1832,7 @@ impl ConstArgs {
                 // TODO t_spec
                 .map(|arg| KwArg::new(arg.keyword, None, arg.expr.downcast()))
                 .collect(),
+            None,
             paren,
         )
     }
@@ -3122,6 +3139,7 @@ pub struct Params {
     pub non_defaults: Vec<NonDefaultParamSignature>,
     pub var_params: Option<Box<NonDefaultParamSignature>>,
     pub defaults: Vec<DefaultParamSignature>,
+    pub kw_var_params: Option<Box<NonDefaultParamSignature>>,
     pub parens: Option<(Token, Token)>,
 }
 
@@ -3129,10 +3147,11 @@ impl fmt::Display for Params {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "({}, {}, {})",
+            "({}, {}, {}, {})",
             fmt_vec(&self.non_defaults),
             fmt_option!(pre "*", &self.var_params),
-            fmt_vec(&self.defaults)
+            fmt_vec(&self.defaults),
+            fmt_option!(pre "**", &self.kw_var_params),
         )
     }
 }
@@ -3145,6 +3164,17 @@ impl Locational for Params {
                 return loc;
             }
         }
+        if let Some(kw_var) = self.kw_var_params.as_deref() {
+            return if let Some(l) = self.non_defaults.first() {
+                Location::concat(l, kw_var)
+            } else if let Some(l) = self.var_params.as_deref() {
+                Location::concat(l, kw_var)
+            } else if let Some(l) = self.defaults.first() {
+                Location::concat(l, kw_var)
+            } else {
+                kw_var.loc()
+            };
+        }
         match (
             self.non_defaults.first(),
             self.var_params.as_ref(),
@@ -3165,6 +3195,7 @@ type RawParams = (
     Vec<NonDefaultParamSignature>,
     Option<Box<NonDefaultParamSignature>>,
     Vec<DefaultParamSignature>,
+    Option<Box<NonDefaultParamSignature>>,
     Option<(Token, Token)>,
 );
 
@@ -3173,12 +3204,14 @@ impl Params {
         non_defaults: Vec<NonDefaultParamSignature>,
         var_params: Option<NonDefaultParamSignature>,
         defaults: Vec<DefaultParamSignature>,
+        kw_var_params: Option<NonDefaultParamSignature>,
         parens: Option<(Token, Token)>,
     ) -> Self {
         Self {
             non_defaults,
             var_params: var_params.map(Box::new),
             defaults,
+            kw_var_params: kw_var_params.map(Box::new),
             parens,
         }
     }
@@ -3188,6 +3221,7 @@ impl Params {
             self.non_defaults,
             self.var_params,
             self.defaults,
+            self.kw_var_params,
             self.parens,
         )
     }
@@ -3334,7 +3368,7 @@ impl LambdaSignature {
     pub fn do_sig(do_symbol: &Token) -> Self {
         let parens = Some((do_symbol.clone(), do_symbol.clone()));
         Self::new(
-            Params::new(vec![], None, vec![], parens),
+            Params::new(vec![], None, vec![], None, parens),
             None,
             TypeBoundSpecs::empty(),
         )
@@ -3828,6 +3862,40 @@ impl Expr {
         }
     }
 
+    /// `name.yield x` or `name.yield_from x` is called in the expression
+    /// (not including the ones in nested subroutine definitions)
+    pub fn contains_yield_of(&self, name: &str) -> bool {
+        match self {
+            Expr::Call(call) => {
+                let is_yield = matches!(
+                    call.attr_name.as_ref().map(|ident| &ident.inspect()[..]),
+                    Some("yield" | "yield_from")
+                ) && matches!(
+                    call.obj.as_ref(),
+                    Expr::Accessor(Accessor::Ident(ident)) if &ident.inspect()[..] == name
+                );
+                is_yield
+                    || call.obj.contains_yield_of(name)
+                    || call
+                        .args
+                        .pos_args()
+                        .iter()
+                        .any(|arg| arg.expr.contains_yield_of(name))
+                    || call
+                        .args
+                        .kw_args()
+                        .iter()
+                        .any(|arg| arg.expr.contains_yield_of(name))
+            }
+            Expr::Lambda(lambda) => lambda.body.contains_yield_of(name),
+            Expr::BinOp(bin) => bin.args.iter().any(|arg| arg.contains_yield_of(name)),
+            Expr::UnaryOp(unary) => unary.args.iter().any(|arg| arg.contains_yield_of(name)),
+            Expr::TypeAscription(tasc) => tasc.expr.contains_yield_of(name),
+            Expr::Def(def) if !def.is_subr() => def.body.block.contains_yield_of(name),
+            _ => false,
+        }
+    }
+
     pub fn local(name: &str, lineno: u32, col_begin: u32) -> Self {
         Self::Accessor(Accessor::local(Token::new(
             TokenKind::Symbol,
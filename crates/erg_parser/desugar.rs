@@ -56,7 +56,7 @@ impl Desugarer {
     }
 
     fn desugar_args(mut desugar: impl FnMut(Expr) -> Expr, args: Args) -> Args {
-        let (pos_args, var_args, kw_args, paren) = args.deconstruct();
+        let (pos_args, var_args, kw_args, kw_var_args, paren) = args.deconstruct();
         let pos_args = pos_args
             .into_iter()
             .map(|arg| PosArg::new(desugar(arg.expr)))
@@ -68,7 +68,8 @@ impl Desugarer {
                 KwArg::new(arg.keyword, arg.t_spec, desugar(arg.expr)) // TODO: t_spec
             })
             .collect();
-        Args::new(pos_args, var_args, kw_args, paren)
+        let kw_var_args = kw_var_args.map(|arg| PosArg::new(desugar(arg.expr)));
+        Args::new(pos_args, var_args, kw_args, kw_var_args, paren)
     }
 
     fn perform_desugar_acc(mut desugar: impl FnMut(Expr) -> Expr, acc: Accessor) -> Accessor {
@@ -139,12 +140,12 @@ impl Desugarer {
             }
             Expr::Array(array) => match array {
                 Array::Normal(arr) => {
-                    let (elems, _, _, _) = arr.elems.deconstruct();
+                    let (elems, _, _, _, _) = arr.elems.deconstruct();
                     let elems = elems
                         .into_iter()
                         .map(|elem| PosArg::new(desugar(elem.expr)))
                         .collect();
-                    let elems = Args::new(elems, None, vec![], None);
+                    let elems = Args::new(elems, None, vec![], None, None);
                     let arr = NormalArray::new(arr.l_sqbr, arr.r_sqbr, elems);
                     Expr::Array(Array::Normal(arr))
                 }
@@ -169,24 +170,24 @@ impl Desugarer {
             },
             Expr::Tuple(tuple) => match tuple {
                 Tuple::Normal(tup) => {
-                    let (elems, _, _, paren) = tup.elems.deconstruct();
+                    let (elems, _, _, _, paren) = tup.elems.deconstruct();
                     let elems = elems
                         .into_iter()
                         .map(|elem| PosArg::new(desugar(elem.expr)))
                         .collect();
-                    let new_tup = Args::new(elems, None, vec![], paren);
+                    let new_tup = Args::new(elems, None, vec![], None, paren);
                     let tup = NormalTuple::new(new_tup);
                     Expr::Tuple(Tuple::Normal(tup))
                 }
             },
             Expr::Set(set) => match set {
                 astSet::Normal(set) => {
-                    let (elems, _, _, _) = set.elems.deconstruct();
+                    let (elems, _, _, _, _) = set.elems.deconstruct();
                     let elems = elems
                         .into_iter()
                         .map(|elem| PosArg::new(desugar(elem.expr)))
                         .collect();
-                    let elems = Args::new(elems, None, vec![], None);
+                    let elems = Args::new(elems, None, vec![], None, None);
                     let set = NormalSet::new(set.l_brace, set.r_brace, elems);
                     Expr::Set(astSet::Normal(set))
                 }
@@ -353,7 +354,7 @@ impl Desugarer {
                             ));
                             let param =
                                 NonDefaultParamSignature::new(ParamPattern::VarName(param), None);
-                            let params = Params::new(vec![param], None, vec![], None);
+                            let params = Params::new(vec![param], None, vec![], None, None);
                             let sig = Signature::Subr(SubrSignature::new(
                                 set! {},
                                 name,
@@ -5,7 +5,7 @@ use std::fmt;
 
 use erg_common::config::Input;
 use erg_common::error::{
-    ErrorCore, ErrorDisplay, ErrorKind::*, Location, MultiErrorDisplay, SubMessage,
+    ErrorCore, ErrorDisplay, ErrorKind::*, FixIt, Location, MultiErrorDisplay, SubMessage,
 };
 use erg_common::style::{Attribute, Color, StyledStr, StyledString, StyledStrings, THEME};
 use erg_common::traits::Stream;
@@ -62,54 +62,69 @@ impl LexError {
         }
     }
 
+    pub fn push_fix_it(&mut self, fix_it: FixIt) {
+        if let Some(sub_msg) = self.0.sub_messages.get_mut(0) {
+            sub_msg.push_fix_it(fix_it)
+        }
+    }
+
     pub fn compiler_bug(errno: usize, loc: Location, fn_name: &str, line: u32) -> Self {
         const URL: StyledStr = StyledStr::new(
             "https://github.com/erg-lang/erg",
             Some(ACCENT),
             Some(Attribute::Underline),
         );
-        Self::new(ErrorCore::new(
-            vec![SubMessage::only_loc(loc)],
-            switch_lang!(
-                "japanese" => format!("これはErg compilerのバグです、開発者に報告して下さい ({URL})\n{fn_name}:{line}より発生"),
-                "simplified_chinese" => format!("这是Erg编译器的一个错误，请报告给{URL}\n原因来自: {fn_name}:{line}"),
-                "traditional_chinese" => format!("這是Erg編譯器的一個錯誤，請報告給{URL}\n原因來自: {fn_name}:{line}"),
-                "english" => format!("this is a bug of the Erg compiler, please report it to {URL}\ncaused from: {fn_name}:{line}"),
-            ),
-            errno,
-            CompilerSystemError,
-            loc,
-        ))
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("これはErg compilerのバグです、開発者に報告して下さい ({URL})\n{fn_name}:{line}より発生"),
+                    "simplified_chinese" => format!("这是Erg编译器的一个错误，请报告给{URL}\n原因来自: {fn_name}:{line}"),
+                    "traditional_chinese" => format!("這是Erg編譯器的一個錯誤，請報告給{URL}\n原因來自: {fn_name}:{line}"),
+                    "english" => format!("this is a bug of the Erg compiler, please report it to {URL}\ncaused from: {fn_name}:{line}"),
+                ),
+                errno,
+                CompilerSystemError,
+                loc,
+            )
+            .with_code("E0002"),
+        )
     }
 
     pub fn feature_error(errno: usize, loc: Location, name: &str) -> Self {
-        Self::new(ErrorCore::new(
-            vec![SubMessage::only_loc(loc)],
-            switch_lang!(
-                "japanese" => format!("この機能({name})はまだ正式に提供されていません"),
-                "simplified_chinese" => format!("此功能（{name}）尚未实现"),
-                "traditional_chinese" => format!("此功能（{name}）尚未實現"),
-                "english" => format!("this feature({name}) is not implemented yet"),
-            ),
-            errno,
-            FeatureError,
-            loc,
-        ))
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("この機能({name})はまだ正式に提供されていません"),
+                    "simplified_chinese" => format!("此功能（{name}）尚未实现"),
+                    "traditional_chinese" => format!("此功能（{name}）尚未實現"),
+                    "english" => format!("this feature({name}) is not implemented yet"),
+                ),
+                errno,
+                FeatureError,
+                loc,
+            )
+            .with_code("E0003"),
+        )
     }
 
     pub fn simple_syntax_error(errno: usize, loc: Location) -> Self {
-        Self::new(ErrorCore::new(
-            vec![SubMessage::only_loc(loc)],
-            switch_lang!(
-                "japanese" => "不正な構文です",
-                "simplified_chinese" => "无效的语法",
-                "traditional_chinese" => "無效的語法",
-                "english" => "invalid syntax",
-            ),
-            errno,
-            SyntaxError,
-            loc,
-        ))
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => "不正な構文です",
+                    "simplified_chinese" => "无效的语法",
+                    "traditional_chinese" => "無效的語法",
+                    "english" => "invalid syntax",
+                ),
+                errno,
+                SyntaxError,
+                loc,
+            )
+            .with_code("E0004"),
+        )
     }
 
     pub fn syntax_error<S: Into<String>>(
@@ -118,13 +133,16 @@ impl LexError {
         desc: S,
         hint: Option<String>,
     ) -> Self {
-        Self::new(ErrorCore::new(
-            vec![SubMessage::ambiguous_new(loc, vec![], hint)],
-            desc,
-            errno,
-            SyntaxError,
-            loc,
-        ))
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], hint)],
+                desc,
+                errno,
+                SyntaxError,
+                loc,
+            )
+            .with_code("E0005"),
+        )
     }
 
     pub fn syntax_warning<S: Into<String>>(
@@ -133,13 +151,16 @@ impl LexError {
         desc: S,
         hint: Option<String>,
     ) -> Self {
-        Self::new(ErrorCore::new(
-            vec![SubMessage::ambiguous_new(loc, vec![], hint)],
-            desc,
-            errno,
-            SyntaxWarning,
-            loc,
-        ))
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], hint)],
+                desc,
+                errno,
+                SyntaxWarning,
+                loc,
+            )
+            .with_code("W0001"),
+        )
     }
 
     pub fn no_var_error(
@@ -158,18 +179,21 @@ impl LexError {
             )
         });
         let name = StyledString::new(name, Some(ERR), Some(Attribute::Underline));
-        Self::new(ErrorCore::new(
-            vec![SubMessage::ambiguous_new(loc, vec![], hint)],
-            switch_lang!(
-                "japanese" => format!("{name}という変数は定義されていません"),
-                "simplified_chinese" => format!("{name}未定义"),
-                "traditional_chinese" => format!("{name}未定義"),
-                "english" => format!("{name} is not defined"),
-            ),
-            errno,
-            NameError,
-            loc,
-        ))
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], hint)],
+                switch_lang!(
+                    "japanese" => format!("{name}という変数は定義されていません"),
+                    "simplified_chinese" => format!("{name}未定义"),
+                    "traditional_chinese" => format!("{name}未定義"),
+                    "english" => format!("{name} is not defined"),
+                ),
+                errno,
+                NameError,
+                loc,
+            )
+            .with_code("E0006"),
+        )
     }
 
     pub fn invalid_chunk_error(errno: usize, loc: Location) -> LexError {
@@ -186,7 +210,9 @@ impl LexError {
             "english" => "`;` or newline should be added",
         )
         .to_string();
-        Self::syntax_error(errno, loc, msg, Some(hint))
+        let mut err = Self::syntax_error(errno, loc, msg, Some(hint));
+        err.0.code = Some("E0007");
+        err
     }
 
     pub fn invalid_arg_decl_error(errno: usize, loc: Location) -> LexError {
@@ -203,11 +229,13 @@ impl LexError {
             "english" => "`,` or newline should be added",
         )
         .to_string();
-        Self::syntax_error(errno, loc, msg, Some(hint))
+        let mut err = Self::syntax_error(errno, loc, msg, Some(hint));
+        err.0.code = Some("E0008");
+        err
     }
 
     pub fn invalid_definition_of_last_block(errno: usize, loc: Location) -> LexError {
-        Self::syntax_error(
+        let mut err = Self::syntax_error(
             errno,
             loc,
             switch_lang!(
@@ -217,11 +245,13 @@ impl LexError {
                 "english" => "cannot define a variable at the end of a block",
             ),
             None,
-        )
+        );
+        err.0.code = Some("E0009");
+        err
     }
 
     pub fn failed_to_analyze_block(errno: usize, loc: Location) -> LexError {
-        Self::syntax_error(
+        let mut err = Self::syntax_error(
             errno,
             loc,
             switch_lang!(
@@ -231,7 +261,9 @@ impl LexError {
                 "english" => "failed to parse a block",
             ),
             None,
-        )
+        );
+        err.0.code = Some("E0010");
+        err
     }
 
     pub fn invalid_mutable_symbol(errno: usize, lit: &str, loc: Location) -> LexError {
@@ -289,13 +321,16 @@ impl LexError {
             "traditional_chinese" => "無效的可變符號",
             "english" => "invalid mutable symbol",
         );
-        Self::new(ErrorCore::new(
-            vec![SubMessage::ambiguous_new(loc, vec![expect, found], None)],
-            main_msg,
-            errno,
-            SyntaxError,
-            loc,
-        ))
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![expect, found], None)],
+                main_msg,
+                errno,
+                SyntaxError,
+                loc,
+            )
+            .with_code("E0011"),
+        )
     }
 }
 
@@ -163,6 +163,8 @@ pub struct Lexer /*<'a>*/ {
     /// 0-origin, indicates the column number in which the token appears
     col_token_starts: u32,
     interpol_stack: Vec<Interpolation>,
+    /// doc comments (`## ...`, `#[[ ... ]]#`), which are not emitted as tokens
+    doc_comments: Vec<Token>,
 }
 
 impl Lexer /*<'a>*/ {
@@ -177,6 +179,7 @@ impl Lexer /*<'a>*/ {
             lineno_token_starts: 0,
             col_token_starts: 0,
             interpol_stack: vec![Interpolation::Not],
+            doc_comments: vec![],
         }
     }
 
@@ -192,6 +195,7 @@ impl Lexer /*<'a>*/ {
             lineno_token_starts: 0,
             col_token_starts: 0,
             interpol_stack: vec![Interpolation::Not],
+            doc_comments: vec![],
         }
     }
 
@@ -213,6 +217,50 @@ impl Lexer /*<'a>*/ {
         }
     }
 
+    /// Lexes the source code and returns the doc comments in order of appearance.
+    /// In addition to docstrings (`'''...'''`), `## ...` lines (consecutive lines are merged) and `#[[ ... ]]#` are included.
+    /// The line number of each token is that of its first line.
+    pub fn lex_doc_comments(mut self) -> Vec<Token> {
+        let mut docs = self
+            .by_ref()
+            .flatten()
+            .filter(|token| token.is(TokenKind::DocComment))
+            .map(|token| {
+                // multi-line string tokens have the line number of the last line
+                let lineno = token.lineno - token.content.matches('\n').count() as u32;
+                Token::new(token.kind, token.content, lineno, token.col_begin)
+            })
+            .collect::<Vec<_>>();
+        docs.append(&mut self.doc_comments);
+        docs.sort_by_key(|token| token.lineno);
+        docs
+    }
+
+    fn push_doc_comment(&mut self, content: String, lineno: u32, col_begin: u32) {
+        if let Some(last) = self.doc_comments.last_mut() {
+            // merge consecutive `##` lines
+            let last_lineno = last.lineno + last.content.lines().count() as u32 - 1;
+            if content.starts_with("##")
+                && last.content.starts_with("##")
+                && last_lineno + 1 == lineno
+            {
+                *last = Token::new(
+                    TokenKind::DocComment,
+                    format!("{}\n{content}", last.content),
+                    last.lineno,
+                    last.col_begin,
+                );
+                return;
+            }
+        }
+        self.doc_comments.push(Token::new(
+            TokenKind::DocComment,
+            content,
+            lineno,
+            col_begin,
+        ));
+    }
+
     fn emit_token(&mut self, kind: TokenKind, cont: &str) -> Token {
         let cont = self.str_cache.get(cont);
         // cannot use String::len() for multi-byte characters
@@ -295,6 +343,11 @@ impl Lexer /*<'a>*/ {
     // +, -, * etc. may be pre/bin
     // and, or, is!, isnot!, in, notin, as, dot, cross may be bin/function
     fn op_fix(&self) -> Option<OpFix> {
+        self.op_fix_of_len(1)
+    }
+
+    /// `op_len`: the length of the operator already consumed (e.g. 2 for `**`)
+    fn op_fix_of_len(&self, op_len: usize) -> Option<OpFix> {
         match self.prev_token.category() {
             // unary: `[ +`, `= +`, `+ +`, `, +`, `:: +`
             TokenCategory::LEnclosure
@@ -310,7 +363,7 @@ impl Lexer /*<'a>*/ {
             TokenCategory::REnclosure
             | TokenCategory::Literal
             | TokenCategory::StrInterpRight
-            | TokenCategory::Symbol => match (self.peek_prev_prev_ch(), self.peek_cur_ch()) {
+            | TokenCategory::Symbol => match (self.peek_before_op_ch(op_len), self.peek_cur_ch()) {
                 (Some(' '), Some(' ')) => Some(OpFix::Infix), // x + 1: bin
                 (Some(' '), Some(_)) => Some(OpFix::Prefix),  // x +1: unary
                 (Some(_), Some(' ')) => Some(OpFix::Infix),   // x+ 1 : bin
@@ -332,8 +385,11 @@ impl Lexer /*<'a>*/ {
         self.chars.get(now).copied()
     }
 
-    fn peek_prev_prev_ch(&self) -> Option<char> {
-        self.chars.get(self.cursor.checked_sub(2)?).copied()
+    /// the character just before the operator of length `op_len`
+    fn peek_before_op_ch(&self, op_len: usize) -> Option<char> {
+        self.chars
+            .get(self.cursor.checked_sub(op_len + 1)?)
+            .copied()
     }
 
     fn peek_prev_ch(&self) -> Option<char> {
@@ -370,12 +426,19 @@ impl Lexer /*<'a>*/ {
             }
             s.push(self.consume().unwrap());
         }
+        if s.starts_with("##") {
+            self.push_doc_comment(s, self.lineno_token_starts + 1, self.col_token_starts);
+        }
         Ok(())
     }
 
     fn lex_multi_line_comment(&mut self) -> LexResult<()> {
         let mut s = "".to_string();
         let mut nest_level = 0;
+        // the whole content is kept only for doc comments (`#[[ ... ]]#`)
+        let is_doc = self.chars.get(self.cursor + 2) == Some(&'[');
+        let mut doc = "".to_string();
+        let (lineno, col_begin) = (self.lineno_token_starts + 1, self.col_token_starts);
         while let Some(c) = self.peek_cur_ch() {
             if let Some(next_c) = self.peek_next_ch() {
                 match (c, next_c) {
@@ -385,6 +448,10 @@ impl Lexer /*<'a>*/ {
                         if nest_level == 0 {
                             self.consume(); // ]
                             self.consume(); // #
+                            if is_doc {
+                                doc.push_str("]#");
+                                self.push_doc_comment(doc, lineno, col_begin);
+                            }
                             return Ok(());
                         }
                     }
@@ -394,6 +461,9 @@ impl Lexer /*<'a>*/ {
                     self.lineno_token_starts += 1;
                     self.col_token_starts = 0;
                     s.clear();
+                    if is_doc {
+                        doc.push(c);
+                    }
                     self.consume();
                     continue;
                 }
@@ -412,6 +482,9 @@ impl Lexer /*<'a>*/ {
                     None,
                 ));
             }
+            if is_doc {
+                doc.push(c);
+            }
             s.push(self.consume().unwrap());
         }
         let comment = self.emit_token(Illegal, &s);
@@ -1325,10 +1398,14 @@ impl Iterator for Lexer /*<'a>*/ {
                 }
             },
             Some('*') => match self.peek_cur_ch() {
-                // TODO: infix/prefix
                 Some('*') => {
                     self.consume();
-                    self.accept(Pow, "**")
+                    // `f **kwargs`: prefix, `x ** y`: infix
+                    let kind = match self.op_fix_of_len(2) {
+                        Some(OpFix::Prefix) => PreDblStar,
+                        _ => Pow,
+                    };
+                    self.accept(kind, "**")
                 }
                 _ => {
                     let kind = match self.op_fix() {
@@ -713,17 +713,23 @@ impl Parser {
                 expr: Expr::UnaryOp(unary),
             }) if unary.op.is(PreStar) => {
                 let pos_args = PosArg::new(unary.deconstruct().1);
-                Args::new(vec![], Some(pos_args), vec![], None)
+                Args::new(vec![], Some(pos_args), vec![], None, None)
             }
             PosOrKwArg::Pos(PosArg {
                 expr: Expr::TypeAscription(TypeAscription { expr, t_spec }),
             }) if matches!(expr.as_ref(), Expr::UnaryOp(unary) if unary.op.is(PreStar)) => {
                 let Expr::UnaryOp(unary) = *expr else { unreachable!() };
                 let var_args = PosArg::new(unary.deconstruct().1.type_asc_expr(t_spec));
-                Args::new(vec![], Some(var_args), vec![], None)
+                Args::new(vec![], Some(var_args), vec![], None, None)
+            }
+            PosOrKwArg::Pos(PosArg {
+                expr: Expr::UnaryOp(unary),
+            }) if unary.op.is(PreDblStar) => {
+                let kw_var_args = PosArg::new(unary.deconstruct().1);
+                Args::new(vec![], None, vec![], Some(kw_var_args), None)
             }
             PosOrKwArg::Pos(arg) => Args::pos_only(vec![arg], None),
-            PosOrKwArg::Kw(arg) => Args::new(vec![], None, vec![arg], None),
+            PosOrKwArg::Kw(arg) => Args::new(vec![], None, vec![arg], None, None),
         };
         loop {
             match self.peek_kind() {
@@ -766,11 +772,22 @@ impl Parser {
                     }
                     if style.needs_parens() && self.cur_is(RParen) {
                         let rp = self.lpop();
-                        let (pos_args, var_args, kw_args, _) = args.deconstruct();
-                        args = Args::new(pos_args, var_args, kw_args, Some((lp.unwrap(), rp)));
+                        let (pos_args, var_args, kw_args, kw_var_args, _) = args.deconstruct();
+                        args = Args::new(
+                            pos_args,
+                            var_args,
+                            kw_args,
+                            kw_var_args,
+                            Some((lp.unwrap(), rp)),
+                        );
                         break;
                     }
-                    if !args.kw_is_empty() {
+                    if self.cur_is(PreDblStar) {
+                        let kw_var_args = self
+                            .try_reduce_kw_var_args(in_type_args)
+                            .map_err(|_| self.stack_dec(fn_name!()))?;
+                        args.set_kw_var_args(kw_var_args);
+                    } else if !args.kw_is_empty() {
                         args.push_kw(
                             self.try_reduce_kw_arg(in_type_args)
                                 .map_err(|_| self.stack_dec(fn_name!()))?,
@@ -806,12 +823,12 @@ impl Parser {
                 Some(RParen) => {
                     if let Some(lp) = lp {
                         let rp = self.lpop();
-                        let (pos_args, var_args, kw_args, _) = args.deconstruct();
-                        args = Args::new(pos_args, var_args, kw_args, Some((lp, rp)));
+                        let (pos_args, var_args, kw_args, kw_var_args, _) = args.deconstruct();
+                        args = Args::new(pos_args, var_args, kw_args, kw_var_args, Some((lp, rp)));
                     } else {
                         // e.g. f(g 1)
-                        let (pos_args, var_args, kw_args, _) = args.deconstruct();
-                        args = Args::new(pos_args, var_args, kw_args, None);
+                        let (pos_args, var_args, kw_args, kw_var_args, _) = args.deconstruct();
+                        args = Args::new(pos_args, var_args, kw_args, kw_var_args, None);
                     }
                     break;
                 }
@@ -829,8 +846,14 @@ impl Parser {
                                 debug_exit_info!(self);
                                 return Err(());
                             }
-                            let (pos_args, var_args, kw_args, _) = args.deconstruct();
-                            args = Args::new(pos_args, var_args, kw_args, Some((lp.unwrap(), rp)));
+                            let (pos_args, var_args, kw_args, kw_var_args, _) = args.deconstruct();
+                            args = Args::new(
+                                pos_args,
+                                var_args,
+                                kw_args,
+                                kw_var_args,
+                                Some((lp.unwrap(), rp)),
+                            );
                         }
                         break;
                     }
@@ -874,6 +897,42 @@ impl Parser {
         Ok(args)
     }
 
+    /// `**kw`
+    fn try_reduce_kw_var_args(&mut self, in_type_args: bool) -> ParseResult<PosArg> {
+        debug_call_info!(self);
+        match self
+            .try_reduce_arg(in_type_args)
+            .map_err(|_| self.stack_dec(fn_name!()))?
+        {
+            PosOrKwArg::Pos(PosArg {
+                expr: Expr::UnaryOp(unary),
+            }) if unary.op.is(PreDblStar) => {
+                debug_exit_info!(self);
+                Ok(PosArg::new(unary.deconstruct().1))
+            }
+            other => {
+                let loc = match other {
+                    PosOrKwArg::Pos(arg) => arg.loc(),
+                    PosOrKwArg::Kw(arg) => arg.loc(),
+                };
+                let err = ParseError::syntax_error(
+                    line!() as usize,
+                    loc,
+                    switch_lang!(
+                        "japanese" => "可変長キーワード引数が期待されています",
+                        "simplified_chinese" => "应该是可变长度关键字参数",
+                        "traditional_chinese" => "應該是可變長度關鍵字參數",
+                        "english" => "variable-length keyword arguments are expected",
+                    ),
+                    None,
+                );
+                self.errs.push(err);
+                debug_exit_info!(self);
+                Err(())
+            }
+        }
+    }
+
     fn try_reduce_arg(&mut self, in_type_args: bool) -> ParseResult<PosOrKwArg> {
         debug_call_info!(self);
         match self.peek_kind() {
@@ -2367,7 +2426,7 @@ impl Parser {
                 expr: Expr::UnaryOp(unary),
             }) if unary.op.is(PreStar) => {
                 let var_args = Some(PosArg::new(unary.deconstruct().1));
-                Args::new(vec![], var_args, vec![], None)
+                Args::new(vec![], var_args, vec![], None, None)
             }
             PosOrKwArg::Pos(PosArg {
                 expr: Expr::TypeAscription(TypeAscription { expr, t_spec }),
@@ -2375,10 +2434,16 @@ impl Parser {
                 let Expr::UnaryOp(unary) = *expr else { unreachable!() };
                 let expr = unary.deconstruct().1;
                 let var_args = Some(PosArg::new(expr.type_asc_expr(t_spec)));
-                Args::new(vec![], var_args, vec![], None)
+                Args::new(vec![], var_args, vec![], None, None)
+            }
+            PosOrKwArg::Pos(PosArg {
+                expr: Expr::UnaryOp(unary),
+            }) if unary.op.is(PreDblStar) => {
+                let kw_var_args = Some(PosArg::new(unary.deconstruct().1));
+                Args::new(vec![], None, vec![], kw_var_args, None)
             }
             PosOrKwArg::Pos(pos) => Args::pos_only(vec![pos], None),
-            PosOrKwArg::Kw(kw) => Args::new(vec![], None, vec![kw], None),
+            PosOrKwArg::Kw(kw) => Args::new(vec![], None, vec![kw], None, None),
         };
         #[allow(clippy::while_let_loop)]
         loop {
@@ -2400,6 +2465,11 @@ impl Parser {
                         .try_reduce_arg(false)
                         .map_err(|_| self.stack_dec(fn_name!()))?
                     {
+                        PosOrKwArg::Pos(PosArg {
+                            expr: Expr::UnaryOp(unary),
+                        }) if unary.op.is(PreDblStar) => {
+                            args.set_kw_var_args(PosArg::new(unary.deconstruct().1));
+                        }
                         PosOrKwArg::Pos(arg) if args.kw_is_empty() && args.var_args.is_none() => {
                             match arg.expr {
                                 Expr::UnaryOp(unary) if unary.op.is(PreStar) => {
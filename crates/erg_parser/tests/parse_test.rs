@@ -1,5 +1,5 @@
 use erg_common::config::{ErgConfig, Input};
-use erg_common::error::MultiErrorDisplay;
+use erg_common::error::{FixIt, Location, MultiErrorDisplay};
 use erg_common::spawn::exec_new_thread;
 use erg_common::traits::{Runnable, Stream};
 
@@ -116,3 +116,31 @@ fn expect_failure(file_path: &'static str, errs_len: usize) -> Result<(), ()> {
         }
     }
 }
+
+fn parse_fix_its(code: &'static str) -> Vec<FixIt> {
+    let cfg = ErgConfig {
+        input: Input::pipe(code.to_string()),
+        ..ErgConfig::default()
+    };
+    let mut parser = ParserRunner::new(cfg);
+    let errs = parser.parse(code.to_string()).unwrap_err();
+    errs.iter()
+        .flat_map(|err| err.core.fix_its().cloned())
+        .collect()
+}
+
+#[test]
+fn parse_type_spec_fix_its() {
+    assert_eq!(
+        parse_fix_its("x: 1 or 2 = 1\n"),
+        vec![FixIt::new(Location::range(1, 3, 1, 9), "{1, 2}")]
+    );
+    assert_eq!(
+        parse_fix_its("x: [1, 2] = 1\n"),
+        vec![FixIt::new(Location::range(1, 3, 1, 9), "{1, 2}")]
+    );
+    assert_eq!(
+        parse_fix_its("x: [Int, 2] = [1, 2]\n"),
+        vec![FixIt::new(Location::range(1, 3, 1, 11), "[Int; 2]")]
+    );
+}
@@ -14,6 +14,7 @@ const FILE2: &str = "tests/test2_advanced_syntax.er";
 const FILE3: &str = "tests/test3_literal_syntax.er";
 const FILE4: &str = "tests/multi_line_str_literal.er";
 const FILE5: &str = "tests/for.er";
+const FILE6: &str = "tests/doc_comment.er";
 
 #[test]
 fn test_lexer_for_basic() -> ParseResult<()> {
@@ -454,6 +455,32 @@ fn for_loop() -> ParseResult<()> {
     Ok(())
 }
 
+#[test]
+fn doc_comments() {
+    let lexer = Lexer::new(Input::File(FILE6.into()));
+    let docs = lexer.lex_doc_comments();
+    let docs = docs
+        .iter()
+        .map(|doc| (doc.kind, doc.lineno, &doc.content[..]))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        docs,
+        [
+            (
+                DocComment,
+                1,
+                "## adds two numbers\n## ```erg\n## assert add(1, 2) == 3\n## ```"
+            ),
+            (
+                DocComment,
+                7,
+                "#[[\n    ```erg\n    assert sub(3, 2) == 1\n    ```\n]]#"
+            ),
+            (DocComment, 16, "'''\n    docstring\n    '''"),
+        ]
+    );
+}
+
 #[test]
 fn tesop_te_prec() {
     assert_eq!(Mod.precedence(), Some(170));
@@ -1,3 +1,4 @@
+use erg_common::error::{FixIt, Location};
 use erg_common::switch_lang;
 use erg_common::traits::{Locational, Stream};
 
@@ -69,7 +70,7 @@ impl Parser {
             },
             Expr::Tuple(tuple) => match tuple {
                 Tuple::Normal(tup) => {
-                    let (elems, _, _, paren) = tup.elems.deconstruct();
+                    let (elems, _, _, _, paren) = tup.elems.deconstruct();
                     let mut const_elems = vec![];
                     for elem in elems.into_iter() {
                         let const_expr = Self::validate_const_expr(elem.expr)?;
@@ -100,7 +101,7 @@ impl Parser {
                         "complex const function call",
                     ));
                 };
-                let (pos_args, _, _, paren) = call.args.deconstruct();
+                let (pos_args, _, _, _, paren) = call.args.deconstruct();
                 let mut const_pos_args = vec![];
                 for elem in pos_args.into_iter() {
                     let const_expr = Self::validate_const_expr(elem.expr)?;
@@ -155,7 +156,7 @@ impl Parser {
     fn call_to_predecl_type_spec(call: Call) -> Result<PreDeclTypeSpec, ParseError> {
         match *call.obj {
             Expr::Accessor(Accessor::Ident(ident)) => {
-                let (_pos_args, _var_args, _kw_args, paren) = call.args.deconstruct();
+                let (_pos_args, _var_args, _kw_args, _kw_var, paren) = call.args.deconstruct();
                 let mut pos_args = vec![];
                 for arg in _pos_args.into_iter() {
                     let const_expr = Self::validate_const_expr(arg.expr)?;
@@ -249,8 +250,20 @@ impl Parser {
     fn array_to_array_type_spec(array: Array) -> Result<ArrayTypeSpec, ParseError> {
         match array {
             Array::Normal(arr) => {
-                // TODO: add hint
-                let err = ParseError::simple_syntax_error(line!() as usize, arr.loc());
+                let mut err = ParseError::simple_syntax_error(line!() as usize, arr.loc());
+                let elems = arr.elems.pos_args().iter().map(|arg| &arg.expr);
+                // `[1, 2]` => `{1, 2}`, `[Int, 2]` => `[Int; 2]`
+                if elems.clone().all(|elem| matches!(elem, Expr::Literal(_))) {
+                    let elems = elems.map(|elem| elem.to_string()).collect::<Vec<_>>();
+                    err.push_fix_it(FixIt::new(arr.loc(), format!("{{{}}}", elems.join(", "))));
+                } else if let [Expr::Accessor(Accessor::Ident(elem)), Expr::Literal(len)] =
+                    &elems.collect::<Vec<_>>()[..]
+                {
+                    if len.is(TokenKind::NatLit) {
+                        let replacement = format!("[{}; {len}]", elem.inspect());
+                        err.push_fix_it(FixIt::new(arr.loc(), replacement));
+                    }
+                }
                 Err(err)
             }
             Array::WithLength(arr) => {
@@ -346,6 +359,24 @@ impl Parser {
         }
     }
 
+    /// `1 or 2 or 3` => `Some([1, 2, 3])`
+    fn literal_operands(bin: &BinOp) -> Option<Vec<&Literal>> {
+        if !matches!(bin.op.kind, TokenKind::OrOp | TokenKind::AndOp) {
+            return None;
+        }
+        let mut lits = vec![];
+        for arg in bin.args.iter() {
+            match arg.as_ref() {
+                Expr::Literal(lit) => lits.push(lit),
+                Expr::BinOp(inner) if inner.op.kind == bin.op.kind => {
+                    lits.extend(Self::literal_operands(inner)?);
+                }
+                _ => return None,
+            }
+        }
+        Some(lits)
+    }
+
     pub fn expr_to_type_spec(rhs: Expr) -> Result<TypeSpec, ParseError> {
         match rhs {
             Expr::Accessor(acc) => Self::accessor_to_type_spec(acc),
@@ -378,7 +409,14 @@ impl Parser {
                 Ok(TypeSpec::Tuple(tup))
             }
             Expr::BinOp(bin) => {
-                if bin.op.kind.is_range_op() {
+                if let Some(lits) = Self::literal_operands(&bin) {
+                    // `1 or 2` => `{1, 2}`
+                    let loc = Location::concat(lits[0], lits[lits.len() - 1]);
+                    let elems = lits.iter().map(|lit| lit.to_string()).collect::<Vec<_>>();
+                    let mut err = ParseError::simple_syntax_error(line!() as usize, loc);
+                    err.push_fix_it(FixIt::new(loc, format!("{{{}}}", elems.join(", "))));
+                    Err(err)
+                } else if bin.op.kind.is_range_op() {
                     let op = bin.op;
                     let mut args = bin.args.into_iter();
                     let lhs = Self::validate_const_expr(*args.next().unwrap())?;
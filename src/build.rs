@@ -0,0 +1,353 @@
+//! defines the package builder (`erg build`).
+//!
+//! The build consists of the following steps:
+//! 1. check the `erg` code blocks in the documents (`doc/**/*.md`)
+//! 2. compile the package into `build/debug` or `build/release`
+//! 3. generate the launcher scripts of the application (`build/<profile>/bin`)
+//! 4. run the tests (see `test_runner`)
+//!
+//! The output directory can be added to `sys.path`: the package is placed in `build/<profile>/<name>`
+//! (`src/lib.er` -> `<name>/__init__.pyc`, `src/main.er` -> `<name>/__main__.pyc`), and the dependencies beside it.
+//! The build is incremental. The modules are recompiled only if they or their dependencies have been modified since the last build.
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Instant, SystemTime};
+
+use erg_common::config::{ErgConfig, ErgMode, Input};
+use erg_common::error::MultiErrorDisplay;
+use erg_common::help_messages::build_message;
+use erg_common::normalize_path;
+use erg_common::python_util::which_python;
+use erg_common::traits::{ExitStatus, Runnable};
+
+use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::module::package::{package_module_path, Manifest, MANIFEST_FILE};
+use erg_compiler::module::SharedCompilerResource;
+use erg_compiler::Compiler;
+
+use crate::pack::current_package;
+use crate::test_runner::{erg_code_blocks, run_tests, TestOptions};
+
+/// the list of the source files of the last build (placed in the output directory)
+const BUILD_INFO_FILE: &str = ".build-info";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Profile {
+    Debug,
+    Release,
+}
+
+impl Profile {
+    const fn dir_name(&self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Release => "release",
+        }
+    }
+
+    /// The release build is optimized (`opt_level >= 2`).
+    fn opt_level(&self, cfg: &ErgConfig) -> u8 {
+        match self {
+            Self::Debug => cfg.opt_level,
+            Self::Release => cfg.opt_level.max(2),
+        }
+    }
+
+    /// The line number tables and docstrings are stripped only in the release build.
+    const fn strip(&self) -> bool {
+        matches!(self, Self::Release)
+    }
+}
+
+#[derive(Debug)]
+struct BuildOptions {
+    profile: Profile,
+    check_docs: bool,
+    run_tests: bool,
+}
+
+impl BuildOptions {
+    /// `erg build [--release] [--no-doc] [--no-test]`
+    fn parse(args: &[&str]) -> Result<Option<Self>, String> {
+        let mut opts = Self {
+            profile: Profile::Debug,
+            check_docs: true,
+            run_tests: true,
+        };
+        for &arg in args {
+            match arg {
+                "-h" | "--help" => return Ok(None),
+                "--release" => opts.profile = Profile::Release,
+                "--no-doc" => opts.check_docs = false,
+                "--no-test" => opts.run_tests = false,
+                other => return Err(format!("unknown option: {other}")),
+            }
+        }
+        Ok(Some(opts))
+    }
+}
+
+pub fn run(cfg: ErgConfig) -> ExitStatus {
+    let res = match BuildOptions::parse(&cfg.runtime_args) {
+        Ok(Some(opts)) => build(&cfg, &opts),
+        Ok(None) => {
+            println!("{}", build_message());
+            return ExitStatus::OK;
+        }
+        Err(msg) => Err(msg),
+    };
+    match res {
+        Ok(()) => ExitStatus::OK,
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            ExitStatus::ERR1
+        }
+    }
+}
+
+fn build(cfg: &ErgConfig, opts: &BuildOptions) -> Result<(), String> {
+    let (root, manifest) = current_package()?;
+    let start = Instant::now();
+    if opts.check_docs {
+        check_docs(cfg, &root)?;
+    }
+    let output = compile_package(cfg, &root, &manifest, opts.profile)?;
+    if manifest.package_type.is_app() {
+        write_launchers(cfg, &output, &manifest.name)?;
+    }
+    eprintln!(
+        "Finished {} [opt_level={}] in {:.2}s",
+        opts.profile.dir_name(),
+        opts.profile.opt_level(cfg),
+        start.elapsed().as_secs_f64()
+    );
+    if opts.run_tests && !run_tests(cfg, &TestOptions::default())? {
+        return Err(format!("the tests of `{}` failed", manifest.name));
+    }
+    Ok(())
+}
+
+fn collect_docs(path: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_docs(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+}
+
+/// Checks the `erg` code blocks in the documents (`doc/**/*.md`).
+fn check_docs(cfg: &ErgConfig, root: &Path) -> Result<(), String> {
+    let mut files = vec![];
+    collect_docs(&root.join("doc"), &mut files);
+    files.sort();
+    let mut failed = 0;
+    for file in files {
+        let Ok(src) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let lines = src.lines().map(ToString::to_string).collect::<Vec<_>>();
+        for (offset, code) in erg_code_blocks(&lines) {
+            let cfg = ErgConfig {
+                mode: ErgMode::FullCheck,
+                input: Input::str(code.clone()),
+                quiet_repl: true,
+                runtime_args: vec![],
+                ..cfg.copy()
+            };
+            if let Err(artifact) = HIRBuilder::new(cfg).build(code, "exec") {
+                artifact.errors.fmt_all_stderr();
+                let rel = file.strip_prefix(root).unwrap_or(&file);
+                eprintln!(
+                    "the code block at {}:{} is invalid",
+                    rel.display(),
+                    offset + 1
+                );
+                failed += 1;
+            }
+        }
+    }
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "{failed} code block(s) in the documents are invalid"
+        ))
+    }
+}
+
+fn mtime(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|meta| meta.modified()).ok()
+}
+
+/// Whether none of the sources recorded in the last build (and the compiler itself) have been modified since then.
+fn is_fresh(output: &Path, entry_outputs: &[PathBuf]) -> bool {
+    let info = output.join(BUILD_INFO_FILE);
+    let (Ok(sources), Some(built)) = (fs::read_to_string(&info), mtime(&info)) else {
+        return false;
+    };
+    let is_older = |path: &Path| mtime(path).is_some_and(|mtime| mtime <= built);
+    entry_outputs.iter().all(|path| path.exists())
+        && env::current_exe().is_ok_and(|exe| is_older(&exe))
+        && sources.lines().all(|source| is_older(Path::new(source)))
+}
+
+/// Compiles the entry points and the modules they import into `build/<profile>` and returns the directory.
+pub(crate) fn compile_package(
+    cfg: &ErgConfig,
+    root: &Path,
+    manifest: &Manifest,
+    profile: Profile,
+) -> Result<PathBuf, String> {
+    let output = root.join("build").join(profile.dir_name());
+    let mut entries = vec![];
+    if manifest.package_type.is_app() {
+        entries.push(&manifest.main);
+    }
+    if manifest.package_type.is_lib() {
+        entries.push(&manifest.lib);
+    }
+    let mut targets = vec![];
+    for entry in entries {
+        let path = root
+            .join(entry)
+            .canonicalize()
+            .map_err(|_| format!("{} does not exist", entry.display()))?;
+        let path = normalize_path(path);
+        let mut pyc_path = output.join(package_module_path(&path).unwrap_or_default());
+        pyc_path.set_extension("pyc");
+        targets.push((path, pyc_path));
+    }
+    let entry_outputs = targets
+        .iter()
+        .map(|(_, pyc)| pyc.clone())
+        .collect::<Vec<_>>();
+    if is_fresh(&output, &entry_outputs) {
+        eprintln!("Fresh {} v{}", manifest.name, manifest.version);
+        return Ok(output);
+    }
+    eprintln!(
+        "Compiling {} v{} ({})",
+        manifest.name,
+        manifest.version,
+        profile.dir_name()
+    );
+    let info = output.join(BUILD_INFO_FILE);
+    let _ = fs::remove_file(&info);
+    let cfg = ErgConfig {
+        mode: ErgMode::Compile,
+        output_dir: Some(Box::leak(
+            output.to_string_lossy().into_owned().into_boxed_str(),
+        )),
+        separate: true,
+        opt_level: profile.opt_level(cfg),
+        strip: profile.strip(),
+        runtime_args: vec![],
+        ..cfg.copy()
+    };
+    let mut sources = vec![root.join(MANIFEST_FILE)];
+    for (path, pyc_path) in targets {
+        // e.g. `src/lib.er` imported by `src/main.er`
+        if sources.contains(&path) {
+            continue;
+        }
+        if let Some(dir) = pyc_path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        let src = fs::read_to_string(&path)
+            .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+        let cfg = ErgConfig {
+            input: Input::File(path.clone()),
+            ..cfg.copy()
+        };
+        let shared = SharedCompilerResource::new(cfg.copy());
+        let mut compiler =
+            Compiler::new_with_cache(cfg, "<module>", shared.clone()).incremental(true);
+        let warns = compiler
+            .compile_and_dump_as_pyc(&pyc_path, src, "exec")
+            .map_err(|artifact| {
+                artifact.warns.fmt_all_stderr();
+                artifact.errors.fmt_all_stderr();
+                format!("could not compile `{}`", manifest.name)
+            })?;
+        warns.fmt_all_stderr();
+        sources.extend(shared.graph.dependencies(&path));
+        sources.push(path);
+    }
+    sources.sort();
+    sources.dedup();
+    let sources = sources
+        .iter()
+        .map(|path| path.to_string_lossy())
+        .collect::<Vec<_>>();
+    fs::write(&info, sources.join("\n"))
+        .map_err(|err| format!("cannot write {}: {err}", info.display()))?;
+    Ok(output)
+}
+
+/// Generates the scripts that run `python -m <name>` with the output directory added to `PYTHONPATH`.
+fn write_launchers(cfg: &ErgConfig, output: &Path, name: &str) -> Result<(), String> {
+    let python = cfg
+        .py_command
+        .map(ToString::to_string)
+        .unwrap_or_else(which_python);
+    let bin = output.join("bin");
+    fs::create_dir_all(&bin).map_err(|err| err.to_string())?;
+    let write = |path: PathBuf, content: String| {
+        fs::write(&path, content).map_err(|err| format!("cannot write {}: {err}", path.display()))
+    };
+    let sh = bin.join(name);
+    write(
+        sh.clone(),
+        format!(
+            "#!/bin/sh
+# generated by `erg build`
+DIR=\"$(cd \"$(dirname \"$0\")/..\" && pwd)\"
+PYTHONPATH=\"$DIR${{PYTHONPATH:+:$PYTHONPATH}}\" exec \"{python}\" -m {name} \"$@\"
+"
+        ),
+    )?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&sh, fs::Permissions::from_mode(0o755))
+            .map_err(|err| err.to_string())?;
+    }
+    write(
+        bin.join(format!("{name}.bat")),
+        format!(
+            "@echo off\r
+rem generated by `erg build`\r
+set \"PYTHONPATH=%~dp0..;%PYTHONPATH%\"\r
+\"{python}\" -m {name} %*\r
+"
+        ),
+    )
+}
+
+/// Runs the application built in `output` (`python -m <name> <args>...`) and returns the exit code.
+pub(crate) fn exec_app(cfg: &ErgConfig, output: &Path, name: &str, args: &[&str]) -> Option<i32> {
+    let python = cfg
+        .py_command
+        .map(ToString::to_string)
+        .unwrap_or_else(which_python);
+    let mut paths = vec![output.to_path_buf()];
+    if let Some(python_path) = env::var_os("PYTHONPATH") {
+        paths.extend(env::split_paths(&python_path));
+    }
+    Command::new(python)
+        .arg("-m")
+        .arg(name)
+        .args(args)
+        .env("PYTHONPATH", env::join_paths(paths).ok()?)
+        .status()
+        .ok()?
+        .code()
+}
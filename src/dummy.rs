@@ -1,21 +1,26 @@
 use std::fs::remove_file;
-use std::io::{Read, Write};
+use std::io;
 use std::net::{Ipv4Addr, SocketAddrV4, TcpListener, TcpStream};
+use std::path::PathBuf;
 use std::process;
 use std::thread::sleep;
 use std::time::Duration;
 
-use erg_common::config::ErgConfig;
-use erg_common::error::MultiErrorDisplay;
+use erg_common::config::{ErgConfig, Input};
+use erg_common::env::erg_std_path;
+use erg_common::error::{ErrorCore, ErrorKind, Location, MultiErrorDisplay, SubMessage};
 use erg_common::python_util::{exec_pyc, spawn_py};
 use erg_common::traits::Runnable;
 
 use erg_compiler::hir::Expr;
+use erg_compiler::source_map::source_map_path;
 use erg_compiler::ty::HasType;
 
-use erg_compiler::error::{CompileError, CompileErrors};
+use erg_compiler::error::{CompileError, CompileErrors, CompileWarnings};
 use erg_compiler::Compiler;
 
+use crate::frame::{read_frame, write_frame};
+
 pub type EvalError = CompileError;
 pub type EvalErrors = CompileErrors;
 
@@ -27,6 +32,190 @@ fn find_available_port() -> u16 {
         .expect("No free port found.")
 }
 
+/// The kinds of messages exchanged with the REPL server (`scripts/repl_server.py`).
+///
+/// Every message is a frame (see `frame.rs`).
+/// A response to `Load` is a sequence of messages terminated by `Status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MessageKind {
+    /// request: execute (or reload) the dumped module
+    Load,
+    /// request: shut down the server
+    Quit,
+    Stdout,
+    Stderr,
+    /// `repr` of the evaluation result (not sent if the result is `None`)
+    Repr,
+    ExcType,
+    ExcMessage,
+    /// a frame per line: `filename\tlineno\tname`
+    Traceback,
+    /// exit status (`0`: OK, `1`: an exception was raised, or the code of `SystemExit`)
+    Status,
+    Closed,
+}
+
+impl MessageKind {
+    const fn as_byte(&self) -> u8 {
+        match self {
+            Self::Load => b'l',
+            Self::Quit => b'q',
+            Self::Stdout => b'o',
+            Self::Stderr => b'e',
+            Self::Repr => b'r',
+            Self::ExcType => b'x',
+            Self::ExcMessage => b'm',
+            Self::Traceback => b't',
+            Self::Status => b's',
+            Self::Closed => b'c',
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b'l' => Some(Self::Load),
+            b'q' => Some(Self::Quit),
+            b'o' => Some(Self::Stdout),
+            b'e' => Some(Self::Stderr),
+            b'r' => Some(Self::Repr),
+            b'x' => Some(Self::ExcType),
+            b'm' => Some(Self::ExcMessage),
+            b't' => Some(Self::Traceback),
+            b's' => Some(Self::Status),
+            b'c' => Some(Self::Closed),
+            _ => None,
+        }
+    }
+}
+
+fn send_message(stream: &mut TcpStream, kind: MessageKind, payload: &str) -> io::Result<()> {
+    write_frame(stream, kind.as_byte(), payload)
+}
+
+fn recv_message(stream: &mut TcpStream) -> io::Result<(MessageKind, String)> {
+    let (kind, payload) = read_frame(stream)?;
+    let kind = MessageKind::from_byte(kind).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown message kind: {kind}"),
+        )
+    })?;
+    Ok((kind, payload))
+}
+
+#[derive(Debug)]
+struct TracebackFrame {
+    filename: String,
+    lineno: u32,
+    name: String,
+}
+
+/// The result of executing a module on the REPL server
+#[derive(Debug, Default)]
+struct ReplResponse {
+    stdout: String,
+    stderr: String,
+    repr: Option<String>,
+    exc_type: Option<String>,
+    exc_message: String,
+    traceback: Vec<TracebackFrame>,
+    status: i32,
+}
+
+impl ReplResponse {
+    fn recv(stream: &mut TcpStream) -> io::Result<Self> {
+        let mut res = Self::default();
+        loop {
+            let (kind, payload) = recv_message(stream)?;
+            match kind {
+                MessageKind::Stdout => res.stdout = payload,
+                MessageKind::Stderr => res.stderr = payload,
+                MessageKind::Repr => res.repr = Some(payload),
+                MessageKind::ExcType => res.exc_type = Some(payload),
+                MessageKind::ExcMessage => res.exc_message = payload,
+                MessageKind::Traceback => {
+                    res.traceback = payload
+                        .lines()
+                        .filter_map(|line| {
+                            let mut fields = line.splitn(3, '\t');
+                            let filename = fields.next()?.to_string();
+                            let lineno = fields.next()?.parse().unwrap_or(0);
+                            let name = fields.next()?.to_string();
+                            Some(TracebackFrame {
+                                filename,
+                                lineno,
+                                name,
+                            })
+                        })
+                        .collect();
+                }
+                MessageKind::Status => {
+                    res.status = payload.parse().unwrap_or(1);
+                    return Ok(res);
+                }
+                other => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unexpected message: {other:?}"),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Convert the raised exception into an error.
+    /// The frames of the Erg script are mapped to the source locations, and the innermost one becomes the main location.
+    fn exception_to_error(&self, input: &Input) -> Option<EvalError> {
+        let exc_type = self.exc_type.as_ref()?;
+        let filename = input.enclosed_name();
+        // frames of the REPL server and `importlib` are omitted
+        let frames = self
+            .traceback
+            .iter()
+            .skip_while(|frame| frame.filename != filename);
+        let mut loc = Location::Unknown;
+        let mut sub_msgs = vec![];
+        let mut extern_frames = vec![];
+        for frame in frames {
+            if frame.filename == filename {
+                loc = if frame.lineno > 0 {
+                    Location::Line(frame.lineno)
+                } else {
+                    Location::Unknown
+                };
+                sub_msgs.push(SubMessage::ambiguous_new(
+                    loc,
+                    vec![format!("in {}", frame.name)],
+                    None,
+                ));
+            } else {
+                extern_frames.push(format!(
+                    "File \"{}\", line {}, in {}",
+                    frame.filename, frame.lineno, frame.name
+                ));
+            }
+        }
+        if let Some(last) = sub_msgs.last_mut() {
+            if !extern_frames.is_empty() {
+                last.set_hint(extern_frames.join("\n"));
+            }
+        } else {
+            sub_msgs.push(SubMessage::only_loc(loc));
+        }
+        // the exception type is displayed as the error kind
+        let msg = if self.exc_message.is_empty() {
+            exc_type.clone()
+        } else {
+            self.exc_message.clone()
+        };
+        Some(EvalError::new(
+            ErrorCore::new(sub_msgs, msg, 0, ErrorKind::from(&exc_type[..]), loc),
+            input.clone(),
+            "<module>".to_owned(),
+        ))
+    }
+}
+
 /// Open the Python interpreter as a server and act as an Erg interpreter by mediating communication
 ///
 /// Pythonインタープリタをサーバーとして開き、通信を仲介することでErgインタープリタとして振る舞う
@@ -71,7 +260,9 @@ impl Runnable for DummyVM {
             let code = include_str!("scripts/repl_server.py")
                 .replace("__PORT__", port.to_string().as_str())
                 .replace("__MODULE__", &cfg.dump_filename().replace('/', "."));
-            spawn_py(cfg.py_command, &code);
+            let dir = cfg.input.dir();
+            let dir = (!dir.as_os_str().is_empty()).then_some(dir.as_path());
+            spawn_py(cfg.py_command, &code, dir);
             let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, port);
             if !cfg.quiet_repl {
                 println!("Connecting to the REPL server...");
@@ -104,24 +295,23 @@ impl Runnable for DummyVM {
 
     fn finish(&mut self) {
         if let Some(stream) = &mut self.stream {
-            if let Err(err) = stream.write_all("exit".as_bytes()) {
+            if let Err(err) = send_message(stream, MessageKind::Quit, "") {
                 eprintln!("Write error: {err}");
                 process::exit(1);
             }
-            let mut buf = [0; 1024];
-            match stream.read(&mut buf) {
-                Result::Ok(n) => {
-                    let s = std::str::from_utf8(&buf[..n]).unwrap();
-                    if s.contains("closed") && !self.cfg().quiet_repl {
+            match recv_message(stream) {
+                Result::Ok((MessageKind::Closed, _)) => {
+                    if !self.cfg().quiet_repl {
                         println!("The REPL server is closed.");
                     }
                 }
+                Result::Ok(_) => {}
                 Result::Err(err) => {
                     eprintln!("Read error: {err}");
                     process::exit(1);
                 }
             }
-            remove_file(self.cfg().dump_pyc_filename()).unwrap_or(());
+            remove_file(self.repl_pyc_path()).unwrap_or(());
         }
     }
 
@@ -145,48 +335,46 @@ impl Runnable for DummyVM {
                 eart.errors
             })?;
         warns.fmt_all_stderr();
-        let code = exec_pyc(&filename, self.cfg().py_command, &self.cfg().runtime_args);
+        // rewrites the tracebacks into the Erg locations
+        // (without it, the locations in the `.pyc` are not reliable on every Python version)
+        let hook = erg_std_path().join("_erg_source_map.py");
+        let hook = hook.exists().then(|| hook.to_string_lossy().into_owned());
+        if hook.is_some() && !self.cfg().source_map {
+            self.compiler
+                .take_source_map()
+                .dump(source_map_path(filename.as_ref()))
+                .expect("failed to dump a source map (maybe permission denied)");
+        }
+        let code = exec_pyc(
+            &filename,
+            hook.as_deref(),
+            self.cfg().py_command,
+            &self.cfg().runtime_args,
+        );
+        if hook.is_some() {
+            remove_file(source_map_path(filename.as_ref())).unwrap_or(());
+        }
         remove_file(&filename).unwrap();
         Ok(code.unwrap_or(1))
     }
 
     fn eval(&mut self, src: String) -> Result<String, EvalErrors> {
-        let path = self.cfg().dump_pyc_filename();
-        let arti = self
-            .compiler
-            .eval_compile_and_dump_as_pyc(path, src, "eval")
-            .map_err(|eart| eart.errors)?;
-        let (last, warns) = (arti.object, arti.warns);
+        let (response, last, warns) = self.load(src)?;
         let mut res = warns.to_string();
-        // Tell the REPL server to execute the code
-        res += &match self.stream.as_mut().unwrap().write("load".as_bytes()) {
-            Result::Ok(_) => {
-                // read the result from the REPL server
-                let mut buf = [0; 1024];
-                match self.stream.as_mut().unwrap().read(&mut buf) {
-                    Result::Ok(n) => {
-                        let s = std::str::from_utf8(&buf[..n])
-                            .expect("failed to parse the response, maybe the output is too long");
-                        if s == "[Exception] SystemExit" {
-                            return Err(EvalErrors::from(EvalError::system_exit()));
-                        }
-                        s.to_string()
-                    }
-                    Result::Err(err) => {
-                        self.finish();
-                        eprintln!("Read error: {err}");
-                        process::exit(1);
-                    }
-                }
-            }
-            Result::Err(err) => {
-                self.finish();
-                eprintln!("Sending error: {err}");
-                process::exit(1);
+        if response.exc_type.as_deref() == Some("SystemExit") {
+            return Err(EvalErrors::from(EvalError::system_exit()));
+        }
+        res.push_str(response.stdout.trim_end_matches('\n'));
+        eprint!("{}", response.stderr);
+        if let Some(err) = response.exception_to_error(&self.cfg().input) {
+            print!("{res}");
+            return Err(EvalErrors::from(err));
+        }
+        if let Some(repr) = &response.repr {
+            if !res.is_empty() {
+                res.push('\n');
             }
-        };
-        if res.ends_with("None") {
-            res.truncate(res.len() - 5);
+            res.push_str(repr);
         }
         if self.cfg().show_type {
             res.push_str(": ");
@@ -205,6 +393,14 @@ impl Runnable for DummyVM {
     }
 }
 
+/// The outputs of the code evaluated by `DummyVM::eval_captured`
+#[derive(Debug)]
+pub struct CapturedEval {
+    pub stdout: String,
+    pub stderr: String,
+    pub result: Result<(), EvalErrors>,
+}
+
 impl DummyVM {
     /// Execute the script specified in the configuration.
     pub fn exec(&mut self) -> Result<i32, EvalErrors> {
@@ -215,4 +411,61 @@ impl DummyVM {
     pub fn eval(&mut self, src: String) -> Result<String, EvalErrors> {
         Runnable::eval(self, src)
     }
+
+    /// Evaluates code passed as a string, and returns the outputs instead of printing them.
+    pub fn eval_captured(&mut self, src: String) -> CapturedEval {
+        match self.load(src) {
+            Ok((response, _, _)) => {
+                let result = if response.exc_type.as_deref() == Some("SystemExit") {
+                    Err(EvalErrors::from(EvalError::system_exit()))
+                } else if let Some(err) = response.exception_to_error(&self.cfg().input) {
+                    Err(EvalErrors::from(err))
+                } else {
+                    Ok(())
+                };
+                CapturedEval {
+                    stdout: response.stdout,
+                    stderr: response.stderr,
+                    result,
+                }
+            }
+            Err(errs) => CapturedEval {
+                stdout: String::new(),
+                stderr: String::new(),
+                result: Err(errs),
+            },
+        }
+    }
+
+    /// The REPL server imports the code from here, i.e. the directory where the server is running.
+    fn repl_pyc_path(&self) -> PathBuf {
+        self.cfg().input.dir().join(self.cfg().dump_pyc_filename())
+    }
+
+    /// Compiles the code and tells the REPL server to execute it.
+    fn load(
+        &mut self,
+        src: String,
+    ) -> Result<(ReplResponse, Option<Expr>, CompileWarnings), EvalErrors> {
+        let path = self.repl_pyc_path();
+        let arti = self
+            .compiler
+            .eval_compile_and_dump_as_pyc(path, src, "eval")
+            .map_err(|eart| eart.errors)?;
+        let stream = self.stream.as_mut().unwrap();
+        if let Err(err) = send_message(stream, MessageKind::Load, "") {
+            self.finish();
+            eprintln!("Sending error: {err}");
+            process::exit(1);
+        }
+        // read the result from the REPL server
+        match ReplResponse::recv(stream) {
+            Result::Ok(response) => Ok((response, arti.object, arti.warns)),
+            Result::Err(err) => {
+                self.finish();
+                eprintln!("Read error: {err}");
+                process::exit(1);
+            }
+        }
+    }
 }
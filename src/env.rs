@@ -0,0 +1,222 @@
+//! defines the virtual environment manager (`erg env`).
+//!
+//! See `erg_common::venv` for the layout of the environments and the project config (`.erg/env.er`).
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use erg_common::config::ErgConfig;
+use erg_common::help_messages::env_message;
+use erg_common::python_util::{detect_magic_number, get_python_version, which_python};
+use erg_common::traits::ExitStatus;
+use erg_common::venv::{env_dir, venv_python, VirtualEnv, ENVS_DIR, ENV_CONFIG_FILE};
+
+use erg_compiler::module::package::MANIFEST_FILE;
+
+const DEFAULT_ENV_NAME: &str = "default";
+
+pub fn run(cfg: ErgConfig) -> ExitStatus {
+    let mut args = cfg.runtime_args.clone().into_iter();
+    let res = match args.next() {
+        Some("create") => create(args.collect()),
+        Some("activate") => args
+            .next()
+            .ok_or_else(|| "the name of the environment is not passed".to_string())
+            .and_then(activate),
+        Some("deactivate") => deactivate(),
+        Some("list") => list(),
+        None | Some("-h" | "--help") => {
+            println!("{}", env_message());
+            Ok(())
+        }
+        Some(other) => Err(format!("unknown subcommand: {other}")),
+    };
+    match res {
+        Ok(()) => ExitStatus::OK,
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            ExitStatus::ERR1
+        }
+    }
+}
+
+/// The nearest directory which is a package root or already has environments (the current directory by default).
+fn project_root() -> Result<PathBuf, String> {
+    let cwd = env::current_dir().map_err(|err| err.to_string())?;
+    let root = cwd
+        .ancestors()
+        .find(|dir| dir.join(MANIFEST_FILE).is_file() || dir.join(ENVS_DIR).is_dir())
+        .map_or_else(|| cwd.clone(), Path::to_path_buf);
+    Ok(root)
+}
+
+/// The environments are the directories in `.erg/envs` named after them,
+/// so names such as `../foo` or `/foo` (or mistyped options such as `--foo`) are rejected.
+fn validate_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    if chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid environment name: `{name}` (only letters, digits and `_` are allowed)"
+        ))
+    }
+}
+
+/// `erg env create [<name>] [--python <command>]`
+fn create(args: Vec<&str>) -> Result<(), String> {
+    let root = project_root()?;
+    let mut name = DEFAULT_ENV_NAME;
+    let mut python = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--python" => {
+                python = Some(
+                    args.next()
+                        .ok_or("the value of `--python` is not passed")?
+                        .to_string(),
+                )
+            }
+            other => name = other,
+        }
+    }
+    validate_name(name)?;
+    let dir = env_dir(&root, name);
+    if dir.exists() {
+        return Err(format!("the environment `{name}` already exists"));
+    }
+    let python = python.unwrap_or_else(which_python);
+    eprintln!("Creating environment `{name}` with {python}");
+    let status = Command::new(&python)
+        .arg("-m")
+        .arg("venv")
+        .arg(&dir)
+        .status()
+        .map_err(|err| format!("cannot run {python}: {err}"))?;
+    if !status.success() {
+        return Err(format!(
+            "failed to create a virtual environment in {}",
+            dir.display()
+        ));
+    }
+    fs::create_dir_all(dir.join("decl")).map_err(|err| err.to_string())?;
+    println!("Created environment `{name}`");
+    if VirtualEnv::read(&root).is_err() {
+        activate(name)?;
+    }
+    Ok(())
+}
+
+/// `erg env activate <name>`
+fn activate(name: &str) -> Result<(), String> {
+    validate_name(name)?;
+    let root = project_root()?;
+    let dir = env_dir(&root, name);
+    let python = venv_python(&dir);
+    if !python.exists() {
+        return Err(format!("the environment `{name}` does not exist"));
+    }
+    let py_command = python.to_string_lossy().into_owned();
+    let output = Command::new(&python)
+        .arg("-c")
+        .arg("import sysconfig; print(sysconfig.get_paths()['purelib'])")
+        .output()
+        .map_err(|err| format!("cannot run {py_command}: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "cannot get the site-packages directory of `{name}` ({py_command} exited with {}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let site_packages = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let venv = VirtualEnv {
+        root,
+        name: name.to_string(),
+        py_magic_num: detect_magic_number(&py_command),
+        target_version: get_python_version(&py_command),
+        py_command,
+        site_packages: PathBuf::from(site_packages),
+    };
+    venv.write()
+        .map_err(|err| format!("cannot write {ENV_CONFIG_FILE}: {err}"))?;
+    println!(
+        "Activated environment `{name}` (Python {})",
+        venv.target_version
+    );
+    Ok(())
+}
+
+/// `erg env deactivate`
+fn deactivate() -> Result<(), String> {
+    let root = project_root()?;
+    let venv = VirtualEnv::read(&root).map_err(|_| "no environment is activated".to_string())?;
+    fs::remove_file(root.join(ENV_CONFIG_FILE)).map_err(|err| err.to_string())?;
+    println!("Deactivated environment `{}`", venv.name);
+    Ok(())
+}
+
+/// `erg env list`
+fn list() -> Result<(), String> {
+    let root = project_root()?;
+    let active = VirtualEnv::read(&root).ok().map(|venv| venv.name);
+    let mut names = fs::read_dir(root.join(ENVS_DIR))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().join("pyvenv.cfg").is_file())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    names.sort();
+    if names.is_empty() {
+        println!("No environments (create one with `erg env create`)");
+    }
+    for name in names {
+        let dir = env_dir(&root, &name);
+        let mark = if active.as_ref() == Some(&name) {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{mark} {name} (Python {}, {} cached declarations)",
+            venv_version(&dir).unwrap_or_else(|| "?".to_string()),
+            count_decls(&dir.join("decl"))
+        );
+    }
+    Ok(())
+}
+
+/// Reads the Python version from `pyvenv.cfg`.
+fn venv_version(dir: &Path) -> Option<String> {
+    let cfg = fs::read_to_string(dir.join("pyvenv.cfg")).ok()?;
+    cfg.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        matches!(key.trim(), "version" | "version_info").then(|| value.trim().to_string())
+    })
+}
+
+fn count_decls(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                count_decls(&path)
+            } else {
+                usize::from(path.to_string_lossy().ends_with(".d.er"))
+            }
+        })
+        .sum()
+}
@@ -0,0 +1,25 @@
+//! shows the explanations of the error codes (`erg --explain <code>`).
+use erg_common::config::ErgConfig;
+use erg_common::traits::ExitStatus;
+
+use erg_compiler::error::codes::{explain, explain_kind, lookup, lookup_kind, COMPILE_DIAGNOSTICS};
+
+pub fn run(cfg: ErgConfig) -> ExitStatus {
+    let Some(code) = cfg.runtime_args.first() else {
+        // list the codes of the compile-time diagnostics
+        for diag in COMPILE_DIAGNOSTICS.iter() {
+            println!("{}: {} ({})", diag.code, diag.summary, diag.kind);
+        }
+        return ExitStatus::OK;
+    };
+    if let Some(diag) = lookup(code) {
+        println!("{}", explain(diag));
+        ExitStatus::OK
+    } else if let Some(kind) = lookup_kind(code) {
+        println!("{}", explain_kind(kind));
+        ExitStatus::OK
+    } else {
+        eprintln!("error: unknown error code: {code}");
+        ExitStatus::ERR1
+    }
+}
@@ -0,0 +1,23 @@
+//! Length-prefixed framing shared by the REPL server protocol and the compile server protocol.
+//!
+//! Every frame is `[kind: 1 byte][length: 4 bytes (big endian)][payload: UTF-8]`.
+use std::io::{self, Read, Write};
+
+pub(crate) fn write_frame(stream: &mut impl Write, kind: u8, payload: &str) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(5 + payload.len());
+    buf.push(kind);
+    buf.extend((payload.len() as u32).to_be_bytes());
+    buf.extend(payload.as_bytes());
+    stream.write_all(&buf)
+}
+
+pub(crate) fn read_frame(stream: &mut impl Read) -> io::Result<(u8, String)> {
+    let mut header = [0; 5];
+    stream.read_exact(&mut header)?;
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+    let mut payload = vec![0; len as usize];
+    stream.read_exact(&mut payload)?;
+    let payload = String::from_utf8(payload)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok((header[0], payload))
+}
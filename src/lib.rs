@@ -1,4 +1,11 @@
 extern crate erg_common;
 extern crate erg_compiler;
+pub mod build;
 mod dummy;
-pub use dummy::DummyVM;
+pub mod env;
+pub mod explain;
+mod frame;
+pub mod pack;
+pub mod server;
+pub mod test_runner;
+pub use dummy::{CapturedEval, DummyVM};
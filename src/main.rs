@@ -11,15 +11,34 @@ use erg_parser::lex::LexerRunner;
 use erg_parser::ParserRunner;
 
 use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::declare_gen::DeclFileGenerator;
+use erg_compiler::interpret::Interpreter;
 use erg_compiler::lower::ASTLowerer;
 use erg_compiler::transpile::Transpiler;
 use erg_compiler::ty::deserialize::Deserializer;
 use erg_compiler::Compiler;
 
+use erg::server::{self, CompileServer};
 use erg::DummyVM;
+use erg::{build, env, explain, pack, test_runner};
 
 fn run() {
     let cfg = ErgConfig::parse();
+    if cfg.client {
+        if cfg.mode == Server {
+            let stat = if server::shutdown(&cfg) {
+                ExitStatus::OK
+            } else {
+                eprintln!("The compile server is not running");
+                ExitStatus::ERR1
+            };
+            std::process::exit(stat.code);
+        }
+        // fall back to the local compilation if the server is not available
+        if let Some(stat) = server::request(&cfg) {
+            std::process::exit(stat.code);
+        }
+    }
     let stat = match cfg.mode {
         Lex => LexerRunner::run(cfg),
         Parse => ParserRunner::run(cfg),
@@ -28,8 +47,16 @@ fn run() {
         FullCheck => HIRBuilder::run(cfg),
         Compile => Compiler::run(cfg),
         Transpile => Transpiler::run(cfg),
+        DeclareGen => DeclFileGenerator::run(cfg),
+        Interpret => Interpreter::run_in_new_thread(cfg),
+        Server => CompileServer::run(cfg),
         Execute => DummyVM::run(cfg),
         Read => Deserializer::run(cfg),
+        Pack => pack::run(cfg),
+        Env => env::run(cfg),
+        Test => test_runner::run(cfg),
+        Build => build::run(cfg),
+        Explain => explain::run(cfg),
         LanguageServer => {
             #[cfg(feature = "els")]
             {
@@ -0,0 +1,375 @@
+//! defines the package manager (`erg pack`).
+//!
+//! See `erg_compiler::module::package` for the manifest (`package.er`) and the lockfile (`package.lock.er`).
+//! The dependencies are vendored in `.erg/deps`, where module resolution searches for them.
+//! Each dependency has its own dependencies installed in its `.erg/deps`.
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use erg_common::config::ErgConfig;
+use erg_common::help_messages::pack_message;
+use erg_common::semver::VersionReq;
+use erg_common::traits::ExitStatus;
+
+use erg_compiler::module::package::{
+    find_package_root, Dependency, DependencySource, LockedPackage, Lockfile, Manifest,
+    PackageType, DEPS_DIR, MANIFEST_FILE,
+};
+
+use crate::build::{self, Profile};
+
+/// directories that are not copied when vendoring a package
+pub(crate) const IGNORED_DIRS: [&str; 4] = [".git", ".erg", "build", "__pycache__"];
+
+pub fn run(cfg: ErgConfig) -> ExitStatus {
+    let mut args = cfg.runtime_args.clone().into_iter();
+    let res = match args.next() {
+        Some("init") => init(args.collect()),
+        Some("build") => {
+            return build::run(ErgConfig {
+                runtime_args: args.collect(),
+                ..cfg.copy()
+            })
+        }
+        Some("add") => add(args.collect()),
+        Some("install") => current_package().and_then(|(root, manifest)| install(&root, &manifest)),
+        Some("run") => run_app(&cfg, args.skip_while(|arg| *arg == "--").collect()),
+        None | Some("-h" | "--help") => {
+            println!("{}", pack_message());
+            Ok(())
+        }
+        Some(other) => Err(format!("unknown subcommand: {other}")),
+    };
+    match res {
+        Ok(()) => ExitStatus::OK,
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            ExitStatus::ERR1
+        }
+    }
+}
+
+pub(crate) fn current_package() -> Result<(PathBuf, Manifest), String> {
+    let cwd = env::current_dir().map_err(|err| err.to_string())?;
+    let root = find_package_root(&cwd).ok_or_else(|| {
+        format!("could not find `{MANIFEST_FILE}` in this or any parent directory")
+    })?;
+    let manifest = Manifest::read(&root)?;
+    Ok((root, manifest))
+}
+
+/// `erg pack init [app|lib|hybrid] [--name <name>]`
+fn init(args: Vec<&str>) -> Result<(), String> {
+    let root = env::current_dir().map_err(|err| err.to_string())?;
+    let mut package_type = PackageType::App;
+    let mut name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "package".to_string());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--name" => name = option_value(&mut args, arg)?.to_string(),
+            other => package_type = other.parse()?,
+        }
+    }
+    if root.join(MANIFEST_FILE).exists() {
+        return Err(format!("`{MANIFEST_FILE}` already exists"));
+    }
+    let manifest = format!(
+        "name = \"{name}\"
+version = \"0.1.0\"
+description = \"\"
+authors = []
+license = \"\"
+type = \"{package_type}\"
+dependencies = {{=}}
+"
+    );
+    let write = |path: PathBuf, content: &str| {
+        fs::write(&path, content).map_err(|err| format!("cannot write {}: {err}", path.display()))
+    };
+    fs::create_dir_all(root.join("src")).map_err(|err| err.to_string())?;
+    write(root.join(MANIFEST_FILE), &manifest)?;
+    if package_type.is_app() && !root.join("src/main.er").exists() {
+        write(root.join("src/main.er"), "print! \"Hello, world!\"\n")?;
+    }
+    if package_type.is_lib() && !root.join("src/lib.er").exists() {
+        write(root.join("src/lib.er"), ".add x: Int, y: Int = x + y\n")?;
+    }
+    if !root.join(".gitignore").exists() {
+        write(root.join(".gitignore"), "/build/\n__pycache__/\n")?;
+    }
+    println!("Created {package_type} package `{name}`");
+    Ok(())
+}
+
+/// `erg pack run [-- <args>...]`
+fn run_app(cfg: &ErgConfig, args: Vec<&'static str>) -> Result<(), String> {
+    let (root, manifest) = current_package()?;
+    if !manifest.package_type.is_app() {
+        return Err(format!("`{}` is not an application package", manifest.name));
+    }
+    let output = build::compile_package(cfg, &root, &manifest, Profile::Debug)?;
+    let code = build::exec_app(cfg, &output, &manifest.name, &args).unwrap_or(1);
+    if code == 0 {
+        Ok(())
+    } else {
+        Err(format!("the application exited with code {code}"))
+    }
+}
+
+/// `erg pack add <name> [<version>] (--path <path> | --git <url> [--rev <rev>]) [--as <alias>]`
+fn add(args: Vec<&str>) -> Result<(), String> {
+    let (root, manifest) = current_package()?;
+    let mut args = args.into_iter();
+    let name = args.next().ok_or("the name of the package is not passed")?;
+    let mut dep = Dependency {
+        alias: name.to_string(),
+        name: name.to_string(),
+        version: VersionReq::ANY,
+        source: DependencySource::Path(PathBuf::new()),
+    };
+    let (mut path, mut git, mut rev) = (None, None, None);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--path" => path = Some(option_value(&mut args, arg)?),
+            "--git" => git = Some(option_value(&mut args, arg)?),
+            "--rev" => rev = Some(option_value(&mut args, arg)?.to_string()),
+            "--as" => dep.alias = option_value(&mut args, arg)?.to_string(),
+            version => dep.version = version.parse()?,
+        }
+    }
+    dep.source = match (path, git) {
+        (Some(path), None) => DependencySource::Path(PathBuf::from(path)),
+        (None, Some(url)) => DependencySource::Git {
+            url: url.to_string(),
+            rev,
+        },
+        _ => return Err("specify either `--path` or `--git`".into()),
+    };
+    if manifest.dependencies.iter().any(|d| d.alias == dep.alias) {
+        return Err(format!("the dependency `{}` already exists", dep.alias));
+    }
+    let manifest_path = root.join(MANIFEST_FILE);
+    let src = fs::read_to_string(&manifest_path).map_err(|err| err.to_string())?;
+    fs::write(&manifest_path, add_dependency(&src, &dep)).map_err(|err| err.to_string())?;
+    let manifest = Manifest::read(&root).inspect_err(|_| {
+        // restore the original manifest
+        let _ = fs::write(&manifest_path, &src);
+    })?;
+    println!("Added `{}` to the dependencies", dep.alias);
+    install(&root, &manifest)
+}
+
+fn option_value<'a>(
+    args: &mut impl Iterator<Item = &'a str>,
+    opt: &str,
+) -> Result<&'a str, String> {
+    args.next()
+        .ok_or_else(|| format!("the value of `{opt}` is not passed"))
+}
+
+/// Adds `dep` to the `dependencies` record of the manifest source.
+fn add_dependency(src: &str, dep: &Dependency) -> String {
+    let line = format!("    {}", dep.to_erg());
+    let mut lines = src.lines().map(ToString::to_string).collect::<Vec<_>>();
+    let deps = lines.iter().position(|l| {
+        l.starts_with("dependencies") && l.split_once('=').is_some_and(|(_, r)| r.contains('{'))
+    });
+    match deps {
+        Some(idx) if lines[idx].trim_end().ends_with("{=}") => {
+            let empty = lines[idx].rfind("{=}").unwrap();
+            lines[idx].replace_range(empty.., "{");
+            lines.insert(idx + 1, line);
+            lines.insert(idx + 2, "}".to_string());
+        }
+        // a single-line record, e.g. `dependencies = { foo = pack(...) }`
+        Some(idx) if lines[idx].trim_end().ends_with('}') => {
+            let end = lines[idx].rfind('}').unwrap();
+            let record = lines[idx][..end].trim_end().to_string();
+            lines[idx] = format!("{record}; {} }}", dep.to_erg());
+        }
+        Some(idx) => {
+            // append to the end of the record
+            let end = lines[idx..]
+                .iter()
+                .position(|l| l.trim() == "}")
+                .map_or(idx + 1, |end| idx + end);
+            lines.insert(end, line);
+        }
+        None => {
+            lines.push("dependencies = {".to_string());
+            lines.push(line);
+            lines.push("}".to_string());
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+/// `erg pack install`: vendors the dependencies and updates the lockfile.
+fn install(root: &Path, manifest: &Manifest) -> Result<(), String> {
+    let mut installer = Installer {
+        root,
+        locked: Lockfile::read(root)?,
+        lockfile: Lockfile::default(),
+    };
+    installer.install_deps(root, root, manifest)?;
+    installer
+        .lockfile
+        .write(root)
+        .map_err(|err| format!("cannot write the lockfile: {err}"))?;
+    eprintln!(
+        "Installed {} package(s) for {} v{}",
+        installer.lockfile.packages.len(),
+        manifest.name,
+        manifest.version
+    );
+    Ok(())
+}
+
+struct Installer<'a> {
+    root: &'a Path,
+    /// the previous lockfile
+    locked: Lockfile,
+    lockfile: Lockfile,
+}
+
+impl Installer<'_> {
+    /// `src_root` is the original location of the package (relative `path` dependencies are resolved from it),
+    /// `pkg_root` is where the package is installed.
+    fn install_deps(
+        &mut self,
+        src_root: &Path,
+        pkg_root: &Path,
+        manifest: &Manifest,
+    ) -> Result<(), String> {
+        let deps_dir = pkg_root.join(DEPS_DIR);
+        for dep in manifest.dependencies.iter() {
+            let dest = deps_dir.join(&dep.alias);
+            let rel_dest = dest.strip_prefix(self.root).unwrap_or(&dest).to_path_buf();
+            let source = dep.source.to_string();
+            let (dep_src_root, rev) = match &dep.source {
+                DependencySource::Path(path) => {
+                    let src = src_root.join(path).canonicalize().map_err(|err| {
+                        format!("cannot find `{}` ({}): {err}", dep.alias, path.display())
+                    })?;
+                    copy_dir(&src, &dest)
+                        .map_err(|err| format!("cannot copy `{}`: {err}", dep.alias))?;
+                    (src, None)
+                }
+                DependencySource::Git { url, rev } => {
+                    let locked_rev = self
+                        .locked
+                        .packages
+                        .iter()
+                        .find(|pack| {
+                            pack.path == rel_dest
+                                && pack.source == source
+                                && dep.version.matches(&pack.version)
+                        })
+                        .and_then(|pack| pack.rev.clone());
+                    let rev = fetch_git(url, rev.as_ref().or(locked_rev.as_ref()), &dest)?;
+                    (dest.clone(), Some(rev))
+                }
+            };
+            let dep_manifest = Manifest::read(&dest)?;
+            if dep_manifest.name != dep.name {
+                return Err(format!(
+                    "the name of the package `{}` is `{}`, not `{}`",
+                    dep.alias, dep_manifest.name, dep.name
+                ));
+            }
+            if !dep.version.matches(&dep_manifest.version) {
+                return Err(format!(
+                    "`{}` v{} does not match the required version `{}`",
+                    dep.name, dep_manifest.version, dep.version
+                ));
+            }
+            eprintln!(
+                "Installed {} v{} ({source})",
+                dep.name, dep_manifest.version
+            );
+            self.lockfile.packages.push(LockedPackage {
+                name: dep.name.clone(),
+                alias: dep.alias.clone(),
+                version: dep_manifest.version.clone(),
+                source,
+                rev,
+                path: rel_dest,
+            });
+            self.install_deps(&dep_src_root, &dest, &dep_manifest)?;
+        }
+        // remove the dependencies no longer used
+        if let Ok(entries) = fs::read_dir(&deps_dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                if !manifest
+                    .dependencies
+                    .iter()
+                    .any(|dep| name == dep.alias.as_str())
+                {
+                    let _ = fs::remove_dir_all(entry.path());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copies the package `src` to `dest` (replacing `dest`).
+fn copy_dir(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if dest.exists() {
+        fs::remove_dir_all(dest)?;
+    }
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            if IGNORED_DIRS.iter().any(|dir| entry.file_name() == *dir) {
+                continue;
+            }
+            copy_dir(&path, &dest.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, dest.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Clones the repository into `dest` (at `rev` if specified) and returns the commit hash.
+fn fetch_git(url: &str, rev: Option<&String>, dest: &Path) -> Result<String, String> {
+    let git = |args: &[&str], dir: Option<&Path>| {
+        let mut cmd = Command::new("git");
+        if let Some(dir) = dir {
+            cmd.current_dir(dir);
+        }
+        let output = cmd
+            .args(args)
+            .output()
+            .map_err(|err| format!("cannot execute git: {err}"))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(format!(
+                "`git {}` failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    };
+    if dest.exists() {
+        fs::remove_dir_all(dest).map_err(|err| err.to_string())?;
+    }
+    let dest_str = dest.to_string_lossy();
+    git(&["clone", "--quiet", url, &dest_str], None)?;
+    if let Some(rev) = rev {
+        git(&["checkout", "--quiet", rev], Some(dest))?;
+    }
+    let rev = git(&["rev-parse", "HEAD"], Some(dest))?;
+    fs::remove_dir_all(dest.join(".git")).map_err(|err| err.to_string())?;
+    Ok(rev)
+}
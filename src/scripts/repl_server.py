@@ -11,36 +11,84 @@ __server_socket.bind(('127.0.0.1', __PORT__))
 __server_socket.listen(1)
 (__client_socket, __client_address) = __server_socket.accept()
 
-__already_loaded = False
-__res = ''
+# Every message is framed as [kind: 1 byte][length: 4 bytes (big endian)][payload: UTF-8]
+# requests: l (load), q (quit)
+# responses: o (stdout), e (stderr), r (repr of the result), x (exception type), m (exception message),
+#            t (traceback, a frame per line: filename, lineno, name separated by tabs), s (exit status, always last), c (closed)
+def __recv_exact(size):
+    buf = b''
+    while len(buf) < size:
+        chunk = __client_socket.recv(size - len(buf))
+        if not chunk:
+            raise ConnectionResetError
+        buf += chunk
+    return buf
+
+def __recv_msg():
+    header = __recv_exact(5)
+    size = int.from_bytes(header[1:], 'big')
+    return (header[:1].decode(), __recv_exact(size).decode())
+
+def __send_msg(kind, payload):
+    data = payload.encode()
+    __client_socket.sendall(kind.encode() + len(data).to_bytes(4, 'big') + data)
+
+__module = None
+__res = None
+
+def __displayhook(value):
+    global __res
+    if value is not None:
+        __res = repr(value)
+
+__sys.displayhook = __displayhook
 
 while True:
     try:
-        __order = __client_socket.recv(1024).decode()
+        (__kind, _) = __recv_msg()
     except ConnectionResetError: # when the server was crashed
         break
-    if __order == 'quit' or __order == 'exit': # when the server was closed successfully
-        __client_socket.send('closed'.encode())
+    if __kind == 'q': # when the server was closed successfully
+        __send_msg('c', '')
         break
-    elif __order == 'load':
-        __sys.stdout = __io.StringIO()
+    elif __kind == 'l':
+        __res = None
+        __exc = None
+        __status = 0
+        (__sys.stdout, __sys.stderr) = (__io.StringIO(), __io.StringIO())
         try:
-            if __already_loaded:
+            if __module is None:
                 # __MODULE__ will be replaced with module name
-                __res = str(exec('__importlib.reload(__MODULE__)'))
+                __module = __importlib.import_module('__MODULE__')
             else:
-                __res = str(exec('import __MODULE__'))
-        except SystemExit:
-            __client_socket.send('[Exception] SystemExit'.encode())
-            continue
-        except e:
-            __res = str(e)
-        __already_loaded = True
-        __out = __sys.stdout.getvalue()[:-1]
-        __res = __out + '\n' + __res
-        __client_socket.send(__res.encode())
+                __importlib.reload(__module)
+        except SystemExit as e:
+            __exc = e
+            __status = e.code if isinstance(e.code, int) else int(e.code is not None)
+        except BaseException as e:
+            __exc = e
+            __status = 1
+        (__out, __err) = (__sys.stdout.getvalue(), __sys.stderr.getvalue())
+        (__sys.stdout, __sys.stderr) = (__sys.__stdout__, __sys.__stderr__)
+        __send_msg('o', __out)
+        __send_msg('e', __err)
+        if __res is not None:
+            __send_msg('r', __res)
+        if __exc is not None:
+            __send_msg('x', type(__exc).__name__)
+            __send_msg('m', str(__exc))
+            # traceback.extract_tb is not used because the position tables of Erg code objects may be incomplete
+            __frames = []
+            __tb = __exc.__traceback__
+            while __tb is not None:
+                __code = __tb.tb_frame.f_code
+                __frames.append(__code.co_filename + '\t' + str(__tb.tb_lineno) + '\t' + __code.co_name)
+                __tb = __tb.tb_next
+            __send_msg('t', '\n'.join(__frames))
+        __send_msg('s', str(__status))
     else:
-        __client_socket.send('unknown operation'.encode())
+        __send_msg('e', 'unknown operation')
+        __send_msg('s', '1')
 
 __client_socket.close()
 __server_socket.close()
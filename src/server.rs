@@ -0,0 +1,378 @@
+//! defines `CompileServer`, a daemon that keeps the compiler resources warm, and its client.
+//!
+//! The server keeps `SharedCompilerResource` (the builtin context and the checked modules) between requests.
+//! Cached modules are invalidated when their files are modified, together with the modules that depend on them.
+//! The client (`erg --client`) sends `check`/`compile`/`exec` requests over a local Unix socket;
+//! in the case of `exec`, the server only compiles the script and the client executes the `.pyc` file.
+use std::env;
+use std::fs::{metadata, remove_file};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use erg_common::config::{ErgConfig, ErgMode, Input};
+use erg_common::dict::Dict;
+use erg_common::python_util::exec_pyc;
+use erg_common::traits::{ExitStatus, Stream};
+use erg_common::{log, normalize_path};
+
+use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::module::SharedCompilerResource;
+use erg_compiler::Compiler;
+
+use crate::frame::{read_frame, write_frame};
+
+/// The kinds of messages exchanged between the compile server and its clients.
+///
+/// Every message is a frame (see `frame.rs`).
+/// A response to `Request` is a sequence of messages terminated by `Status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MessageKind {
+    /// request: `mode\npath\npyc_path` (the paths are absolute, `pyc_path` is empty for `check`)
+    Request,
+    /// request: shut down the server
+    Quit,
+    Stdout,
+    /// errors and warnings
+    Stderr,
+    /// `code\tnum_errors`
+    Status,
+}
+
+impl MessageKind {
+    const fn as_byte(&self) -> u8 {
+        match self {
+            Self::Request => b'r',
+            Self::Quit => b'q',
+            Self::Stdout => b'o',
+            Self::Stderr => b'e',
+            Self::Status => b's',
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b'r' => Some(Self::Request),
+            b'q' => Some(Self::Quit),
+            b'o' => Some(Self::Stdout),
+            b'e' => Some(Self::Stderr),
+            b's' => Some(Self::Status),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn send_message(
+    stream: &mut std::os::unix::net::UnixStream,
+    kind: MessageKind,
+    payload: &str,
+) -> io::Result<()> {
+    write_frame(stream, kind.as_byte(), payload)
+}
+
+#[cfg(unix)]
+fn recv_message(stream: &mut std::os::unix::net::UnixStream) -> io::Result<(MessageKind, String)> {
+    let (kind, payload) = read_frame(stream)?;
+    let kind = MessageKind::from_byte(kind).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown message kind: {kind}"),
+        )
+    })?;
+    Ok((kind, payload))
+}
+
+/// The default path is `$TMPDIR/erg-server-$USER.sock`.
+fn socket_path(cfg: &ErgConfig) -> PathBuf {
+    if let Some(socket) = cfg.server_socket {
+        return PathBuf::from(socket);
+    }
+    let name = match env::var("USER") {
+        Ok(user) if !user.is_empty() => format!("erg-server-{user}.sock"),
+        _ => "erg-server.sock".to_string(),
+    };
+    env::temp_dir().join(name)
+}
+
+fn is_served(mode: ErgMode) -> bool {
+    matches!(
+        mode,
+        ErgMode::FullCheck | ErgMode::Compile | ErgMode::Execute
+    )
+}
+
+#[derive(Debug)]
+struct Response {
+    stdout: String,
+    stderr: String,
+    status: ExitStatus,
+}
+
+/// Keeps the builtin context and the checked modules between requests.
+///
+/// Options of the server (e.g. `--py-command`, `--no-std`) are applied to all requests.
+#[derive(Debug)]
+pub struct CompileServer {
+    cfg: ErgConfig,
+    shared: SharedCompilerResource,
+    /// modification times of the cached modules at the time they were checked
+    mtimes: Dict<PathBuf, SystemTime>,
+}
+
+impl CompileServer {
+    pub fn new(cfg: ErgConfig) -> Self {
+        Self {
+            shared: SharedCompilerResource::new(cfg.copy()),
+            cfg,
+            mtimes: Dict::new(),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn run(_cfg: ErgConfig) -> ExitStatus {
+        eprintln!("The compile server is only supported on Unix");
+        ExitStatus::ERR1
+    }
+
+    #[cfg(unix)]
+    pub fn run(cfg: ErgConfig) -> ExitStatus {
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        let path = socket_path(&cfg);
+        if UnixStream::connect(&path).is_ok() {
+            eprintln!("The compile server is already running: {}", path.display());
+            return ExitStatus::ERR1;
+        }
+        // a socket file left by a server that was killed
+        remove_file(&path).unwrap_or(());
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(err) => {
+                eprintln!("Cannot bind {}: {err}", path.display());
+                return ExitStatus::ERR1;
+            }
+        };
+        if !cfg.quiet_repl {
+            println!("The compile server is listening on {}", path.display());
+        }
+        let mut server = Self::new(cfg);
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    eprintln!("Connection error: {err}");
+                    continue;
+                }
+            };
+            match server.handle(&mut stream) {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(err) => eprintln!("Communication error: {err}"),
+            }
+        }
+        remove_file(&path).unwrap_or(());
+        ExitStatus::OK
+    }
+
+    /// Returns `true` if the server should be shut down.
+    #[cfg(unix)]
+    fn handle(&mut self, stream: &mut std::os::unix::net::UnixStream) -> io::Result<bool> {
+        match recv_message(stream)? {
+            (MessageKind::Request, payload) => {
+                let res = self.dispatch(&payload);
+                if !res.stdout.is_empty() {
+                    send_message(stream, MessageKind::Stdout, &res.stdout)?;
+                }
+                if !res.stderr.is_empty() {
+                    send_message(stream, MessageKind::Stderr, &res.stderr)?;
+                }
+                let status = format!("{}\t{}", res.status.code, res.status.num_errors);
+                send_message(stream, MessageKind::Status, &status)?;
+                Ok(false)
+            }
+            (MessageKind::Quit, _) => {
+                send_message(stream, MessageKind::Status, "0\t0")?;
+                Ok(true)
+            }
+            (other, _) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unexpected message: {other:?}"),
+            )),
+        }
+    }
+
+    fn dispatch(&mut self, payload: &str) -> Response {
+        let mut lines = payload.lines();
+        let mode = lines.next().and_then(|mode| ErgMode::try_from(mode).ok());
+        let path = lines.next().map(|path| normalize_path(PathBuf::from(path)));
+        let pyc_path = lines.next().unwrap_or_default();
+        let (Some(mode), Some(path)) = (mode, path) else {
+            return Response {
+                stdout: String::new(),
+                stderr: format!("invalid request: {payload}\n"),
+                status: ExitStatus::ERR1,
+            };
+        };
+        log!(info "request: {mode} {}", path.display());
+        self.invalidate_modified();
+        // the main module is always checked again
+        self.shared.index.remove_path(&path);
+        self.shared.graph.remove(&path);
+        let mut cfg = self.cfg.copy();
+        cfg.mode = mode;
+        cfg.input = Input::File(path);
+        let res = match mode {
+            ErgMode::Compile | ErgMode::Execute => self.compile(cfg, Path::new(pyc_path)),
+            _ => self.check(cfg),
+        };
+        self.record_mtimes();
+        res
+    }
+
+    fn check(&mut self, mut cfg: ErgConfig) -> Response {
+        let src = cfg.input.read();
+        let mut builder = HIRBuilder::new_with_cache(cfg, "<module>", self.shared.clone());
+        match builder.build(src, "exec") {
+            Ok(artifact) => Response {
+                stdout: format!("{}\n", artifact.object),
+                stderr: artifact.warns.to_string(),
+                status: ExitStatus::OK,
+            },
+            Err(artifact) => Response {
+                stdout: String::new(),
+                stderr: format!("{}{}", artifact.warns, artifact.errors),
+                status: ExitStatus::new(1, artifact.errors.len()),
+            },
+        }
+    }
+
+    fn compile(&mut self, mut cfg: ErgConfig, pyc_path: &Path) -> Response {
+        let src = cfg.input.read();
+        let mut compiler = Compiler::new_with_cache(cfg, "<module>", self.shared.clone());
+        match compiler.compile_and_dump_as_pyc(pyc_path, src, "exec") {
+            Ok(warns) => Response {
+                stdout: String::new(),
+                stderr: warns.to_string(),
+                status: ExitStatus::OK,
+            },
+            Err(artifact) => Response {
+                stdout: String::new(),
+                stderr: format!("{}{}", artifact.warns, artifact.errors),
+                status: ExitStatus::new(1, artifact.errors.len()),
+            },
+        }
+    }
+
+    fn cached_paths(&self) -> Vec<PathBuf> {
+        self.shared
+            .mod_cache
+            .keys()
+            .chain(self.shared.py_mod_cache.keys())
+            .filter(|path| path.is_file())
+            .collect()
+    }
+
+    /// Removes the cached modules whose files have been modified (or removed) since they were checked.
+    fn invalidate_modified(&mut self) {
+        let mut modified = vec![];
+        for (path, checked) in self.mtimes.iter() {
+            let mtime = metadata(path).and_then(|meta| meta.modified()).ok();
+            if mtime.as_ref() != Some(checked) {
+                modified.push(path.clone());
+            }
+        }
+        for path in modified {
+            log!(info "{} is modified", path.display());
+            self.shared.remove_module(&path);
+        }
+        let cached = self.cached_paths();
+        self.mtimes.retain(|path, _| cached.contains(path));
+    }
+
+    fn record_mtimes(&mut self) {
+        for path in self.cached_paths() {
+            if self.mtimes.get(&path).is_none() {
+                if let Ok(mtime) = metadata(&path).and_then(|meta| meta.modified()) {
+                    self.mtimes.insert(path, mtime);
+                }
+            }
+        }
+    }
+}
+
+/// Sends the request to the compile server.
+/// Returns `None` if the request cannot be handled by the server (e.g. the server is not running),
+/// then the caller should process the request by itself.
+#[cfg(unix)]
+pub fn request(cfg: &ErgConfig) -> Option<ExitStatus> {
+    use std::os::unix::net::UnixStream;
+
+    if !is_served(cfg.mode) {
+        return None;
+    }
+    let Input::File(path) = &cfg.input else {
+        return None;
+    };
+    let cwd = env::current_dir().ok()?;
+    let path = normalize_path(cwd.join(path));
+    let pyc_path = match cfg.mode {
+        ErgMode::Compile | ErgMode::Execute => normalize_path(cwd.join(cfg.dump_pyc_path())),
+        _ => PathBuf::new(),
+    };
+    let mut stream = UnixStream::connect(socket_path(cfg)).ok()?;
+    let payload = format!("{}\n{}\n{}", cfg.mode, path.display(), pyc_path.display());
+    send_message(&mut stream, MessageKind::Request, &payload).ok()?;
+    let status = loop {
+        match recv_message(&mut stream) {
+            Ok((MessageKind::Stdout, out)) => print!("{out}"),
+            Ok((MessageKind::Stderr, err)) => eprint!("{err}"),
+            Ok((MessageKind::Status, status)) => {
+                let mut status = status.split('\t').map(|n| n.parse().unwrap_or(1));
+                let code = status.next().unwrap_or(1);
+                let num_errors = status.next().unwrap_or(0);
+                break ExitStatus::new(code as i32, num_errors);
+            }
+            Ok((other, _)) => {
+                eprintln!("Unexpected message from the compile server: {other:?}");
+                return Some(ExitStatus::ERR1);
+            }
+            Err(err) => {
+                eprintln!("Read error: {err}");
+                return Some(ExitStatus::ERR1);
+            }
+        }
+    };
+    if cfg.mode == ErgMode::Execute && status.code == 0 {
+        let code = exec_pyc(
+            pyc_path.to_string_lossy(),
+            None,
+            cfg.py_command,
+            &cfg.runtime_args,
+        );
+        remove_file(&pyc_path).unwrap_or(());
+        return Some(ExitStatus::new(code.unwrap_or(1), 0));
+    }
+    Some(status)
+}
+
+#[cfg(not(unix))]
+pub fn request(_cfg: &ErgConfig) -> Option<ExitStatus> {
+    None
+}
+
+/// Shuts down the compile server. Returns `false` if the server is not running.
+#[cfg(unix)]
+pub fn shutdown(cfg: &ErgConfig) -> bool {
+    use std::os::unix::net::UnixStream;
+
+    let Ok(mut stream) = UnixStream::connect(socket_path(cfg)) else {
+        return false;
+    };
+    send_message(&mut stream, MessageKind::Quit, "").is_ok() && recv_message(&mut stream).is_ok()
+}
+
+#[cfg(not(unix))]
+pub fn shutdown(_cfg: &ErgConfig) -> bool {
+    false
+}
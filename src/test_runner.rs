@@ -0,0 +1,609 @@
+//! defines the test runner (`erg test`).
+//!
+//! `@Test` subroutines are collected from `tests/**/*.er` (black-box tests) and `*.test.er` (white-box tests).
+//! A white-box test file `foo.test.er` is compiled together with `foo.er`, so it can test private subroutines.
+//! In addition, the `erg` code blocks in the doc comments (`##`, `#[[ ]]#`, `'''`) of all modules are run as doctests.
+//! Each test is executed in a new `DummyVM` (i.e. a new Python process) to isolate it from the others.
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
+
+use erg_common::config::{DummyStdin, ErgConfig, ErgMode, Input};
+use erg_common::help_messages::test_message;
+use erg_common::traits::{ExitStatus, Locational, Runnable};
+
+use erg_parser::lex::Lexer;
+
+use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::error::CompileErrors;
+use erg_compiler::hir::{Expr, Signature};
+use erg_compiler::module::package::find_package_root;
+
+use crate::pack::IGNORED_DIRS;
+use crate::{CapturedEval, DummyVM};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Human,
+    JUnit,
+}
+
+#[derive(Debug)]
+pub(crate) struct TestOptions {
+    paths: Vec<PathBuf>,
+    filter: Option<String>,
+    format: OutputFormat,
+}
+
+impl Default for TestOptions {
+    /// all the tests of the package, reported in the human-readable format
+    fn default() -> Self {
+        Self {
+            paths: vec![],
+            filter: None,
+            format: OutputFormat::Human,
+        }
+    }
+}
+
+impl TestOptions {
+    /// `erg test [<path>...] [--filter <pattern>] [--format human|junit]`
+    fn parse(args: &[&str]) -> Result<Option<Self>, String> {
+        let mut opts = Self::default();
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            match arg {
+                "-h" | "--help" => return Ok(None),
+                "--filter" => {
+                    let pattern = args.next().ok_or("the value of `--filter` is not passed")?;
+                    opts.filter = Some(pattern.to_string());
+                }
+                "--format" => {
+                    opts.format = match args.next().copied() {
+                        Some("human") => OutputFormat::Human,
+                        Some("junit") => OutputFormat::JUnit,
+                        Some(other) => return Err(format!("unknown format: {other}")),
+                        None => return Err("the value of `--format` is not passed".into()),
+                    }
+                }
+                other if other.starts_with('-') => return Err(format!("unknown option: {other}")),
+                path => opts.paths.push(PathBuf::from(path)),
+            }
+        }
+        Ok(Some(opts))
+    }
+}
+
+/// A part of the evaluated code and where it comes from
+#[derive(Debug, Clone)]
+struct Segment {
+    file: String,
+    /// the line in `file` where the segment starts (1-origin)
+    first_line: usize,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+enum CaseKind {
+    Run,
+    Skip(String),
+    /// the test file could not be compiled
+    Broken(String),
+}
+
+#[derive(Debug, Clone)]
+struct TestCase {
+    /// e.g. `tests/add.er::test_add!`, `src/lib.er::doc(line 3)`
+    name: String,
+    /// the file which defines the test
+    suite: String,
+    /// the directory where the test is run (imports are resolved from here)
+    dir: PathBuf,
+    src: String,
+    segments: Vec<Segment>,
+    kind: CaseKind,
+}
+
+impl TestCase {
+    fn new(name: String, suite: &str, dir: &Path) -> Self {
+        Self {
+            name,
+            suite: suite.to_string(),
+            dir: dir.to_path_buf(),
+            src: String::new(),
+            segments: vec![],
+            kind: CaseKind::Run,
+        }
+    }
+
+    fn push_code(&mut self, file: &str, first_line: usize, code: &str) {
+        self.src.push_str(code);
+        if !code.ends_with('\n') {
+            self.src.push('\n');
+        }
+        self.segments.push(Segment {
+            file: file.to_string(),
+            first_line,
+            len: code.lines().count(),
+        });
+    }
+
+    /// Maps a line of the evaluated code to the original position (e.g. `tests/add.er:3`).
+    fn locate(&self, line: Option<u32>) -> String {
+        let Some(mut line) = line.map(|l| l as usize).filter(|l| *l > 0) else {
+            return self.suite.clone();
+        };
+        for seg in self.segments.iter() {
+            if line <= seg.len {
+                return format!("{}:{}", seg.file, seg.first_line + line - 1);
+            }
+            line -= seg.len;
+        }
+        self.suite.clone()
+    }
+
+    fn describe_errors(&self, errs: &CompileErrors) -> String {
+        let mut msg = String::new();
+        for err in errs.iter() {
+            let kind = format!("{:?}", err.core.kind);
+            let loc = self.locate(err.core.loc.ln_begin());
+            if err.core.main_message == kind {
+                let _ = writeln!(msg, "{kind} ({loc})");
+            } else {
+                let _ = writeln!(msg, "{kind}: {} ({loc})", err.core.main_message);
+            }
+            // e.g. the frames of the traceback (`in test_add!`)
+            for sub_msg in err.core.sub_messages.iter().flat_map(|sub| sub.get_msg()) {
+                let _ = writeln!(msg, "    {sub_msg}");
+            }
+        }
+        msg
+    }
+}
+
+#[derive(Debug)]
+enum Outcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+#[derive(Debug)]
+struct TestResult {
+    name: String,
+    suite: String,
+    outcome: Outcome,
+    stdout: String,
+    stderr: String,
+    time: Duration,
+}
+
+pub fn run(cfg: ErgConfig) -> ExitStatus {
+    let res = match TestOptions::parse(&cfg.runtime_args) {
+        Ok(Some(opts)) => run_tests(&cfg, &opts),
+        Ok(None) => {
+            println!("{}", test_message());
+            return ExitStatus::OK;
+        }
+        Err(msg) => Err(msg),
+    };
+    match res {
+        Ok(true) => ExitStatus::OK,
+        Ok(false) => ExitStatus::ERR1,
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            ExitStatus::ERR1
+        }
+    }
+}
+
+/// Returns whether all the tests passed.
+pub(crate) fn run_tests(cfg: &ErgConfig, opts: &TestOptions) -> Result<bool, String> {
+    let cwd = env::current_dir().map_err(|err| err.to_string())?;
+    let root = find_package_root(&cwd).unwrap_or_else(|| cwd.clone());
+    let targets = if opts.paths.is_empty() {
+        vec![root.clone()]
+    } else {
+        opts.paths.iter().map(|path| cwd.join(path)).collect()
+    };
+    let mut cases = vec![];
+    for target in targets {
+        if !target.exists() {
+            return Err(format!("{} does not exist", target.display()));
+        }
+        let explicit = target.is_file();
+        let mut files = vec![];
+        collect_files(&target, &mut files);
+        files.sort();
+        for file in files {
+            let rel = display_path(&root, &file);
+            if explicit || is_test_file(&root, &file) {
+                cases.extend(collect_test_subrs(cfg, &file, &rel));
+            }
+            cases.extend(collect_doctests(&file, &rel));
+        }
+    }
+    if let Some(filter) = &opts.filter {
+        cases.retain(|case| case.name.contains(&filter[..]));
+    }
+    let human = opts.format == OutputFormat::Human;
+    if human {
+        let plural = if cases.len() == 1 { "" } else { "s" };
+        println!("running {} test{plural}", cases.len());
+    }
+    let start = Instant::now();
+    let mut results = vec![];
+    for case in cases {
+        let result = run_case(cfg, case);
+        if human {
+            let status = match &result.outcome {
+                Outcome::Passed => "ok",
+                Outcome::Failed(_) => "FAILED",
+                Outcome::Skipped(_) => "skipped",
+            };
+            println!("test {} ... {status}", result.name);
+        }
+        results.push(result);
+    }
+    let elapsed = start.elapsed();
+    match opts.format {
+        OutputFormat::Human => print_summary(&results, elapsed),
+        OutputFormat::JUnit => print!("{}", junit_xml(&results, elapsed)),
+    }
+    Ok(results
+        .iter()
+        .all(|result| !matches!(result.outcome, Outcome::Failed(_))))
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        let name = path.to_string_lossy();
+        if name.ends_with(".er") && !name.ends_with(".d.er") {
+            files.push(path.to_path_buf());
+        }
+        return;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if IGNORED_DIRS.iter().any(|dir| entry.file_name() == *dir) {
+            continue;
+        }
+        collect_files(&entry.path(), files);
+    }
+}
+
+fn display_path(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    rel.to_string_lossy().replace('\\', "/")
+}
+
+/// `tests/**/*.er` or `*.test.er`
+fn is_test_file(root: &Path, path: &Path) -> bool {
+    path.to_string_lossy().ends_with(".test.er")
+        || path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .components()
+            .any(|comp| comp.as_os_str() == "tests")
+}
+
+/// Collects `@Test` subroutines by compiling the file.
+fn collect_test_subrs(cfg: &ErgConfig, path: &Path, rel: &str) -> Vec<TestCase> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let Ok(src) = fs::read_to_string(path) else {
+        return vec![];
+    };
+    // `foo.test.er` is compiled together with `foo.er`
+    let base = path
+        .to_string_lossy()
+        .strip_suffix(".test.er")
+        .and_then(|base| fs::read_to_string(format!("{base}.er")).ok())
+        .map(|base_src| (rel.replace(".test.er", ".er"), base_src));
+    let mut template = TestCase::new(String::new(), rel, dir);
+    if let Some((base_rel, base_src)) = &base {
+        template.push_code(base_rel, 1, base_src);
+    }
+    template.push_code(rel, 1, &src);
+    let hir_cfg = ErgConfig {
+        mode: ErgMode::FullCheck,
+        input: Input::File(path.to_path_buf()),
+        quiet_repl: true,
+        ..cfg.copy()
+    };
+    let mut builder = HIRBuilder::new(hir_cfg);
+    let hir = match builder.build(template.src.clone(), "exec") {
+        Ok(artifact) => artifact.object,
+        Err(artifact) => {
+            let mut case = template;
+            case.name = format!("{rel} (compile)");
+            case.kind = CaseKind::Broken(case.describe_errors(&artifact.errors));
+            return vec![case];
+        }
+    };
+    let mut cases = vec![];
+    for chunk in hir.module.iter() {
+        let Expr::Def(def) = chunk else {
+            continue;
+        };
+        let Signature::Subr(sig) = &def.sig else {
+            continue;
+        };
+        if !sig.is_test() {
+            continue;
+        }
+        let name = sig.ident.inspect();
+        let mut case = TestCase {
+            name: format!("{rel}::{name}"),
+            ..template.clone()
+        };
+        if !sig.params.non_defaults.is_empty() || sig.params.var_params.is_some() {
+            case.kind = CaseKind::Skip("parameterized tests are not supported".into());
+        }
+        case.push_code(
+            rel,
+            sig.ident.ln_begin().unwrap_or(0) as usize,
+            &format!("{name}()"),
+        );
+        cases.push(case);
+    }
+    cases
+}
+
+/// Collects the `erg` code blocks in the doc comments.
+fn collect_doctests(path: &Path, rel: &str) -> Vec<TestCase> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let Ok(src) = fs::read_to_string(path) else {
+        return vec![];
+    };
+    let mut cases = vec![];
+    for doc in Lexer::from_str(src).lex_doc_comments() {
+        for (offset, code) in erg_code_blocks(&doc_lines(&doc.content)) {
+            let first_line = doc.lineno as usize + offset;
+            let mut case = TestCase::new(format!("{rel}::doc(line {first_line})"), rel, dir);
+            case.push_code(rel, first_line, &code);
+            cases.push(case);
+        }
+    }
+    cases
+}
+
+/// Extracts the code blocks fenced with ```` ```erg ````.
+/// Returns the index of the first line of each block and its (dedented) code.
+pub(crate) fn erg_code_blocks(lines: &[String]) -> Vec<(usize, String)> {
+    let mut blocks = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(lang) = lines[i].trim().strip_prefix("```") else {
+            i += 1;
+            continue;
+        };
+        let Some(len) = lines[i + 1..].iter().position(|line| line.trim() == "```") else {
+            break;
+        };
+        if lang.trim() == "erg" {
+            blocks.push((i + 1, dedent(&lines[i + 1..i + 1 + len])));
+        }
+        i += len + 2;
+    }
+    blocks
+}
+
+/// Strips the comment markers. The n-th line corresponds to the n-th line of the doc comment.
+fn doc_lines(doc: &str) -> Vec<String> {
+    if doc.starts_with("##") {
+        doc.lines()
+            .map(|line| line.trim_start().trim_start_matches('#').to_string())
+            .collect()
+    } else {
+        let doc = doc
+            .trim_start_matches("#[[")
+            .trim_end_matches("]]#")
+            .trim_start_matches("'''")
+            .trim_end_matches("'''");
+        doc.lines().map(ToString::to_string).collect()
+    }
+}
+
+fn dedent(lines: &[String]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn run_case(cfg: &ErgConfig, case: TestCase) -> TestResult {
+    let start = Instant::now();
+    let (outcome, stdout, stderr) = match &case.kind {
+        CaseKind::Skip(reason) => (
+            Outcome::Skipped(reason.clone()),
+            String::new(),
+            String::new(),
+        ),
+        CaseKind::Broken(msg) => (Outcome::Failed(msg.clone()), String::new(), String::new()),
+        CaseKind::Run => match eval_isolated(cfg, &case) {
+            Ok(CapturedEval {
+                stdout,
+                stderr,
+                result,
+            }) => {
+                let outcome = match result {
+                    Ok(()) => Outcome::Passed,
+                    Err(errs) => Outcome::Failed(case.describe_errors(&errs)),
+                };
+                (outcome, stdout, stderr)
+            }
+            Err(msg) => (Outcome::Failed(msg), String::new(), String::new()),
+        },
+    };
+    TestResult {
+        name: case.name,
+        suite: case.suite,
+        outcome,
+        stdout,
+        stderr,
+        time: start.elapsed(),
+    }
+}
+
+/// Evaluates the test code in a new `DummyVM`.
+/// The imports are resolved from the directory of the test file.
+fn eval_isolated(cfg: &ErgConfig, case: &TestCase) -> Result<CapturedEval, String> {
+    let dir = case
+        .dir
+        .canonicalize()
+        .map_err(|err| format!("cannot run the test in {}: {err}\n", case.dir.display()))?;
+    let cfg = ErgConfig {
+        mode: ErgMode::Execute,
+        input: Input::DummyREPL(
+            DummyStdin::new(format!("erg_test_{}", process::id()), vec![]).with_dir(dir),
+        ),
+        quiet_repl: true,
+        runtime_args: vec![],
+        ..cfg.copy()
+    };
+    let mut vm = DummyVM::new(cfg);
+    Ok(vm.eval_captured(case.src.clone()))
+}
+
+fn print_summary(results: &[TestResult], elapsed: Duration) {
+    let failed = results
+        .iter()
+        .filter(|result| matches!(result.outcome, Outcome::Failed(_)))
+        .collect::<Vec<_>>();
+    if !failed.is_empty() {
+        println!("\nfailures:");
+        for result in failed.iter() {
+            let Outcome::Failed(msg) = &result.outcome else {
+                continue;
+            };
+            println!("\n---- {} ----", result.name);
+            print!("{msg}");
+            if !result.stdout.is_empty() {
+                println!("stdout:\n{}", result.stdout.trim_end());
+            }
+            if !result.stderr.is_empty() {
+                println!("stderr:\n{}", result.stderr.trim_end());
+            }
+        }
+    }
+    let passed = results
+        .iter()
+        .filter(|result| matches!(result.outcome, Outcome::Passed))
+        .count();
+    let skipped = results.len() - passed - failed.len();
+    println!(
+        "\ntest result: {}. {passed} passed; {} failed; {skipped} skipped; finished in {:.2}s",
+        if failed.is_empty() { "ok" } else { "FAILED" },
+        failed.len(),
+        elapsed.as_secs_f64()
+    );
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn junit_xml(results: &[TestResult], elapsed: Duration) -> String {
+    let count = |results: &[&TestResult], f: fn(&Outcome) -> bool| {
+        results.iter().filter(|result| f(&result.outcome)).count()
+    };
+    let is_failed = |outcome: &Outcome| matches!(outcome, Outcome::Failed(_));
+    let is_skipped = |outcome: &Outcome| matches!(outcome, Outcome::Skipped(_));
+    let mut suites: Vec<(&str, Vec<&TestResult>)> = vec![];
+    for result in results {
+        match suites.iter_mut().find(|(suite, _)| *suite == result.suite) {
+            Some((_, cases)) => cases.push(result),
+            None => suites.push((&result.suite, vec![result])),
+        }
+    }
+    let all = results.iter().collect::<Vec<_>>();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"erg test\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+        all.len(),
+        count(&all, is_failed),
+        count(&all, is_skipped),
+        elapsed.as_secs_f64()
+    );
+    for (suite, cases) in suites {
+        let time: Duration = cases.iter().map(|result| result.time).sum();
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+            escape_xml(suite),
+            cases.len(),
+            count(&cases, is_failed),
+            count(&cases, is_skipped),
+            time.as_secs_f64()
+        );
+        for result in cases {
+            let name = result
+                .name
+                .strip_prefix(&format!("{suite}::"))
+                .unwrap_or(&result.name);
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape_xml(name),
+                escape_xml(suite),
+                result.time.as_secs_f64()
+            );
+            match &result.outcome {
+                Outcome::Passed if result.stdout.is_empty() && result.stderr.is_empty() => {
+                    xml.push_str("/>\n");
+                    continue;
+                }
+                Outcome::Passed => xml.push_str(">\n"),
+                Outcome::Failed(msg) => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <failure message=\"{}\">{}</failure>",
+                        escape_xml(msg.lines().next().unwrap_or_default()),
+                        escape_xml(msg)
+                    );
+                }
+                Outcome::Skipped(reason) => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <skipped message=\"{}\"/>",
+                        escape_xml(reason)
+                    );
+                }
+            }
+            if !result.stdout.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape_xml(&result.stdout)
+                );
+            }
+            if !result.stderr.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-err>{}</system-err>",
+                    escape_xml(&result.stderr)
+                );
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
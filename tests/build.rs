@@ -0,0 +1,92 @@
+mod common;
+use common::{erg, expect_stdout, python, TempDir};
+
+use std::fs;
+use std::path::Path;
+#[cfg(unix)]
+use std::process::Command;
+
+/// Returns both stdout and stderr, since the build progress is reported to stderr.
+fn erg_build(root: &Path, args: &[&str]) -> String {
+    let output = erg(root, args);
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap() + &String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn build_package() {
+    let dir = TempDir::new("build");
+    let root = dir.join("calc");
+    fs::create_dir_all(&root).unwrap();
+    erg_build(&root, &["pack", "init", "hybrid"]);
+    fs::create_dir_all(root.join("doc")).unwrap();
+    fs::create_dir_all(root.join("tests")).unwrap();
+    fs::write(
+        root.join("src/lib.er"),
+        "util = import \"util\"
+
+.add x: Int, y: Int =
+    '''
+    adds two integers
+    '''
+    x + y
+.double x: Int = util.twice x
+",
+    )
+    .unwrap();
+    fs::write(root.join("src/util.er"), ".twice x: Int = x * 2\n").unwrap();
+    fs::write(
+        root.join("src/main.er"),
+        "lib = import \"lib\"\nprint! lib.double lib.add(1, 2)\n",
+    )
+    .unwrap();
+    fs::write(root.join("doc/usage.md"), "```erg\nprint! 1 + 2\n```\n").unwrap();
+    fs::write(
+        root.join("tests/add.er"),
+        "lib = import \"../src/lib\"\n\n@Test\ntest_add!() =\n    assert lib.add(1, 2) == 3\n",
+    )
+    .unwrap();
+    let out = erg_build(&root, &["build"]);
+    assert!(out.contains("Compiling calc v0.1.0 (debug)"), "{out}");
+    assert!(out.contains("test tests/add.er::test_add! ... ok"), "{out}");
+    let debug = root.join("build/debug");
+    for module in ["calc/__init__.pyc", "calc/__main__.pyc", "calc/util.pyc"] {
+        assert!(debug.join(module).exists(), "{module} was not generated");
+    }
+    // the output directory can be used as a Python path
+    let imported = expect_stdout(python(
+        &debug,
+        &["-c", "import calc; print(calc.double(4), calc.add.__doc__)"],
+    ));
+    assert_eq!(imported, "8 \n    adds two integers\n    \n");
+    #[cfg(unix)]
+    {
+        let output = Command::new(debug.join("bin/calc")).output().unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "6\n");
+    }
+    let out = erg_build(&root, &["build", "--no-test"]);
+    assert!(out.contains("Fresh calc v0.1.0"), "{out}");
+    erg_build(&root, &["build", "--release", "--no-test"]);
+    // docstrings are stripped in the release build
+    let release = root.join("build/release");
+    let imported = expect_stdout(python(
+        &release,
+        &["-c", "import calc; print(calc.double(4), calc.add.__doc__)"],
+    ));
+    assert_eq!(imported, "8 None\n");
+}
+
+#[test]
+fn compile_opt_level_keeps_docstrings() {
+    let dir = TempDir::new("opt_level");
+    fs::write(
+        dir.join("doc.er"),
+        ".f x: Int =\n    '''\n    doc\n    '''\n    x\n",
+    )
+    .unwrap();
+    let output = erg(&dir, &["--mode", "compile", "-o", "2", "doc.er"]);
+    assert!(output.status.success(), "{output:?}");
+    // only `erg build --release` strips the docstrings
+    let imported = expect_stdout(python(&dir, &["-c", "import doc; print(doc.f.__doc__)"]));
+    assert_eq!(imported, "\n    doc\n    \n");
+}
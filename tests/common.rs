@@ -1,5 +1,9 @@
 #![allow(dead_code)]
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
 
 use erg_common::config::{DummyStdin, ErgConfig, Input};
 use erg_common::error::MultiErrorDisplay;
@@ -9,6 +13,7 @@ use erg_common::style::{colors::DEBUG_MAIN, RESET};
 use erg_common::traits::{ExitStatus, Runnable, Stream};
 
 use erg_compiler::error::CompileErrors;
+use erg_compiler::interpret::{exec_new_interpreter_thread, Interpreter};
 
 use erg::DummyVM;
 
@@ -109,12 +114,108 @@ pub(crate) fn expect_failure(file_path: &'static str, errs_len: usize) -> Result
     }
 }
 
-fn set_cfg(mut cfg: ErgConfig) -> ErgConfig {
-    cfg.py_command = if cfg!(windows) {
-        Some("python")
+pub(crate) fn expect_interpret_success(file_path: &'static str) -> Result<(), ()> {
+    match exec_interpreter(file_path) {
+        Ok(0) => Ok(()),
+        Ok(i) => {
+            println!("err: should succeed, but end with {i}");
+            Err(())
+        }
+        Err(errs) => {
+            println!("err: should succeed, but got errors");
+            errs.fmt_all_stderr();
+            Err(())
+        }
+    }
+}
+
+pub(crate) fn expect_interpret_failure(file_path: &'static str, errs_len: usize) -> Result<(), ()> {
+    match exec_interpreter(file_path) {
+        Ok(i) => {
+            println!("err: should fail, but end with {i}");
+            Err(())
+        }
+        Err(errs) => {
+            errs.fmt_all_stderr();
+            if errs.len() == errs_len {
+                Ok(())
+            } else {
+                println!(
+                    "err: number of errors should be {errs_len}, but got {}",
+                    errs.len()
+                );
+                Err(())
+            }
+        }
+    }
+}
+
+fn py_command() -> &'static str {
+    if cfg!(windows) {
+        "python"
     } else {
-        Some("python3")
-    };
+        "python3"
+    }
+}
+
+/// A temporary directory for a test. It is removed on drop, even if the test fails.
+pub(crate) struct TempDir(PathBuf);
+
+impl Deref for TempDir {
+    type Target = Path;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+impl TempDir {
+    /// `$TMP/erg-test-{name}-{pid}`
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("erg-test-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+/// Runs the `erg` binary in `dir`.
+pub(crate) fn erg(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_erg"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+/// Runs Python in `dir`.
+pub(crate) fn python<S: AsRef<OsStr>>(dir: &Path, args: &[S]) -> Output {
+    Command::new(py_command())
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+/// Asserts that the command succeeded and returns its stdout.
+pub(crate) fn expect_stdout(output: Output) -> String {
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn set_cfg(mut cfg: ErgConfig) -> ErgConfig {
+    cfg.py_command = Some(py_command());
     let py_ver_minor = env!("PYTHON_VERSION_MINOR").parse::<u8>().unwrap();
     let py_ver_micro = env!("PYTHON_VERSION_MICRO").parse::<u8>().unwrap();
     let py_magic_num = env!("PYTHON_MAGIC_NUMBER").parse::<u32>().unwrap();
@@ -136,6 +237,13 @@ fn _exec_file(file_path: &'static str) -> Result<i32, CompileErrors> {
     vm.exec()
 }
 
+fn _exec_interpreter(file_path: &'static str) -> Result<i32, CompileErrors> {
+    println!("{DEBUG_MAIN}[test] interpret {file_path}{RESET}");
+    let cfg = ErgConfig::with_main_path(PathBuf::from(file_path));
+    let mut interpreter = Interpreter::new(cfg);
+    interpreter.exec()
+}
+
 /// WARN: You must quit REPL manually (use `:exit`, `:quit` or call something shutdowns the interpreter)
 pub fn _exec_repl(name: &'static str, lines: Vec<String>) -> Result<ExitStatus, CompileErrors> {
     println!("{DEBUG_MAIN}[test] exec dummy REPL: {lines:?}{RESET}");
@@ -152,6 +260,10 @@ pub(crate) fn exec_file(file_path: &'static str) -> Result<i32, CompileErrors> {
     exec_new_thread(move || _exec_file(file_path))
 }
 
+pub(crate) fn exec_interpreter(file_path: &'static str) -> Result<i32, CompileErrors> {
+    exec_new_interpreter_thread(move || _exec_interpreter(file_path))
+}
+
 pub(crate) fn exec_repl(
     name: &'static str,
     lines: Vec<String>,
@@ -0,0 +1,83 @@
+mod common;
+use common::{erg, expect_stdout, TempDir};
+
+use std::fs;
+
+fn assert_contains(code: &str, expected: &[&str]) {
+    for expected in expected {
+        assert!(
+            code.contains(expected),
+            "`{expected}` not found in:\n{code}"
+        );
+    }
+}
+
+#[test]
+fn declare_gen() {
+    let dir = TempDir::new("declare-gen");
+    fs::write(
+        dir.join("lib.er"),
+        r#".C = Inheritable Class { .x = Int; y = Str }
+.C.
+    f self = self.x
+    new_c x: Int = .C.new { .x = x; y = "a" }
+.D = Inherit .C, Additional := { .z = Float }
+.Show = Trait { .show = (self: Self) -> Str }
+.id|T: Type|(x: T): T = x
+.add(a: Int, b := 1): Int = a + b
+.pi = 3.14
+.opt(x: Int or Str): Int or Str = x
+.mk(x: Int): .C = .C.new { .x = x; y = "b" }
+.p! x: Int = print! x
+private = 1
+"#,
+    )
+    .unwrap();
+    expect_stdout(erg(&dir, &["--mode", "declare-gen", "lib.er"]));
+    let decl = fs::read_to_string(dir.join("lib.d.er")).unwrap();
+    assert_contains(
+        &decl,
+        &[
+            ".C = Inheritable(Class({.x = Int; y = Str}))\n.C.\n    f: (self: .C) -> Int\n",
+            ".D = Inherit(.C, Additional := {.z = Float})\n",
+            ".Show = Trait({.show = (self: .Show) -> Str})\n",
+            ".id: |T: Type|(x: T) -> T\n",
+            ".add: (a: Int, b := Int) -> Int\n",
+            ".pi: Float\n",
+            ".mk: (x: Int) -> .C\n",
+            // the compiled name of `p!`
+            ".p! = 'p__erg_proc__': (x: Int) => NoneType\n",
+        ],
+    );
+    assert!(!decl.contains("private"), "{decl}");
+    let stub = fs::read_to_string(dir.join("lib.pyi")).unwrap();
+    assert_contains(
+        &stub,
+        &[
+            "from typing import ",
+            "T = TypeVar(\"T\")\n",
+            "class C:\n    x: int\n    def __init__(self, param: Any) -> None: ...\n    def f(self) -> int: ...\n    @staticmethod\n    def new_c(x: int) -> C: ...\n",
+            "class D(C):\n    z: float\n",
+            "class Show(Protocol):\n    def show(self) -> str: ...\n",
+            "def id(x: T) -> T: ...\n",
+            "def add(a: int, b: int = ...) -> int: ...\n",
+            "pi: float\n",
+            "def opt(x: Union[int, str]) -> Union[int, str]: ...\n",
+            "def mk(x: int) -> C: ...\n",
+            "def p__erg_proc__(x: int) -> None: ...\n",
+        ],
+    );
+    // other Erg projects can use the compiled module without the source
+    expect_stdout(erg(&dir, &["--mode", "compile", "lib.er"]));
+    fs::remove_file(dir.join("lib.er")).unwrap();
+    fs::write(
+        dir.join("main.er"),
+        r#"lib = pyimport "lib"
+c = lib.mk 1
+print! c.f(), lib.add(1), lib.id("a"), lib.opt(2)
+lib.p! 3
+"#,
+    )
+    .unwrap();
+    assert_eq!(expect_stdout(erg(&dir, &["main.er"])), "1 2 a 2\n3\n");
+}
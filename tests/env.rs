@@ -0,0 +1,74 @@
+mod common;
+use common::{erg, expect_stdout, TempDir};
+
+use std::fs;
+
+use erg_common::venv::VirtualEnv;
+
+#[test]
+fn env_site_packages() {
+    let dir = TempDir::new("env");
+    expect_stdout(erg(&dir, &["env", "create", "dev"]));
+    let venv = VirtualEnv::find(&dir).unwrap();
+    assert_eq!(venv.name, "dev");
+    assert!(venv
+        .py_command
+        .starts_with(&venv.root.to_string_lossy()[..]));
+    // a package installed only in the environment, with its declaration cached
+    fs::write(
+        venv.site_packages.join("greetpkg.py"),
+        "def hello(name):\n    return 'hello, ' + name\n",
+    )
+    .unwrap();
+    fs::write(
+        venv.decl_dir().join("greetpkg.d.er"),
+        ".hello: (name: Str) -> Str\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("main.er"),
+        "g = pyimport \"greetpkg\"\nprint! g.hello \"erg\"\n",
+    )
+    .unwrap();
+    assert_eq!(expect_stdout(erg(&dir, &["main.er"])), "hello, erg\n");
+    assert_eq!(
+        expect_stdout(erg(&dir, &["env", "list"])),
+        "* dev (Python 3.11.7, 1 cached declarations)\n"
+            .replace("3.11.7", &venv.target_version.to_string())
+    );
+    expect_stdout(erg(&dir, &["env", "deactivate"]));
+    assert!(VirtualEnv::find(&dir).is_none());
+}
+
+#[test]
+fn env_invalid_name() {
+    let dir = TempDir::new("env-invalid");
+    for name in ["--foo", "../x", "/tmp/x", "a/b", ""] {
+        for args in [&["env", "create", name][..], &["env", "activate", name][..]] {
+            let output = erg(&dir, args);
+            assert!(!output.status.success(), "{args:?}");
+            let stderr = String::from_utf8(output.stderr).unwrap();
+            assert!(stderr.contains("invalid environment name"), "{stderr}");
+        }
+    }
+    assert!(!dir.join(".erg").exists());
+    assert!(!dir.parent().unwrap().join("x").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn env_activate_broken_python() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new("env-broken");
+    let bin = dir.join(".erg/envs/broken/bin");
+    fs::create_dir_all(&bin).unwrap();
+    let python = bin.join("python");
+    fs::write(&python, "#!/bin/sh\necho broken >&2\nexit 3\n").unwrap();
+    fs::set_permissions(&python, fs::Permissions::from_mode(0o755)).unwrap();
+    let output = erg(&dir, &["env", "activate", "broken"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("exited with"), "{stderr}");
+    assert!(VirtualEnv::find(&dir).is_none());
+}
@@ -0,0 +1,97 @@
+mod common;
+use common::{erg, expect_stdout, python, TempDir};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use erg_common::config::{ErgConfig, ErgMode, Input};
+use erg_common::spawn::exec_new_thread;
+use erg_common::traits::Runnable;
+
+use erg_compiler::module::interface::interface_path;
+use erg_compiler::HIRBuilder;
+
+fn check(file: PathBuf) -> bool {
+    exec_new_thread(move || {
+        let cfg = ErgConfig {
+            mode: ErgMode::FullCheck,
+            input: Input::File(file),
+            quiet_repl: true,
+            ..ErgConfig::default()
+        };
+        HIRBuilder::new(cfg).exec().is_ok()
+    })
+}
+
+fn header(path: &Path) -> String {
+    let interface = fs::read_to_string(interface_path(path)).unwrap();
+    interface.lines().next().unwrap().to_string()
+}
+
+#[test]
+fn interface_cache() {
+    let dir = TempDir::new("interface");
+    let main = dir.join("main.er");
+    let lib = dir.join("lib.er");
+    fs::write(&main, "lib = import \"lib\"\n\nprint! lib.f 1\n").unwrap();
+    fs::write(&lib, ".f x: Int = x + 1\n").unwrap();
+    assert!(check(main.clone()));
+    let first = header(&lib);
+    assert!(first.starts_with("##[interface]"));
+    // `.g` exists only in the interface, so this passes only if the interface is loaded instead of the source
+    let interface = interface_path(&lib);
+    let mut decls = fs::read_to_string(&interface).unwrap();
+    decls.push_str(".g: (x: Int) -> Int\n");
+    fs::write(&interface, &decls).unwrap();
+    fs::write(&main, "lib = import \"lib\"\n\nprint! lib.g 1\n").unwrap();
+    assert!(check(main.clone()));
+    assert_eq!(fs::read_to_string(&interface).unwrap(), decls);
+    // changing the source invalidates the interface
+    fs::write(&lib, ".f x: Int = x + 2\n").unwrap();
+    assert!(!check(main.clone()));
+    assert_ne!(header(&lib), first);
+    fs::write(&main, "lib = import \"lib\"\n\nprint! lib.f 1\n").unwrap();
+    assert!(check(main));
+}
+
+#[test]
+fn interface_transitive_invalidation() {
+    let dir = TempDir::new("interface-transitive");
+    let main = dir.join("main.er");
+    let c = dir.join("c.er");
+    fs::write(&main, "a = import \"a\"\n\nprint! a.v + 1\n").unwrap();
+    fs::write(dir.join("a.er"), "b = import \"b\"\n\n.v = b.v\n").unwrap();
+    fs::write(dir.join("b.er"), "c = import \"c\"\n\n.v = c.v\n").unwrap();
+    fs::write(&c, ".v = 1\n").unwrap();
+    assert!(check(main.clone()));
+    // `a` does not import `c` directly, but the type of `a.v` depends on it
+    fs::write(&c, ".v = \"a\"\n").unwrap();
+    assert!(!check(main.clone()));
+    fs::write(&c, ".v = 2\n").unwrap();
+    assert!(check(main));
+    // the interfaces are not written into the source tree
+    assert!(!dir.join("__pycache__").exists());
+}
+
+#[test]
+fn interface_separate_compilation() {
+    let dir = TempDir::new("interface-separate");
+    let main = dir.join("main.er");
+    let lib = dir.join("lib.er");
+    fs::write(&main, "lib = import \"lib\"\n\nprint! lib.f 1\n").unwrap();
+    fs::write(&lib, "util = import \"util\"\n\n.f x: Int = util.inc x\n").unwrap();
+    fs::write(dir.join("util.er"), ".inc x: Int = x + 1\n").unwrap();
+    assert!(check(main.clone()));
+    let out = expect_stdout(erg(&dir, &["--mode", "compile", "--separate", "main.er"]));
+    assert_eq!(out, "");
+    // `lib.pyc` and `util.pyc` are generated from the sources even though the interface of `lib` is loaded
+    // (and `util` is not imported by the interface)
+    assert_eq!(expect_stdout(python(&dir, &["main.pyc"])), "2\n");
+    // `.g` exists only in the interface, so this compiles only if the interface is loaded
+    let interface = interface_path(&lib);
+    let mut decls = fs::read_to_string(&interface).unwrap();
+    decls.push_str(".g: (x: Int) -> Int\n");
+    fs::write(&interface, decls).unwrap();
+    fs::write(&main, "lib = import \"lib\"\n\nprint! lib.g 1\n").unwrap();
+    expect_stdout(erg(&dir, &["--mode", "compile", "--separate", "main.er"]));
+}
@@ -0,0 +1,197 @@
+mod common;
+use common::{expect_interpret_failure, expect_interpret_success};
+
+#[test]
+fn interpret_addition() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/addition.er")
+}
+
+#[test]
+fn interpret_advanced_type_spec() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/advanced_type_spec.er")
+}
+
+#[test]
+fn interpret_assert_cast() -> Result<(), ()> {
+    expect_interpret_success("examples/assert_cast.er")
+}
+
+#[test]
+fn interpret_class() -> Result<(), ()> {
+    expect_interpret_success("examples/class.er")
+}
+
+#[test]
+fn interpret_class_attr() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/class_attr.er")
+}
+
+#[test]
+fn interpret_comment() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/comment.er")
+}
+
+#[test]
+fn interpret_comptime() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/comptime.er")
+}
+
+#[test]
+fn interpret_control() -> Result<(), ()> {
+    expect_interpret_success("examples/control.er")
+}
+
+#[test]
+fn interpret_control_expr() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/control_expr.er")
+}
+
+#[test]
+fn interpret_cyclic_import() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/cyclic_import.er")
+}
+
+#[test]
+fn interpret_dict() -> Result<(), ()> {
+    expect_interpret_success("examples/dict.er")
+}
+
+#[test]
+fn interpret_fib() -> Result<(), ()> {
+    expect_interpret_success("examples/fib.er")
+}
+
+#[test]
+fn interpret_helloworld() -> Result<(), ()> {
+    expect_interpret_success("examples/helloworld.er")
+}
+
+#[test]
+fn interpret_if() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/if.er")
+}
+
+#[test]
+fn interpret_impl() -> Result<(), ()> {
+    expect_interpret_success("examples/impl.er")
+}
+
+#[test]
+fn interpret_import() -> Result<(), ()> {
+    expect_interpret_success("examples/import.er")
+}
+
+#[test]
+fn interpret_infer_class() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/infer_class.er")
+}
+
+#[test]
+fn interpret_infer_trait() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/infer_trait.er")
+}
+
+#[test]
+fn interpret_interpolation() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/interpolation.er")
+}
+
+#[test]
+fn interpret_long() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/long.er")
+}
+
+#[test]
+fn interpret_mut() -> Result<(), ()> {
+    expect_interpret_success("examples/mut.er")
+}
+
+#[test]
+fn interpret_mut_array() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/mut_array.er")
+}
+
+#[test]
+fn interpret_mut_effect() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/mut_effect.er")
+}
+
+#[test]
+fn interpret_nested() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/nested.er")
+}
+
+#[test]
+fn interpret_ownership() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/ownership.er")
+}
+
+#[test]
+fn interpret_patch() -> Result<(), ()> {
+    expect_interpret_success("examples/patch.er")
+}
+
+#[test]
+fn interpret_pattern() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/pattern.er")
+}
+
+#[test]
+fn interpret_quantified() -> Result<(), ()> {
+    expect_interpret_success("examples/quantified.er")
+}
+
+#[test]
+fn interpret_raw_ident() -> Result<(), ()> {
+    expect_interpret_success("examples/raw_ident.er")
+}
+
+#[test]
+fn interpret_rec() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/rec.er")
+}
+
+#[test]
+fn interpret_record() -> Result<(), ()> {
+    expect_interpret_success("examples/record.er")
+}
+
+#[test]
+fn interpret_return() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/return.er")
+}
+
+#[test]
+fn interpret_trait() -> Result<(), ()> {
+    expect_interpret_success("examples/trait.er")
+}
+
+#[test]
+fn interpret_try() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/try.er")
+}
+
+#[test]
+fn interpret_tuple() -> Result<(), ()> {
+    expect_interpret_success("examples/tuple.er")
+}
+
+#[test]
+fn interpret_unpack() -> Result<(), ()> {
+    expect_interpret_success("examples/unpack.er")
+}
+
+#[test]
+fn interpret_var_args() -> Result<(), ()> {
+    expect_interpret_success("tests/should_ok/var_args.er")
+}
+
+#[test]
+fn interpret_pyimport_err() -> Result<(), ()> {
+    expect_interpret_failure("examples/use_py.er", 1)
+}
+
+#[test]
+fn interpret_recursion_err() -> Result<(), ()> {
+    expect_interpret_failure("tests/should_err/recursion.er", 1)
+}
@@ -0,0 +1,72 @@
+mod common;
+use common::{erg, TempDir};
+
+use std::fs;
+use std::process::Output;
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn lint_levels() {
+    let dir = TempDir::new("lint-levels");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("src/main.er"), "t = {x = Int}\nx = 1\nprint! t\n").unwrap();
+
+    let output = erg(&dir, &["--mode", "check", "src/main.er"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(stderr(&output).contains("W0004"), "{output:?}");
+    assert!(stderr(&output).contains("W0007"), "{output:?}");
+
+    let output = erg(
+        &dir,
+        &["-A", "UnusedWarning", "--mode", "check", "src/main.er"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert!(!stderr(&output).contains("W0004"), "{output:?}");
+    assert!(stderr(&output).contains("W0007"), "{output:?}");
+
+    let output = erg(&dir, &["-D", "W0007", "--mode", "check", "src/main.er"]);
+    assert!(!output.status.success(), "{output:?}");
+    assert!(stderr(&output).contains("-D W0007"), "{output:?}");
+
+    // the command line options take precedence over `.erg/lint.er`
+    fs::create_dir_all(dir.join(".erg")).unwrap();
+    fs::write(dir.join(".erg/lint.er"), "warnings = \"deny\"\n").unwrap();
+    let output = erg(&dir, &["--mode", "check", "src/main.er"]);
+    assert!(!output.status.success(), "{output:?}");
+    let output = erg(&dir, &["-W", "warnings", "--mode", "check", "src/main.er"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let output = erg(&dir, &["-D", "Unused", "src/main.er"]);
+    assert_eq!(output.status.code(), Some(2), "{output:?}");
+}
+
+#[test]
+fn return_in_loop() {
+    let dir = TempDir::new("return-in-loop");
+    fs::write(
+        dir.join("main.er"),
+        "h!(): Int = 0
+g!(): Int =
+    for! 0..<5, i =>
+        if! i == 2, do!:
+            h!.return i
+    -1
+f!(): Int =
+    for! 0..<5, i =>
+        if! i == 2, do!:
+            f!.return i
+    -1
+print! g!(), f!()
+",
+    )
+    .unwrap();
+    let output = erg(&dir, &["--mode", "check", "main.er"]);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = stderr(&output);
+    // only `h!.return` (which returns from `g!`) is reported
+    assert_eq!(stderr.matches("W0008").count(), 1, "{stderr}");
+    assert!(stderr.contains("line 5"), "{stderr}");
+}
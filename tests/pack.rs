@@ -0,0 +1,37 @@
+mod common;
+use common::{erg, expect_stdout, TempDir};
+
+use std::fs;
+
+#[test]
+fn pack_path_dependency() {
+    let dir = TempDir::new("pack");
+    let (lib, app) = (dir.join("mathx"), dir.join("app"));
+    fs::create_dir_all(&lib).unwrap();
+    fs::create_dir_all(&app).unwrap();
+    expect_stdout(erg(&lib, &["pack", "init", "lib"]));
+    fs::write(
+        lib.join("src/lib.er"),
+        "util = import \"util\"\n.double x: Int = util.twice x\n",
+    )
+    .unwrap();
+    fs::write(lib.join("src/util.er"), ".twice x: Int = x * 2\n").unwrap();
+    expect_stdout(erg(&app, &["pack", "init", "app"]));
+    expect_stdout(erg(
+        &app,
+        &["pack", "add", "mathx", "0.1.*", "--path", "../mathx"],
+    ));
+    let manifest = fs::read_to_string(app.join("package.er")).unwrap();
+    assert!(manifest.contains("mathx = pack(\"mathx\", \"0.1.*\", path := \"../mathx\")"));
+    let lockfile = fs::read_to_string(app.join("package.lock.er")).unwrap();
+    assert!(lockfile.contains("version = \"0.1.0\"; source = \"path+../mathx\""));
+    assert!(app.join(".erg/deps/mathx/src/util.er").exists());
+    fs::write(
+        app.join("src/main.er"),
+        "mathx = import \"mathx\"\nprint! mathx.double 21\n",
+    )
+    .unwrap();
+    assert_eq!(expect_stdout(erg(&app, &["pack", "run"])), "42\n");
+    assert!(app.join("build/debug/mathx/__init__.pyc").exists());
+    assert!(app.join("build/debug/mathx/util.pyc").exists());
+}
@@ -183,3 +183,26 @@ fn exec_repl_invalid_def_after_the_at_sign() -> Result<(), ()> {
         1,
     )
 }
+
+#[test]
+fn exec_repl_long_output() -> Result<(), ()> {
+    expect_repl_success(
+        "repl_long_output",
+        ["print! \"a\" * 10000", "\"b\" * 10000", "exit()"]
+            .into_iter()
+            .map(|x| x.to_string())
+            .collect(),
+    )
+}
+
+#[test]
+fn exec_repl_runtime_error() -> Result<(), ()> {
+    expect_repl_failure(
+        "repl_runtime_error",
+        ["f x = 1 / x", "f 0", "print! \"still alive\"", "exit()"]
+            .into_iter()
+            .map(|x| x.to_string())
+            .collect(),
+        1,
+    )
+}
@@ -0,0 +1,90 @@
+mod common;
+use common::{expect_stdout, python, TempDir};
+
+use std::fs;
+
+use erg_common::config::{ErgConfig, ErgMode, Input};
+use erg_common::spawn::exec_new_thread;
+use erg_common::traits::Runnable;
+
+use erg_compiler::Compiler;
+
+#[test]
+fn separate_compilation() {
+    let dir = TempDir::new("separate");
+    let out = dir.join("out");
+    fs::create_dir_all(dir.join("pkg/sub")).unwrap();
+    fs::create_dir_all(&out).unwrap();
+    fs::write(
+        dir.join("main.er"),
+        "lib = import \"lib\"\npkg = import \"pkg\"\n\nprint! lib.f 1\nprint! pkg.name\n",
+    )
+    .unwrap();
+    fs::write(dir.join("lib.er"), ".f x: Int = x + 1\n").unwrap();
+    fs::write(
+        dir.join("pkg/__init__.er"),
+        "deep = import \"sub/deep\"\n.name = deep.g \"pkg\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("pkg/sub/deep.er"), ".g s: Str = s + \"!\"\n").unwrap();
+    let cfg = ErgConfig {
+        mode: ErgMode::Compile,
+        input: Input::File(dir.join("main.er")),
+        output_dir: Some(Box::leak(
+            out.to_string_lossy().into_owned().into_boxed_str(),
+        )),
+        separate: true,
+        ..ErgConfig::default()
+    };
+    assert_eq!(
+        exec_new_thread(move || Compiler::new(cfg).exec().ok()),
+        Some(0)
+    );
+    for module in [
+        "main.pyc",
+        "lib.pyc",
+        "pkg/__init__.pyc",
+        "pkg/sub/deep.pyc",
+    ] {
+        assert!(out.join(module).exists(), "{module} was not generated");
+    }
+    assert_eq!(expect_stdout(python(&out, &["main.pyc"])), "2\npkg!\n");
+    // the compiled modules can be imported from Python
+    let imported = expect_stdout(python(
+        &out,
+        &["-c", "import pkg.sub.deep as d; print(d.g('py'))"],
+    ));
+    assert_eq!(imported, "py!\n");
+}
+
+#[test]
+fn separate_compilation_out_of_tree() {
+    let dir = TempDir::new("separate-out-of-tree");
+    let out = dir.join("out");
+    for sub in ["app", "a", "b"] {
+        fs::create_dir_all(dir.join(sub)).unwrap();
+    }
+    fs::create_dir_all(&out).unwrap();
+    fs::write(
+        dir.join("app/main.er"),
+        "a = import \"../a/util\"\nb = import \"../b/util\"\n\nprint! a.name, b.name\n",
+    )
+    .unwrap();
+    fs::write(dir.join("a/util.er"), ".name = \"a\"\n").unwrap();
+    fs::write(dir.join("b/util.er"), ".name = \"b\"\n").unwrap();
+    let cfg = ErgConfig {
+        mode: ErgMode::Compile,
+        input: Input::File(dir.join("app/main.er")),
+        output_dir: Some(Box::leak(
+            out.to_string_lossy().into_owned().into_boxed_str(),
+        )),
+        separate: true,
+        ..ErgConfig::default()
+    };
+    assert_eq!(
+        exec_new_thread(move || Compiler::new(cfg).exec().ok()),
+        Some(0)
+    );
+    // the modules with the same name are not overwritten by each other
+    assert_eq!(expect_stdout(python(&out, &["main.pyc"])), "a b\n");
+}
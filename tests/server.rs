@@ -0,0 +1,102 @@
+#![cfg(unix)]
+mod common;
+use common::TempDir;
+
+use std::fs;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use erg_common::config::{ErgConfig, ErgMode, Input};
+use erg_common::traits::ExitStatus;
+
+use erg::server::{self, CompileServer};
+
+const STACK_SIZE: usize = 32 * 1024 * 1024;
+
+fn server_cfg(name: &str) -> ErgConfig {
+    let socket = std::env::temp_dir().join(format!("erg-test-{name}-{}.sock", std::process::id()));
+    ErgConfig {
+        mode: ErgMode::Server,
+        server_socket: Some(Box::leak(
+            socket.to_string_lossy().into_owned().into_boxed_str(),
+        )),
+        quiet_repl: true,
+        ..ErgConfig::default()
+    }
+}
+
+fn wait_for_server(cfg: &ErgConfig) {
+    for _ in 0..100 {
+        if UnixStream::connect(cfg.server_socket.unwrap()).is_ok() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    panic!("the compile server did not start");
+}
+
+fn check(cfg: &ErgConfig, file: impl AsRef<Path>) -> (i32, usize) {
+    let cfg = ErgConfig {
+        mode: ErgMode::FullCheck,
+        input: Input::File(file.as_ref().to_path_buf()),
+        client: true,
+        ..cfg.clone()
+    };
+    let status = server::request(&cfg).expect("the request was not handled");
+    (status.code, status.num_errors)
+}
+
+/// Rewrites `path`, making sure that its mtime changes even on file systems with a coarse timestamp resolution.
+fn edit(path: &Path, content: &str) {
+    let before = fs::metadata(path).and_then(|meta| meta.modified()).unwrap();
+    fs::write(path, content).unwrap();
+    let file = fs::File::options().write(true).open(path).unwrap();
+    if file.metadata().and_then(|meta| meta.modified()).unwrap() == before {
+        file.set_modified(before + Duration::from_secs(1)).unwrap();
+    }
+}
+
+fn start_server(cfg: &ErgConfig) -> thread::JoinHandle<ExitStatus> {
+    let server_conf = cfg.clone();
+    let handle = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || CompileServer::run(server_conf))
+        .unwrap();
+    wait_for_server(cfg);
+    handle
+}
+
+#[test]
+fn server_check() {
+    let cfg = server_cfg("check");
+    let handle = start_server(&cfg);
+    assert_eq!(check(&cfg, "tests/should_ok/addition.er"), (0, 0));
+    assert_eq!(check(&cfg, "tests/should_err/addition.er"), (1, 9));
+    // the second request reuses the cached modules
+    assert_eq!(check(&cfg, "tests/should_ok/addition.er"), (0, 0));
+    assert!(server::shutdown(&cfg));
+    assert_eq!(handle.join().unwrap().code, 0);
+    assert!(!server::shutdown(&cfg));
+}
+
+#[test]
+fn server_check_modified_import() {
+    let dir = TempDir::new("server-modified");
+    let main = dir.join("main.er");
+    let leaf = dir.join("leaf.er");
+    fs::write(&main, "mid = import \"mid\"\nprint! mid.x + 1\n").unwrap();
+    fs::write(dir.join("mid.er"), "leaf = import \"leaf\"\n.x = leaf.y\n").unwrap();
+    fs::write(&leaf, ".y = 1\n").unwrap();
+    let cfg = server_cfg("modified");
+    let handle = start_server(&cfg);
+    assert_eq!(check(&cfg, &main), (0, 0));
+    // `main` depends on `leaf` indirectly, so both `mid` and `main` must be rechecked
+    edit(&leaf, ".y = \"a\"\n");
+    assert_eq!(check(&cfg, &main), (1, 1));
+    edit(&leaf, ".y = 2\n");
+    assert_eq!(check(&cfg, &main), (0, 0));
+    assert!(server::shutdown(&cfg));
+    assert_eq!(handle.join().unwrap().code, 0);
+}
@@ -0,0 +1,109 @@
+mod common;
+use common::{erg, python, TempDir};
+
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+use erg_common::env::erg_std_path;
+
+const SRC: &str = r#"f x: Int =
+    y = x + 1
+    assert y == 0, "y must be 0"
+    y
+print! "start"
+z = f 1
+print! z
+"#;
+
+fn temp_dir(name: &str) -> TempDir {
+    let dir = TempDir::new(name);
+    fs::write(dir.join("fail.er"), SRC).unwrap();
+    dir
+}
+
+/// runs `file` with the traceback hook
+fn python_with_hook(dir: &Path, file: &str) -> Output {
+    let hook = erg_std_path().join("_erg_source_map.py");
+    python(dir, &[hook.as_os_str(), file.as_ref()])
+}
+
+fn assert_erg_traceback(output: &Output) {
+    assert!(!output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "start\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for expected in [
+        "File \"fail.er\", line 6, in <module>\n    z = f 1\n",
+        "File \"fail.er\", line 3, in ",
+        "    assert y == 0, \"y must be 0\"\n",
+        "AssertionError: y must be 0",
+    ] {
+        assert!(
+            stderr.contains(expected),
+            "`{expected}` not found in:\n{stderr}"
+        );
+    }
+}
+
+#[test]
+fn source_map_transpile() {
+    let dir = temp_dir("source-map-transpile");
+    let output = erg(&dir, &["--mode", "transpile", "--source-map", "fail.er"]);
+    assert!(output.status.success(), "{output:?}");
+    let map = fs::read_to_string(dir.join("fail.py.map")).unwrap();
+    assert!(
+        map.starts_with("{\"version\": 1, \"file\": \"fail.py\", \"sources\": [\"fail.er\"]"),
+        "{map}"
+    );
+    // `assert` (line 3, column 4)
+    assert!(map.contains(", 0, 3, 4]"), "{map}");
+    assert_erg_traceback(&python_with_hook(&dir, "fail.py"));
+}
+
+#[test]
+fn source_map_compile() {
+    let dir = temp_dir("source-map-compile");
+    let output = erg(&dir, &["--mode", "compile", "--source-map", "fail.er"]);
+    assert!(output.status.success(), "{output:?}");
+    let map = fs::read_to_string(dir.join("fail.pyc.map")).unwrap();
+    assert!(map.contains("\"offsets\": [[\"<module>\", 1, "), "{map}");
+    // `assert` in `f` (line 3, column 4)
+    assert!(
+        map.contains("[\"f\", 2, ") && map.contains(", 0, 3, 4]"),
+        "{map}"
+    );
+    assert_erg_traceback(&python_with_hook(&dir, "fail.pyc"));
+}
+
+#[test]
+fn source_map_exec() {
+    let dir = temp_dir("source-map-exec");
+    // the hook is installed even without `--source-map`
+    for args in [&["--source-map", "fail.er"][..], &["fail.er"]] {
+        assert_erg_traceback(&erg(&dir, args));
+        // the intermediate files are removed
+        assert!(!dir.join("fail.pyc").exists());
+        assert!(!dir.join("fail.pyc.map").exists());
+    }
+}
+
+#[test]
+fn source_map_extended_arg() {
+    let dir = TempDir::new("source-map-extended-arg");
+    // the body is wider than 255 bytes, so `EXTENDED_ARG`s are inserted before the jump of `if!`
+    let mut src = "x = 1\nif! x == 1:\n    do!:\n".to_string();
+    for i in 0..40 {
+        src += &format!("        print! {i}\n");
+    }
+    src += "        assert x == 0, \"x must be 0\"\n        print! \"unreachable\"\n";
+    fs::write(dir.join("wide.er"), src).unwrap();
+    let output = erg(&dir, &["--mode", "compile", "--source-map", "wide.er"]);
+    assert!(output.status.success(), "{output:?}");
+    let output = python_with_hook(&dir, "wide.pyc");
+    assert!(!output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("File \"wide.er\", line 44, in <module>\n    assert x == 0"),
+        "{stderr}"
+    );
+}
@@ -1,5 +1,7 @@
 mod common;
-use common::{expect_end_with, expect_failure, expect_success};
+use common::{erg, expect_end_with, expect_failure, expect_stdout, expect_success};
+
+use std::path::Path;
 
 #[test]
 fn exec_addition_ok() -> Result<(), ()> {
@@ -16,6 +18,17 @@ fn exec_assert_cast() -> Result<(), ()> {
     expect_success("examples/assert_cast.er")
 }
 
+#[test]
+fn exec_async() -> Result<(), ()> {
+    expect_success("tests/should_ok/async.er")
+}
+
+#[test]
+fn exec_async_blocks() {
+    let out = expect_stdout(erg(Path::new("."), &["tests/should_ok/async_blocks.er"]));
+    assert_eq!(out, "2\n3\n11\n2\n101\n101\n");
+}
+
 #[test]
 fn exec_class() -> Result<(), ()> {
     expect_success("examples/class.er")
@@ -31,6 +44,11 @@ fn exec_comment() -> Result<(), ()> {
     expect_success("tests/should_ok/comment.er")
 }
 
+#[test]
+fn exec_comptime() -> Result<(), ()> {
+    expect_success("tests/should_ok/comptime.er")
+}
+
 #[test]
 fn exec_control() -> Result<(), ()> {
     expect_success("examples/control.er")
@@ -41,6 +59,11 @@ fn exec_control_expr() -> Result<(), ()> {
     expect_success("tests/should_ok/control_expr.er")
 }
 
+#[test]
+fn exec_cyclic_import() -> Result<(), ()> {
+    expect_success("tests/should_ok/cyclic_import.er")
+}
+
 #[test]
 fn exec_dict() -> Result<(), ()> {
     expect_success("examples/dict.er")
@@ -51,6 +74,11 @@ fn exec_fib() -> Result<(), ()> {
     expect_success("examples/fib.er")
 }
 
+#[test]
+fn exec_generator() -> Result<(), ()> {
+    expect_success("tests/should_ok/generator.er")
+}
+
 #[test]
 fn exec_helloworld() -> Result<(), ()> {
     // HACK: When running the test with pre-commit, the exit code is 1 (the cause is unknown)
@@ -106,11 +134,21 @@ fn exec_mut_array() -> Result<(), ()> {
     expect_success("tests/should_ok/mut_array.er")
 }
 
+#[test]
+fn exec_mut_effect() -> Result<(), ()> {
+    expect_success("tests/should_ok/mut_effect.er")
+}
+
 #[test]
 fn exec_nested() -> Result<(), ()> {
     expect_success("tests/should_ok/nested.er")
 }
 
+#[test]
+fn exec_ownership() -> Result<(), ()> {
+    expect_success("tests/should_ok/ownership.er")
+}
+
 #[test]
 fn exec_patch() -> Result<(), ()> {
     expect_success("examples/patch.er")
@@ -151,6 +189,11 @@ fn exec_trait() -> Result<(), ()> {
     expect_success("examples/trait.er")
 }
 
+#[test]
+fn exec_try() -> Result<(), ()> {
+    expect_success("tests/should_ok/try.er")
+}
+
 #[test]
 fn exec_tuple() -> Result<(), ()> {
     expect_success("examples/tuple.er")
@@ -181,6 +224,36 @@ fn exec_args() -> Result<(), ()> {
     expect_failure("tests/should_err/args.er", 16)
 }
 
+#[test]
+fn exec_async_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/async.er", 4)
+}
+
+#[test]
+fn exec_comptime_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/comptime.er", 2)
+}
+
+#[test]
+fn exec_cyclic_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/cyclic.er", 4)
+}
+
+#[test]
+fn exec_cyclic_ref() -> Result<(), ()> {
+    expect_failure("tests/should_err/cyclic_ref.er", 2)
+}
+
+#[test]
+fn exec_cyclic_refmut() -> Result<(), ()> {
+    expect_failure("tests/should_err/cyclic_refmut.er", 1)
+}
+
+#[test]
+fn exec_generator_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/generator.er", 3)
+}
+
 #[test]
 fn exec_infer_union_array() -> Result<(), ()> {
     expect_failure("tests/should_err/infer_union_array.er", 1)
@@ -225,11 +298,26 @@ fn exec_subtyping() -> Result<(), ()> {
     expect_failure("tests/should_err/subtyping.er", 6)
 }
 
+#[test]
+fn exec_type_spec_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/type_spec.er", 3)
+}
+
 #[test]
 fn exec_callable() -> Result<(), ()> {
     expect_failure("tests/should_err/callable.er", 4)
 }
 
+#[test]
+fn exec_mut_array_cycle() -> Result<(), ()> {
+    expect_failure("tests/should_err/mut_array_cycle.er", 1)
+}
+
+#[test]
+fn exec_mut_effect_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/mut_effect.er", 4)
+}
+
 #[test]
 fn exec_multiline_invalid_next() -> Result<(), ()> {
     expect_failure("tests/should_err/multi_line_invalid_nest.er", 1)
@@ -240,14 +328,24 @@ fn exec_quantified_err() -> Result<(), ()> {
     expect_failure("tests/should_err/quantified.er", 3)
 }
 
+#[test]
+fn exec_recursion_err() -> Result<(), ()> {
+    expect_end_with("tests/should_err/recursion.er", 1)
+}
+
 #[test]
 fn exec_var_args() -> Result<(), ()> {
     expect_success("tests/should_ok/var_args.er")
 }
 
+#[test]
+fn exec_try_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/try.er", 4)
+}
+
 #[test]
 fn exec_var_args_err() -> Result<(), ()> {
-    expect_failure("tests/should_err/var_args.er", 2)
+    expect_failure("tests/should_err/var_args.er", 4)
 }
 
 #[test]
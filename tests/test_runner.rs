@@ -0,0 +1,92 @@
+mod common;
+use common::{erg, TempDir};
+
+use std::fs;
+
+#[test]
+fn test_subcommand() {
+    let dir = TempDir::new("runner");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::create_dir_all(dir.join("tests")).unwrap();
+    fs::write(
+        dir.join("src/lib.er"),
+        "## ```erg
+## lib = import \"lib\"
+## assert lib.add(1, 2) == 3
+## ```
+.add x: Int, y: Int = x + y
+
+double x: Int = x * 2
+",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("src/lib.test.er"),
+        "@Test\ntest_double!() =\n    assert double(2) == 4\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("tests/add.er"),
+        "lib = import \"../src/lib\"
+
+@Test
+test_add!() =
+    print! \"adding\"
+    assert lib.add(1, 1) == 2
+
+@Test
+test_fail!() =
+    assert lib.add(1, 1) == 3
+
+not_a_test!() = print! \"not a test\"
+",
+    )
+    .unwrap();
+    let output = erg(&dir, &["test"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!output.status.success(), "{stdout}");
+    for line in [
+        "test src/lib.er::doc(line 2) ... ok",
+        "test src/lib.test.er::test_double! ... ok",
+        "test tests/add.er::test_add! ... ok",
+        "test tests/add.er::test_fail! ... FAILED",
+        "test result: FAILED. 3 passed; 1 failed; 0 skipped",
+    ] {
+        assert!(stdout.contains(line), "{line} not found in:\n{stdout}");
+    }
+    assert!(!stdout.contains("not_a_test"));
+    let output = erg(&dir, &["test", "--filter", "test_add", "--format", "junit"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.starts_with("<?xml"));
+    assert!(stdout.contains("<testsuite name=\"tests/add.er\" tests=\"1\" failures=\"0\""));
+    assert!(stdout.contains("<system-out>adding\n</system-out>"));
+}
+
+#[test]
+fn test_subcommand_imports() {
+    let dir = TempDir::new("runner_imports");
+    fs::create_dir_all(dir.join("tests")).unwrap();
+    fs::write(dir.join("tests/helper.er"), ".two = 2\n").unwrap();
+    fs::write(dir.join("tests/py_helper.py"), "def three(): return 3\n").unwrap();
+    fs::write(dir.join("tests/py_helper.d.er"), ".three: () -> Int\n").unwrap();
+    fs::write(
+        dir.join("tests/imports.er"),
+        "helper = import \"helper\"
+py_helper = pyimport \"py_helper\"
+
+@Test
+test_imports!() =
+    assert helper.two + py_helper.three() == 5
+",
+    )
+    .unwrap();
+    // the imports are resolved from `tests/`, not from the current directory
+    let output = erg(&dir, &["test"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("test tests/imports.er::test_imports! ... ok"),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,222 @@
+mod common;
+use common::{erg, expect_stdout, python, TempDir};
+
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn transpile_type_annotations() {
+    let dir = TempDir::new("transpile");
+    fs::write(
+        dir.join("annot.er"),
+        r#"x = 1
+arr = [1, 2, 3]
+add(a: Int, b: Int): Int = a + b
+id|T: Type|(y: T): T = y
+g!(n: Nat) =
+    print! n
+rec = {.a = 1; .b = "b"}
+opt(i: Int or Str): Int or Str = i
+hof(h: Int -> Int): Int = h 1
+total(*xs: Int): Int = sum xs
+print! add(x, 2), id(1), rec.a, opt(1), hof(i -> i + 1), arr, total(1, 2)
+g! 1
+"#,
+    )
+    .unwrap();
+    expect_stdout(erg(&dir, &["--mode", "transpile", "annot.er"]));
+    let code = fs::read_to_string(dir.join("annot.py")).unwrap();
+    assert!(code.starts_with("from __future__ import annotations\n"));
+    for expected in [
+        "x__: int = ",
+        "arr__: list[int] = ",
+        "def add__(a__: int,b__: int,) -> int:",
+        "def id__(y__: TypeVar__T,) -> TypeVar__T:",
+        "TypeVar__T = TypeVar('TypeVar__T')",
+        "def g__erg_proc____(n__: int,) -> None:",
+        "class Record0__(NamedTuple):\n    a: int\n    b: str\n",
+        "rec__: Record0__ = Record0__(a=",
+        "def opt__(i__: Union[int, str],) -> Union[int, str]:",
+        "def hof__(h__: Callable[[int], int],) -> int:",
+        "def total__(*xs__: int,) -> int:",
+    ] {
+        assert!(
+            code.contains(expected),
+            "`{expected}` not found in:\n{code}"
+        );
+    }
+    assert_eq!(
+        expect_stdout(python(&dir, &["annot.py"])),
+        "3 1 1 1 2 [1, 2, 3] 3\n1\n"
+    );
+    // the annotations must be accepted by mypy (skipped if mypy is not installed)
+    if python(&dir, &["-m", "mypy", "--version"]).status.success() {
+        // the inlined prelude is not annotated, so only the transpiled code of `annot.er` is checked
+        let start = code
+            .lines()
+            .position(|line| line.starts_with("x__: int = "))
+            .unwrap()
+            + 1;
+        let output = python(&dir, &["-m", "mypy", "--no-error-summary", "annot.py"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let errors = stdout
+            .lines()
+            .filter(|line| line.contains(": error: "))
+            .filter(|line| {
+                line.strip_prefix("annot.py:")
+                    .and_then(|rest| rest.split(':').next())
+                    .and_then(|lineno| lineno.parse::<usize>().ok())
+                    .is_some_and(|lineno| lineno >= start)
+            })
+            .collect::<Vec<_>>();
+        assert!(errors.is_empty(), "{stdout}");
+    }
+}
+
+#[test]
+fn transpile_readable() {
+    let dir = TempDir::new("readable");
+    fs::write(
+        dir.join("readable.er"),
+        r#"x = 1
+f(n: Int): Int =
+    y = n * 2
+    y + x
+f!(n: Int) =
+    if! n > 2:
+        do!:
+            print! "big"
+            print! n
+        do!:
+            print! "small"
+k(n: Int): Int =
+    add = (i: Int) ->
+        j = i + n
+        j * 2
+    add 1
+z =
+    w = f 3
+    w + 1
+sign = match z:
+    0 -> "zero"
+    _ -> "non-zero"
+C = Class { .x = Int }
+C.
+    get_x self = self.x
+print! f(1), k(2), z, sign, C.new({ .x = 3 }).get_x()
+f! 3
+"#,
+    )
+    .unwrap();
+    expect_stdout(erg(
+        &dir,
+        &["--mode", "transpile", "--readable", "readable.er"],
+    ));
+    let code = fs::read_to_string(dir.join("readable.py")).unwrap();
+    for expected in [
+        "x: int = ",
+        "def f(n: int,) -> int:\n    y = (n * Nat(2))\n",
+        "def f__(n: int,)",
+        "    if (n > Nat(2)):\n",
+        "    else:\n",
+        "def k(n: int,) -> int:\n    def lambda_0__(i: int,) -> int:\n",
+        "w: int = (f)(Nat(3),)\nz: int = (w + Nat(1))\n",
+        "match z:\n    case 0:\n        sign = Str(\"zero\")\n    case _:\n",
+        "    def get_x(self",
+    ] {
+        assert!(
+            code.contains(expected),
+            "`{expected}` not found in:\n{code}"
+        );
+    }
+    assert!(!code.contains("global "), "{code}");
+    assert_eq!(
+        expect_stdout(python(&dir, &["readable.py"])),
+        "3 6 8 non-zero 3\nbig\n3\n"
+    );
+}
+
+/// The files whose outputs cannot be the same in both backends
+const BACKEND_DIFF_SKIPS: [&str; 3] = [
+    // prints an object (the repr contains the memory address)
+    "cyclic_import",
+    // prints a function object
+    "rec",
+    // `i % 3` is rejected by the type checker (`Int` has no `ModOutput`) in every mode, so it cannot be transpiled
+    "fizzbuzz",
+];
+
+fn copy_dir(src: &Path, dst: &Path) {
+    fs::create_dir_all(dst).unwrap();
+    for entry in fs::read_dir(src).unwrap() {
+        let path = entry.unwrap().path();
+        let dst = dst.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_dir(&path, &dst);
+        } else {
+            fs::copy(&path, &dst).unwrap();
+        }
+    }
+}
+
+/// Block arguments that are not lambdas, parameter patterns and `[x; n]`
+const BLOCK_ARGS: &str = r#"f!() = print! "f"
+g!() = print! "g"
+if! True, f!, g!
+if! False, f!, g!
+x = if! False, f!, g!
+print! x
+h() = "h"
+k() = "k"
+print! if True, h, k
+print! if False, h, k
+p!(i) = print! i
+for! 0..<2, p!
+for! 0..<2, _ =>
+    print! "_"
+for! [[1, 2], [3, 4]], ([a, b]) =>
+    print! a + b
+add((a, b)) = a + b
+print! add((1, 2))
+print! [1; 3], {1; 3}
+read!(file) = file.read!()
+source = with! open!("block_args.er"), read!
+print! len source
+"#;
+
+/// Runs every `tests/should_ok` file with the bytecode backend and the transpiler (in both modes)
+#[test]
+fn transpile_and_compile_outputs_match() {
+    let dir = TempDir::new("backends");
+    copy_dir(Path::new("tests/should_ok"), &dir);
+    fs::write(dir.join("block_args.er"), BLOCK_ARGS).unwrap();
+    let mut files = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "er"))
+        .collect::<Vec<_>>();
+    files.sort();
+    for path in files {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        if BACKEND_DIFF_SKIPS.contains(&name) {
+            continue;
+        }
+        let file = format!("{name}.er");
+        let compiled = erg(&dir, &[&file]);
+        for mode in [&[][..], &["--readable"]] {
+            let args = [&["--mode", "transpile"][..], mode, &[&file]].concat();
+            expect_stdout(erg(&dir, &args));
+            let transpiled = python(&dir, &[format!("{name}.py")]);
+            assert_eq!(
+                compiled.status.success(),
+                transpiled.status.success(),
+                "{name} {mode:?}: {transpiled:?}"
+            );
+            assert_eq!(
+                String::from_utf8_lossy(&compiled.stdout),
+                String::from_utf8_lossy(&transpiled.stdout),
+                "{name} {mode:?}"
+            );
+        }
+    }
+}